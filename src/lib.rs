@@ -5,14 +5,21 @@
 //! ## Módulos
 //!
 //! - `state`: Estado do terminal (buffer, cursor, scroll)
+//! - `pty`: Pseudo-terminal entre o terminal e processos filhos
 //! - `render`: Renderização (fonte, texto, decorações)
 //! - `ui`: Componentes visuais (janela, barra de título)
 //! - `shell`: Shell interativo com comandos
+//!
+//! ## Testes
+//!
+//! Os testes rodam no host (`cargo test-host`): os comandos de arquivo usam
+//! um `MemoryFs` no lugar do sistema de arquivos do RedstoneOS.
 
-#![no_std]
+#![cfg_attr(not(test), no_std)]
 
 extern crate alloc;
 
+pub mod pty;
 pub mod render;
 pub mod shell;
 pub mod state;
@@ -23,14 +23,9 @@
 
 extern crate alloc;
 
-mod render;
-mod shell;
-mod state;
-mod ui;
-
 use redpowder::println;
 use redpowder::window::Window;
-use ui::window::TerminalWindow;
+use terminal::TerminalWindow;
 
 /// Alocador global
 #[global_allocator]
@@ -90,7 +85,7 @@ fn run() -> Result<(), redpowder::syscall::SysError> {
             break;
         }
 
-        // Atualizar animações (cursor piscante)
+        // Atualizar animações e avançar o comando em execução
         let old_cursor_visible = terminal.state.cursor_visible;
         terminal.tick();
         if old_cursor_visible != terminal.state.cursor_visible {
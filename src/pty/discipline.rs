@@ -0,0 +1,165 @@
+//! # Line Discipline
+//!
+//! Tratamento da entrada do teclado antes de chegar ao processo.
+//!
+//! - **Canônico**: acumula a linha, com edição (apagar caractere, apagar
+//!   linha) e só a entrega ao processo no Enter.
+//! - **Raw**: cada byte é entregue imediatamente.
+//!
+//! Em ambos os modos, com `isig`, ^C, ^\ e ^Z geram sinais em vez de dados.
+
+use super::{Shared, Signal};
+
+/// Configuração da disciplina de linha
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Termios {
+    /// Modo canônico (edição de linha)
+    pub canonical: bool,
+    /// Ecoa os caracteres digitados
+    pub echo: bool,
+    /// Gera sinais para ^C, ^\ e ^Z
+    pub isig: bool,
+    /// Converte `\r` em `\n` na entrada
+    pub icrnl: bool,
+    /// Apaga caractere (DEL)
+    pub erase: u8,
+    /// Apaga linha (^U)
+    pub kill: u8,
+    /// Fim de arquivo (^D)
+    pub eof: u8,
+    /// Interromper (^C)
+    pub intr: u8,
+    /// Encerrar com core (^\)
+    pub quit: u8,
+    /// Suspender (^Z)
+    pub susp: u8,
+}
+
+impl Termios {
+    /// Modo padrão: canônico com eco e sinais
+    pub const fn cooked() -> Self {
+        Self {
+            canonical: true,
+            echo: true,
+            isig: true,
+            icrnl: true,
+            erase: 0x7F,
+            kill: 0x15,
+            eof: 0x04,
+            intr: 0x03,
+            quit: 0x1C,
+            susp: 0x1A,
+        }
+    }
+
+    /// Modo raw: sem edição, eco ou sinais
+    pub const fn raw() -> Self {
+        Self {
+            canonical: false,
+            echo: false,
+            isig: false,
+            icrnl: false,
+            ..Self::cooked()
+        }
+    }
+}
+
+impl Default for Termios {
+    fn default() -> Self {
+        Self::cooked()
+    }
+}
+
+/// Processa um byte vindo do teclado
+pub(super) fn input_byte(shared: &mut Shared, mut byte: u8) {
+    let t = shared.termios;
+
+    if t.isig {
+        let signal = if byte == t.intr {
+            Some(Signal::Interrupt)
+        } else if byte == t.quit {
+            Some(Signal::Quit)
+        } else if byte == t.susp {
+            Some(Signal::Suspend)
+        } else {
+            None
+        };
+
+        if let Some(signal) = signal {
+            shared.line.clear();
+            shared.signals.push_back(signal);
+            if t.echo {
+                echo_control(shared, byte);
+                shared.output.push_back(b'\n');
+            }
+            return;
+        }
+    }
+
+    if t.icrnl && byte == b'\r' {
+        byte = b'\n';
+    }
+
+    if !t.canonical {
+        shared.input.push_back(byte);
+        if t.echo {
+            shared.output.push_back(byte);
+        }
+        return;
+    }
+
+    if byte == t.erase || byte == 0x08 {
+        if erase_char(shared) && t.echo {
+            shared.output.extend(*b"\x08 \x08");
+        }
+    } else if byte == t.kill {
+        while erase_char(shared) {
+            if t.echo {
+                shared.output.extend(*b"\x08 \x08");
+            }
+        }
+    } else if byte == t.eof {
+        if shared.line.is_empty() {
+            shared.eof = true;
+        } else {
+            // ^D com texto entrega a linha sem o '\n'
+            let line = core::mem::take(&mut shared.line);
+            shared.input.extend(line);
+        }
+    } else if byte == b'\n' {
+        let line = core::mem::take(&mut shared.line);
+        shared.input.extend(line);
+        shared.input.push_back(b'\n');
+        if t.echo {
+            shared.output.push_back(b'\n');
+        }
+    } else {
+        shared.line.push(byte);
+        if t.echo {
+            if byte < 0x20 && byte != b'\t' {
+                echo_control(shared, byte);
+            } else {
+                shared.output.push_back(byte);
+            }
+        }
+    }
+}
+
+/// Apaga o último caractere (UTF-8) da linha. Retorna se apagou algo.
+fn erase_char(shared: &mut Shared) -> bool {
+    let mut erased = false;
+    while let Some(byte) = shared.line.pop() {
+        erased = true;
+        // Parar no byte inicial da sequência
+        if byte & 0xC0 != 0x80 {
+            break;
+        }
+    }
+    erased
+}
+
+/// Ecoa caractere de controle como `^X`
+fn echo_control(shared: &mut Shared, byte: u8) {
+    shared.output.push_back(b'^');
+    shared.output.push_back((byte ^ 0x40) & 0x7F);
+}
@@ -0,0 +1,337 @@
+//! # Pseudo-Terminal
+//!
+//! Canal de E/S entre o terminal e um processo filho.
+//!
+//! ```text
+//!  teclado ──► PtyMaster::write ──► disciplina de linha ──► PtySlave::read ──► filho
+//!  tela ◄──── PtyMaster::read ◄──── (eco + saída) ◄──────── PtySlave::write ◄── filho
+//! ```
+//!
+//! O lado mestre fica com o [`TerminalWindow`](crate::ui::window::TerminalWindow);
+//! o escravo é o stdin/stdout do filho. Os dois lados compartilham o mesmo
+//! buffer em memória, então um par recém-criado já funciona como loopback.
+
+mod discipline;
+
+pub use discipline::Termios;
+
+use alloc::collections::VecDeque;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
+/// Tamanho da janela em caracteres
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WinSize {
+    /// Colunas
+    pub cols: u16,
+    /// Linhas
+    pub rows: u16,
+}
+
+/// Sinal entregue a um processo.
+///
+/// Os quatro primeiros são gerados pela disciplina de linha; os demais
+/// vêm do controle de jobs (`fg`, `bg`, `kill`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Signal {
+    /// ^C
+    Interrupt,
+    /// ^\
+    Quit,
+    /// ^Z
+    Suspend,
+    /// Tamanho da janela mudou
+    WindowChange,
+    /// Continuar processo suspenso
+    Continue,
+    /// Pedido de término
+    Terminate,
+    /// Término forçado
+    Kill,
+}
+
+/// Estado compartilhado entre mestre e escravo
+struct Shared {
+    termios: Termios,
+    winsize: WinSize,
+    /// Linha em edição (modo canônico)
+    line: Vec<u8>,
+    /// Dados prontos para o escravo ler
+    input: VecDeque<u8>,
+    /// Dados para o mestre ler (saída do escravo + eco)
+    output: VecDeque<u8>,
+    /// Sinais pendentes para o processo em primeiro plano
+    signals: VecDeque<Signal>,
+    /// ^D recebido com a linha vazia
+    eof: bool,
+}
+
+/// Cria um par mestre/escravo
+pub fn pair(winsize: WinSize) -> (PtyMaster, PtySlave) {
+    let shared = Rc::new(RefCell::new(Shared {
+        termios: Termios::cooked(),
+        winsize,
+        line: Vec::new(),
+        input: VecDeque::new(),
+        output: VecDeque::new(),
+        signals: VecDeque::new(),
+        eof: false,
+    }));
+
+    (
+        PtyMaster {
+            shared: shared.clone(),
+        },
+        PtySlave { shared },
+    )
+}
+
+/// Lado do terminal
+pub struct PtyMaster {
+    shared: Rc<RefCell<Shared>>,
+}
+
+impl PtyMaster {
+    /// Envia bytes digitados, que passam pela disciplina de linha
+    pub fn write(&self, data: &[u8]) {
+        let mut shared = self.shared.borrow_mut();
+        for &byte in data {
+            discipline::input_byte(&mut shared, byte);
+        }
+    }
+
+    /// Lê saída pendente (eco e escrita do escravo). Retorna bytes lidos.
+    pub fn read(&self, buf: &mut [u8]) -> usize {
+        drain(&mut self.shared.borrow_mut().output, buf)
+    }
+
+    /// Retira o próximo sinal pendente
+    pub fn take_signal(&self) -> Option<Signal> {
+        self.shared.borrow_mut().signals.pop_front()
+    }
+
+    /// Informa novo tamanho da janela, gerando `WindowChange` se mudou
+    pub fn set_winsize(&self, winsize: WinSize) {
+        let mut shared = self.shared.borrow_mut();
+        if shared.winsize != winsize {
+            shared.winsize = winsize;
+            shared.signals.push_back(Signal::WindowChange);
+        }
+    }
+
+    /// Tamanho atual da janela
+    pub fn winsize(&self) -> WinSize {
+        self.shared.borrow().winsize
+    }
+
+    /// Modo atual da disciplina de linha
+    pub fn termios(&self) -> Termios {
+        self.shared.borrow().termios
+    }
+}
+
+/// Lado do processo filho
+pub struct PtySlave {
+    shared: Rc<RefCell<Shared>>,
+}
+
+impl PtySlave {
+    /// Lê entrada disponível.
+    ///
+    /// Retorna `None` se não há nada ainda, `Some(0)` em fim de arquivo
+    /// (^D) e `Some(n)` com os bytes lidos. No modo canônico, cada leitura
+    /// retorna no máximo uma linha.
+    pub fn read(&self, buf: &mut [u8]) -> Option<usize> {
+        let mut shared = self.shared.borrow_mut();
+
+        if shared.input.is_empty() {
+            if shared.eof {
+                shared.eof = false;
+                return Some(0);
+            }
+            return None;
+        }
+
+        let canonical = shared.termios.canonical;
+        let mut n = 0;
+        while n < buf.len() {
+            let Some(byte) = shared.input.pop_front() else {
+                break;
+            };
+            buf[n] = byte;
+            n += 1;
+            if canonical && byte == b'\n' {
+                break;
+            }
+        }
+        Some(n)
+    }
+
+    /// Escreve saída do filho para o terminal
+    pub fn write(&self, data: &[u8]) {
+        self.shared.borrow_mut().output.extend(data.iter().copied());
+    }
+
+    /// Tamanho atual da janela
+    pub fn winsize(&self) -> WinSize {
+        self.shared.borrow().winsize
+    }
+
+    /// Modo atual da disciplina de linha
+    pub fn termios(&self) -> Termios {
+        self.shared.borrow().termios
+    }
+
+    /// Altera o modo da disciplina de linha
+    pub fn set_termios(&self, termios: Termios) {
+        let mut shared = self.shared.borrow_mut();
+        // Ao sair do modo canônico, a linha parcial fica disponível
+        if shared.termios.canonical && !termios.canonical {
+            let line = core::mem::take(&mut shared.line);
+            shared.input.extend(line);
+        }
+        shared.termios = termios;
+    }
+}
+
+/// Move bytes de uma fila para `buf`
+fn drain(queue: &mut VecDeque<u8>, buf: &mut [u8]) -> usize {
+    let n = queue.len().min(buf.len());
+    for (dst, src) in buf.iter_mut().zip(queue.drain(..n)) {
+        *dst = src;
+    }
+    n
+}
+
+// =============================================================================
+// TESTES
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{pair, PtyMaster, PtySlave, Signal, Termios, WinSize};
+    use alloc::vec::Vec;
+
+    const SIZE: WinSize = WinSize { cols: 80, rows: 24 };
+
+    /// Tudo o que o mestre tem para ler (eco e saída do escravo)
+    fn screen(master: &PtyMaster) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut buf = [0u8; 16];
+        loop {
+            match master.read(&mut buf) {
+                0 => return out,
+                n => out.extend_from_slice(&buf[..n]),
+            }
+        }
+    }
+
+    /// Próxima leitura do escravo
+    fn read(slave: &PtySlave) -> Option<Vec<u8>> {
+        let mut buf = [0u8; 64];
+        slave.read(&mut buf).map(|n| Vec::from(&buf[..n]))
+    }
+
+    #[test]
+    fn canonical_line_is_delivered_on_enter() {
+        let (master, slave) = pair(SIZE);
+        master.write(b"ls -l");
+        assert_eq!(read(&slave), None);
+        master.write(b"\r");
+        assert_eq!(read(&slave).as_deref(), Some(&b"ls -l\n"[..]));
+        assert_eq!(screen(&master), b"ls -l\n");
+    }
+
+    #[test]
+    fn one_line_per_read() {
+        let (master, slave) = pair(SIZE);
+        master.write(b"a\nb\n");
+        assert_eq!(read(&slave).as_deref(), Some(&b"a\n"[..]));
+        assert_eq!(read(&slave).as_deref(), Some(&b"b\n"[..]));
+        assert_eq!(read(&slave), None);
+    }
+
+    #[test]
+    fn erase_and_kill_edit_the_line() {
+        let (master, slave) = pair(SIZE);
+        master.write("xé\x7f\x7fab".as_bytes());
+        master.write(b"\x15ok\n");
+        assert_eq!(read(&slave).as_deref(), Some(&b"ok\n"[..]));
+        assert_eq!(
+            screen(&master),
+            "xé\x08 \x08\x08 \x08ab\x08 \x08\x08 \x08ok\n".as_bytes()
+        );
+    }
+
+    #[test]
+    fn interrupt_discards_the_line() {
+        let (master, slave) = pair(SIZE);
+        master.write(b"rm -rf\x03");
+        assert_eq!(master.take_signal(), Some(Signal::Interrupt));
+        assert_eq!(master.take_signal(), None);
+        assert_eq!(screen(&master), b"rm -rf^C\n");
+        master.write(b"\n");
+        assert_eq!(read(&slave).as_deref(), Some(&b"\n"[..]));
+    }
+
+    #[test]
+    fn eof_on_empty_line_and_partial_line() {
+        let (master, slave) = pair(SIZE);
+        master.write(b"abc\x04");
+        assert_eq!(read(&slave).as_deref(), Some(&b"abc"[..]));
+        master.write(b"\x04");
+        assert_eq!(read(&slave).as_deref(), Some(&b""[..]));
+        assert_eq!(read(&slave), None);
+    }
+
+    #[test]
+    fn slave_output_reaches_the_master() {
+        let (master, slave) = pair(SIZE);
+        slave.write(b"\x1b[1mola\x1b[0m\n");
+        assert_eq!(screen(&master), b"\x1b[1mola\x1b[0m\n");
+    }
+
+    #[test]
+    fn raw_mode_passes_bytes_through() {
+        let (master, slave) = pair(SIZE);
+        slave.set_termios(Termios::raw());
+        assert!(!slave.termios().canonical);
+        assert!(!master.termios().echo);
+
+        master.write(b"q\x03\r\x7f");
+        assert_eq!(read(&slave).as_deref(), Some(&b"q\x03\r\x7f"[..]));
+        assert_eq!(master.take_signal(), None);
+        assert_eq!(screen(&master), b"");
+    }
+
+    #[test]
+    fn leaving_canonical_mode_releases_the_partial_line() {
+        let (master, slave) = pair(SIZE);
+        master.write(b"ab");
+        assert_eq!(read(&slave), None);
+
+        slave.set_termios(Termios::raw());
+        assert_eq!(read(&slave).as_deref(), Some(&b"ab"[..]));
+        slave.set_termios(Termios::cooked());
+        master.write(b"c");
+        assert_eq!(read(&slave), None);
+    }
+
+    #[test]
+    fn window_change_only_when_size_differs() {
+        let (master, slave) = pair(SIZE);
+        master.set_winsize(SIZE);
+        assert_eq!(master.take_signal(), None);
+
+        let bigger = WinSize {
+            cols: 120,
+            rows: 40,
+        };
+        master.set_winsize(bigger);
+        assert_eq!(master.take_signal(), Some(Signal::WindowChange));
+        assert_eq!(master.take_signal(), None);
+        assert_eq!(slave.winsize(), bigger);
+        assert_eq!(master.winsize(), bigger);
+    }
+}
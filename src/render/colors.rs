@@ -53,3 +53,44 @@ pub const MINIMIZE_BUTTON_HOVER: u32 = 0xFF484F58;
 
 /// Cor da borda da janela
 pub const WINDOW_BORDER: u32 = 0xFF30363D;
+
+/// Paleta ANSI de 16 cores (SGR 30-37 / 90-97), no tema do terminal
+pub const ANSI_PALETTE: [u32; 16] = [
+    0xFF484F58, // preto
+    0xFFF85149, // vermelho
+    0xFF3FB950, // verde
+    0xFFD29922, // amarelo
+    0xFF58A6FF, // azul
+    0xFFBC8CFF, // magenta
+    0xFF39C5CF, // ciano
+    0xFFB1BAC4, // branco
+    0xFF6E7681, // preto brilhante
+    0xFFFF7B72, // vermelho brilhante
+    0xFF56D364, // verde brilhante
+    0xFFE3B341, // amarelo brilhante
+    0xFF79C0FF, // azul brilhante
+    0xFFD2A8FF, // magenta brilhante
+    0xFF56D4DD, // ciano brilhante
+    0xFFFFFFFF, // branco brilhante
+];
+
+/// Converte índice da paleta xterm de 256 cores para ARGB
+pub const fn xterm_256(index: u8) -> u32 {
+    match index {
+        0..=15 => ANSI_PALETTE[index as usize],
+        16..=231 => {
+            // Cubo 6x6x6
+            let i = index - 16;
+            let levels = [0x00, 0x5F, 0x87, 0xAF, 0xD7, 0xFF];
+            let r = levels[(i / 36) as usize] as u32;
+            let g = levels[((i / 6) % 6) as usize] as u32;
+            let b = levels[(i % 6) as usize] as u32;
+            0xFF000000 | (r << 16) | (g << 8) | b
+        }
+        _ => {
+            // Escala de cinza
+            let v = 8 + (index - 232) as u32 * 10;
+            0xFF000000 | (v << 16) | (v << 8) | v
+        }
+    }
+}
@@ -33,6 +33,8 @@ impl TextRenderer {
         }
     }
 
+    // TODO: Revisar no futuro
+    #[allow(unused)]
     /// Desenha um caractere na posição especificada
     pub fn draw_char(&self, window: &mut Window, x: u32, y: u32, c: char) {
         let glyph = self.font.get_glyph(c);
@@ -55,6 +57,8 @@ impl TextRenderer {
         }
     }
 
+    // TODO: Revisar no futuro
+    #[allow(unused)]
     /// Desenha uma string na posição especificada
     pub fn draw_string(&self, window: &mut Window, x: u32, y: u32, text: &str) {
         let mut cursor_x = x;
@@ -92,7 +96,15 @@ impl TextRenderer {
     }
 
     /// Desenha caractere com cores específicas
-    fn draw_char_colored(&self, window: &mut Window, x: u32, y: u32, c: char, fg: u32, bg: u32) {
+    pub fn draw_char_colored(
+        &self,
+        window: &mut Window,
+        x: u32,
+        y: u32,
+        c: char,
+        fg: u32,
+        bg: u32,
+    ) {
         let glyph = self.font.get_glyph(c);
 
         for (row, &byte) in glyph.iter().enumerate() {
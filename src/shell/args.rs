@@ -0,0 +1,427 @@
+//! # Argument Parsing
+//!
+//! Parser de opções compartilhado pelos comandos internos.
+//!
+//! Cada comando declara suas opções com [`OptSpec`]; o parser trata as
+//! formas abaixo e produz os mesmos erros para todos os comandos.
+//!
+//! | Forma             | Significado                                  |
+//! |-------------------|----------------------------------------------|
+//! | `-a -l`, `-al`    | Flags curtas, separadas ou combinadas        |
+//! | `-d 2`, `-d2`     | Opção curta com valor                        |
+//! | `--json`          | Flag longa                                   |
+//! | `--depth 2`       | Opção longa com valor                        |
+//! | `--depth=2`       | Opção longa com valor no mesmo argumento     |
+//! | `--`              | Fim das opções; o resto é posicional         |
+//! | `-`               | Posicional (ex.: `cd -`)                     |
+
+use super::ShellContext;
+use crate::state::terminal::TerminalState;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Tipo do valor de uma opção, validado durante o parse
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueKind {
+    /// Texto livre
+    Text,
+    /// Inteiro sem sinal
+    Number,
+}
+
+/// Opção aceita por um comando
+#[derive(Debug, Clone, Copy)]
+pub struct OptSpec {
+    /// Forma curta (`-l`)
+    pub short: Option<char>,
+    /// Forma longa (`--long`)
+    pub long: Option<&'static str>,
+    /// Nome do valor, se a opção recebe um (`-d <n>`)
+    pub value: Option<&'static str>,
+    /// Tipo do valor
+    pub kind: ValueKind,
+    /// Descrição exibida na ajuda
+    pub help: &'static str,
+}
+
+impl OptSpec {
+    /// Opção sem valor
+    pub const fn flag(short: Option<char>, long: Option<&'static str>, help: &'static str) -> Self {
+        Self {
+            short,
+            long,
+            value: None,
+            kind: ValueKind::Text,
+            help,
+        }
+    }
+
+    /// Opção com valor de texto
+    pub const fn value(
+        short: Option<char>,
+        long: Option<&'static str>,
+        value: &'static str,
+        help: &'static str,
+    ) -> Self {
+        Self {
+            short,
+            long,
+            value: Some(value),
+            kind: ValueKind::Text,
+            help,
+        }
+    }
+
+    /// Opção com valor numérico
+    pub const fn number(
+        short: Option<char>,
+        long: Option<&'static str>,
+        value: &'static str,
+        help: &'static str,
+    ) -> Self {
+        Self {
+            short,
+            long,
+            value: Some(value),
+            kind: ValueKind::Number,
+            help,
+        }
+    }
+
+    /// Forma exibida na ajuda: `-d, --depth <n>`
+    pub fn usage(&self) -> String {
+        let mut s = String::new();
+        if let Some(c) = self.short {
+            s.push('-');
+            s.push(c);
+        }
+        if let Some(long) = self.long {
+            if !s.is_empty() {
+                s.push_str(", ");
+            }
+            s.push_str("--");
+            s.push_str(long);
+        }
+        if let Some(value) = self.value {
+            s.push_str(" <");
+            s.push_str(value);
+            s.push('>');
+        }
+        s
+    }
+
+    /// Nome usado nas mensagens de erro (`-d` ou `--depth`)
+    fn display(&self) -> String {
+        match (self.short, self.long) {
+            (Some(c), _) => format!("-{}", c),
+            (None, Some(long)) => format!("--{}", long),
+            (None, None) => String::new(),
+        }
+    }
+
+    /// A opção atende por `name` (`"d"` ou `"depth"`)
+    fn is(&self, name: &str) -> bool {
+        self.long == Some(name) || {
+            let mut chars = name.chars();
+            matches!((chars.next(), chars.next()), (Some(c), None) if self.short == Some(c))
+        }
+    }
+}
+
+/// Erro de parse de argumentos
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArgError {
+    /// Opção não declarada
+    UnknownOption(String),
+    /// Opção que exige valor veio sem ele
+    MissingValue(String),
+    /// Flag recebeu valor (`--json=x`)
+    UnexpectedValue(String),
+    /// Valor não é do tipo esperado
+    InvalidValue { option: String, value: String },
+}
+
+impl ArgError {
+    /// Mensagem para o usuário
+    pub fn message(&self) -> String {
+        match self {
+            ArgError::UnknownOption(opt) => format!("opcao desconhecida: {}", opt),
+            ArgError::MissingValue(opt) => format!("opcao requer um valor: {}", opt),
+            ArgError::UnexpectedValue(opt) => format!("opcao nao aceita valor: {}", opt),
+            ArgError::InvalidValue { option, value } => {
+                format!("valor invalido para {}: '{}'", option, value)
+            }
+        }
+    }
+}
+
+/// Resultado do parse
+pub struct Args<'a> {
+    specs: &'a [OptSpec],
+    /// Opções encontradas: (índice em `specs`, valor)
+    found: Vec<(usize, Option<&'a str>)>,
+    /// Argumentos que não são opções, na ordem
+    pub positional: Vec<&'a str>,
+}
+
+impl<'a> Args<'a> {
+    /// Sem parse: todos os argumentos são posicionais
+    pub fn raw(args: &[&'a str]) -> Self {
+        Self {
+            specs: &[],
+            found: Vec::new(),
+            positional: args.to_vec(),
+        }
+    }
+
+    /// Quantas vezes a opção apareceu
+    pub fn count(&self, name: &str) -> usize {
+        self.found
+            .iter()
+            .filter(|(i, _)| self.specs[*i].is(name))
+            .count()
+    }
+
+    /// A flag foi passada
+    pub fn flag(&self, name: &str) -> bool {
+        self.count(name) > 0
+    }
+
+    /// Valor da opção (a última ocorrência vence)
+    pub fn value(&self, name: &str) -> Option<&'a str> {
+        self.found
+            .iter()
+            .rev()
+            .find(|(i, _)| self.specs[*i].is(name))
+            .and_then(|(_, v)| *v)
+    }
+
+    /// Valor numérico da opção (já validado no parse)
+    pub fn number(&self, name: &str) -> Option<u64> {
+        self.value(name).and_then(|v| v.parse().ok())
+    }
+
+    /// Registra uma opção, validando o tipo do valor
+    fn push(&mut self, index: usize, value: Option<&'a str>) -> Result<(), ArgError> {
+        let spec = &self.specs[index];
+        if let (ValueKind::Number, Some(value)) = (spec.kind, value) {
+            if value.parse::<u64>().is_err() {
+                return Err(ArgError::InvalidValue {
+                    option: spec.display(),
+                    value: String::from(value),
+                });
+            }
+        }
+        self.found.push((index, value));
+        Ok(())
+    }
+}
+
+/// Separa opções e argumentos posicionais segundo `specs`
+pub fn parse<'a>(specs: &'a [OptSpec], args: &[&'a str]) -> Result<Args<'a>, ArgError> {
+    let mut parsed = Args {
+        specs,
+        found: Vec::new(),
+        positional: Vec::new(),
+    };
+
+    let mut i = 0;
+    while i < args.len() {
+        let arg = args[i];
+        i += 1;
+
+        if arg == "--" {
+            parsed.positional.extend_from_slice(&args[i..]);
+            break;
+        }
+
+        if let Some(long) = arg.strip_prefix("--") {
+            let (name, inline) = match long.split_once('=') {
+                Some((name, value)) => (name, Some(value)),
+                None => (long, None),
+            };
+            let index = specs
+                .iter()
+                .position(|s| s.long == Some(name))
+                .ok_or_else(|| ArgError::UnknownOption(format!("--{}", name)))?;
+            let spec = &specs[index];
+
+            let value = match (spec.value, inline) {
+                (None, None) => None,
+                (None, Some(_)) => return Err(ArgError::UnexpectedValue(format!("--{}", name))),
+                (Some(_), Some(value)) => Some(value),
+                (Some(_), None) => {
+                    let value = args
+                        .get(i)
+                        .ok_or_else(|| ArgError::MissingValue(format!("--{}", name)))?;
+                    i += 1;
+                    Some(*value)
+                }
+            };
+            parsed.push(index, value)?;
+            continue;
+        }
+
+        let shorts = match arg.strip_prefix('-') {
+            Some(shorts) if !shorts.is_empty() => shorts,
+            // Sem '-', ou '-' sozinho
+            _ => {
+                parsed.positional.push(arg);
+                continue;
+            }
+        };
+
+        // Flags combinadas; uma opção com valor consome o resto (`-d2`)
+        for (pos, c) in shorts.char_indices() {
+            let index = specs
+                .iter()
+                .position(|s| s.short == Some(c))
+                .ok_or_else(|| ArgError::UnknownOption(format!("-{}", c)))?;
+
+            if specs[index].value.is_none() {
+                parsed.push(index, None)?;
+                continue;
+            }
+
+            let rest = &shorts[pos + c.len_utf8()..];
+            let value = if !rest.is_empty() {
+                rest
+            } else {
+                let value = args
+                    .get(i)
+                    .ok_or_else(|| ArgError::MissingValue(format!("-{}", c)))?;
+                i += 1;
+                value
+            };
+            parsed.push(index, Some(value))?;
+            break;
+        }
+    }
+
+    Ok(parsed)
+}
+
+/// Escreve um erro de uso no formato comum a todos os comandos.
+///
+/// ```text
+/// tree: opcao desconhecida: -x
+/// Uso: tree [caminho]... [opcoes]
+/// Digite 'help tree' para mais informacoes.
+/// ```
+pub fn report_error(
+    output: &mut TerminalState,
+    ctx: &mut ShellContext,
+    cmd: &str,
+    synopsis: &str,
+    message: &str,
+) {
+    output.write_line(&format!("{}: {}", cmd, message));
+    output.write_line(&format!("Uso: {}", synopsis));
+    output.write_line(&format!("Digite 'help {}' para mais informacoes.", cmd));
+    ctx.last_exit_code = EXIT_USAGE;
+}
+
+/// Código de saída para erro de uso
+pub const EXIT_USAGE: i32 = 2;
+
+// =============================================================================
+// TESTES
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, ArgError, Args, OptSpec};
+    use crate::shell::testing::{run, shell};
+    use crate::shell::vfs::MemoryFs;
+
+    const SPECS: &[OptSpec] = &[
+        OptSpec::flag(Some('a'), Some("all"), ""),
+        OptSpec::flag(Some('v'), None, ""),
+        OptSpec::flag(None, Some("json"), ""),
+        OptSpec::number(Some('d'), Some("depth"), "n", ""),
+        OptSpec::value(Some('o'), None, "arquivo", ""),
+    ];
+
+    fn ok<'a>(args: &[&'a str]) -> Args<'a> {
+        parse(SPECS, args).unwrap()
+    }
+
+    fn err(args: &[&str]) -> ArgError {
+        parse(SPECS, args).err().unwrap()
+    }
+
+    #[test]
+    fn short_long_and_combined_forms() {
+        let args = ok(&["-av", "x", "--json", "-d2", "--depth", "3", "-", "y"]);
+        assert!(args.flag("a") && args.flag("all") && args.flag("v") && args.flag("json"));
+        assert_eq!(args.positional, ["x", "-", "y"]);
+        // A última ocorrência vence
+        assert_eq!(args.number("depth"), Some(3));
+
+        let args = ok(&["--depth=4", "-vvo", "saida", "-v"]);
+        assert_eq!(args.number("d"), Some(4));
+        assert_eq!(args.value("o"), Some("saida"));
+        assert_eq!(args.count("v"), 3);
+        assert!(!args.flag("a"));
+    }
+
+    #[test]
+    fn value_can_be_attached_or_look_like_an_option() {
+        let args = ok(&["-ao-x", "-o", "--json"]);
+        assert!(args.flag("a"));
+        assert_eq!(args.value("o"), Some("--json"));
+        assert!(!args.flag("json"));
+    }
+
+    #[test]
+    fn double_dash_ends_options() {
+        let args = ok(&["-a", "--", "-v", "--json"]);
+        assert!(args.flag("a") && !args.flag("v"));
+        assert_eq!(args.positional, ["-v", "--json"]);
+    }
+
+    #[test]
+    fn errors_name_the_option() {
+        assert_eq!(err(&["-x"]), ArgError::UnknownOption("-x".into()));
+        assert_eq!(err(&["-ax"]), ArgError::UnknownOption("-x".into()));
+        assert_eq!(err(&["--nada"]), ArgError::UnknownOption("--nada".into()));
+        assert_eq!(err(&["-d"]), ArgError::MissingValue("-d".into()));
+        assert_eq!(err(&["--depth"]), ArgError::MissingValue("--depth".into()));
+        assert_eq!(
+            err(&["--json=1"]),
+            ArgError::UnexpectedValue("--json".into())
+        );
+        assert_eq!(
+            err(&["--depth=-1"]),
+            ArgError::InvalidValue {
+                option: "-d".into(),
+                value: "-1".into()
+            }
+        );
+        assert_eq!(err(&["-dx"]).message(), "valor invalido para -d: 'x'");
+    }
+
+    #[test]
+    fn usage_and_raw_arguments() {
+        assert_eq!(SPECS[0].usage(), "-a, --all");
+        assert_eq!(SPECS[2].usage(), "--json");
+        assert_eq!(SPECS[3].usage(), "-d, --depth <n>");
+
+        let args = Args::raw(&["-a", "--json"]);
+        assert!(!args.flag("a"));
+        assert_eq!(args.positional, ["-a", "--json"]);
+    }
+
+    #[test]
+    fn commands_report_errors_the_same_way() {
+        let mut ctx = shell(MemoryFs::new());
+        assert_eq!(
+            run(&mut ctx, "tree -x"),
+            "tree: opcao desconhecida: -x\n\
+             Uso: tree [caminho]... [opcoes]\n\
+             Digite 'help tree' para mais informacoes.\n"
+        );
+        assert_eq!(ctx.last_exit_code, 2);
+    }
+}
@@ -0,0 +1,446 @@
+//! # Arithmetic Expansion
+//!
+//! Avaliador de expressões inteiras usado por `$((...))`.
+//!
+//! Suporta os operadores do shell POSIX com a precedência usual do C:
+//!
+//! | Precedência | Operadores                         |
+//! |-------------|------------------------------------|
+//! | maior       | `+ - ! ~` (unários)                |
+//! |             | `**`                               |
+//! |             | `* / %`                            |
+//! |             | `+ -`                              |
+//! |             | `<< >>`                            |
+//! |             | `< <= > >=`                        |
+//! |             | `== !=`                            |
+//! |             | `&`, `^`, `|`                      |
+//! |             | `&&`, `||`                         |
+//! |             | `?:`                               |
+//! | menor       | `= += -= *= /= %= <<= >>= &= ^= |=` |
+//!
+//! Variáveis sem valor numérico valem 0.
+
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// Erro de avaliação aritmética
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArithError {
+    /// Expressão mal formada
+    Syntax,
+    /// Divisão ou resto por zero
+    DivisionByZero,
+    /// Expoente negativo em `**`
+    NegativeExponent,
+    /// Lado esquerdo de atribuição não é variável
+    InvalidAssignment,
+}
+
+impl ArithError {
+    /// Mensagem do erro
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ArithError::Syntax => "erro de sintaxe na expressao",
+            ArithError::DivisionByZero => "divisao por zero",
+            ArithError::NegativeExponent => "expoente negativo",
+            ArithError::InvalidAssignment => "atribuicao invalida",
+        }
+    }
+}
+
+/// Avalia uma expressão, lendo e gravando variáveis em `vars`
+pub fn eval(expr: &str, vars: &mut BTreeMap<String, String>) -> Result<i64, ArithError> {
+    let toks = tokenize(expr)?;
+    if toks.is_empty() {
+        return Ok(0);
+    }
+
+    let mut parser = Parser { toks, pos: 0, vars };
+    let value = parser.assign(true)?;
+    if parser.pos != parser.toks.len() {
+        return Err(ArithError::Syntax);
+    }
+    Ok(value)
+}
+
+// =============================================================================
+// TOKENIZER
+// =============================================================================
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Tok {
+    Num(i64),
+    Ident(String),
+    Op(&'static str),
+}
+
+/// Operadores, do mais longo para o mais curto
+const OPERATORS: &[&str] = &[
+    "<<=", ">>=", "**", "<<", ">>", "<=", ">=", "==", "!=", "&&", "||", "+=", "-=", "*=", "/=",
+    "%=", "&=", "^=", "|=", "+", "-", "*", "/", "%", "<", ">", "=", "!", "~", "&", "^", "|", "?",
+    ":", "(", ")",
+];
+
+fn tokenize(expr: &str) -> Result<Vec<Tok>, ArithError> {
+    let bytes = expr.as_bytes();
+    let mut toks = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let c = bytes[i];
+
+        if c.is_ascii_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        if c.is_ascii_digit() {
+            let start = i;
+            while i < bytes.len() && bytes[i].is_ascii_alphanumeric() {
+                i += 1;
+            }
+            toks.push(Tok::Num(parse_number(&expr[start..i])?));
+            continue;
+        }
+
+        // `$nome` é aceito como sinônimo de `nome`
+        if c == b'$' || c.is_ascii_alphabetic() || c == b'_' {
+            if c == b'$' {
+                i += 1;
+            }
+            let start = i;
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                i += 1;
+            }
+            if start == i {
+                return Err(ArithError::Syntax);
+            }
+            toks.push(Tok::Ident(expr[start..i].to_string()));
+            continue;
+        }
+
+        match OPERATORS.iter().find(|op| expr[i..].starts_with(**op)) {
+            Some(op) => {
+                toks.push(Tok::Op(op));
+                i += op.len();
+            }
+            None => return Err(ArithError::Syntax),
+        }
+    }
+
+    Ok(toks)
+}
+
+/// Converte literal numérico (decimal, `0x` hexadecimal ou `0` octal)
+fn parse_number(s: &str) -> Result<i64, ArithError> {
+    let (digits, radix) = if let Some(hex) = s.strip_prefix("0x").or(s.strip_prefix("0X")) {
+        (hex, 16)
+    } else if s.len() > 1 && s.starts_with('0') {
+        (&s[1..], 8)
+    } else {
+        (s, 10)
+    };
+
+    u64::from_str_radix(digits, radix)
+        .map(|n| n as i64)
+        .map_err(|_| ArithError::Syntax)
+}
+
+// =============================================================================
+// PARSER / AVALIADOR
+// =============================================================================
+
+/// Parser descendente recursivo que avalia enquanto analisa.
+///
+/// O parâmetro `ev` indica se o ramo deve ser avaliado de fato; é falso
+/// nos ramos descartados por `&&`, `||` e `?:`, onde atribuições e
+/// divisões por zero não devem ter efeito.
+struct Parser<'a> {
+    toks: Vec<Tok>,
+    pos: usize,
+    vars: &'a mut BTreeMap<String, String>,
+}
+
+impl Parser<'_> {
+    fn peek_op(&self) -> Option<&'static str> {
+        match self.toks.get(self.pos) {
+            Some(Tok::Op(op)) => Some(op),
+            _ => None,
+        }
+    }
+
+    fn expect(&mut self, op: &str) -> Result<(), ArithError> {
+        if self.peek_op() == Some(op) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(ArithError::Syntax)
+        }
+    }
+
+    /// atribuição := IDENT op= atribuição | ternário
+    fn assign(&mut self, ev: bool) -> Result<i64, ArithError> {
+        if let Some(&Tok::Op(op)) = self.toks.get(self.pos + 1) {
+            let is_assign = op.ends_with('=') && !matches!(op, "==" | "!=" | "<=" | ">=");
+            if is_assign {
+                let name = match &self.toks[self.pos] {
+                    Tok::Ident(name) => name.clone(),
+                    _ => return Err(ArithError::InvalidAssignment),
+                };
+                self.pos += 2;
+
+                let rhs = self.assign(ev)?;
+                let value = if op == "=" {
+                    rhs
+                } else {
+                    let current = self.var(&name);
+                    apply(&op[..op.len() - 1], current, rhs, ev)?
+                };
+
+                if ev {
+                    self.vars.insert(name, value.to_string());
+                }
+                return Ok(value);
+            }
+        }
+
+        self.ternary(ev)
+    }
+
+    /// ternário := binário [ '?' atribuição ':' atribuição ]
+    fn ternary(&mut self, ev: bool) -> Result<i64, ArithError> {
+        let cond = self.binary(1, ev)?;
+        if self.peek_op() != Some("?") {
+            return Ok(cond);
+        }
+
+        self.pos += 1;
+        let a = self.assign(ev && cond != 0)?;
+        self.expect(":")?;
+        let b = self.assign(ev && cond == 0)?;
+        Ok(if cond != 0 { a } else { b })
+    }
+
+    /// Operadores binários por precedence climbing
+    fn binary(&mut self, min_prec: u8, ev: bool) -> Result<i64, ArithError> {
+        let mut lhs = self.unary(ev)?;
+
+        while let Some(op) = self.peek_op() {
+            let prec = match precedence(op) {
+                Some(p) if p >= min_prec => p,
+                _ => break,
+            };
+            self.pos += 1;
+
+            // `**` associa à direita
+            let next_min = if op == "**" { prec } else { prec + 1 };
+            let rhs_ev = match op {
+                "&&" => ev && lhs != 0,
+                "||" => ev && lhs == 0,
+                _ => ev,
+            };
+
+            let rhs = self.binary(next_min, rhs_ev)?;
+            lhs = apply(op, lhs, rhs, ev)?;
+        }
+
+        Ok(lhs)
+    }
+
+    fn unary(&mut self, ev: bool) -> Result<i64, ArithError> {
+        match self.peek_op() {
+            Some("-") => {
+                self.pos += 1;
+                Ok(self.unary(ev)?.wrapping_neg())
+            }
+            Some("+") => {
+                self.pos += 1;
+                self.unary(ev)
+            }
+            Some("!") => {
+                self.pos += 1;
+                Ok((self.unary(ev)? == 0) as i64)
+            }
+            Some("~") => {
+                self.pos += 1;
+                Ok(!self.unary(ev)?)
+            }
+            _ => self.primary(ev),
+        }
+    }
+
+    fn primary(&mut self, ev: bool) -> Result<i64, ArithError> {
+        let tok = self.toks.get(self.pos).cloned().ok_or(ArithError::Syntax)?;
+        self.pos += 1;
+
+        match tok {
+            Tok::Num(n) => Ok(n),
+            Tok::Ident(name) => Ok(self.var(&name)),
+            Tok::Op("(") => {
+                let value = self.assign(ev)?;
+                self.expect(")")?;
+                Ok(value)
+            }
+            Tok::Op(_) => Err(ArithError::Syntax),
+        }
+    }
+
+    /// Valor numérico de uma variável (0 se ausente ou não numérica)
+    fn var(&self, name: &str) -> i64 {
+        self.vars
+            .get(name)
+            .and_then(|v| {
+                let v = v.trim();
+                match v.strip_prefix('-') {
+                    Some(abs) => parse_number(abs).ok().map(i64::wrapping_neg),
+                    None => parse_number(v).ok(),
+                }
+            })
+            .unwrap_or(0)
+    }
+}
+
+/// Precedência de operador binário (maior liga mais forte)
+fn precedence(op: &str) -> Option<u8> {
+    let prec = match op {
+        "||" => 1,
+        "&&" => 2,
+        "|" => 3,
+        "^" => 4,
+        "&" => 5,
+        "==" | "!=" => 6,
+        "<" | "<=" | ">" | ">=" => 7,
+        "<<" | ">>" => 8,
+        "+" | "-" => 9,
+        "*" | "/" | "%" => 10,
+        "**" => 11,
+        _ => return None,
+    };
+    Some(prec)
+}
+
+/// Aplica operador binário
+fn apply(op: &str, a: i64, b: i64, ev: bool) -> Result<i64, ArithError> {
+    let value = match op {
+        "||" => (a != 0 || b != 0) as i64,
+        "&&" => (a != 0 && b != 0) as i64,
+        "|" => a | b,
+        "^" => a ^ b,
+        "&" => a & b,
+        "==" => (a == b) as i64,
+        "!=" => (a != b) as i64,
+        "<" => (a < b) as i64,
+        "<=" => (a <= b) as i64,
+        ">" => (a > b) as i64,
+        ">=" => (a >= b) as i64,
+        "<<" => a.wrapping_shl(b as u32),
+        ">>" => a.wrapping_shr(b as u32),
+        "+" => a.wrapping_add(b),
+        "-" => a.wrapping_sub(b),
+        "*" => a.wrapping_mul(b),
+        "/" | "%" if b == 0 => {
+            if ev {
+                return Err(ArithError::DivisionByZero);
+            }
+            0
+        }
+        "/" => a.wrapping_div(b),
+        "%" => a.wrapping_rem(b),
+        "**" => {
+            if b < 0 {
+                if ev {
+                    return Err(ArithError::NegativeExponent);
+                }
+                0
+            } else {
+                a.wrapping_pow(b.min(u32::MAX as i64) as u32)
+            }
+        }
+        _ => return Err(ArithError::Syntax),
+    };
+    Ok(value)
+}
+
+// =============================================================================
+// TESTES
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{eval, ArithError};
+    use alloc::collections::BTreeMap;
+    use alloc::string::String;
+
+    fn calc(expr: &str) -> Result<i64, ArithError> {
+        eval(expr, &mut BTreeMap::new())
+    }
+
+    #[test]
+    fn precedence_follows_c() {
+        assert_eq!(calc("2+3*4"), Ok(14));
+        assert_eq!(calc("(2+3)*4"), Ok(20));
+        assert_eq!(calc("10-2-3"), Ok(5));
+        assert_eq!(calc("2*3**2"), Ok(18));
+        assert_eq!(calc("2**3**2"), Ok(512));
+        assert_eq!(calc("-2**2"), Ok(4));
+        assert_eq!(calc("1+2<<1"), Ok(6));
+        assert_eq!(calc("1<2==1"), Ok(1));
+        assert_eq!(calc("6&3|8"), Ok(10));
+        assert_eq!(calc("1||0&&0"), Ok(1));
+        assert_eq!(calc("!0+~0"), Ok(0));
+        assert_eq!(calc("1?2:3"), Ok(2));
+        assert_eq!(calc("0?1:0?2:3"), Ok(3));
+        assert_eq!(calc(""), Ok(0));
+    }
+
+    #[test]
+    fn literals_in_other_bases() {
+        assert_eq!(calc("0x1F + 010"), Ok(39));
+        assert_eq!(calc("08"), Err(ArithError::Syntax));
+    }
+
+    #[test]
+    fn division_by_zero_only_where_evaluated() {
+        assert_eq!(calc("1/0"), Err(ArithError::DivisionByZero));
+        assert_eq!(calc("5%0"), Err(ArithError::DivisionByZero));
+        assert_eq!(calc("0&&1/0"), Ok(0));
+        assert_eq!(calc("1||1/0"), Ok(1));
+        assert_eq!(calc("0?1/0:7"), Ok(7));
+        assert_eq!(calc("2**-1"), Err(ArithError::NegativeExponent));
+    }
+
+    #[test]
+    fn overflow_wraps_around() {
+        assert_eq!(calc("9223372036854775807+1"), Ok(i64::MIN));
+        assert_eq!(calc("9223372036854775807*2"), Ok(-2));
+        assert_eq!(calc("(-9223372036854775807-1)/-1"), Ok(i64::MIN));
+        assert_eq!(calc("(-9223372036854775807-1)%-1"), Ok(0));
+        assert_eq!(calc("18446744073709551616"), Err(ArithError::Syntax));
+    }
+
+    #[test]
+    fn variables_are_read_and_assigned() {
+        let mut vars = BTreeMap::new();
+        vars.insert(String::from("x"), String::from("5"));
+        vars.insert(String::from("s"), String::from("abc"));
+
+        assert_eq!(eval("x*2 + $x", &mut vars), Ok(15));
+        assert_eq!(eval("s + nada", &mut vars), Ok(0));
+        assert_eq!(eval("x += 3", &mut vars), Ok(8));
+        assert_eq!(eval("a = b = 4", &mut vars), Ok(4));
+        assert_eq!(eval("0 && (x = 1)", &mut vars), Ok(0));
+        assert_eq!(vars.get("x").map(String::as_str), Some("8"));
+        assert_eq!(vars.get("a").map(String::as_str), Some("4"));
+        assert_eq!(vars.get("b").map(String::as_str), Some("4"));
+    }
+
+    #[test]
+    fn malformed_expressions() {
+        assert_eq!(calc("1+"), Err(ArithError::Syntax));
+        assert_eq!(calc("(1"), Err(ArithError::Syntax));
+        assert_eq!(calc("1 2"), Err(ArithError::Syntax));
+        assert_eq!(calc("1 @ 2"), Err(ArithError::Syntax));
+        assert_eq!(calc("1 = 2"), Err(ArithError::InvalidAssignment));
+    }
+}
@@ -2,107 +2,70 @@
 //!
 //! Comandos internos do shell.
 
+use super::registry::{self, Category};
+use super::CommandResult;
+use crate::shell::pager;
+use crate::shell::ShellContext;
 use crate::state::terminal::TerminalState;
 
-/// help - Mostra ajuda
-pub fn cmd_help(output: &mut TerminalState, args: &[&str]) {
+/// help - Mostra ajuda, gerada a partir do registro de comandos.
+///
+/// Na tela, uma ajuda maior que a janela abre no pager.
+pub fn cmd_help(
+    output: &mut TerminalState,
+    ctx: &mut ShellContext,
+    args: &[&str],
+) -> CommandResult {
     // Se tiver argumento, mostra ajuda específica
-    if !args.is_empty() {
-        show_command_help(output, args[0]);
-        return;
+    if let Some(name) = args.first() {
+        return match ctx.commands.find(name) {
+            Some(command) => pager::page_output(output, "help", |out| {
+                registry::write_help(out, command.as_ref())
+            }),
+            None => {
+                output.write_str("Ajuda nao disponivel para: ");
+                output.write_line(name);
+                ctx.last_exit_code = 1;
+                CommandResult::Ok
+            }
+        };
     }
 
+    pager::page_output(output, "help", |out| write_overview(out, ctx))
+}
+
+/// Lista de todos os comandos, por categoria
+fn write_overview(output: &mut TerminalState, ctx: &ShellContext) {
     output.write_line("");
     output.write_line("=== RedstoneOS Terminal v0.2.0 ===");
     output.write_line("");
-    output.write_line("COMANDOS DE ARQUIVOS:");
-    output.write_line("  ls [path]        Lista arquivos e diretorios");
-    output.write_line("  cd <path>        Muda diretorio atual");
-    output.write_line("  pwd              Mostra diretorio atual");
-    output.write_line("  cat <file>       Mostra conteudo de arquivo");
-    output.write_line("  tree [path]      Mostra arvore de diretorios");
-    output.write_line("  stat <path>      Mostra informacoes de arquivo");
-    output.write_line("  mkdir <path>     Cria diretorio");
-    output.write_line("  rmdir <path>     Remove diretorio vazio");
-    output.write_line("  rm <file>        Remove arquivo");
-    output.write_line("  cp <src> <dst>   Copia arquivo");
-    output.write_line("  mv <src> <dst>   Move/renomeia arquivo");
-    output.write_line("");
-    output.write_line("COMANDOS DE SISTEMA:");
-    output.write_line("  uptime           Tempo desde boot");
-    output.write_line("  ps [--json]      Lista processos");
-    output.write_line("  kill <pid>       Mata processo");
-    output.write_line("  top              Monitor de processos");
-    output.write_line("  jobs             Lista jobs");
-    output.write_line("  sysinfo          Informacoes do sistema");
-    output.write_line("  meminfo          Informacoes de memoria");
-    output.write_line("");
-    output.write_line("OUTROS:");
-    output.write_line("  help [cmd]       Mostra esta ajuda");
-    output.write_line("  clear            Limpa a tela");
-    output.write_line("  exit             Sai do terminal");
-    output.write_line("  echo <text>      Imprime texto");
-    output.write_line("  ver              Versao do sistema");
-    output.write_line("");
-    output.write_line("FLAGS FUTURAS:");
-    output.write_line("  ls --json        Saida em formato JSON");
-    output.write_line("  ps --json        Saida em formato JSON");
-    output.write_line("");
-}
 
-/// Mostra ajuda de um comando específico
-fn show_command_help(output: &mut TerminalState, cmd: &str) {
-    match cmd {
-        "ls" => {
-            output.write_line("ls - Lista arquivos e diretorios");
-            output.write_line("");
-            output.write_line("USO: ls [opcoes] [caminho]");
-            output.write_line("");
-            output.write_line("OPCOES:");
-            output.write_line("  -l         Lista detalhada");
-            output.write_line("  -a         Mostra arquivos ocultos");
-            output.write_line("  --json     Saida em JSON (futuro)");
-            output.write_line("");
-            output.write_line("EXEMPLOS:");
-            output.write_line("  ls");
-            output.write_line("  ls /apps");
-            output.write_line("  ls -l /system");
-        }
-        "cd" => {
-            output.write_line("cd - Muda diretorio atual");
-            output.write_line("");
-            output.write_line("USO: cd <caminho>");
-            output.write_line("");
-            output.write_line("EXEMPLOS:");
-            output.write_line("  cd /apps");
-            output.write_line("  cd ..");
-            output.write_line("  cd /");
-        }
-        "cat" => {
-            output.write_line("cat - Mostra conteudo de arquivo");
-            output.write_line("");
-            output.write_line("USO: cat <arquivo>");
-            output.write_line("");
-            output.write_line("EXEMPLOS:");
-            output.write_line("  cat /apps/config.txt");
+    let width = registry::column_width(
+        ctx.commands.iter().map(|c| c.synopsis()).chain(
+            Category::ALL
+                .iter()
+                .flat_map(|c| c.notes().iter().map(|n| n.0)),
+        ),
+    );
+
+    for category in Category::ALL {
+        let mut commands = ctx
+            .commands
+            .iter()
+            .filter(|c| c.category() == category)
+            .peekable();
+        if commands.peek().is_none() {
+            continue;
         }
-        "tree" => {
-            output.write_line("tree - Mostra arvore de diretorios");
-            output.write_line("");
-            output.write_line("USO: tree [caminho] [opcoes]");
-            output.write_line("");
-            output.write_line("OPCOES:");
-            output.write_line("  -d <n>     Profundidade maxima");
-            output.write_line("");
-            output.write_line("EXEMPLOS:");
-            output.write_line("  tree");
-            output.write_line("  tree /system");
-            output.write_line("  tree / -d 2");
+
+        output.write_line(category.title());
+        for command in commands {
+            registry::write_entry(output, command.synopsis(), command.summary(), width);
         }
-        _ => {
-            output.write_str("Ajuda nao disponivel para: ");
-            output.write_line(cmd);
+        for (usage, text) in category.notes() {
+            registry::write_entry(output, usage, text, width);
         }
+        output.write_line("");
     }
 }
 
@@ -0,0 +1,205 @@
+//! # Data Commands
+//!
+//! Comandos que transformam tabelas num pipeline.
+//!
+//! Todos leem a tabela do estágio anterior (`ctx.stdin`) e a entregam com
+//! [`table::emit`], exceto `to-json` e `to-csv`, que a convertem em texto.
+
+use crate::shell::args::Args;
+use crate::shell::table::{self, Table, Value};
+use crate::shell::ShellContext;
+use crate::state::terminal::TerminalState;
+use alloc::format;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+// =============================================================================
+// where - Filtra linhas
+// =============================================================================
+
+pub fn cmd_where(output: &mut TerminalState, ctx: &mut ShellContext, args: &Args) {
+    let Some(mut table) = input_table(output, ctx, "where") else {
+        return;
+    };
+
+    let &[column, op, value] = args.positional.as_slice() else {
+        fail(output, ctx, "where: uso: where <coluna> <op> <valor>");
+        return;
+    };
+    let Some(index) = column_index(output, ctx, "where", &table, column) else {
+        return;
+    };
+
+    let expected = Value::parse(value);
+    let test: fn(&Value, &Value) -> bool = match op {
+        "==" | "=" => |a, b| a.compare(b) == Ordering::Equal,
+        "!=" => |a, b| a.compare(b) != Ordering::Equal,
+        ">" => |a, b| a.compare(b) == Ordering::Greater,
+        ">=" => |a, b| a.compare(b) != Ordering::Less,
+        "<" => |a, b| a.compare(b) == Ordering::Less,
+        "<=" => |a, b| a.compare(b) != Ordering::Greater,
+        "=~" => |a, b| a.to_text().contains(b.to_text().as_str()),
+        "!~" => |a, b| !a.to_text().contains(b.to_text().as_str()),
+        _ => {
+            fail(
+                output,
+                ctx,
+                &format!(
+                    "where: operador invalido: {} (use == != > >= < <= =~ !~)",
+                    op
+                ),
+            );
+            return;
+        }
+    };
+
+    table.rows.retain(|row| test(&row[index], &expected));
+    table::emit(ctx, output, table);
+}
+
+// =============================================================================
+// sort-by - Ordena por colunas
+// =============================================================================
+
+pub fn cmd_sort_by(output: &mut TerminalState, ctx: &mut ShellContext, args: &Args) {
+    let Some(mut table) = input_table(output, ctx, "sort-by") else {
+        return;
+    };
+
+    if args.positional.is_empty() {
+        fail(output, ctx, "sort-by: falta o nome da coluna");
+        return;
+    }
+
+    let mut keys = Vec::with_capacity(args.positional.len());
+    for column in &args.positional {
+        match column_index(output, ctx, "sort-by", &table, column) {
+            Some(index) => keys.push(index),
+            None => return,
+        }
+    }
+
+    // Ordenação estável: empates mantêm a ordem de entrada
+    table.rows.sort_by(|a, b| {
+        keys.iter()
+            .map(|&k| a[k].compare(&b[k]))
+            .find(|o| *o != Ordering::Equal)
+            .unwrap_or(Ordering::Equal)
+    });
+    if args.flag("r") {
+        table.rows.reverse();
+    }
+    table::emit(ctx, output, table);
+}
+
+// =============================================================================
+// select - Escolhe colunas
+// =============================================================================
+
+pub fn cmd_select(output: &mut TerminalState, ctx: &mut ShellContext, args: &Args) {
+    let Some(table) = input_table(output, ctx, "select") else {
+        return;
+    };
+
+    let mut indices = Vec::with_capacity(args.positional.len());
+    for column in &args.positional {
+        match column_index(output, ctx, "select", &table, column) {
+            Some(index) => indices.push(index),
+            None => return,
+        }
+    }
+
+    let mut selected = Table::new(&args.positional);
+    for row in &table.rows {
+        selected.push(indices.iter().map(|&i| row[i].clone()).collect());
+    }
+    table::emit(ctx, output, selected);
+}
+
+// =============================================================================
+// first - Primeiras linhas
+// =============================================================================
+
+pub fn cmd_first(output: &mut TerminalState, ctx: &mut ShellContext, args: &Args) {
+    let Some(mut table) = input_table(output, ctx, "first") else {
+        return;
+    };
+
+    let count = match args.positional.first() {
+        None => 1,
+        Some(n) => match n.parse::<usize>() {
+            Ok(n) => n,
+            Err(_) => {
+                fail(output, ctx, &format!("first: numero invalido: '{}'", n));
+                return;
+            }
+        },
+    };
+
+    table.rows.truncate(count);
+    table::emit(ctx, output, table);
+}
+
+// =============================================================================
+// to-json / to-csv - Converte em texto
+// =============================================================================
+
+pub fn cmd_to_json(output: &mut TerminalState, ctx: &mut ShellContext) {
+    if let Some(table) = input_table(output, ctx, "to-json") {
+        output.write_line(&table.to_json());
+    }
+}
+
+pub fn cmd_to_csv(output: &mut TerminalState, ctx: &mut ShellContext) {
+    if let Some(table) = input_table(output, ctx, "to-csv") {
+        output.write_str(&table.to_csv());
+    }
+}
+
+// =============================================================================
+// HELPERS
+// =============================================================================
+
+/// Tabela recebida do estágio anterior
+fn input_table(output: &mut TerminalState, ctx: &mut ShellContext, cmd: &str) -> Option<Table> {
+    match ctx.stdin.take() {
+        Some(input) => Some(input.into_table()),
+        None => {
+            fail(
+                output,
+                ctx,
+                &format!("{}: sem entrada; use num pipeline (ex.: ls | {})", cmd, cmd),
+            );
+            None
+        }
+    }
+}
+
+/// Índice da coluna, escrevendo as colunas disponíveis se não existir
+fn column_index(
+    output: &mut TerminalState,
+    ctx: &mut ShellContext,
+    cmd: &str,
+    table: &Table,
+    column: &str,
+) -> Option<usize> {
+    let index = table.column(column);
+    if index.is_none() {
+        fail(
+            output,
+            ctx,
+            &format!(
+                "{}: coluna desconhecida: {} (colunas: {})",
+                cmd,
+                column,
+                table.columns.join(", ")
+            ),
+        );
+    }
+    index
+}
+
+fn fail(output: &mut TerminalState, ctx: &mut ShellContext, message: &str) {
+    output.write_line(message);
+    ctx.last_exit_code = 1;
+}
@@ -0,0 +1,55 @@
+//! # Edit
+//!
+//! `edit <arquivo>`: abre o editor em tela cheia (veja
+//! [`crate::shell::editor`]).
+
+use super::error_to_str;
+use super::CommandResult;
+use crate::shell::args::{self, Args};
+use crate::shell::editor::Editor;
+use crate::shell::path;
+use crate::shell::ShellContext;
+use crate::state::terminal::TerminalState;
+use alloc::boxed::Box;
+
+const EDIT_SYNOPSIS: &str = "edit <arquivo>";
+
+pub fn cmd_edit(output: &mut TerminalState, ctx: &mut ShellContext, args: &Args) -> CommandResult {
+    let file = match args.positional.as_slice() {
+        [file] => *file,
+        [] => {
+            args::report_error(output, ctx, "edit", EDIT_SYNOPSIS, "falta o arquivo");
+            return CommandResult::Ok;
+        }
+        _ => {
+            args::report_error(
+                output,
+                ctx,
+                "edit",
+                EDIT_SYNOPSIS,
+                "apenas um arquivo por vez",
+            );
+            return CommandResult::Ok;
+        }
+    };
+
+    // O editor precisa do teclado e da tela
+    if output.is_capturing() {
+        output.write_line("edit: precisa do terminal; nao funciona em pipelines ou capturas");
+        ctx.last_exit_code = 1;
+        return CommandResult::Ok;
+    }
+
+    let full_path = path::resolve(&ctx.cwd, file);
+    match Editor::open(ctx.fs.as_ref(), &full_path) {
+        Ok(editor) => CommandResult::Screen(Box::new(editor)),
+        Err(e) => {
+            output.write_str("edit: ");
+            output.write_str(&full_path);
+            output.write_str(": ");
+            output.write_line(error_to_str(e));
+            ctx.last_exit_code = 1;
+            CommandResult::Ok
+        }
+    }
+}
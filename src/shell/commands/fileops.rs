@@ -0,0 +1,1453 @@
+//! # File Operations
+//!
+//! Comandos que alteram o sistema de arquivos: `mkdir`, `rmdir`, `rm`, `cp`,
+//! `mv`, `touch`, `truncate` e `write`.
+//!
+//! Todo acesso passa por `ctx.fs` ([`Vfs`]). `rm -r`, `cp` e `mv` rodam
+//! como [`Task`]: uma fila de operações é processada aos poucos, e arquivos
+//! são copiados em blocos de [`CHUNK_SIZE`] bytes, então cópias grandes não
+//! travam a tela e podem ser interrompidas com ^C. Com `-i`, a tarefa pausa
+//! com uma pergunta antes de cada remoção ou sobrescrita. Links simbólicos
+//! dentro de uma árvore são recriados no destino, nunca seguidos.
+//! `truncate` também é uma tarefa, gravando o novo tamanho bloco a bloco.
+
+use super::fs::suggest_path;
+use super::CommandResult;
+use super::{error_to_str, write_error};
+use crate::shell::args::Args;
+use crate::shell::path;
+use crate::shell::task::{Task, TaskStatus};
+use crate::shell::vfs::{write_all, Metadata, Vfs, VfsFile};
+use crate::shell::ShellContext;
+use crate::state::terminal::TerminalState;
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use redpowder::SysError;
+
+/// Bytes copiados por passo da tarefa
+pub const CHUNK_SIZE: usize = 4096;
+
+// =============================================================================
+// mkdir - Cria diretório
+// =============================================================================
+
+pub fn cmd_mkdir(output: &mut TerminalState, ctx: &mut ShellContext, args: &Args) {
+    if args.positional.is_empty() {
+        fail(output, ctx, "mkdir: falta operando");
+        return;
+    }
+
+    let parents = args.flag("p");
+    for arg in &args.positional {
+        let full_path = path::resolve(&ctx.cwd, arg);
+
+        if parents {
+            // Criar cada componente que falta, de cima para baixo
+            let mut path = String::new();
+            for comp in full_path.split('/').filter(|c| !c.is_empty()) {
+                path.push('/');
+                path.push_str(comp);
+                match ctx.fs.stat(&path) {
+                    Ok(meta) if meta.is_dir() => continue,
+                    Ok(_) => {
+                        report(output, ctx, "mkdir", &path, "Nao e um diretorio");
+                        break;
+                    }
+                    Err(_) => {}
+                }
+                if let Err(e) = ctx.fs.mkdir(&path) {
+                    report(output, ctx, "mkdir", &path, error_to_str(e));
+                    break;
+                }
+                verbose(output, args, &format!("mkdir: criado '{}'", path));
+            }
+            continue;
+        }
+
+        if ctx.fs.exists(&full_path) {
+            report(output, ctx, "mkdir", &full_path, "Ja existe");
+            continue;
+        }
+        match ctx.fs.mkdir(&full_path) {
+            Ok(()) => verbose(output, args, &format!("mkdir: criado '{}'", full_path)),
+            Err(SysError::NotFound) => {
+                report(output, ctx, "mkdir", &full_path, "Diretorio pai nao existe");
+                output.write_line("(use -p para criar os diretorios pais)");
+            }
+            Err(e) => report(output, ctx, "mkdir", &full_path, error_to_str(e)),
+        }
+    }
+}
+
+// =============================================================================
+// rmdir - Remove diretório vazio
+// =============================================================================
+
+pub fn cmd_rmdir(output: &mut TerminalState, ctx: &mut ShellContext, args: &Args) {
+    if args.positional.is_empty() {
+        fail(output, ctx, "rmdir: falta operando");
+        return;
+    }
+
+    for arg in &args.positional {
+        let mut path = path::resolve(&ctx.cwd, arg);
+
+        // Com -p, remove também os pais que ficarem vazios
+        loop {
+            if !remove_empty_dir(output, ctx, &path) {
+                break;
+            }
+            verbose(output, args, &format!("rmdir: removido '{}'", path));
+            if !args.flag("p") {
+                break;
+            }
+            match path.rfind('/') {
+                Some(0) | None => break,
+                Some(i) => path.truncate(i),
+            }
+        }
+    }
+}
+
+/// Remove um diretório vazio, reportando o motivo se não puder
+fn remove_empty_dir(output: &mut TerminalState, ctx: &mut ShellContext, path: &str) -> bool {
+    let result = match ctx.fs.stat(path) {
+        Err(e) => Err(error_to_str(e)),
+        Ok(meta) if !meta.is_dir() => Err("Nao e um diretorio"),
+        Ok(_) => match ctx.fs.read_dir(path) {
+            Ok(entries) if !entries.is_empty() => Err("Diretorio nao vazio"),
+            _ => ctx.fs.rmdir(path).map_err(error_to_str),
+        },
+    };
+
+    match result {
+        Ok(()) => true,
+        Err(msg) => {
+            report(output, ctx, "rmdir", path, msg);
+            false
+        }
+    }
+}
+
+// =============================================================================
+// rm - Remove arquivos
+// =============================================================================
+
+pub fn cmd_rm(output: &mut TerminalState, ctx: &mut ShellContext, args: &Args) -> CommandResult {
+    let force = args.flag("f");
+    if args.positional.is_empty() {
+        if !force {
+            fail(output, ctx, "rm: falta operando");
+        }
+        return CommandResult::Ok;
+    }
+
+    let mut task = FileTask::new("rm", args);
+    task.interactive = args.flag("i") && !force;
+
+    for arg in &args.positional {
+        let full_path = path::resolve(&ctx.cwd, arg);
+
+        // Proteções: nunca a raiz, `.` ou `..`
+        let last = arg.trim_end_matches('/').rsplit('/').next().unwrap_or("");
+        if full_path == "/" || last == "." || last == ".." {
+            report(output, ctx, "rm", arg, "recusando remover '/', '.' ou '..'");
+            continue;
+        }
+
+        match ctx.fs.stat(&full_path) {
+            Err(SysError::NotFound) if force => {}
+            Err(e) => {
+                report(output, ctx, "rm", &full_path, error_to_str(e));
+                if matches!(e, SysError::NotFound) {
+                    suggest_path(output, ctx.fs.as_ref(), &full_path);
+                }
+            }
+            Ok(meta) if meta.is_dir() && !recursive(args) => {
+                report(output, ctx, "rm", &full_path, "E um diretorio (use -r)");
+            }
+            Ok(meta) if meta.is_dir() => task.ops.push_back(Op::RemoveTree(full_path)),
+            Ok(_) => task.ops.push_back(Op::Remove {
+                path: full_path,
+                checked: false,
+            }),
+        }
+    }
+
+    task.into_result()
+}
+
+// =============================================================================
+// cp - Copia arquivos
+// =============================================================================
+
+pub fn cmd_cp(output: &mut TerminalState, ctx: &mut ShellContext, args: &Args) -> CommandResult {
+    let Some((sources, dest)) = plan_transfer(output, ctx, "cp", args, true) else {
+        return CommandResult::Ok;
+    };
+
+    let mut task = FileTask::new("cp", args);
+    task.preserve_times = args.flag("p");
+
+    for (src, dst) in sources.into_iter().zip(dest) {
+        // Com -r, um link na linha de comando é copiado como link
+        let meta = if recursive(args) {
+            ctx.fs.stat(&src)
+        } else {
+            ctx.fs.metadata(&src)
+        };
+        match meta {
+            Err(e) => report(output, ctx, "cp", &src, error_to_str(e)),
+            Ok(meta) if meta.is_symlink() => task.ops.push_back(Op::CopyLink {
+                src,
+                dst,
+                checked: false,
+            }),
+            Ok(meta) if meta.is_dir() && !recursive(args) => {
+                report(output, ctx, "cp", &src, "omitindo diretorio (use -r)");
+            }
+            Ok(meta) if meta.is_dir() => {
+                if path::is_inside(&dst, &src) {
+                    report(
+                        output,
+                        ctx,
+                        "cp",
+                        &src,
+                        "nao e possivel copiar um diretorio para dentro dele mesmo",
+                    );
+                    continue;
+                }
+                task.ops.push_back(Op::CopyDir { src, dst });
+            }
+            Ok(_) => task.ops.push_back(Op::CopyFile {
+                src,
+                dst,
+                checked: false,
+            }),
+        }
+    }
+
+    task.into_result()
+}
+
+// =============================================================================
+// mv - Move/renomeia
+// =============================================================================
+
+pub fn cmd_mv(output: &mut TerminalState, ctx: &mut ShellContext, args: &Args) -> CommandResult {
+    let Some((sources, dest)) = plan_transfer(output, ctx, "mv", args, false) else {
+        return CommandResult::Ok;
+    };
+
+    let mut task = FileTask::new("mv", args);
+    task.interactive = args.flag("i") && !args.flag("f");
+    task.preserve_times = true;
+
+    // Origem igual ao destino já foi recusada em `plan_transfer`
+    for (src, dst) in sources.into_iter().zip(dest) {
+        let meta = match ctx.fs.stat(&src) {
+            Ok(meta) => meta,
+            Err(e) => {
+                report(output, ctx, "mv", &src, error_to_str(e));
+                if matches!(e, SysError::NotFound) {
+                    suggest_path(output, ctx.fs.as_ref(), &src);
+                }
+                continue;
+            }
+        };
+        if meta.is_dir() && path::is_inside(&dst, &src) {
+            report(
+                output,
+                ctx,
+                "mv",
+                &src,
+                "nao e possivel mover um diretorio para dentro dele mesmo",
+            );
+            continue;
+        }
+        task.ops.push_back(Op::Rename {
+            src,
+            dst,
+            checked: false,
+        });
+    }
+
+    task.into_result()
+}
+
+/// Origens e destinos de `cp`/`mv`.
+///
+/// Com um diretório como destino (obrigatório para várias origens), cada
+/// origem vai para `destino/nome`. Pares em que origem e destino são o
+/// mesmo arquivo são recusados aqui: abrir o destino para escrita
+/// truncaria a origem. `follow` diz se links no último componente contam
+/// pelo destino (`cp`) ou pela própria entrada (`mv`).
+fn plan_transfer(
+    output: &mut TerminalState,
+    ctx: &mut ShellContext,
+    cmd: &str,
+    args: &Args,
+    follow: bool,
+) -> Option<(Vec<String>, Vec<String>)> {
+    let (dest, sources) = match args.positional.split_last() {
+        Some((dest, sources)) if !sources.is_empty() => (dest, sources),
+        Some((dest, _)) => {
+            fail(
+                output,
+                ctx,
+                &format!("{}: falta o destino depois de '{}'", cmd, dest),
+            );
+            return None;
+        }
+        None => {
+            fail(output, ctx, &format!("{}: falta operando", cmd));
+            return None;
+        }
+    };
+
+    let dest_path = path::resolve(&ctx.cwd, dest);
+    let into_dir = ctx.fs.is_dir(&dest_path);
+    if sources.len() > 1 && !into_dir {
+        report(output, ctx, cmd, &dest_path, "destino nao e um diretorio");
+        return None;
+    }
+
+    let mut planned = (Vec::new(), Vec::new());
+    for src in sources {
+        let src = path::resolve(&ctx.cwd, src);
+        let dst = if into_dir {
+            path::join(&dest_path, path::file_name(&src))
+        } else {
+            dest_path.clone()
+        };
+
+        let fs = ctx.fs.as_ref();
+        if let (Some(a), Some(b)) = (
+            physical_path(fs, &src, follow),
+            physical_path(fs, &dst, follow),
+        ) {
+            if a == b {
+                fail(
+                    output,
+                    ctx,
+                    &format!("{}: '{}' e '{}' sao o mesmo arquivo", cmd, src, dst),
+                );
+                continue;
+            }
+        }
+        planned.0.push(src);
+        planned.1.push(dst);
+    }
+    Some(planned)
+}
+
+/// Caminho sem links de uma entrada que pode ainda não existir.
+///
+/// O diretório pai é sempre canonizado; o último componente só é seguido
+/// com `follow`.
+fn physical_path(fs: &dyn Vfs, path: &str, follow: bool) -> Option<String> {
+    if follow {
+        if let Ok(real) = path::canonicalize(fs, "/", path) {
+            return Some(real);
+        }
+    }
+    let dir = path::canonicalize(fs, "/", path::parent(path)).ok()?;
+    Some(path::join(&dir, path::file_name(path)))
+}
+
+// =============================================================================
+// touch - Cria arquivo ou atualiza a data
+// =============================================================================
+
+pub fn cmd_touch(output: &mut TerminalState, ctx: &mut ShellContext, args: &Args) {
+    if args.positional.is_empty() {
+        fail(output, ctx, "touch: falta operando");
+        return;
+    }
+
+    for arg in &args.positional {
+        let full_path = path::resolve(&ctx.cwd, arg);
+        let result = if ctx.fs.metadata(&full_path).is_ok() {
+            ctx.fs.touch(&full_path)
+        } else if args.flag("c") {
+            continue;
+        } else {
+            ctx.fs.create(&full_path).map(|_| ())
+        };
+
+        if let Err(e) = result {
+            let msg = write_error(ctx.fs.as_ref(), &full_path, e);
+            report(output, ctx, "touch", &full_path, &msg);
+        }
+    }
+}
+
+// =============================================================================
+// truncate - Define o tamanho do arquivo
+// =============================================================================
+
+/// Novo tamanho pedido em `-s`
+#[derive(Debug, Clone, Copy)]
+enum SizeSpec {
+    /// `N`: exatamente N bytes
+    Set(u64),
+    /// `+N`: N bytes a mais
+    Grow(u64),
+    /// `-N`: N bytes a menos
+    Shrink(u64),
+}
+
+impl SizeSpec {
+    /// `[+-]N[KMG]`, com sufixos em potências de 1024
+    fn parse(spec: &str) -> Option<Self> {
+        let (kind, rest): (fn(u64) -> Self, &str) = match spec.as_bytes().first()? {
+            b'+' => (SizeSpec::Grow, &spec[1..]),
+            b'-' => (SizeSpec::Shrink, &spec[1..]),
+            _ => (SizeSpec::Set, spec),
+        };
+
+        let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+        let n: u64 = rest[..digits].parse().ok()?;
+        let unit: u64 = match &rest[digits..] {
+            "" => 1,
+            "K" | "k" => 1 << 10,
+            "M" => 1 << 20,
+            "G" => 1 << 30,
+            _ => return None,
+        };
+        Some(kind(n.checked_mul(unit)?))
+    }
+
+    fn apply(self, size: u64) -> u64 {
+        match self {
+            SizeSpec::Set(n) => n,
+            SizeSpec::Grow(n) => size.saturating_add(n),
+            SizeSpec::Shrink(n) => size.saturating_sub(n),
+        }
+    }
+}
+
+pub fn cmd_truncate(
+    output: &mut TerminalState,
+    ctx: &mut ShellContext,
+    args: &Args,
+) -> CommandResult {
+    let Some(spec) = args.value("s") else {
+        fail(output, ctx, "truncate: falta o tamanho (-s N)");
+        return CommandResult::Ok;
+    };
+    let Some(size) = SizeSpec::parse(spec) else {
+        fail(
+            output,
+            ctx,
+            &format!("truncate: tamanho invalido: '{}'", spec),
+        );
+        return CommandResult::Ok;
+    };
+    if args.positional.is_empty() {
+        fail(output, ctx, "truncate: falta operando");
+        return CommandResult::Ok;
+    }
+
+    CommandResult::Task(Box::new(TruncateTask {
+        files: args
+            .positional
+            .iter()
+            .map(|arg| path::resolve(&ctx.cwd, arg))
+            .collect(),
+        size,
+        create: !args.flag("c"),
+        current: None,
+    }))
+}
+
+/// Arquivo sendo regravado com o novo tamanho
+struct Resizing {
+    path: String,
+    /// Cópia em construção, renomeada sobre `path` no fim; `None` quando o
+    /// conteúdo antigo é descartado e `path` é gravado direto
+    temp: Option<String>,
+    src: Option<Box<dyn VfsFile>>,
+    dst: Box<dyn VfsFile>,
+    /// Bytes do conteúdo antigo ainda a copiar
+    keep: u64,
+    /// Zeros ainda a escrever
+    zeros: u64,
+    /// Permissões a restaurar depois do rename
+    mode: u32,
+}
+
+/// Redimensiona cada arquivo em blocos de [`CHUNK_SIZE`] bytes.
+///
+/// O [`Vfs`] não tem como encurtar ou estender um arquivo aberto, então o
+/// começo que fica é copiado para um temporário ao lado, completado com
+/// zeros e renomeado sobre o original. Interrompida, a tarefa remove o
+/// temporário e o original continua intacto.
+struct TruncateTask {
+    files: VecDeque<String>,
+    size: SizeSpec,
+    /// Criar arquivos que não existem (sem `-c`)
+    create: bool,
+    current: Option<Resizing>,
+}
+
+impl TruncateTask {
+    /// Prepara o próximo arquivo; `None` se não há nada a fazer nele
+    fn start(&self, fs: &mut dyn Vfs, path: String) -> Result<Option<Resizing>, SysError> {
+        let meta = match fs.metadata(&path) {
+            Ok(meta) if meta.is_dir() => return Err(SysError::IsDirectory),
+            Ok(meta) => Some(meta),
+            Err(SysError::NotFound) if !self.create => return Ok(None),
+            Err(SysError::NotFound) => None,
+            Err(e) => return Err(e),
+        };
+        let old_len = meta.map_or(0, |meta| meta.size);
+        let new_len = self.size.apply(old_len);
+        let keep = new_len.min(old_len);
+
+        if keep == 0 {
+            let dst = fs.create(&path)?;
+            return Ok(Some(Resizing {
+                path,
+                temp: None,
+                src: None,
+                dst,
+                keep: 0,
+                zeros: new_len,
+                mode: 0,
+            }));
+        }
+
+        // O rename substituiria um link pelo arquivo: grava no alvo
+        let path = physical_path(fs, &path, true).unwrap_or(path);
+        let temp = path::join(
+            path::parent(&path),
+            &format!(".{}.truncate", path::file_name(&path)),
+        );
+        let src = fs.open(&path)?;
+        let dst = fs.create(&temp)?;
+        Ok(Some(Resizing {
+            path,
+            temp: Some(temp),
+            src: Some(src),
+            dst,
+            keep,
+            zeros: new_len - keep,
+            mode: meta.map_or(0, |meta| meta.mode),
+        }))
+    }
+
+    /// Grava um bloco; `Ok(true)` quando o arquivo está completo
+    fn advance(job: &mut Resizing) -> Result<bool, SysError> {
+        let mut buf = [0u8; CHUNK_SIZE];
+        if job.keep > 0 {
+            let want = job.keep.min(CHUNK_SIZE as u64) as usize;
+            let n = match job.src.as_mut() {
+                Some(src) => src.read(&mut buf[..want])?,
+                None => 0,
+            };
+            if n == 0 {
+                // O arquivo encolheu durante a cópia: completa com zeros
+                job.zeros += job.keep;
+                job.keep = 0;
+            } else {
+                write_all(job.dst.as_mut(), &buf[..n])?;
+                job.keep -= n as u64;
+            }
+            return Ok(false);
+        }
+        if job.zeros > 0 {
+            let n = job.zeros.min(CHUNK_SIZE as u64) as usize;
+            write_all(job.dst.as_mut(), &buf[..n])?;
+            job.zeros -= n as u64;
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// Troca o original pela cópia
+    fn finish(fs: &mut dyn Vfs, job: Resizing) -> Result<(), SysError> {
+        // Fecha os dois arquivos antes do rename
+        let Resizing {
+            path,
+            temp,
+            src,
+            dst,
+            mode,
+            ..
+        } = job;
+        drop((src, dst));
+
+        let Some(temp) = temp else {
+            return Ok(());
+        };
+        if let Err(e) = fs.rename(&temp, &path) {
+            let _ = fs.remove(&temp);
+            return Err(e);
+        }
+        if mode != 0 {
+            fs.set_mode(&path, mode)?;
+        }
+        Ok(())
+    }
+}
+
+impl Task for TruncateTask {
+    fn step(&mut self, ctx: &mut ShellContext, output: &mut TerminalState) -> TaskStatus {
+        let Some(job) = self.current.as_mut() else {
+            let Some(path) = self.files.pop_front() else {
+                return TaskStatus::Done;
+            };
+            match self.start(ctx.fs.as_mut(), path.clone()) {
+                Ok(job) => self.current = job,
+                Err(e) => {
+                    let msg = write_error(ctx.fs.as_ref(), &path, e);
+                    report(output, ctx, "truncate", &path, &msg);
+                }
+            }
+            return TaskStatus::Pending;
+        };
+
+        let result = match Self::advance(job) {
+            Ok(false) => return TaskStatus::Pending,
+            Ok(true) => {
+                let job = self.current.take().unwrap();
+                let path = job.path.clone();
+                Self::finish(ctx.fs.as_mut(), job).map_err(|e| (path, e))
+            }
+            Err(e) => {
+                let path = job.path.clone();
+                self.cancel(ctx);
+                Err((path, e))
+            }
+        };
+        if let Err((path, e)) = result {
+            report(output, ctx, "truncate", &path, error_to_str(e));
+        }
+        TaskStatus::Pending
+    }
+
+    fn cancel(&mut self, ctx: &mut ShellContext) {
+        if let Some(temp) = self.current.take().and_then(|job| job.temp) {
+            let _ = ctx.fs.remove(&temp);
+        }
+    }
+}
+
+// =============================================================================
+// write - Grava o que for digitado
+// =============================================================================
+
+pub fn cmd_write(output: &mut TerminalState, ctx: &mut ShellContext, args: &Args) -> CommandResult {
+    let file = match args.positional.as_slice() {
+        [file] => *file,
+        [] => {
+            fail(output, ctx, "write: falta o arquivo");
+            return CommandResult::Ok;
+        }
+        _ => {
+            fail(output, ctx, "write: apenas um arquivo por vez");
+            return CommandResult::Ok;
+        }
+    };
+
+    let full_path = path::resolve(&ctx.cwd, file);
+    let stdin = ctx.stdin.take();
+    let file = match ctx.fs.create(&full_path) {
+        Ok(file) => file,
+        Err(e) => {
+            let msg = write_error(ctx.fs.as_ref(), &full_path, e);
+            report(output, ctx, "write", &full_path, &msg);
+            return CommandResult::Ok;
+        }
+    };
+
+    // Num pipeline, grava a entrada inteira em vez de ler do teclado
+    let (pending, eof) = match stdin {
+        Some(input) => (Some(input.into_text()), true),
+        None => (None, false),
+    };
+    CommandResult::Task(Box::new(WriteTask {
+        path: full_path,
+        file,
+        pending,
+        eof,
+    }))
+}
+
+/// Grava no arquivo cada linha digitada, até ^D
+struct WriteTask {
+    path: String,
+    file: Box<dyn VfsFile>,
+    /// Texto recebido e ainda não gravado
+    pending: Option<String>,
+    /// A entrada terminou
+    eof: bool,
+}
+
+impl Task for WriteTask {
+    fn step(&mut self, ctx: &mut ShellContext, output: &mut TerminalState) -> TaskStatus {
+        if let Some(text) = self.pending.take() {
+            if let Err(e) = write_all(self.file.as_mut(), text.as_bytes()) {
+                let msg = write_error(ctx.fs.as_ref(), &self.path, e);
+                report(output, ctx, "write", &self.path, &msg);
+                return TaskStatus::Done;
+            }
+        }
+
+        if self.eof {
+            TaskStatus::Done
+        } else {
+            TaskStatus::Input
+        }
+    }
+
+    fn input(&mut self, text: Option<&str>) {
+        match text {
+            Some(text) => self.pending.get_or_insert_with(String::new).push_str(text),
+            None => self.eof = true,
+        }
+    }
+}
+
+// =============================================================================
+// TAREFA
+// =============================================================================
+
+/// Operação pendente
+enum Op {
+    /// Remove um arquivo ou link (`checked`: já confirmado com `-i`)
+    Remove { path: String, checked: bool },
+    /// Enfileira o conteúdo do diretório e depois o próprio diretório
+    RemoveTree(String),
+    /// Remove um diretório já esvaziado
+    RemoveDir { path: String, checked: bool },
+    /// Copia o conteúdo de um arquivo
+    CopyFile {
+        src: String,
+        dst: String,
+        checked: bool,
+    },
+    /// Recria um link simbólico com o mesmo alvo
+    CopyLink {
+        src: String,
+        dst: String,
+        checked: bool,
+    },
+    /// Cria o diretório de destino e enfileira o conteúdo
+    CopyDir { src: String, dst: String },
+    /// Aplica permissões e data ao final da cópia
+    SetMeta { path: String, meta: Metadata },
+    /// Renomeia; entre sistemas de arquivos, copia e remove a origem
+    Rename {
+        src: String,
+        dst: String,
+        checked: bool,
+    },
+}
+
+/// Cópia de arquivo em andamento
+struct Copying {
+    src: Box<dyn VfsFile>,
+    dst: Box<dyn VfsFile>,
+    dst_path: String,
+    meta: Metadata,
+}
+
+/// Fila de operações de `rm`, `cp` e `mv`
+struct FileTask {
+    cmd: &'static str,
+    ops: VecDeque<Op>,
+    copying: Option<Copying>,
+    /// Operação aguardando a resposta a uma pergunta
+    asking: Option<Op>,
+    /// `-i`: perguntar antes de remover ou sobrescrever
+    interactive: bool,
+    /// `-n`: não sobrescrever
+    no_clobber: bool,
+    /// `-v`: descrever cada operação
+    verbose: bool,
+    /// Preservar a data de modificação (`cp -p`, `mv`)
+    preserve_times: bool,
+    /// Alguma operação falhou
+    failed: bool,
+}
+
+impl FileTask {
+    fn new(cmd: &'static str, args: &Args) -> Self {
+        Self {
+            cmd,
+            ops: VecDeque::new(),
+            copying: None,
+            asking: None,
+            interactive: args.flag("i"),
+            no_clobber: args.flag("n"),
+            verbose: args.flag("v"),
+            preserve_times: false,
+            failed: false,
+        }
+    }
+
+    /// Nada a fazer: retorna direto; senão, roda como tarefa
+    fn into_result(self) -> CommandResult {
+        if self.ops.is_empty() {
+            CommandResult::Ok
+        } else {
+            CommandResult::Task(Box::new(self))
+        }
+    }
+
+    fn error(&mut self, output: &mut TerminalState, path: &str, msg: &str) {
+        output.write_line(&format!("{}: {}: {}", self.cmd, path, msg));
+        self.failed = true;
+    }
+
+    /// Erro numa cópia: o resto da fila é descartado, para `mv` não
+    /// remover uma origem que não foi copiada por inteiro
+    fn abort(&mut self, output: &mut TerminalState, path: &str, e: SysError) {
+        self.error(output, path, error_to_str(e));
+        if !self.ops.is_empty() {
+            output.write_line(&format!("{}: operacao interrompida", self.cmd));
+            self.ops.clear();
+        }
+    }
+
+    fn ask(&mut self, output: &mut TerminalState, question: &str, op: Op) -> TaskStatus {
+        output.write_str(&format!("{}: {}? [s/N] ", self.cmd, question));
+        self.asking = Some(op);
+        TaskStatus::Ask
+    }
+
+    fn say(&self, output: &mut TerminalState, line: &str) {
+        if self.verbose {
+            output.write_line(line);
+        }
+    }
+
+    /// Destino existente: pula (`-n`), pergunta (`-i`) ou segue
+    fn check_overwrite(
+        &mut self,
+        fs: &dyn Vfs,
+        output: &mut TerminalState,
+        dst: &str,
+        op: Op,
+    ) -> Option<TaskStatus> {
+        if !fs.exists(dst) {
+            self.ops.push_front(op);
+            return None;
+        }
+        if self.no_clobber {
+            return None;
+        }
+        if self.interactive {
+            return Some(self.ask(output, &format!("sobrescrever '{}'", dst), op));
+        }
+        self.ops.push_front(op);
+        None
+    }
+
+    /// Copia o próximo bloco do arquivo em andamento
+    fn copy_chunk(&mut self, fs: &mut dyn Vfs, output: &mut TerminalState) {
+        let Some(copy) = self.copying.as_mut() else {
+            return;
+        };
+
+        let mut buf = [0u8; CHUNK_SIZE];
+        let result = copy.src.read(&mut buf).and_then(|n| {
+            let mut written = 0;
+            while written < n {
+                match copy.dst.write(&buf[written..n])? {
+                    0 => return Err(SysError::IoError),
+                    w => written += w,
+                }
+            }
+            Ok(n)
+        });
+
+        match result {
+            Ok(0) => {
+                let Some(copy) = self.copying.take() else {
+                    return;
+                };
+                self.apply_meta(fs, output, &copy.dst_path, copy.meta);
+            }
+            Ok(_) => {}
+            Err(e) => {
+                let Some(copy) = self.copying.take() else {
+                    return;
+                };
+                self.abort(output, &copy.dst_path, e);
+            }
+        }
+    }
+
+    /// Permissões sempre; data com `preserve_times`
+    fn apply_meta(
+        &mut self,
+        fs: &mut dyn Vfs,
+        output: &mut TerminalState,
+        path: &str,
+        meta: Metadata,
+    ) {
+        let mut result = Ok(());
+        if meta.mode != 0 {
+            result = fs.set_mode(path, meta.mode);
+        }
+        if self.preserve_times {
+            result = result.and(fs.set_mtime(path, meta.mtime));
+        }
+        // Sistemas sem suporte a metadados não são um erro
+        match result {
+            Ok(()) | Err(SysError::NotImplemented) => {}
+            Err(e) => self.error(output, path, error_to_str(e)),
+        }
+    }
+
+    /// Executa uma operação da fila
+    fn run(&mut self, fs: &mut dyn Vfs, output: &mut TerminalState, op: Op) -> TaskStatus {
+        match op {
+            Op::Remove { path, checked } => {
+                if self.interactive && !checked {
+                    let question = format!("remover '{}'", path);
+                    return self.ask(
+                        output,
+                        &question,
+                        Op::Remove {
+                            path,
+                            checked: true,
+                        },
+                    );
+                }
+                match fs.remove(&path) {
+                    Ok(()) => self.say(output, &format!("removido '{}'", path)),
+                    Err(e) => self.error(output, &path, error_to_str(e)),
+                }
+            }
+            Op::RemoveTree(path) => match fs.read_dir(&path) {
+                Ok(entries) => {
+                    self.ops.push_front(Op::RemoveDir {
+                        path: path.clone(),
+                        checked: false,
+                    });
+                    for entry in entries.iter().rev() {
+                        let child = path::join(&path, &entry.name);
+                        // Links para diretórios são removidos, não seguidos
+                        let is_dir = fs.stat(&child).is_ok_and(|m| m.is_dir());
+                        self.ops.push_front(if is_dir {
+                            Op::RemoveTree(child)
+                        } else {
+                            Op::Remove {
+                                path: child,
+                                checked: false,
+                            }
+                        });
+                    }
+                }
+                Err(e) => self.error(output, &path, error_to_str(e)),
+            },
+            Op::RemoveDir { path, checked } => {
+                if self.interactive && !checked {
+                    let question = format!("remover diretorio '{}'", path);
+                    return self.ask(
+                        output,
+                        &question,
+                        Op::RemoveDir {
+                            path,
+                            checked: true,
+                        },
+                    );
+                }
+                match fs.rmdir(&path) {
+                    Ok(()) => self.say(output, &format!("removido diretorio '{}'", path)),
+                    // `NotEmpty`: algum filho não foi removido (erro ou
+                    // resposta "não")
+                    Err(e) => self.error(output, &path, error_to_str(e)),
+                }
+            }
+            Op::CopyFile { src, dst, checked } => {
+                if !checked {
+                    let op = Op::CopyFile {
+                        src,
+                        dst: dst.clone(),
+                        checked: true,
+                    };
+                    return self
+                        .check_overwrite(fs, output, &dst, op)
+                        .unwrap_or(TaskStatus::Pending);
+                }
+
+                let meta = match fs.stat(&src) {
+                    Ok(meta) => meta,
+                    Err(e) => {
+                        self.abort(output, &src, e);
+                        return TaskStatus::Pending;
+                    }
+                };
+                let opened = fs.open(&src).map_err(|e| (src.as_str(), e)).and_then(|r| {
+                    fs.create(&dst)
+                        .map(|w| (r, w))
+                        .map_err(|e| (dst.as_str(), e))
+                });
+                match opened {
+                    Ok((src_file, dst_file)) => {
+                        self.say(output, &format!("'{}' -> '{}'", src, dst));
+                        self.copying = Some(Copying {
+                            src: src_file,
+                            dst: dst_file,
+                            dst_path: dst,
+                            meta,
+                        });
+                    }
+                    Err((path, e)) => {
+                        let path = String::from(path);
+                        self.abort(output, &path, e);
+                    }
+                }
+            }
+            Op::CopyDir { src, dst } => {
+                let meta = match fs.stat(&src) {
+                    Ok(meta) => meta,
+                    Err(e) => {
+                        self.abort(output, &src, e);
+                        return TaskStatus::Pending;
+                    }
+                };
+                if !fs.is_dir(&dst) {
+                    if let Err(e) = fs.mkdir(&dst) {
+                        self.abort(output, &dst, e);
+                        return TaskStatus::Pending;
+                    }
+                    self.say(output, &format!("'{}' -> '{}'", src, dst));
+                }
+
+                let entries = match fs.read_dir(&src) {
+                    Ok(entries) => entries,
+                    Err(e) => {
+                        self.abort(output, &src, e);
+                        return TaskStatus::Pending;
+                    }
+                };
+                self.ops.push_front(Op::SetMeta {
+                    path: dst.clone(),
+                    meta,
+                });
+                for entry in entries.iter().rev() {
+                    let child_src = path::join(&src, &entry.name);
+                    let child_dst = path::join(&dst, &entry.name);
+                    // `stat` não segue links: um link para diretório não
+                    // vira cópia do diretório (nem do arquivo) apontado
+                    self.ops.push_front(match fs.stat(&child_src) {
+                        Ok(meta) if meta.is_dir() => Op::CopyDir {
+                            src: child_src,
+                            dst: child_dst,
+                        },
+                        Ok(meta) if meta.is_symlink() => Op::CopyLink {
+                            src: child_src,
+                            dst: child_dst,
+                            checked: false,
+                        },
+                        _ => Op::CopyFile {
+                            src: child_src,
+                            dst: child_dst,
+                            checked: false,
+                        },
+                    });
+                }
+            }
+            Op::CopyLink { src, dst, checked } => {
+                if !checked {
+                    let op = Op::CopyLink {
+                        src,
+                        dst: dst.clone(),
+                        checked: true,
+                    };
+                    return self
+                        .check_overwrite(fs, output, &dst, op)
+                        .unwrap_or(TaskStatus::Pending);
+                }
+
+                let target = match fs.read_link(&src) {
+                    Ok(target) => target,
+                    Err(e) => {
+                        self.abort(output, &src, e);
+                        return TaskStatus::Pending;
+                    }
+                };
+                // Um arquivo ou link no destino é substituído
+                if fs.stat(&dst).is_ok_and(|m| !m.is_dir()) {
+                    if let Err(e) = fs.remove(&dst) {
+                        self.abort(output, &dst, e);
+                        return TaskStatus::Pending;
+                    }
+                }
+                match fs.symlink(&target, &dst) {
+                    Ok(()) => self.say(output, &format!("'{}' -> '{}'", src, dst)),
+                    // Sem suporte a links, `cp` segue sem ele; `mv` não pode,
+                    // porque removeria a origem depois
+                    Err(SysError::NotImplemented) if self.cmd == "cp" => {
+                        self.error(output, &src, "omitindo link simbolico (nao suportado)");
+                    }
+                    Err(e) => self.abort(output, &dst, e),
+                }
+            }
+            Op::SetMeta { path, meta } => self.apply_meta(fs, output, &path, meta),
+            Op::Rename { src, dst, checked } => {
+                if !checked {
+                    let op = Op::Rename {
+                        src,
+                        dst: dst.clone(),
+                        checked: true,
+                    };
+                    return self
+                        .check_overwrite(fs, output, &dst, op)
+                        .unwrap_or(TaskStatus::Pending);
+                }
+
+                match fs.rename(&src, &dst) {
+                    Ok(()) => self.say(output, &format!("renomeado '{}' -> '{}'", src, dst)),
+                    // Destino que não pode ser substituído
+                    Err(
+                        e @ (SysError::IsDirectory | SysError::NotDirectory | SysError::NotEmpty),
+                    ) => {
+                        self.error(output, &dst, error_to_str(e));
+                    }
+                    // Sem rename entre sistemas de arquivos: copiar e depois
+                    // remover. Qualquer outro erro é reportado; tratá-lo como
+                    // cópia juntaria as árvores e apagaria a origem.
+                    Err(SysError::NotImplemented) => {
+                        // Um link é movido como link, sem seguir o alvo
+                        let copy = match fs.stat(&src) {
+                            Ok(meta) if meta.is_dir() => Op::CopyDir {
+                                src: src.clone(),
+                                dst,
+                            },
+                            Ok(meta) if meta.is_symlink() => Op::CopyLink {
+                                src: src.clone(),
+                                dst,
+                                checked: true,
+                            },
+                            _ => Op::CopyFile {
+                                src: src.clone(),
+                                dst,
+                                checked: true,
+                            },
+                        };
+                        let remove = if matches!(copy, Op::CopyDir { .. }) {
+                            Op::RemoveTree(src)
+                        } else {
+                            Op::Remove {
+                                path: src,
+                                checked: true,
+                            }
+                        };
+                        self.ops.push_front(remove);
+                        self.ops.push_front(copy);
+                    }
+                    Err(e) => self.error(output, &src, error_to_str(e)),
+                }
+            }
+        }
+        TaskStatus::Pending
+    }
+}
+
+impl Task for FileTask {
+    fn step(&mut self, ctx: &mut ShellContext, output: &mut TerminalState) -> TaskStatus {
+        if self.copying.is_some() {
+            self.copy_chunk(ctx.fs.as_mut(), output);
+            return TaskStatus::Pending;
+        }
+
+        match self.ops.pop_front() {
+            Some(op) => self.run(ctx.fs.as_mut(), output, op),
+            None => {
+                if self.failed {
+                    ctx.last_exit_code = 1;
+                }
+                TaskStatus::Done
+            }
+        }
+    }
+
+    fn answer(&mut self, yes: bool) {
+        if let Some(op) = self.asking.take() {
+            if yes {
+                self.ops.push_front(op);
+            }
+        }
+    }
+}
+
+// =============================================================================
+// HELPERS
+// =============================================================================
+
+/// `-r` ou `-R`
+fn recursive(args: &Args) -> bool {
+    args.flag("r") || args.flag("R")
+}
+
+fn verbose(output: &mut TerminalState, args: &Args, line: &str) {
+    if args.flag("v") {
+        output.write_line(line);
+    }
+}
+
+/// `cmd: caminho: mensagem`, com código de saída 1
+fn report(output: &mut TerminalState, ctx: &mut ShellContext, cmd: &str, path: &str, msg: &str) {
+    output.write_line(&format!("{}: {}: {}", cmd, path, msg));
+    ctx.last_exit_code = 1;
+}
+
+fn fail(output: &mut TerminalState, ctx: &mut ShellContext, message: &str) {
+    output.write_line(message);
+    ctx.last_exit_code = 1;
+}
+
+// =============================================================================
+// TESTES
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::shell::commands::{execute_command, CommandResult};
+    use crate::shell::task::TaskStatus;
+    use crate::shell::testing::{contents, run, shell};
+    use crate::shell::vfs::MemoryFs;
+    use crate::shell::ShellContext;
+    use crate::state::terminal::TerminalState;
+
+    fn data() -> ShellContext {
+        let mut fs = MemoryFs::new();
+        fs.add_file("/data/a.txt", b"alpha\n")
+            .add_file("/data/b.log", b"beta\n")
+            .add_file("/data/sub/c.txt", b"gamma\n");
+        shell(fs)
+    }
+
+    #[test]
+    fn cp_copies_contents() {
+        let mut ctx = data();
+        assert_eq!(run(&mut ctx, "cp /data/a.txt /data/copy.txt"), "");
+        assert_eq!(contents(&ctx, "/data/copy.txt").as_deref(), Some("alpha\n"));
+        assert_eq!(contents(&ctx, "/data/a.txt").as_deref(), Some("alpha\n"));
+    }
+
+    #[test]
+    fn cp_into_directory_keeps_names() {
+        let mut ctx = data();
+        assert_eq!(run(&mut ctx, "cp /data/a.txt /data/b.log /data/sub"), "");
+        assert_eq!(
+            contents(&ctx, "/data/sub/a.txt").as_deref(),
+            Some("alpha\n")
+        );
+        assert_eq!(contents(&ctx, "/data/sub/b.log").as_deref(), Some("beta\n"));
+    }
+
+    #[test]
+    fn cp_onto_itself_is_refused() {
+        let mut ctx = data();
+        assert_eq!(
+            run(&mut ctx, "cp /data/a.txt /data/a.txt"),
+            "cp: '/data/a.txt' e '/data/a.txt' sao o mesmo arquivo\n"
+        );
+        assert_eq!(ctx.last_exit_code, 1);
+        assert_eq!(contents(&ctx, "/data/a.txt").as_deref(), Some("alpha\n"));
+    }
+
+    #[test]
+    fn cp_onto_itself_through_links_is_refused() {
+        let mut ctx = data();
+        run(&mut ctx, "cp /data/b.log /data/sub/../b.log");
+        assert_eq!(ctx.last_exit_code, 1);
+        assert_eq!(contents(&ctx, "/data/b.log").as_deref(), Some("beta\n"));
+
+        let mut fs = MemoryFs::new();
+        fs.add_file("/data/a.txt", b"alpha\n")
+            .add_symlink("/alias", "/data")
+            .add_symlink("/data/la", "a.txt");
+        let mut ctx = shell(fs);
+        run(&mut ctx, "cp /alias/a.txt /data/la");
+        assert_eq!(ctx.last_exit_code, 1);
+        assert_eq!(contents(&ctx, "/data/a.txt").as_deref(), Some("alpha\n"));
+    }
+
+    #[test]
+    fn cp_directory_needs_recursive() {
+        let mut ctx = data();
+        assert_eq!(
+            run(&mut ctx, "cp /data/sub /copy"),
+            "cp: /data/sub: omitindo diretorio (use -r)\n"
+        );
+        assert!(!ctx.fs.exists("/copy"));
+    }
+
+    #[test]
+    fn cp_recursive_copies_tree() {
+        let mut ctx = data();
+        assert_eq!(run(&mut ctx, "cp -r /data /copy"), "");
+        assert_eq!(contents(&ctx, "/copy/a.txt").as_deref(), Some("alpha\n"));
+        assert_eq!(
+            contents(&ctx, "/copy/sub/c.txt").as_deref(),
+            Some("gamma\n")
+        );
+    }
+
+    #[test]
+    fn cp_recursive_recreates_symlinks() {
+        let mut fs = MemoryFs::new();
+        fs.add_file("/data/sub/f", b"1")
+            .add_symlink("/data/sub/loop", "/data/sub")
+            .add_symlink("/data/sub/lf", "f");
+        let mut ctx = shell(fs);
+
+        assert_eq!(run(&mut ctx, "cp -r /data/sub /z"), "");
+        assert_eq!(ctx.last_exit_code, 0);
+        assert!(ctx.fs.stat("/z/loop").unwrap().is_symlink());
+        assert_eq!(ctx.fs.read_link("/z/loop").unwrap(), "/data/sub");
+        assert_eq!(ctx.fs.read_link("/z/lf").unwrap(), "f");
+        assert_eq!(contents(&ctx, "/z/lf").as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn cp_into_itself_is_refused() {
+        let mut ctx = data();
+        assert_eq!(
+            run(&mut ctx, "cp -r /data /data/sub"),
+            "cp: /data: nao e possivel copiar um diretorio para dentro dele mesmo\n"
+        );
+    }
+
+    #[test]
+    fn mv_renames() {
+        let mut ctx = data();
+        assert_eq!(run(&mut ctx, "mv /data/a.txt /data/z.txt"), "");
+        assert!(!ctx.fs.exists("/data/a.txt"));
+        assert_eq!(contents(&ctx, "/data/z.txt").as_deref(), Some("alpha\n"));
+    }
+
+    #[test]
+    fn mv_onto_itself_is_refused() {
+        let mut ctx = data();
+        ctx.cwd = "/data".into();
+        assert_eq!(
+            run(&mut ctx, "mv a.txt a.txt"),
+            "mv: '/data/a.txt' e '/data/a.txt' sao o mesmo arquivo\n"
+        );
+        assert_eq!(ctx.last_exit_code, 1);
+        assert_eq!(contents(&ctx, "/data/a.txt").as_deref(), Some("alpha\n"));
+    }
+
+    #[test]
+    fn mv_onto_non_empty_directory_fails() {
+        let mut fs = MemoryFs::new();
+        fs.add_file("/t/d/x", b"1").add_file("/u/d/y", b"2");
+        let mut ctx = shell(fs);
+
+        assert_eq!(
+            run(&mut ctx, "mv /t/d /u"),
+            "mv: /u/d: Diretorio nao vazio\n"
+        );
+        assert_eq!(ctx.last_exit_code, 1);
+        assert_eq!(contents(&ctx, "/t/d/x").as_deref(), Some("1"));
+        assert!(!ctx.fs.exists("/u/d/x"));
+    }
+
+    #[test]
+    fn mv_directory_into_itself_is_refused() {
+        let mut ctx = data();
+        assert_eq!(
+            run(&mut ctx, "mv /data /data/sub"),
+            "mv: /data: nao e possivel mover um diretorio para dentro dele mesmo\n"
+        );
+        assert!(ctx.fs.exists("/data/sub/c.txt"));
+    }
+
+    #[test]
+    fn rm_removes_files_only() {
+        let mut ctx = data();
+        assert_eq!(run(&mut ctx, "rm /data/a.txt"), "");
+        assert!(!ctx.fs.exists("/data/a.txt"));
+        assert_eq!(
+            run(&mut ctx, "rm /data/sub"),
+            "rm: /data/sub: E um diretorio (use -r)\n"
+        );
+        assert!(ctx.fs.exists("/data/sub"));
+    }
+
+    #[test]
+    fn rm_recursive_does_not_follow_links() {
+        let mut fs = MemoryFs::new();
+        fs.add_file("/keep/k", b"k")
+            .add_file("/tmp/x/f", b"")
+            .add_symlink("/tmp/x/link", "/keep");
+        let mut ctx = shell(fs);
+
+        assert_eq!(run(&mut ctx, "rm -r /tmp/x"), "");
+        assert!(!ctx.fs.exists("/tmp/x"));
+        assert_eq!(contents(&ctx, "/keep/k").as_deref(), Some("k"));
+    }
+
+    #[test]
+    fn rm_refuses_root() {
+        let mut ctx = data();
+        assert_eq!(
+            run(&mut ctx, "rm -rf /"),
+            "rm: /: recusando remover '/', '.' ou '..'\n"
+        );
+        assert!(ctx.fs.exists("/data"));
+    }
+
+    #[test]
+    fn truncate_shrinks_and_grows_in_chunks() {
+        let mut ctx = data();
+        assert_eq!(run(&mut ctx, "truncate -s 3 /data/a.txt"), "");
+        assert_eq!(contents(&ctx, "/data/a.txt").as_deref(), Some("alp"));
+
+        assert_eq!(run(&mut ctx, "truncate -s +10000 /data/a.txt"), "");
+        let grown = ctx.fs.stat("/data/a.txt").unwrap();
+        assert_eq!(grown.size, 10003);
+        assert_eq!(ctx.fs.read_dir("/data").unwrap().len(), 3);
+
+        assert_eq!(run(&mut ctx, "truncate -s 0 /data/b.log /data/new"), "");
+        assert_eq!(contents(&ctx, "/data/b.log").as_deref(), Some(""));
+        assert_eq!(contents(&ctx, "/data/new").as_deref(), Some(""));
+        assert_eq!(run(&mut ctx, "truncate -c -s 5 /data/none"), "");
+        assert!(!ctx.fs.exists("/data/none"));
+    }
+
+    #[test]
+    fn truncate_through_link_resizes_target() {
+        let mut fs = MemoryFs::new();
+        fs.add_file("/data/a.txt", b"alpha\n")
+            .add_symlink("/data/link", "/data/a.txt");
+        let mut ctx = shell(fs);
+
+        assert_eq!(run(&mut ctx, "truncate -s 2 /data/link"), "");
+        assert!(ctx.fs.stat("/data/link").unwrap().is_symlink());
+        assert_eq!(contents(&ctx, "/data/a.txt").as_deref(), Some("al"));
+    }
+
+    #[test]
+    fn interrupted_truncate_keeps_original() {
+        let mut ctx = data();
+        let mut output = TerminalState::new(80, 24);
+        let CommandResult::Task(mut task) =
+            execute_command("truncate -s +1M /data/a.txt", &mut ctx, &mut output)
+        else {
+            panic!("esperava uma tarefa");
+        };
+
+        for _ in 0..3 {
+            assert_eq!(task.step(&mut ctx, &mut output), TaskStatus::Pending);
+        }
+        assert!(ctx.fs.exists("/data/.a.txt.truncate"));
+        task.cancel(&mut ctx);
+
+        assert!(!ctx.fs.exists("/data/.a.txt.truncate"));
+        assert_eq!(contents(&ctx, "/data/a.txt").as_deref(), Some("alpha\n"));
+    }
+}
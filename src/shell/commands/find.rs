@@ -0,0 +1,582 @@
+//! # Find
+//!
+//! `find [caminho...] [expressao]`: percorre as árvores e avalia a
+//! expressão em cada entrada.
+//!
+//! ```text
+//! expressao := ou
+//! ou        := e  ( (-o | -or) e )*
+//! e         := nao ( [-a | -and] nao )*      (justapor também é "e")
+//! nao       := (! | -not) nao | primario
+//! primario  := ( expressao ) | teste | acao
+//! ```
+//!
+//! | Teste / ação        | Verdadeiro quando                              |
+//! |---------------------|------------------------------------------------|
+//! | `-name glob`        | O nome casa com o glob (`-iname` ignora caixa) |
+//! | `-type f\|d\|l`     | Arquivo, diretório ou link                     |
+//! | `-size [+-]N[ckMG]` | Tamanho (padrão em blocos de 512 bytes)        |
+//! | `-newer arquivo`    | Modificado depois do arquivo                   |
+//! | `-print`, `-print0` | Sempre; escreve o caminho (com `\n` / NUL)     |
+//! | `-exec cmd {} ;`    | O comando terminou com código 0                |
+//!
+//! `-maxdepth` e `-mindepth` limitam a profundidade em qualquer posição.
+//! Sem nenhuma ação, a expressão inteira ganha um `-print` no fim.
+//!
+//! A caminhada é uma [`Task`] que visita uma entrada por passo, em
+//! pré-ordem e por ordem de nome. Links não são seguidos. Diretórios que
+//! não podem ser lidos são reportados e pulados.
+
+use super::{dispatch, error_to_str, CommandResult};
+use crate::shell::args::{self, Args};
+use crate::shell::path;
+use crate::shell::task::{self, Task, TaskStatus};
+use crate::shell::vfs::{file_type, Metadata};
+use crate::shell::ShellContext;
+use crate::state::regex::Regex;
+use crate::state::terminal::TerminalState;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+const FIND_SYNOPSIS: &str = "find [caminho...] [expressao]";
+
+pub fn cmd_find(output: &mut TerminalState, ctx: &mut ShellContext, args: &Args) -> CommandResult {
+    let words = &args.positional;
+
+    // Caminhos vêm antes do primeiro termo da expressão
+    let split = words
+        .iter()
+        .position(|w| (w.starts_with('-') && w.len() > 1) || *w == "(" || *w == "!")
+        .unwrap_or(words.len());
+    let (roots, expr) = words.split_at(split);
+
+    let mut parser = ExprParser {
+        words: expr,
+        pos: 0,
+        max_depth: usize::MAX,
+        min_depth: 0,
+        has_action: false,
+    };
+    let parsed = parser.parse(ctx);
+    let expr = match parsed {
+        Ok(expr) => expr,
+        Err(message) => {
+            args::report_error(output, ctx, "find", FIND_SYNOPSIS, &message);
+            return CommandResult::Ok;
+        }
+    };
+    // Sem ação, imprime o que passar na expressão
+    let expr = if parser.has_action {
+        expr
+    } else {
+        Expr::And(Box::new(expr), Box::new(Expr::Print))
+    };
+
+    let roots: Vec<&str> = if roots.is_empty() {
+        Vec::from(["."])
+    } else {
+        roots.to_vec()
+    };
+    let pending: Vec<Pending> = roots
+        .iter()
+        .rev()
+        .map(|root| Pending {
+            display: String::from(*root),
+            full_path: path::resolve(&ctx.cwd, root),
+            depth: 0,
+        })
+        .collect();
+
+    // O find não lê a entrada do pipeline; os comandos de -exec também não
+    ctx.stdin = None;
+
+    CommandResult::Task(Box::new(FindTask {
+        pending,
+        expr,
+        max_depth: parser.max_depth,
+        min_depth: parser.min_depth,
+        failed: false,
+    }))
+}
+
+// =============================================================================
+// EXPRESSÃO
+// =============================================================================
+
+/// Comparação de `-size`
+#[derive(Debug, Clone, Copy)]
+enum SizeCmp {
+    /// `+N`
+    Greater,
+    /// `-N`
+    Less,
+    /// `N`
+    Equal,
+}
+
+enum Expr {
+    /// `-name` / `-iname`
+    Name(Regex),
+    /// `-type`
+    Type(u8),
+    /// `-size`: comparação, N e o tamanho da unidade em bytes
+    Size(SizeCmp, u64, u64),
+    /// `-newer`: mtime de referência
+    Newer(u64),
+    /// `-true` / `-false` (e `-maxdepth`, que vale como verdadeiro)
+    Const(bool),
+    Print,
+    Print0,
+    /// `-exec`: palavras do comando, com `{}` ainda no lugar
+    Exec(Vec<String>),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+struct ExprParser<'a, 'w> {
+    words: &'a [&'w str],
+    pos: usize,
+    max_depth: usize,
+    min_depth: usize,
+    /// Apareceu `-print`, `-print0` ou `-exec`
+    has_action: bool,
+}
+
+impl<'w> ExprParser<'_, 'w> {
+    fn peek(&self) -> Option<&'w str> {
+        self.words.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<&'w str> {
+        let word = self.words.get(self.pos).copied();
+        self.pos += 1;
+        word
+    }
+
+    /// Argumento obrigatório de `option`
+    fn value(&mut self, option: &str) -> Result<&'w str, String> {
+        self.next()
+            .ok_or_else(|| format!("falta argumento para {}", option))
+    }
+
+    fn parse(&mut self, ctx: &ShellContext) -> Result<Expr, String> {
+        if self.peek().is_none() {
+            return Ok(Expr::Const(true));
+        }
+        let expr = self.parse_or(ctx)?;
+        match self.peek() {
+            None => Ok(expr),
+            Some(")") => Err(String::from("')' sem '('")),
+            Some(word) => Err(format!("termo inesperado: {}", word)),
+        }
+    }
+
+    fn parse_or(&mut self, ctx: &ShellContext) -> Result<Expr, String> {
+        let mut left = self.parse_and(ctx)?;
+        while matches!(self.peek(), Some("-o" | "-or")) {
+            self.pos += 1;
+            let right = self.parse_and(ctx)?;
+            left = Expr::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self, ctx: &ShellContext) -> Result<Expr, String> {
+        let mut left = self.parse_not(ctx)?;
+        loop {
+            match self.peek() {
+                None | Some("-o" | "-or" | ")") => break,
+                Some("-a" | "-and") => self.pos += 1,
+                _ => {}
+            }
+            let right = self.parse_not(ctx)?;
+            left = Expr::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_not(&mut self, ctx: &ShellContext) -> Result<Expr, String> {
+        if matches!(self.peek(), Some("!" | "-not")) {
+            self.pos += 1;
+            return Ok(Expr::Not(Box::new(self.parse_not(ctx)?)));
+        }
+        self.parse_primary(ctx)
+    }
+
+    fn parse_primary(&mut self, ctx: &ShellContext) -> Result<Expr, String> {
+        let Some(word) = self.next() else {
+            return Err(String::from("expressao incompleta"));
+        };
+
+        Ok(match word {
+            "(" => {
+                let expr = self.parse_or(ctx)?;
+                if self.next() != Some(")") {
+                    return Err(String::from("falta ')'"));
+                }
+                expr
+            }
+            "-name" | "-iname" => {
+                let pattern = self.value(word)?;
+                let regex = Regex::glob(pattern, word == "-iname")
+                    .map_err(|e| format!("{}: {}", word, e.message()))?;
+                Expr::Name(regex)
+            }
+            "-type" => match self.value(word)? {
+                "f" => Expr::Type(file_type::FILE),
+                "d" => Expr::Type(file_type::DIR),
+                "l" => Expr::Type(file_type::SYMLINK),
+                other => return Err(format!("tipo desconhecido: {} (use f, d ou l)", other)),
+            },
+            "-size" => {
+                let value = self.value(word)?;
+                parse_size(value).ok_or_else(|| format!("tamanho invalido: {}", value))?
+            }
+            "-newer" => {
+                let file = self.value(word)?;
+                let full_path = path::resolve(&ctx.cwd, file);
+                match ctx.fs.metadata(&full_path) {
+                    Ok(meta) => Expr::Newer(meta.mtime),
+                    Err(e) => return Err(format!("{}: {}", full_path, error_to_str(e))),
+                }
+            }
+            "-maxdepth" | "-mindepth" => {
+                let value = self.value(word)?;
+                let n = value
+                    .parse()
+                    .map_err(|_| format!("valor invalido para {}: {}", word, value))?;
+                if word == "-maxdepth" {
+                    self.max_depth = n;
+                } else {
+                    self.min_depth = n;
+                }
+                Expr::Const(true)
+            }
+            "-true" => Expr::Const(true),
+            "-false" => Expr::Const(false),
+            "-print" | "-print0" => {
+                self.has_action = true;
+                if word == "-print" {
+                    Expr::Print
+                } else {
+                    Expr::Print0
+                }
+            }
+            "-exec" => {
+                let mut command = Vec::new();
+                loop {
+                    match self.next() {
+                        Some(";") => break,
+                        Some(word) => command.push(String::from(word)),
+                        None => return Err(String::from("-exec sem ';' no fim")),
+                    }
+                }
+                if command.is_empty() {
+                    return Err(String::from("-exec sem comando"));
+                }
+                self.has_action = true;
+                Expr::Exec(command)
+            }
+            other => return Err(format!("predicado desconhecido: {}", other)),
+        })
+    }
+}
+
+/// `-size [+-]N[bckMG]`; sem sufixo, blocos de 512 bytes
+fn parse_size(value: &str) -> Option<Expr> {
+    let (cmp, rest) = match value.as_bytes().first()? {
+        b'+' => (SizeCmp::Greater, &value[1..]),
+        b'-' => (SizeCmp::Less, &value[1..]),
+        _ => (SizeCmp::Equal, value),
+    };
+    let (digits, unit) = match rest.char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+        Some((i, _)) => rest.split_at(i),
+        None => (rest, "b"),
+    };
+    let unit = match unit {
+        "b" => 512,
+        "c" => 1,
+        "k" => 1024,
+        "M" => 1024 * 1024,
+        "G" => 1024 * 1024 * 1024,
+        _ => return None,
+    };
+    Some(Expr::Size(cmp, digits.parse().ok()?, unit))
+}
+
+// =============================================================================
+// CAMINHADA
+// =============================================================================
+
+/// Entrada ainda não visitada
+struct Pending {
+    /// Caminho como é mostrado (relativo ao que foi digitado)
+    display: String,
+    full_path: String,
+    depth: usize,
+}
+
+/// Entrada sendo avaliada
+struct Visit<'a> {
+    display: &'a str,
+    name: &'a str,
+    meta: Metadata,
+}
+
+struct FindTask {
+    /// Pilha de entradas; a próxima a visitar fica no fim
+    pending: Vec<Pending>,
+    expr: Expr,
+    max_depth: usize,
+    min_depth: usize,
+    /// Alguma entrada não pôde ser lida
+    failed: bool,
+}
+
+impl FindTask {
+    fn warn(&mut self, output: &mut TerminalState, path: &str, msg: &str) {
+        output.write_str("find: ");
+        output.write_str(path);
+        output.write_str(": ");
+        output.write_line(msg);
+        self.failed = true;
+    }
+
+    /// Empilha os filhos do diretório, para saírem em ordem de nome
+    fn push_children(&mut self, ctx: &ShellContext, output: &mut TerminalState, entry: &Pending) {
+        let mut children = match ctx.fs.read_dir(&entry.full_path) {
+            Ok(children) => children,
+            Err(e) => {
+                self.warn(output, &entry.display, error_to_str(e));
+                return;
+            }
+        };
+        children.sort_by(|a, b| b.name.cmp(&a.name));
+
+        for child in children {
+            self.pending.push(Pending {
+                display: path::join(&entry.display, &child.name),
+                full_path: path::join(&entry.full_path, &child.name),
+                depth: entry.depth + 1,
+            });
+        }
+    }
+}
+
+impl Task for FindTask {
+    fn step(&mut self, ctx: &mut ShellContext, output: &mut TerminalState) -> TaskStatus {
+        let Some(entry) = self.pending.pop() else {
+            ctx.last_exit_code = i32::from(self.failed);
+            return TaskStatus::Done;
+        };
+
+        let meta = match ctx.fs.stat(&entry.full_path) {
+            Ok(meta) => meta,
+            Err(e) => {
+                self.warn(output, &entry.display, error_to_str(e));
+                return TaskStatus::Pending;
+            }
+        };
+
+        if entry.depth >= self.min_depth {
+            let name = match path::file_name(&entry.display) {
+                // `/` e `.` são o próprio nome
+                "" => entry.display.as_str(),
+                name => name,
+            };
+            let visit = Visit {
+                display: &entry.display,
+                name,
+                meta,
+            };
+            eval(&self.expr, &visit, ctx, output);
+        }
+
+        if meta.is_dir() && entry.depth < self.max_depth {
+            self.push_children(ctx, output, &entry);
+        }
+        TaskStatus::Pending
+    }
+}
+
+/// Avalia a expressão na entrada, com curto-circuito em `-a` e `-o`
+fn eval(expr: &Expr, visit: &Visit, ctx: &mut ShellContext, output: &mut TerminalState) -> bool {
+    match expr {
+        Expr::Name(regex) => regex.is_match(visit.name),
+        Expr::Type(kind) => visit.meta.file_type == *kind,
+        Expr::Size(cmp, n, unit) => {
+            // Como no GNU find, o tamanho é arredondado para cima na unidade
+            let units = visit.meta.size.div_ceil(*unit);
+            let order = units.cmp(n);
+            match cmp {
+                SizeCmp::Greater => order == Ordering::Greater,
+                SizeCmp::Less => order == Ordering::Less,
+                SizeCmp::Equal => order == Ordering::Equal,
+            }
+        }
+        Expr::Newer(mtime) => visit.meta.mtime > *mtime,
+        Expr::Const(value) => *value,
+        Expr::Print => {
+            output.write_line(visit.display);
+            true
+        }
+        Expr::Print0 => {
+            output.write_str(visit.display);
+            output.write_char('\0');
+            true
+        }
+        Expr::Exec(command) => exec(command, visit.display, ctx, output),
+        Expr::Not(inner) => !eval(inner, visit, ctx, output),
+        Expr::And(a, b) => eval(a, visit, ctx, output) && eval(b, visit, ctx, output),
+        Expr::Or(a, b) => eval(a, visit, ctx, output) || eval(b, visit, ctx, output),
+    }
+}
+
+/// Roda o comando de `-exec` com `{}` trocado pelo caminho.
+///
+/// O comando roda até o fim numa captura, já que o teste depende do seu
+/// código de saída, e a saída é copiada para a do `find`.
+fn exec(
+    command: &[String],
+    display: &str,
+    ctx: &mut ShellContext,
+    output: &mut TerminalState,
+) -> bool {
+    let words: Vec<String> = command.iter().map(|w| w.replace("{}", display)).collect();
+    let args: Vec<&str> = words[1..].iter().map(|w| w.as_str()).collect();
+
+    output.begin_capture();
+    let result = dispatch(&words[0], &args, ctx, output, false);
+    match result {
+        CommandResult::Task(task) => {
+            task::run_to_end(task, ctx, output);
+        }
+        CommandResult::Error(msg) => {
+            output.write_str("Erro: ");
+            output.write_line(&msg);
+        }
+        _ => {}
+    }
+    let text = output.end_capture();
+    output.write_str(&text);
+    ctx.last_exit_code == 0
+}
+
+// =============================================================================
+// TESTES
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::shell::testing::{run, shell};
+    use crate::shell::vfs::MemoryFs;
+    use crate::shell::ShellContext;
+
+    fn tree() -> ShellContext {
+        let mut fs = MemoryFs::new();
+        fs.add_file("/w/a.txt", b"alpha\n")
+            .add_file("/w/big", &[b'x'; 600])
+            .add_file("/w/sub/b.log", b"beta\n")
+            .add_file("/w/sub/c.txt", b"gama\n")
+            .add_symlink("/w/l", "a.txt");
+        shell(fs)
+    }
+
+    #[test]
+    fn walks_in_name_order() {
+        let mut ctx = tree();
+        assert_eq!(
+            run(&mut ctx, "find /w"),
+            "/w\n/w/a.txt\n/w/big\n/w/l\n/w/sub\n/w/sub/b.log\n/w/sub/c.txt\n"
+        );
+        run(&mut ctx, "cd /w/sub");
+        assert_eq!(run(&mut ctx, "find"), ".\n./b.log\n./c.txt\n");
+        assert_eq!(run(&mut ctx, "find -print0"), ".\0./b.log\0./c.txt\0");
+    }
+
+    #[test]
+    fn tests_and_operators() {
+        let mut ctx = tree();
+        assert_eq!(
+            run(&mut ctx, "find /w -name '*.txt' -type f"),
+            "/w/a.txt\n/w/sub/c.txt\n"
+        );
+        assert_eq!(run(&mut ctx, "find /w -type l"), "/w/l\n");
+        assert_eq!(run(&mut ctx, "find /w -size +1"), "/w/big\n");
+        assert_eq!(
+            run(&mut ctx, "find /w -size -6c -type f"),
+            "/w/sub/b.log\n/w/sub/c.txt\n"
+        );
+        assert_eq!(run(&mut ctx, "find /w -maxdepth 1 -type d"), "/w\n/w/sub\n");
+        assert_eq!(
+            run(&mut ctx, "find /w -mindepth 2"),
+            "/w/sub/b.log\n/w/sub/c.txt\n"
+        );
+        assert_eq!(
+            run(
+                &mut ctx,
+                "find /w ( -name 'b*' -o -iname '*.TXT' ) ! -type d"
+            ),
+            "/w/a.txt\n/w/big\n/w/sub/b.log\n/w/sub/c.txt\n"
+        );
+    }
+
+    #[test]
+    fn newer_compares_modification_times() {
+        let mut ctx = tree();
+        assert_eq!(run(&mut ctx, "find /w -newer /w/sub/c.txt -type f"), "");
+        run(&mut ctx, "touch /w/a.txt");
+        assert_eq!(
+            run(&mut ctx, "find /w -newer /w/sub/c.txt -type f"),
+            "/w/a.txt\n"
+        );
+        assert!(run(&mut ctx, "find /w -newer /nada").starts_with("find: /nada: "));
+        assert_eq!(ctx.last_exit_code, 2);
+    }
+
+    #[test]
+    fn exec_runs_per_entry_and_acts_as_a_test() {
+        let mut ctx = tree();
+        assert_eq!(
+            run(&mut ctx, "find /w/sub -type f -exec cat {} ;"),
+            "beta\ngama\n"
+        );
+        // Só o que o grep achou chega ao -print
+        assert_eq!(
+            run(
+                &mut ctx,
+                "find /w -name '*.txt' -exec grep -l alpha {} ; -print"
+            ),
+            "/w/a.txt\n/w/a.txt\n"
+        );
+        assert!(run(&mut ctx, "find /w -exec cat {}").starts_with("find: -exec sem ';' no fim\n"));
+    }
+
+    #[test]
+    fn bad_expressions_are_usage_errors() {
+        let mut ctx = tree();
+        assert_eq!(
+            run(&mut ctx, "find /w -type x"),
+            "find: tipo desconhecido: x (use f, d ou l)\n\
+             Uso: find [caminho...] [expressao]\n\
+             Digite 'help find' para mais informacoes.\n"
+        );
+        assert_eq!(ctx.last_exit_code, 2);
+        assert!(run(&mut ctx, "find /w ( -type f").starts_with("find: falta ')'\n"));
+        assert!(run(&mut ctx, "find /w -frobnicate").starts_with("find: predicado desconhecido"));
+    }
+
+    #[test]
+    fn missing_roots_are_reported_and_skipped() {
+        let mut ctx = tree();
+        assert_eq!(
+            run(&mut ctx, "find /nada /w/sub -type f"),
+            "find: /nada: Nao encontrado\n/w/sub/b.log\n/w/sub/c.txt\n"
+        );
+        assert_eq!(ctx.last_exit_code, 1);
+    }
+}
@@ -2,177 +2,498 @@
 //!
 //! Comandos de sistema de arquivos.
 
+use super::{error_to_str, CommandResult};
+use crate::shell::args::Args;
+use crate::shell::json::JsonWriter;
+use crate::shell::ls_colors::{ColorMode, LsColors};
+use crate::shell::path;
+use crate::shell::suggest;
+use crate::shell::table::{self, Table, Value};
+use crate::shell::task::{Task, TaskStatus};
+use crate::shell::vfs::{ByteReader, Vfs, VfsFile};
 use crate::shell::ShellContext;
+use crate::state::ansi::display_width;
 use crate::state::terminal::TerminalState;
+use crate::state::utf8::Utf8Decoder;
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::format;
 use alloc::string::String;
 use alloc::vec::Vec;
-use redpowder::fs::{chdir, exists, getcwd, is_dir, stat, Dir, File};
+use redpowder::SysError;
 
 // =============================================================================
 // ls - Lista arquivos
 // =============================================================================
 
-pub fn cmd_ls(output: &mut TerminalState, ctx: &mut ShellContext, args: &[&str]) {
-    let mut show_details = false;
-    let mut show_hidden = false;
-    let mut json_output = false;
-    let mut path = ctx.cwd.as_str();
-
-    // Parsear argumentos
-    for arg in args {
-        match *arg {
-            "-l" => show_details = true,
-            "-a" => show_hidden = true,
-            "--json" => json_output = true,
-            "-la" | "-al" => {
-                show_details = true;
-                show_hidden = true;
-            }
-            _ if !arg.starts_with('-') => path = arg,
-            _ => {
-                output.write_str("ls: opcao desconhecida: ");
-                output.write_line(arg);
-                return;
+/// Lista cada operando: os que não são diretórios primeiro, juntos, e
+/// depois o conteúdo de cada diretório, com um cabeçalho `dir:` quando há
+/// mais de um operando. Sem `-l`, um link para diretório é listado como o
+/// diretório.
+pub fn cmd_ls(output: &mut TerminalState, ctx: &mut ShellContext, args: &Args) {
+    let Some(colors) = ls_colors(output, ctx, "ls", args) else {
+        return;
+    };
+    let cwd = ctx.cwd.clone();
+    let operands: Vec<&str> = match args.positional.is_empty() {
+        true => Vec::from([cwd.as_str()]),
+        false => args.positional.clone(),
+    };
+    let several = operands.len() > 1;
+
+    let mut files: Vec<LsEntry> = Vec::new();
+    // Operando como digitado e caminho completo
+    let mut dirs: Vec<(&str, String)> = Vec::new();
+    for operand in operands {
+        let full_path = path::resolve(&ctx.cwd, operand);
+        match ctx.fs.stat(&full_path) {
+            Ok(info) if info.is_dir() => dirs.push((operand, full_path)),
+            Ok(info) if info.is_symlink() && !args.flag("l") && ctx.fs.is_dir(&full_path) => {
+                dirs.push((operand, full_path))
             }
+            Ok(_) => files.push(LsEntry::load(ctx.fs.as_ref(), &full_path, operand, false)),
+            Err(e) => ls_error(output, ctx, &full_path, e),
         }
     }
+    sort_entries(&mut files, args);
+    dirs.sort_by(|a, b| a.0.cmp(b.0));
+    if args.flag("r") {
+        dirs.reverse();
+    }
 
-    // JSON output - futuro
-    if json_output {
-        output.write_line("ls --json: Nao implementado ainda");
+    // JSON e tabelas: uma lista só, com os nomes de cada diretório
+    // prefixados pelo operando quando há mais de um
+    if args.flag("json") || ctx.pipe_table {
+        let mut entries = files;
+        for (operand, full_path) in &dirs {
+            let Some(mut listed) = read_entries(output, ctx, full_path, args) else {
+                continue;
+            };
+            if several {
+                for entry in &mut listed {
+                    entry.name = path::join(operand, &entry.name);
+                }
+            }
+            entries.append(&mut listed);
+        }
+        if args.flag("json") {
+            ls_json(output, &entries);
+        } else {
+            table::emit(ctx, output, ls_table(&entries));
+        }
         return;
     }
 
-    // Resolver path
-    let full_path = resolve_path(&ctx.cwd, path);
+    let colors = colors.as_ref();
+    if !files.is_empty() {
+        write_entries(output, &files, args, colors);
+    }
+    for (i, (operand, full_path)) in dirs.iter().enumerate() {
+        let Some(entries) = read_entries(output, ctx, full_path, args) else {
+            continue;
+        };
+        if i > 0 || !files.is_empty() {
+            output.write_line("");
+        }
+        if several {
+            output.write_str(operand);
+            output.write_line(":");
+        }
+        if entries.is_empty() {
+            output.write_line("(diretorio vazio)");
+        } else {
+            write_entries(output, &entries, args, colors);
+        }
+    }
+}
 
-    // TODO: Revisar no futuro
-    #[allow(unused)]
-    // Abrir diretório
-    match Dir::open(&full_path) {
-        Ok(dir) => {
-            let mut entries: Vec<(String, bool, u64)> = Vec::new();
+/// Lê e ordena as entradas do diretório; em caso de erro, já o reportou
+fn read_entries(
+    output: &mut TerminalState,
+    ctx: &mut ShellContext,
+    full_path: &str,
+    args: &Args,
+) -> Option<Vec<LsEntry>> {
+    let dir = match ctx.fs.read_dir(full_path) {
+        Ok(dir) => dir,
+        Err(e) => {
+            ls_error(output, ctx, full_path, e);
+            return None;
+        }
+    };
 
-            for entry in dir.entries() {
-                let name = entry.name();
+    let mut entries: Vec<LsEntry> = Vec::new();
+    for entry in &dir {
+        // Pular arquivos ocultos (se não -a)
+        if !args.flag("a") && entry.name.starts_with('.') {
+            continue;
+        }
 
-                // Pular . e .. e arquivos ocultos (se não -a)
-                if !show_hidden && name.starts_with('.') {
-                    continue;
-                }
+        let entry_path = path::join(full_path, &entry.name);
+        entries.push(LsEntry::load(
+            ctx.fs.as_ref(),
+            &entry_path,
+            &entry.name,
+            entry.is_dir,
+        ));
+    }
+    sort_entries(&mut entries, args);
+    Some(entries)
+}
 
-                entries.push((
-                    String::from(name),
-                    entry.is_dir(),
-                    0, // tamanho - precisaria de stat individual
-                ));
-            }
+fn ls_error(output: &mut TerminalState, ctx: &mut ShellContext, full_path: &str, e: SysError) {
+    output.write_str("ls: nao foi possivel abrir ");
+    output.write_str(full_path);
+    output.write_str(": ");
+    output.write_line(error_to_str(e));
+    ctx.last_exit_code = 1;
+}
 
-            // Ordenar: diretórios primeiro, depois alfabético
-            entries.sort_by(|a, b| match (a.1, b.1) {
-                (true, false) => core::cmp::Ordering::Less,
-                (false, true) => core::cmp::Ordering::Greater,
-                _ => a.0.cmp(&b.0),
-            });
+/// Escreve as entradas no formato pedido (`-l`, `-1` ou colunas)
+fn write_entries(
+    output: &mut TerminalState,
+    entries: &[LsEntry],
+    args: &Args,
+    colors: Option<&LsColors>,
+) {
+    if args.flag("l") {
+        ls_long(output, entries, args.flag("h"), colors);
+    } else if args.flag("1") {
+        for entry in entries {
+            output.write_line(&entry.display_name(colors));
+        }
+    } else {
+        let names: Vec<String> = entries.iter().map(|e| e.display_name(colors)).collect();
+        write_grid(output, &names, output.cols as usize);
+    }
+}
 
-            if entries.is_empty() {
-                output.write_line("(diretorio vazio)");
-                return;
+/// Entrada do `ls`, já com o `stat`
+struct LsEntry {
+    name: String,
+    /// 1 = arquivo, 2 = diretório, 3 = link simbólico
+    file_type: u8,
+    size: u64,
+    mode: u32,
+    mtime: u64,
+    /// Destino, se for link simbólico
+    target: Option<String>,
+}
+
+impl LsEntry {
+    /// Faz o `stat` de `path`, mostrado como `name`; se falhar, usa o tipo
+    /// da listagem
+    fn load(fs: &dyn Vfs, path: &str, name: &str, is_dir: bool) -> Self {
+        let mut entry = Self {
+            name: String::from(name),
+            file_type: if is_dir { 2 } else { 1 },
+            size: 0,
+            mode: 0,
+            mtime: 0,
+            target: None,
+        };
+
+        if let Ok(info) = fs.stat(path) {
+            entry.file_type = info.file_type;
+            entry.size = info.size;
+            entry.mode = info.mode;
+            entry.mtime = info.mtime;
+        }
+        if entry.file_type == 3 {
+            entry.target = fs.read_link(path).ok();
+        }
+        entry
+    }
+
+    fn is_dir(&self) -> bool {
+        self.file_type == 2
+    }
+
+    /// Nome na listagem curta: colorido pelo tipo ou, sem cores, `[dir]` e
+    /// `link@`
+    fn display_name(&self, colors: Option<&LsColors>) -> String {
+        if let Some(colors) = colors {
+            return colors.paint(&self.name, &self.name, self.file_type, self.mode);
+        }
+
+        let mut s = String::new();
+        match self.file_type {
+            2 => {
+                s.push('[');
+                s.push_str(&self.name);
+                s.push(']');
+            }
+            3 => {
+                s.push_str(&self.name);
+                s.push('@');
             }
+            _ => s.push_str(&self.name),
+        }
+        s
+    }
+}
 
-            if show_details {
-                // Formato detalhado
-                output.write_line("TIPO  TAMANHO  NOME");
-                output.write_line("----  -------  ----");
-                for (name, is_dir, size) in entries {
-                    let type_str = if is_dir { "DIR " } else { "FILE" };
-                    output.write_str(type_str);
-                    output.write_str("  ");
-                    // Tamanho (placeholder)
-                    output.write_str("       -");
-                    output.write_str("  ");
-                    if is_dir {
-                        output.write_str("[");
-                        output.write_str(&name);
-                        output.write_line("]");
-                    } else {
-                        output.write_line(&name);
-                    }
-                }
+/// Ordena segundo `-S` (tamanho), `-t` (data) e `-r`; sem eles, diretórios
+/// primeiro e depois por nome
+fn sort_entries(entries: &mut [LsEntry], args: &Args) {
+    if args.flag("S") {
+        entries.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+    } else if args.flag("t") {
+        entries.sort_by(|a, b| b.mtime.cmp(&a.mtime).then_with(|| a.name.cmp(&b.name)));
+    } else {
+        entries.sort_by(|a, b| {
+            b.is_dir()
+                .cmp(&a.is_dir())
+                .then_with(|| a.name.cmp(&b.name))
+        });
+    }
+
+    if args.flag("r") {
+        entries.reverse();
+    }
+}
+
+/// `ls -l`: `drwxr-xr-x  4096  nome -> destino`
+fn ls_long(
+    output: &mut TerminalState,
+    entries: &[LsEntry],
+    human: bool,
+    colors: Option<&LsColors>,
+) {
+    let sizes: Vec<String> = entries
+        .iter()
+        .map(|e| {
+            if human {
+                human_size(e.size)
             } else {
-                // Formato simples
-                let mut line_len = 0;
-                for (name, is_dir, _) in entries {
-                    let display_name = if is_dir {
-                        let mut s = String::from("[");
-                        s.push_str(&name);
-                        s.push(']');
-                        s
-                    } else {
-                        name.clone()
-                    };
-
-                    // Quebrar linha se necessário
-                    if line_len + display_name.len() + 2 > 70 && line_len > 0 {
-                        output.write_line("");
-                        line_len = 0;
-                    }
+                format!("{}", e.size)
+            }
+        })
+        .collect();
+    let width = sizes.iter().map(|s| s.len()).max().unwrap_or(0);
+
+    for (entry, size) in entries.iter().zip(&sizes) {
+        let mut line = mode_string(entry.file_type, entry.mode);
+        line.push_str("  ");
+        for _ in size.len()..width {
+            line.push(' ');
+        }
+        line.push_str(size);
+        line.push_str("  ");
+        match colors {
+            Some(colors) => {
+                line.push_str(&colors.paint(&entry.name, &entry.name, entry.file_type, entry.mode))
+            }
+            None => line.push_str(&entry.name),
+        }
+        if let Some(target) = &entry.target {
+            line.push_str(" -> ");
+            line.push_str(target);
+        }
+        output.write_line(&line);
+    }
+}
 
-                    if line_len > 0 {
-                        output.write_str("  ");
-                        line_len += 2;
-                    }
+/// Nomes em colunas, de cima para baixo, como o `ls -C` do GNU: usa o
+/// maior número de colunas que cabe em `width`
+fn write_grid(output: &mut TerminalState, names: &[String], width: usize) {
+    const GAP: usize = 2;
+    let widths: Vec<usize> = names.iter().map(|n| display_width(n)).collect();
+
+    let mut layout = (
+        1,
+        names.len(),
+        Vec::from([widths.iter().copied().max().unwrap_or(0)]),
+    );
+    for cols in (2..=names.len()).rev() {
+        let rows = names.len().div_ceil(cols);
+        // Com menos linhas, colunas finais podem ficar vazias
+        if (cols - 1) * rows >= names.len() {
+            continue;
+        }
+        let col_widths: Vec<usize> = widths
+            .chunks(rows)
+            .map(|c| c.iter().copied().max().unwrap_or(0))
+            .collect();
+        let total = col_widths.iter().sum::<usize>() + GAP * (col_widths.len() - 1);
+        if total <= width {
+            layout = (cols, rows, col_widths);
+            break;
+        }
+    }
 
-                    output.write_str(&display_name);
-                    line_len += display_name.len();
+    let (cols, rows, col_widths) = layout;
+    for row in 0..rows {
+        let mut line = String::new();
+        for col in 0..cols {
+            let Some(name) = names.get(col * rows + row) else {
+                break;
+            };
+            if col > 0 {
+                line.push_str("  ");
+            }
+            line.push_str(name);
+            // Não preencher a última coluna da linha
+            if names.get((col + 1) * rows + row).is_some() {
+                for _ in widths[col * rows + row]..col_widths[col] {
+                    line.push(' ');
                 }
-                output.write_line("");
             }
         }
-        Err(e) => {
-            output.write_str("ls: nao foi possivel abrir ");
-            output.write_str(&full_path);
-            output.write_str(": ");
-            output.write_line(error_to_str(e));
+        output.write_line(&line);
+    }
+}
+
+/// `ls --json`: array de `{"name", "type", "size", "mode"}` (e `"target"`
+/// para links simbólicos)
+fn ls_json(output: &mut TerminalState, entries: &[LsEntry]) {
+    let mut json = JsonWriter::new();
+    json.begin_array();
+
+    for entry in entries {
+        json.begin_object();
+        json.field_str("name", &entry.name);
+        json.field_str("type", file_type_name(entry.file_type));
+        json.field_u64("size", entry.size);
+        json.field_u64("mode", entry.mode as u64);
+        if let Some(target) = &entry.target {
+            json.field_str("target", target);
         }
+        json.end_object();
+    }
+
+    json.end_array();
+    output.write_line(&json.finish());
+}
+
+/// `ls | ...`: tabela `name type size mode` para o próximo estágio
+fn ls_table(entries: &[LsEntry]) -> Table {
+    let mut table = Table::new(&["name", "type", "size", "mode"]);
+    for entry in entries {
+        table.push(alloc::vec![
+            Value::from(entry.name.as_str()),
+            Value::from(file_type_name(entry.file_type)),
+            Value::from(entry.size),
+            Value::from(entry.mode as u64),
+        ]);
     }
+    table
 }
 
 // =============================================================================
 // cd - Muda diretório
 // =============================================================================
 
-pub fn cmd_cd(output: &mut TerminalState, ctx: &mut ShellContext, args: &[&str]) {
-    let path = args.get(0).unwrap_or(&"/");
+pub fn cmd_cd(output: &mut TerminalState, ctx: &mut ShellContext, args: &Args) {
+    if args.positional.len() > 1 {
+        output.write_line("cd: argumentos demais");
+        ctx.last_exit_code = 1;
+        return;
+    }
+
+    // `cd -` e diretórios achados pelo CDPATH mostram o destino
+    let (full_path, announce) = match args.positional.first().copied() {
+        None => (path::tilde(ctx, "").unwrap_or_default(), false),
+        Some("-") => match ctx.var("OLDPWD") {
+            Some(old) => (old, true),
+            None => {
+                output.write_line("cd: OLDPWD nao definido");
+                ctx.last_exit_code = 1;
+                return;
+            }
+        },
+        Some(target) => match cdpath_lookup(ctx, target) {
+            Some(found) => (found, true),
+            // -P: `..` sobe a partir do destino dos links já percorridos
+            None if args.flag("P") => {
+                let real = path::canonicalize(ctx.fs.as_ref(), &ctx.cwd, target);
+                (
+                    real.unwrap_or_else(|_| path::resolve(&ctx.cwd, target)),
+                    false,
+                )
+            }
+            None => (path::resolve(&ctx.cwd, target), false),
+        },
+    };
 
-    // Resolver path
-    let full_path = resolve_path(&ctx.cwd, path);
+    if change_dir(output, ctx, "cd", &full_path, args.flag("P")) && announce {
+        output.write_line(&ctx.cwd);
+    }
+}
 
+/// Procura `target` nos diretórios de `CDPATH`.
+///
+/// Só vale para caminhos relativos que não começam com `.` ou `..`; uma
+/// entrada vazia em `CDPATH` é o diretório atual e não conta como achado.
+fn cdpath_lookup(ctx: &ShellContext, target: &str) -> Option<String> {
+    if target.starts_with('/')
+        || target == "."
+        || target == ".."
+        || target.starts_with("./")
+        || target.starts_with("../")
+    {
+        return None;
+    }
+
+    let cdpath = ctx.var("CDPATH")?;
+    cdpath
+        .split(':')
+        .filter(|dir| !dir.is_empty() && *dir != ".")
+        .map(|dir| path::resolve(&path::resolve(&ctx.cwd, dir), target))
+        .find(|candidate| ctx.fs.is_dir(candidate))
+}
+
+/// Muda para `full_path` (já resolvido), reportando erros como `cmd`.
+///
+/// Com `physical`, os links são resolvidos e o `PWD` fica com o caminho
+/// real (`cd -P`).
+fn change_dir(
+    output: &mut TerminalState,
+    ctx: &mut ShellContext,
+    cmd: &str,
+    full_path: &str,
+    physical: bool,
+) -> bool {
     // Verificar se existe e é diretório
-    if !exists(&full_path) {
-        output.write_str("cd: ");
-        output.write_str(&full_path);
+    if !ctx.fs.exists(full_path) {
+        output.write_str(cmd);
+        output.write_str(": ");
+        output.write_str(full_path);
         output.write_line(": Nao existe");
-        return;
+        suggest_path(output, ctx.fs.as_ref(), full_path);
+        ctx.last_exit_code = 1;
+        return false;
     }
 
-    if !is_dir(&full_path) {
-        output.write_str("cd: ");
-        output.write_str(&full_path);
+    if !ctx.fs.is_dir(full_path) {
+        output.write_str(cmd);
+        output.write_str(": ");
+        output.write_str(full_path);
         output.write_line(": Nao e um diretorio");
-        return;
+        ctx.last_exit_code = 1;
+        return false;
     }
 
-    // Mudar diretório via syscall
-    match chdir(&full_path) {
-        Ok(_) => {
-            ctx.set_cwd(&full_path);
+    let target = if physical {
+        path::canonicalize(ctx.fs.as_ref(), "/", full_path)
+    } else {
+        Ok(String::from(full_path))
+    };
+
+    // Mudar diretório do processo
+    match target.and_then(|target| ctx.fs.set_current_dir(&target).map(|_| target)) {
+        Ok(target) => {
+            ctx.set_cwd(&target);
+            true
         }
         Err(e) => {
-            output.write_str("cd: ");
-            output.write_str(&full_path);
+            output.write_str(cmd);
+            output.write_str(": ");
+            output.write_str(full_path);
             output.write_str(": ");
             output.write_line(error_to_str(e));
+            ctx.last_exit_code = 1;
+            false
         }
     }
 }
@@ -181,171 +502,818 @@ pub fn cmd_cd(output: &mut TerminalState, ctx: &mut ShellContext, args: &[&str])
 // pwd - Mostra diretório atual
 // =============================================================================
 
-pub fn cmd_pwd(output: &mut TerminalState, ctx: &ShellContext) {
-    // Tentar via syscall
-    let mut buf = [0u8; 256];
-    match getcwd(&mut buf) {
-        Ok(cwd) => output.write_line(cwd),
+pub fn cmd_pwd(output: &mut TerminalState, ctx: &ShellContext, args: &Args) {
+    // -P: caminho real, sem links
+    if args.flag("P") {
+        match path::canonicalize(ctx.fs.as_ref(), "/", &ctx.cwd) {
+            Ok(real) => output.write_line(&real),
+            Err(_) => output.write_line(&ctx.cwd),
+        }
+        return;
+    }
+
+    // PWD lógico, se ainda existir; senão o do sistema de arquivos
+    if ctx.fs.is_dir(&ctx.cwd) {
+        output.write_line(&ctx.cwd);
+        return;
+    }
+    match ctx.fs.current_dir() {
+        Ok(cwd) => output.write_line(&cwd),
         Err(_) => output.write_line(&ctx.cwd),
     }
 }
 
 // =============================================================================
-// cat - Mostra conteúdo de arquivo
+// pushd / popd / dirs - Pilha de diretórios
 // =============================================================================
 
-pub fn cmd_cat(output: &mut TerminalState, ctx: &mut ShellContext, args: &[&str]) {
-    if args.is_empty() {
-        output.write_line("cat: falta operando arquivo");
-        output.write_line("Uso: cat <arquivo>");
+pub fn cmd_pushd(output: &mut TerminalState, ctx: &mut ShellContext, args: &Args) {
+    match args.positional.first().copied() {
+        // Sem argumento: troca o atual com o topo
+        None => {
+            let Some(top) = ctx.dir_stack.first().cloned() else {
+                output.write_line("pushd: sem outro diretorio");
+                ctx.last_exit_code = 1;
+                return;
+            };
+            let old = ctx.cwd.clone();
+            if !change_dir(output, ctx, "pushd", &top, false) {
+                return;
+            }
+            ctx.dir_stack[0] = old;
+        }
+        // +N: gira a pilha até a entrada N ficar no topo
+        Some(arg) if arg.starts_with('+') => {
+            let Some(n) = stack_index(output, ctx, "pushd", arg) else {
+                return;
+            };
+            let mut all = full_stack(ctx);
+            all.rotate_left(n);
+            if !change_dir(output, ctx, "pushd", &all[0], false) {
+                return;
+            }
+            all.remove(0);
+            ctx.dir_stack = all;
+        }
+        Some(target) => {
+            let full_path = path::resolve(&ctx.cwd, target);
+            let old = ctx.cwd.clone();
+            if !change_dir(output, ctx, "pushd", &full_path, false) {
+                return;
+            }
+            ctx.dir_stack.insert(0, old);
+        }
+    }
+    write_dirs(output, ctx, &DirsStyle::default());
+}
+
+pub fn cmd_popd(output: &mut TerminalState, ctx: &mut ShellContext, args: &Args) {
+    if ctx.dir_stack.is_empty() {
+        output.write_line("popd: pilha de diretorios vazia");
+        ctx.last_exit_code = 1;
         return;
     }
 
-    for arg in args {
-        let full_path = resolve_path(&ctx.cwd, arg);
-
-        match File::open(&full_path) {
-            Ok(file) => {
-                let mut buf = [0u8; 512];
-                loop {
-                    match file.read(&mut buf) {
-                        Ok(0) => break, // EOF
-                        Ok(n) => {
-                            // Converter para string e imprimir
-                            if let Ok(text) = core::str::from_utf8(&buf[..n]) {
-                                for c in text.chars() {
-                                    if c == '\n' {
-                                        output.write_line("");
-                                    } else if c >= ' ' || c == '\t' {
-                                        output.write_char(c);
-                                    }
-                                }
-                            } else {
-                                output.write_line("(conteudo binario nao exibido)");
-                                break;
-                            }
-                        }
-                        Err(e) => {
-                            output.write_str("cat: erro ao ler: ");
-                            output.write_line(error_to_str(e));
-                            break;
-                        }
-                    }
+    match args.positional.first().copied() {
+        // +N (N > 0): remove a entrada sem mudar de diretório
+        Some(arg) => {
+            let Some(n) = stack_index(output, ctx, "popd", arg) else {
+                return;
+            };
+            if n == 0 {
+                let top = ctx.dir_stack[0].clone();
+                if !change_dir(output, ctx, "popd", &top, false) {
+                    return;
                 }
+                ctx.dir_stack.remove(0);
+            } else {
+                ctx.dir_stack.remove(n - 1);
             }
-            Err(e) => {
-                output.write_str("cat: ");
-                output.write_str(&full_path);
-                output.write_str(": ");
-                output.write_line(error_to_str(e));
+        }
+        None => {
+            let top = ctx.dir_stack[0].clone();
+            if !change_dir(output, ctx, "popd", &top, false) {
+                return;
             }
+            ctx.dir_stack.remove(0);
+        }
+    }
+    write_dirs(output, ctx, &DirsStyle::default());
+}
+
+pub fn cmd_dirs(output: &mut TerminalState, ctx: &mut ShellContext, args: &Args) {
+    if args.flag("c") {
+        ctx.dir_stack.clear();
+        return;
+    }
+
+    let style = DirsStyle {
+        full: args.flag("l"),
+        lines: args.flag("p") || args.flag("v"),
+        numbered: args.flag("v"),
+    };
+    write_dirs(output, ctx, &style);
+}
+
+/// Formato da listagem de `dirs`
+#[derive(Default)]
+struct DirsStyle {
+    /// `-l`: sem abreviar `$HOME` para `~`
+    full: bool,
+    /// `-p`: uma entrada por linha
+    lines: bool,
+    /// `-v`: uma por linha, com o índice
+    numbered: bool,
+}
+
+/// Diretório atual seguido da pilha
+fn full_stack(ctx: &ShellContext) -> Vec<String> {
+    let mut all = Vec::with_capacity(ctx.dir_stack.len() + 1);
+    all.push(ctx.cwd.clone());
+    all.extend(ctx.dir_stack.iter().cloned());
+    all
+}
+
+fn write_dirs(output: &mut TerminalState, ctx: &ShellContext, style: &DirsStyle) {
+    let entries: Vec<String> = full_stack(ctx)
+        .iter()
+        .map(|dir| {
+            if style.full {
+                dir.clone()
+            } else {
+                path::abbreviate_home(ctx, dir)
+            }
+        })
+        .collect();
+
+    if !style.lines {
+        output.write_line(&entries.join(" "));
+        return;
+    }
+    for (i, entry) in entries.iter().enumerate() {
+        if style.numbered {
+            output.write_line(&format!("{:2}  {}", i, entry));
+        } else {
+            output.write_line(entry);
+        }
+    }
+}
+
+/// Índice de `+N` na listagem de `dirs` (0 = diretório atual)
+fn stack_index(
+    output: &mut TerminalState,
+    ctx: &mut ShellContext,
+    cmd: &str,
+    arg: &str,
+) -> Option<usize> {
+    match arg.strip_prefix('+').and_then(|n| n.parse::<usize>().ok()) {
+        Some(n) if n <= ctx.dir_stack.len() => Some(n),
+        Some(_) => {
+            output.write_line(&format!("{}: {}: indice fora da pilha", cmd, arg));
+            ctx.last_exit_code = 1;
+            None
+        }
+        None => {
+            output.write_line(&format!("{}: {}: argumento invalido (use +N)", cmd, arg));
+            ctx.last_exit_code = 2;
+            None
         }
     }
 }
 
 // =============================================================================
-// tree - Árvore de diretórios
+// cat - Mostra conteúdo de arquivo
 // =============================================================================
 
-pub fn cmd_tree(output: &mut TerminalState, ctx: &mut ShellContext, args: &[&str]) {
-    let mut path = ctx.cwd.as_str();
-    let mut max_depth = 3usize;
-
-    // Parsear argumentos
-    let mut i = 0;
-    while i < args.len() {
-        match args[i] {
-            "-d" => {
-                if i + 1 < args.len() {
-                    if let Ok(d) = args[i + 1].parse::<usize>() {
-                        max_depth = d;
+pub fn cmd_cat(output: &mut TerminalState, ctx: &mut ShellContext, args: &Args) -> CommandResult {
+    // Sem argumentos, lê a entrada do pipeline; `-` também é a entrada
+    let mut sources: VecDeque<Option<String>> = args
+        .positional
+        .iter()
+        .map(|arg| (*arg != "-").then(|| path::resolve(&ctx.cwd, arg)))
+        .collect();
+    if sources.is_empty() {
+        sources.push_back(None);
+    }
+
+    let stdin = ctx.stdin.take().map(|input| input.into_text().into_bytes());
+    if stdin.is_none() && sources.iter().any(|s| s.is_none()) {
+        output.write_line("cat: sem entrada; informe um arquivo ou use num pipeline");
+        output.write_line("Uso: cat [opcoes] [arquivo]...");
+        ctx.last_exit_code = 1;
+        return CommandResult::Ok;
+    }
+
+    let show_all = args.flag("A");
+    CommandResult::Task(Box::new(CatTask {
+        sources,
+        stdin,
+        file: None,
+        decoder: Utf8Decoder::new(),
+        options: CatOptions {
+            number: args.flag("n"),
+            squeeze: args.flag("s"),
+            nonprinting: show_all || args.flag("v"),
+            ends: show_all || args.flag("E"),
+            tabs: show_all || args.flag("T"),
+        },
+        line: 0,
+        line_start: true,
+        blank_run: 0,
+        failed: false,
+    }))
+}
+
+/// Opções do `cat`
+struct CatOptions {
+    /// `-n`: numera as linhas
+    number: bool,
+    /// `-s`: junta linhas em branco seguidas numa só
+    squeeze: bool,
+    /// `-v`: controles como `^X` e DEL como `^?`
+    nonprinting: bool,
+    /// `-E`: `$` no fim de cada linha
+    ends: bool,
+    /// `-T`: TAB como `^I`
+    tabs: bool,
+}
+
+/// `cat` em andamento: um bloco de 512 bytes por passo.
+///
+/// O decodificador guarda sequências UTF-8 partidas entre dois blocos.
+struct CatTask {
+    /// Arquivos ainda não abertos (`None` = entrada do pipeline)
+    sources: VecDeque<Option<String>>,
+    /// Entrada do pipeline, consumida no primeiro `-`
+    stdin: Option<Vec<u8>>,
+    /// Arquivo sendo lido
+    file: Option<(String, Box<dyn VfsFile>)>,
+    decoder: Utf8Decoder,
+    options: CatOptions,
+    /// Última linha numerada
+    line: u64,
+    /// O próximo caractere começa uma linha
+    line_start: bool,
+    /// Linhas em branco seguidas até aqui
+    blank_run: usize,
+    /// Algum arquivo falhou
+    failed: bool,
+}
+
+impl CatTask {
+    /// Escreve um caractere aplicando as opções
+    fn put(&mut self, output: &mut TerminalState, c: char) {
+        let opts = &self.options;
+
+        if self.line_start {
+            if c == '\n' {
+                self.blank_run += 1;
+                if opts.squeeze && self.blank_run > 1 {
+                    return;
+                }
+            } else {
+                self.blank_run = 0;
+            }
+            if opts.number {
+                self.line += 1;
+                output.write_str(&format!("{:6}\t", self.line));
+            }
+            self.line_start = false;
+        }
+
+        match c {
+            '\n' => {
+                if opts.ends {
+                    output.write_char('$');
+                }
+                output.write_char('\n');
+                self.line_start = true;
+            }
+            '\t' if opts.tabs => output.write_str("^I"),
+            '\t' => output.write_char('\t'),
+            '\x7f' if opts.nonprinting => output.write_str("^?"),
+            c if c < ' ' && opts.nonprinting => {
+                output.write_char('^');
+                output.write_char((c as u8 + b'@') as char);
+            }
+            c => output.write_char(c),
+        }
+    }
+
+    /// Abre a próxima fonte; `false` quando não há mais
+    fn open_next(&mut self, ctx: &mut ShellContext, output: &mut TerminalState) -> bool {
+        let Some(source) = self.sources.pop_front() else {
+            return false;
+        };
+
+        match source {
+            None => {
+                // A entrada do pipeline só pode ser lida uma vez
+                let data = self.stdin.take().unwrap_or_default();
+                self.feed(output, &data);
+            }
+            Some(full_path) => match ctx.fs.open(&full_path) {
+                Ok(file) => self.file = Some((full_path, file)),
+                Err(e) => {
+                    output.write_str("cat: ");
+                    output.write_str(&full_path);
+                    output.write_str(": ");
+                    output.write_line(error_to_str(e));
+                    if matches!(e, SysError::NotFound) {
+                        suggest_path(output, ctx.fs.as_ref(), &full_path);
                     }
-                    i += 1;
+                    self.failed = true;
                 }
+            },
+        }
+        true
+    }
+
+    fn feed(&mut self, output: &mut TerminalState, data: &[u8]) {
+        let mut decoder = core::mem::take(&mut self.decoder);
+        decoder.decode_with(data, |c| self.put(output, c));
+        self.decoder = decoder;
+    }
+
+    /// Fim de um arquivo: sequência incompleta vira U+FFFD
+    fn finish_file(&mut self, output: &mut TerminalState) {
+        let mut rest = String::new();
+        self.decoder.finish(&mut rest);
+        for c in rest.chars() {
+            self.put(output, c);
+        }
+        self.file = None;
+    }
+}
+
+impl Task for CatTask {
+    fn step(&mut self, ctx: &mut ShellContext, output: &mut TerminalState) -> TaskStatus {
+        let Some((full_path, file)) = &mut self.file else {
+            if self.open_next(ctx, output) {
+                return TaskStatus::Pending;
+            }
+            if self.failed {
+                ctx.last_exit_code = 1;
+            }
+            return TaskStatus::Done;
+        };
+
+        let mut buf = [0u8; 512];
+        match file.read(&mut buf) {
+            Ok(0) => self.finish_file(output), // EOF
+            // NUL só aparece em binários: não despejar no terminal sem -v
+            Ok(n) if !self.options.nonprinting && buf[..n].contains(&0) => {
+                output.write_str("cat: ");
+                output.write_str(full_path);
+                output.write_line(": conteudo binario nao exibido (use -A)");
+                self.decoder = Utf8Decoder::new();
+                self.file = None;
+            }
+            Ok(n) => self.feed(output, &buf[..n]),
+            Err(e) => {
+                output.write_str("cat: erro ao ler: ");
+                output.write_line(error_to_str(e));
+                self.failed = true;
+                self.finish_file(output);
             }
-            arg if !arg.starts_with('-') => path = arg,
-            _ => {}
         }
-        i += 1;
+        TaskStatus::Pending
     }
+}
+
+// =============================================================================
+// tree - Árvore de diretórios
+// =============================================================================
 
-    let full_path = resolve_path(&ctx.cwd, path);
-    output.write_line(&full_path);
-    tree_recursive(output, &full_path, "", 0, max_depth);
+/// Desenha a árvore de cada operando, um após o outro, com o total somado
+/// no fim; em JSON, várias raízes vão numa lista
+pub fn cmd_tree(output: &mut TerminalState, ctx: &mut ShellContext, args: &Args) -> CommandResult {
+    let Some(colors) = ls_colors(output, ctx, "tree", args) else {
+        return CommandResult::Ok;
+    };
+    let roots: VecDeque<String> = match args.positional.is_empty() {
+        true => VecDeque::from([ctx.cwd.clone()]),
+        false => args
+            .positional
+            .iter()
+            .map(|p| path::resolve(&ctx.cwd, p))
+            .collect(),
+    };
+    let options = TreeOptions {
+        max_depth: args.number("L").map_or(usize::MAX, |n| n as usize),
+        all: args.flag("a"),
+        dirs_only: args.flag("d"),
+        follow: args.flag("l"),
+        sizes: args.flag("s") || args.flag("h"),
+        human: args.flag("h"),
+        report: !args.flag("noreport"),
+    };
+
+    let several = roots.len() > 1;
+    let json = args.flag("json").then(|| {
+        let mut json = JsonWriter::new();
+        if several {
+            json.begin_array();
+        }
+        json
+    });
+    CommandResult::Task(Box::new(TreeTask {
+        roots,
+        several,
+        stack: Vec::new(),
+        options,
+        json,
+        colors,
+        opened: 0,
+        failed: false,
+        dirs: 0,
+        files: 0,
+    }))
 }
 
-fn tree_recursive(
-    output: &mut TerminalState,
-    path: &str,
-    prefix: &str,
-    depth: usize,
+/// Opções do `tree`
+struct TreeOptions {
+    /// `-L n`: níveis abaixo da raiz
     max_depth: usize,
-) {
-    if depth >= max_depth {
-        return;
+    /// `-a`: inclui arquivos ocultos
+    all: bool,
+    /// `-d`: só diretórios
+    dirs_only: bool,
+    /// `-l`: entra em links para diretórios
+    follow: bool,
+    /// `-s`/`-h`: mostra o tamanho de cada entrada
+    sizes: bool,
+    /// `-h`: tamanhos legíveis
+    human: bool,
+    /// Sem `--noreport`: escreve o total no fim
+    report: bool,
+}
+
+/// `tree` em andamento: uma entrada por passo, com a pilha de diretórios
+/// abertos no lugar da recursão
+struct TreeTask {
+    /// Raízes ainda não listadas
+    roots: VecDeque<String>,
+    /// Mais de uma raiz: em JSON, a saída é uma lista
+    several: bool,
+    stack: Vec<TreeLevel>,
+    options: TreeOptions,
+    /// Saída em JSON (`--json`)
+    json: Option<JsonWriter>,
+    /// Cores por tipo, se a saída for colorida
+    colors: Option<LsColors>,
+    /// Raízes abertas com sucesso
+    opened: usize,
+    /// Alguma raiz não pôde ser aberta
+    failed: bool,
+    /// Diretórios listados, para o resumo
+    dirs: usize,
+    /// Arquivos listados, para o resumo
+    files: usize,
+}
+
+/// Diretório sendo listado pelo `tree`
+struct TreeLevel {
+    path: String,
+    /// Caminho com os links já resolvidos, para detectar ciclos
+    real: String,
+    /// Prefixo das linhas (`│   ` / espaços dos níveis acima)
+    prefix: String,
+    /// Entradas ordenadas por nome
+    entries: Vec<TreeEntry>,
+    /// Próxima entrada a escrever
+    next: usize,
+}
+
+/// Entrada listada pelo `tree`
+struct TreeEntry {
+    name: String,
+    /// Tipo como em `stat` (1 arquivo, 2 diretório, 3 link)
+    file_type: u8,
+    mode: u32,
+    size: u64,
+    /// Destino, se for link simbólico
+    target: Option<String>,
+}
+
+impl TreeEntry {
+    fn is_dir(&self) -> bool {
+        self.file_type == 2
     }
+}
 
-    let dir = match Dir::open(path) {
-        Ok(d) => d,
-        Err(_) => return,
-    };
+impl TreeLevel {
+    /// Lê, filtra e ordena as entradas do diretório
+    fn open(
+        fs: &dyn Vfs,
+        path: &str,
+        real: String,
+        prefix: String,
+        options: &TreeOptions,
+    ) -> Result<Self, SysError> {
+        let mut entries: Vec<TreeEntry> = Vec::new();
+
+        for entry in fs.read_dir(path)? {
+            if !options.all && entry.name.starts_with('.') {
+                continue;
+            }
 
-    let mut entries: Vec<(String, bool)> = Vec::new();
-    for entry in dir.entries() {
-        let name = entry.name();
-        if name == "." || name == ".." {
-            continue;
+            let child = path::join(path, &entry.name);
+            let mut item = TreeEntry {
+                file_type: if entry.is_dir { 2 } else { 1 },
+                name: entry.name,
+                mode: 0,
+                size: 0,
+                target: None,
+            };
+            if let Ok(info) = fs.stat(&child) {
+                item.file_type = info.file_type;
+                item.mode = info.mode;
+                item.size = info.size;
+            }
+            if item.file_type == 3 {
+                item.target = fs.read_link(&child).ok();
+            }
+
+            // Com -d, links só aparecem se apontarem para diretórios
+            if options.dirs_only
+                && !item.is_dir()
+                && !(item.file_type == 3 && link_dir(fs, &child).is_some())
+            {
+                continue;
+            }
+            entries.push(item);
         }
-        entries.push((String::from(name), entry.is_dir()));
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(Self {
+            path: String::from(path),
+            real,
+            prefix,
+            entries,
+            next: 0,
+        })
     }
+}
 
-    entries.sort_by(|a, b| a.0.cmp(&b.0));
-    let count = entries.len();
+impl TreeTask {
+    /// Abre uma raiz e escreve sua linha (ou o início do objeto JSON)
+    fn open_root(&mut self, fs: &dyn Vfs, output: &mut TerminalState, full_path: String) {
+        // Mesmo caminho físico dos filhos, para que um link de volta à raiz
+        // seja reconhecido como ciclo
+        let real = path::canonicalize(fs, "/", &full_path).unwrap_or_else(|_| full_path.clone());
+        let root = match TreeLevel::open(fs, &full_path, real, String::new(), &self.options) {
+            Ok(root) => root,
+            Err(e) => {
+                self.failed = true;
+                match &mut self.json {
+                    Some(json) => {
+                        json.begin_object();
+                        json.field_str("name", &full_path);
+                        json.field_str("type", "dir");
+                        json.field_str("error", error_to_str(e));
+                        json.end_object();
+                        output.write_str(&json.take());
+                    }
+                    None => {
+                        output.write_str("tree: ");
+                        output.write_str(&full_path);
+                        output.write_str(": ");
+                        output.write_line(error_to_str(e));
+                        if matches!(e, SysError::NotFound) {
+                            suggest_path(output, fs, &full_path);
+                        }
+                    }
+                }
+                return;
+            }
+        };
+        self.opened += 1;
+
+        // JSON: {"name", "type", "children": [...]} aninhados
+        let descend = self.options.max_depth > 0;
+        match &mut self.json {
+            Some(json) => {
+                json.begin_object();
+                json.field_str("name", &full_path);
+                json.field_str("type", "dir");
+                if descend {
+                    json.key("children");
+                    json.begin_array();
+                } else {
+                    json.end_object();
+                }
+                output.write_str(&json.take());
+            }
+            None => match &self.colors {
+                Some(colors) => output.write_line(&colors.paint(&full_path, &full_path, 2, 0)),
+                None => output.write_line(&full_path),
+            },
+        }
+        if descend {
+            self.stack.push(root);
+        }
+    }
+
+    /// Fecha a saída depois da última raiz
+    fn finish(&mut self, ctx: &mut ShellContext, output: &mut TerminalState) {
+        match &mut self.json {
+            Some(json) => {
+                if self.several {
+                    json.end_array();
+                }
+                output.write_str(&json.take());
+                output.write_line("");
+            }
+            None if self.options.report && self.opened > 0 => self.report(output),
+            None => {}
+        }
+        if self.failed {
+            ctx.last_exit_code = 2;
+        }
+    }
 
-    for (i, (name, is_dir)) in entries.iter().enumerate() {
-        let is_last = i == count - 1;
-        let connector = if is_last { "└── " } else { "├── " };
+    /// Escreve o total (`3 diretorios, 10 arquivos`)
+    fn report(&self, output: &mut TerminalState) {
+        let plural =
+            |n: usize, one: &str, many: &str| format!("{} {}", n, if n == 1 { one } else { many });
 
-        output.write_str(prefix);
-        output.write_str(connector);
-        if *is_dir {
-            output.write_str("[");
-            output.write_str(name);
-            output.write_line("]");
+        output.write_line("");
+        let mut line = plural(self.dirs, "diretorio", "diretorios");
+        if !self.options.dirs_only {
+            line.push_str(", ");
+            line.push_str(&plural(self.files, "arquivo", "arquivos"));
+        }
+        output.write_line(&line);
+    }
+}
 
-            // Recursar
-            let child_prefix = if is_last {
-                let mut p = String::from(prefix);
-                p.push_str("    ");
-                p
+impl Task for TreeTask {
+    fn step(&mut self, ctx: &mut ShellContext, output: &mut TerminalState) -> TaskStatus {
+        let fs = ctx.fs.as_ref();
+        let depth = self.stack.len();
+        let Some(level) = self.stack.last_mut() else {
+            if let Some(root) = self.roots.pop_front() {
+                self.open_root(fs, output, root);
+                return TaskStatus::Pending;
+            }
+            self.finish(ctx, output);
+            return TaskStatus::Done;
+        };
+
+        if level.next == level.entries.len() {
+            self.stack.pop();
+            if let Some(json) = &mut self.json {
+                // Fecha "children" e o objeto do diretório
+                json.end_array();
+                json.end_object();
+                output.write_str(&json.take());
+            }
+            return TaskStatus::Pending;
+        }
+        level.next += 1;
+
+        let level = &self.stack[depth - 1];
+        let entry = &level.entries[level.next - 1];
+        let is_last = level.next == level.entries.len();
+        let child_path = path::join(&level.path, &entry.name);
+
+        // Links contam pelo destino, com ou sem -l, como no filtro de -d
+        let link_target = match entry.file_type {
+            3 => link_dir(fs, &child_path),
+            _ => None,
+        };
+        let counts_as_dir = entry.is_dir() || link_target.is_some();
+
+        // Diretório real onde descer: o próprio, ou o destino do link (-l)
+        let real = match entry.file_type {
+            2 => Some(path::join(&level.real, &entry.name)),
+            _ => link_target.filter(|_| self.options.follow),
+        };
+
+        // Abrir antes de escrever, para anotar erros e ciclos na linha
+        let mut note: Option<String> = None;
+        let mut child: Option<TreeLevel> = None;
+        if let Some(real) = real.filter(|_| depth < self.options.max_depth) {
+            if self.stack.iter().any(|l| l.real == real) {
+                note = Some(String::from("[recursivo, nao seguido]"));
             } else {
-                let mut p = String::from(prefix);
-                p.push_str("│   ");
-                p
-            };
+                let mut child_prefix = level.prefix.clone();
+                child_prefix.push_str(if is_last { "    " } else { "│   " });
+                match TreeLevel::open(fs, &child_path, real, child_prefix, &self.options) {
+                    Ok(level) => child = Some(level),
+                    Err(e) => note = Some(format!("[erro ao abrir: {}]", error_to_str(e))),
+                }
+            }
+        }
+
+        if counts_as_dir {
+            self.dirs += 1;
+        } else {
+            self.files += 1;
+        }
 
-            let child_path = join_path(path, name);
-            tree_recursive(output, &child_path, &child_prefix, depth + 1, max_depth);
+        if let Some(json) = &mut self.json {
+            json.begin_object();
+            json.field_str("name", &entry.name);
+            json.field_str("type", file_type_name(entry.file_type));
+            if self.options.sizes {
+                json.field_u64("size", entry.size);
+            }
+            if let Some(target) = &entry.target {
+                json.field_str("target", target);
+            }
+            if let Some(note) = &note {
+                json.field_str("error", note.trim_matches(['[', ']']));
+            }
+            if child.is_some() {
+                json.key("children");
+                json.begin_array();
+            } else {
+                json.end_object();
+            }
+            output.write_str(&json.take());
         } else {
-            output.write_line(name);
+            let mut line = level.prefix.clone();
+            line.push_str(if is_last { "└── " } else { "├── " });
+
+            if self.options.sizes {
+                let size = if self.options.human {
+                    human_size(entry.size)
+                } else {
+                    format!("{}", entry.size)
+                };
+                line.push('[');
+                for _ in size.len()..if self.options.human { 4 } else { 11 } {
+                    line.push(' ');
+                }
+                line.push_str(&size);
+                line.push_str("]  ");
+            }
+
+            let name = &entry.name;
+            match &self.colors {
+                Some(colors) => {
+                    line.push_str(&colors.paint(name, name, entry.file_type, entry.mode))
+                }
+                None if entry.is_dir() => {
+                    line.push('[');
+                    line.push_str(name);
+                    line.push(']');
+                }
+                None => line.push_str(name),
+            }
+            if let Some(target) = &entry.target {
+                line.push_str(" -> ");
+                line.push_str(target);
+            }
+            if let Some(note) = &note {
+                line.push_str("  ");
+                line.push_str(note);
+            }
+            output.write_line(&line);
         }
+
+        // Descer um nível
+        if let Some(child) = child {
+            self.stack.push(child);
+        }
+        TaskStatus::Pending
     }
 }
 
+/// Diretório apontado por um link, com todos os links resolvidos
+fn link_dir(fs: &dyn Vfs, path: &str) -> Option<String> {
+    path::canonicalize(fs, "/", path)
+        .ok()
+        .filter(|real| fs.is_dir(real))
+}
+
 // =============================================================================
 // stat - Info de arquivo
 // =============================================================================
 
-pub fn cmd_stat(output: &mut TerminalState, ctx: &mut ShellContext, args: &[&str]) {
-    if args.is_empty() {
+pub fn cmd_stat(output: &mut TerminalState, ctx: &mut ShellContext, args: &Args) {
+    if args.positional.is_empty() {
         output.write_line("stat: falta operando");
         return;
     }
 
-    for arg in args {
-        let full_path = resolve_path(&ctx.cwd, arg);
+    if args.flag("json") {
+        stat_json(output, ctx, &args.positional);
+        return;
+    }
+
+    if ctx.pipe_table {
+        let table = stat_table(ctx, &args.positional);
+        table::emit(ctx, output, table);
+        return;
+    }
+
+    for arg in &args.positional {
+        let full_path = path::resolve(&ctx.cwd, arg);
 
-        match stat(&full_path) {
+        match ctx.fs.stat(&full_path) {
             Ok(info) => {
                 output.write_str("  Arquivo: ");
                 output.write_line(&full_path);
@@ -372,105 +1340,203 @@ pub fn cmd_stat(output: &mut TerminalState, ctx: &mut ShellContext, args: &[&str
                 output.write_str(&full_path);
                 output.write_str(": ");
                 output.write_line(error_to_str(e));
+                if matches!(e, SysError::NotFound) {
+                    suggest_path(output, ctx.fs.as_ref(), &full_path);
+                }
             }
         }
     }
 }
 
-// =============================================================================
-// STUBS - Comandos não implementados
-// =============================================================================
-
-pub fn cmd_mkdir(output: &mut TerminalState, _ctx: &mut ShellContext, _args: &[&str]) {
-    output.write_line("mkdir: Nao implementado");
-    output.write_line("(O filesystem ainda e somente leitura)");
-}
-
-pub fn cmd_rmdir(output: &mut TerminalState, _ctx: &mut ShellContext, _args: &[&str]) {
-    output.write_line("rmdir: Nao implementado");
-    output.write_line("(O filesystem ainda e somente leitura)");
-}
-
-pub fn cmd_rm(output: &mut TerminalState, _ctx: &mut ShellContext, _args: &[&str]) {
-    output.write_line("rm: Nao implementado");
-    output.write_line("(O filesystem ainda e somente leitura)");
-}
+/// `stat --json`: array com um objeto por caminho.
+///
+/// `{"path", "type", "size", "mode"}`, ou `{"path", "error"}` se falhar.
+fn stat_json(output: &mut TerminalState, ctx: &ShellContext, paths: &[&str]) {
+    let mut json = JsonWriter::new();
+    json.begin_array();
+
+    for arg in paths {
+        let full_path = path::resolve(&ctx.cwd, arg);
+        json.begin_object();
+        json.field_str("path", &full_path);
+        match ctx.fs.stat(&full_path) {
+            Ok(info) => {
+                json.field_str("type", file_type_name(info.file_type));
+                json.field_u64("size", info.size);
+                json.field_u64("mode", info.mode as u64);
+            }
+            Err(e) => json.field_str("error", error_to_str(e)),
+        }
+        json.end_object();
+    }
 
-pub fn cmd_cp(output: &mut TerminalState, _ctx: &mut ShellContext, _args: &[&str]) {
-    output.write_line("cp: Nao implementado");
-    output.write_line("(O filesystem ainda e somente leitura)");
+    json.end_array();
+    output.write_line(&json.finish());
 }
 
-pub fn cmd_mv(output: &mut TerminalState, _ctx: &mut ShellContext, _args: &[&str]) {
-    output.write_line("mv: Nao implementado");
-    output.write_line("(O filesystem ainda e somente leitura)");
+/// `stat | ...`: tabela `path type size mode`; caminhos que falham ficam
+/// sem tipo
+fn stat_table(ctx: &ShellContext, paths: &[&str]) -> Table {
+    let mut table = Table::new(&["path", "type", "size", "mode"]);
+    for arg in paths {
+        let full_path = path::resolve(&ctx.cwd, arg);
+        let mut row = alloc::vec![Value::from(full_path.as_str())];
+        if let Ok(info) = ctx.fs.stat(&full_path) {
+            row.push(Value::from(file_type_name(info.file_type)));
+            row.push(Value::from(info.size));
+            row.push(Value::from(info.mode as u64));
+        }
+        table.push(row);
+    }
+    table
 }
 
 // =============================================================================
 // HELPERS
 // =============================================================================
 
-/// Resolve um path relativo ao CWD
-fn resolve_path(cwd: &str, path: &str) -> String {
-    if path.starts_with('/') {
-        // Path absoluto
-        normalize_path(path)
-    } else {
-        // Path relativo
-        let mut full = String::from(cwd.trim_end_matches('/'));
-        full.push('/');
-        full.push_str(path);
-        normalize_path(&full)
+/// Sugere um caminho parecido que existe, se houver
+pub(super) fn suggest_path(output: &mut TerminalState, fs: &dyn Vfs, full_path: &str) {
+    if let Some(suggestion) = suggest::path(fs, full_path) {
+        output.write_str("Voce quis dizer '");
+        output.write_str(&suggestion);
+        output.write_line("'?");
     }
 }
 
-/// Normaliza um path (remove //, ., ..)
-fn normalize_path(path: &str) -> String {
-    let mut components: Vec<&str> = Vec::new();
+/// Abre a entrada de um comando: o arquivo, ou a entrada do pipeline se
+/// o argumento for `-` ou faltar.
+///
+/// Retorna o arquivo e seu tamanho; em caso de erro, já o reportou.
+pub(super) fn open_input(
+    output: &mut TerminalState,
+    ctx: &mut ShellContext,
+    cmd: &str,
+    arg: Option<&str>,
+) -> Option<(Box<dyn VfsFile>, u64)> {
+    let Some(arg) = arg.filter(|a| *a != "-") else {
+        return match ctx.stdin.take() {
+            Some(input) => {
+                let reader = ByteReader::new(input.into_text().into_bytes());
+                let size = reader.len() as u64;
+                Some((Box::new(reader), size))
+            }
+            None => {
+                output.write_str(cmd);
+                output.write_line(": sem entrada; informe um arquivo ou use num pipeline");
+                ctx.last_exit_code = 1;
+                None
+            }
+        };
+    };
 
-    for comp in path.split('/') {
-        match comp {
-            "" | "." => continue,
-            ".." => {
-                components.pop();
+    let full_path = path::resolve(&ctx.cwd, arg);
+    let opened = ctx.fs.metadata(&full_path).and_then(|meta| {
+        if meta.is_dir() {
+            return Err(SysError::IsDirectory);
+        }
+        ctx.fs.open(&full_path).map(|file| (file, meta.size))
+    });
+    match opened {
+        Ok(opened) => Some(opened),
+        Err(e) => {
+            output.write_str(cmd);
+            output.write_str(": ");
+            output.write_str(&full_path);
+            output.write_str(": ");
+            output.write_line(error_to_str(e));
+            if matches!(e, SysError::NotFound) {
+                suggest_path(output, ctx.fs.as_ref(), &full_path);
             }
-            _ => components.push(comp),
+            ctx.last_exit_code = 1;
+            None
         }
     }
+}
 
-    let mut result = String::from("/");
-    for (i, comp) in components.iter().enumerate() {
-        if i > 0 {
-            result.push('/');
-        }
-        result.push_str(comp);
+/// Nome do tipo de arquivo na saída JSON
+fn file_type_name(file_type: u8) -> &'static str {
+    match file_type {
+        1 => "file",
+        2 => "dir",
+        3 => "symlink",
+        _ => "other",
     }
+}
 
-    if result.is_empty() {
-        String::from("/")
-    } else {
-        result
+/// Cores de `LS_COLORS`, se a saída deve ser colorida segundo `--color`.
+///
+/// `None` dentro de `Some` desliga as cores; `None` indica valor inválido,
+/// já reportado.
+fn ls_colors(
+    output: &mut TerminalState,
+    ctx: &mut ShellContext,
+    cmd: &str,
+    args: &Args,
+) -> Option<Option<LsColors>> {
+    let Some(mode) = ColorMode::parse(args.value("color")) else {
+        output.write_str(cmd);
+        output.write_str(": valor invalido para --color: ");
+        output.write_line(args.value("color").unwrap_or_default());
+        ctx.last_exit_code = 2;
+        return None;
+    };
+
+    if !mode.enabled(output.is_capturing()) {
+        return Some(None);
     }
+    Some(Some(LsColors::new(ctx.var("LS_COLORS").as_deref())))
 }
 
-/// Junta dois paths
-fn join_path(base: &str, child: &str) -> String {
-    let mut path = String::from(base.trim_end_matches('/'));
-    path.push('/');
-    path.push_str(child);
-    path
+/// Permissões no formato `drwxr-xr-x`.
+///
+/// Sistemas de arquivos que não registram permissões (mode 0) mostram `?`.
+fn mode_string(file_type: u8, mode: u32) -> String {
+    let mut s = String::with_capacity(10);
+    s.push(match file_type {
+        2 => 'd',
+        3 => 'l',
+        1 => '-',
+        _ => '?',
+    });
+
+    if mode == 0 {
+        s.push_str("?????????");
+        return s;
+    }
+
+    for shift in [6, 3, 0] {
+        let bits = (mode >> shift) & 0o7;
+        s.push(if bits & 0o4 != 0 { 'r' } else { '-' });
+        s.push(if bits & 0o2 != 0 { 'w' } else { '-' });
+        s.push(if bits & 0o1 != 0 { 'x' } else { '-' });
+    }
+    s
 }
 
-/// Converte erro para string
-fn error_to_str(e: redpowder::SysError) -> &'static str {
-    match e {
-        redpowder::SysError::NotFound => "Nao encontrado",
-        redpowder::SysError::PermissionDenied => "Permissao negada",
-        redpowder::SysError::IsDirectory => "E um diretorio",
-        redpowder::SysError::NotDirectory => "Nao e um diretorio",
-        redpowder::SysError::NotImplemented => "Nao implementado",
-        redpowder::SysError::IoError => "Erro de E/S",
-        _ => "Erro desconhecido",
+/// Tamanho legível (`-h`): `512`, `1.5K`, `23M`, como o GNU
+fn human_size(size: u64) -> String {
+    const UNITS: [char; 5] = ['K', 'M', 'G', 'T', 'P'];
+    if size < 1024 {
+        return format!("{}", size);
+    }
+
+    // Décimos da unidade, arredondando para cima
+    let mut value = size;
+    let mut unit = 0;
+    while value >= 1024 * 1024 && unit + 1 < UNITS.len() {
+        value /= 1024;
+        unit += 1;
+    }
+    let tenths = (value * 10).div_ceil(1024);
+
+    let whole = tenths.div_ceil(10);
+    if tenths < 100 {
+        format!("{}.{}{}", tenths / 10, tenths % 10, UNITS[unit])
+    } else if whole >= 1024 && unit + 1 < UNITS.len() {
+        format!("1.0{}", UNITS[unit + 1])
+    } else {
+        format!("{}{}", whole, UNITS[unit])
     }
 }
 
@@ -495,3 +1561,289 @@ fn write_number(output: &mut TerminalState, n: u64) {
         output.write_str(s);
     }
 }
+
+// =============================================================================
+// TESTES
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::shell::testing::{run, shell};
+    use crate::shell::vfs::MemoryFs;
+    use crate::shell::ShellContext;
+
+    fn docs() -> ShellContext {
+        let mut fs = MemoryFs::new();
+        fs.add_file("/docs/a.txt", b"alpha\n")
+            .add_file("/docs/sub/b.txt", b"beta\n")
+            .add_symlink("/docs/la", "a.txt");
+        shell(fs)
+    }
+
+    #[test]
+    fn cd_dash_returns_to_the_previous_directory() {
+        let mut ctx = docs();
+        assert_eq!(run(&mut ctx, "cd -"), "cd: OLDPWD nao definido\n");
+        assert_eq!(ctx.last_exit_code, 1);
+
+        assert_eq!(run(&mut ctx, "cd /docs/sub"), "");
+        assert_eq!(run(&mut ctx, "cd -"), "/\n");
+        assert_eq!(run(&mut ctx, "cd -"), "/docs/sub\n");
+        assert_eq!(ctx.var("OLDPWD").as_deref(), Some("/"));
+    }
+
+    #[test]
+    fn cd_resolves_dot_dot_by_the_typed_path() {
+        let mut fs = MemoryFs::new();
+        fs.add_dir("/docs/sub").add_symlink("/ln", "/docs/sub");
+        let mut ctx = shell(fs);
+
+        run(&mut ctx, "cd /docs/sub/../../..//docs/");
+        assert_eq!(ctx.cwd, "/docs");
+        run(&mut ctx, "cd /ln");
+        assert_eq!(ctx.cwd, "/ln");
+        assert_eq!(run(&mut ctx, "pwd -P"), "/docs/sub\n");
+        run(&mut ctx, "cd ..");
+        assert_eq!(ctx.cwd, "/");
+
+        run(&mut ctx, "cd -P /ln/..");
+        assert_eq!(ctx.cwd, "/docs");
+        run(&mut ctx, "cd /ln");
+        run(&mut ctx, "cd -P ..");
+        assert_eq!(ctx.cwd, "/docs");
+    }
+
+    #[test]
+    fn cd_searches_cdpath() {
+        let mut ctx = docs();
+        ctx.set_var("CDPATH", ":/docs");
+        assert_eq!(run(&mut ctx, "cd sub"), "/docs/sub\n");
+        // Caminhos com `./` não passam pelo CDPATH
+        assert_eq!(run(&mut ctx, "cd ./sub"), "cd: /docs/sub/sub: Nao existe\n");
+        assert_eq!(ctx.cwd, "/docs/sub");
+    }
+
+    #[test]
+    fn pushd_and_popd_keep_a_stack() {
+        let mut ctx = docs();
+        assert_eq!(run(&mut ctx, "pushd /docs"), "/docs /\n");
+        assert_eq!(run(&mut ctx, "pushd sub"), "/docs/sub /docs /\n");
+        assert_eq!(
+            run(&mut ctx, "dirs -v"),
+            " 0  /docs/sub\n 1  /docs\n 2  /\n"
+        );
+        assert_eq!(run(&mut ctx, "pushd +2"), "/ /docs/sub /docs\n");
+        assert_eq!(run(&mut ctx, "popd"), "/docs/sub /docs\n");
+        assert_eq!(ctx.cwd, "/docs/sub");
+        assert_eq!(run(&mut ctx, "popd +1"), "/docs/sub\n");
+        assert_eq!(run(&mut ctx, "popd"), "popd: pilha de diretorios vazia\n");
+    }
+
+    #[test]
+    fn ls_lists_directories_first() {
+        let mut ctx = docs();
+        assert_eq!(run(&mut ctx, "ls /docs"), "[sub]  a.txt  la@\n");
+    }
+
+    #[test]
+    fn ls_long_shows_sizes_and_link_targets() {
+        let mut ctx = docs();
+        assert_eq!(
+            run(&mut ctx, "ls -l /docs"),
+            "drwxr-xr-x  0  sub\n-rw-r--r--  6  a.txt\nlrwxrwxrwx  5  la -> a.txt\n"
+        );
+    }
+
+    #[test]
+    fn ls_missing_directory_fails() {
+        let mut ctx = docs();
+        assert_eq!(
+            run(&mut ctx, "ls /nope"),
+            "ls: nao foi possivel abrir /nope: Nao encontrado\n"
+        );
+        assert_eq!(ctx.last_exit_code, 1);
+    }
+
+    #[test]
+    fn ls_lists_every_operand() {
+        let mut ctx = docs();
+        assert_eq!(
+            run(&mut ctx, "ls /docs/sub /docs/a.txt /docs"),
+            "/docs/a.txt\n\n/docs:\n[sub]  a.txt  la@\n\n/docs/sub:\nb.txt\n"
+        );
+        assert_eq!(
+            run(&mut ctx, "ls -l /docs/la"),
+            "lrwxrwxrwx  5  /docs/la -> a.txt\n"
+        );
+        assert_eq!(run(&mut ctx, "ls /docs/sub/b.txt"), "/docs/sub/b.txt\n");
+    }
+
+    #[test]
+    fn ls_keeps_going_after_a_missing_operand() {
+        let mut ctx = docs();
+        assert_eq!(
+            run(&mut ctx, "ls /nope /docs/sub"),
+            "ls: nao foi possivel abrir /nope: Nao encontrado\n/docs/sub:\nb.txt\n"
+        );
+        assert_eq!(ctx.last_exit_code, 1);
+    }
+
+    #[test]
+    fn cat_concatenates_and_follows_links() {
+        let mut ctx = docs();
+        assert_eq!(
+            run(&mut ctx, "cat /docs/la /docs/sub/b.txt"),
+            "alpha\nbeta\n"
+        );
+        assert_eq!(ctx.last_exit_code, 0);
+    }
+
+    #[test]
+    fn cat_directory_fails() {
+        let mut ctx = docs();
+        assert_eq!(
+            run(&mut ctx, "cat /docs/sub"),
+            "cat: /docs/sub: E um diretorio\n"
+        );
+        assert_eq!(ctx.last_exit_code, 1);
+    }
+
+    #[test]
+    fn tree_draws_branches_and_totals() {
+        let mut ctx = docs();
+        assert_eq!(
+            run(&mut ctx, "tree /docs"),
+            "/docs\n├── a.txt\n├── la -> a.txt\n└── [sub]\n    └── b.txt\n\n\
+             1 diretorio, 3 arquivos\n"
+        );
+    }
+
+    #[test]
+    fn tree_dirs_only() {
+        let mut ctx = docs();
+        assert_eq!(
+            run(&mut ctx, "tree -d /docs"),
+            "/docs\n└── [sub]\n\n1 diretorio\n"
+        );
+    }
+
+    #[test]
+    fn tree_counts_directory_links_as_directories() {
+        let mut fs = MemoryFs::new();
+        fs.add_file("/t/f", b"")
+            .add_file("/t/sub/g", b"")
+            .add_symlink("/t/ld", "sub")
+            .add_symlink("/t/lf", "f");
+        let mut ctx = shell(fs);
+
+        assert_eq!(
+            run(&mut ctx, "tree /t"),
+            "/t\n├── f\n├── ld -> sub\n├── lf -> f\n└── [sub]\n    └── g\n\n\
+             2 diretorios, 3 arquivos\n"
+        );
+        assert_eq!(
+            run(&mut ctx, "tree -d /t"),
+            "/t\n├── ld -> sub\n└── [sub]\n\n2 diretorios\n"
+        );
+        assert!(run(&mut ctx, "tree -l /t").ends_with("\n2 diretorios, 4 arquivos\n"));
+    }
+
+    /// Nomes com aspas, barra invertida e controle, que o JSON escapa
+    fn odd_names() -> ShellContext {
+        let mut fs = MemoryFs::new();
+        fs.add_file("/q/a\"b", b"12")
+            .add_file("/q/c\\d\te", b"")
+            .add_file("/q/ação", b"")
+            .add_symlink("/q/l", "a\"b");
+        shell(fs)
+    }
+
+    #[test]
+    fn ls_json_escapes_names() {
+        let mut ctx = odd_names();
+        assert_eq!(
+            run(&mut ctx, "ls --json /q"),
+            concat!(
+                r#"[{"name":"a\"b","type":"file","size":2,"mode":420},"#,
+                r#"{"name":"ação","type":"file","size":0,"mode":420},"#,
+                r#"{"name":"c\\d\te","type":"file","size":0,"mode":420},"#,
+                r#"{"name":"l","type":"symlink","size":3,"mode":511,"target":"a\"b"}]"#,
+                "\n"
+            )
+        );
+    }
+
+    #[test]
+    fn stat_json_reports_each_path() {
+        let mut ctx = odd_names();
+        assert_eq!(
+            run(&mut ctx, "stat --json /q/a\\\"b /nope"),
+            concat!(
+                r#"[{"path":"/q/a\"b","type":"file","size":2,"mode":420},"#,
+                r#"{"path":"/nope","error":"Nao encontrado"}]"#,
+                "\n"
+            )
+        );
+    }
+
+    #[test]
+    fn tree_json_nests_children() {
+        let mut ctx = docs();
+        assert_eq!(
+            run(&mut ctx, "tree --json /docs"),
+            concat!(
+                r#"{"name":"/docs","type":"dir","children":["#,
+                r#"{"name":"a.txt","type":"file"},"#,
+                r#"{"name":"la","type":"symlink","target":"a.txt"},"#,
+                r#"{"name":"sub","type":"dir","children":[{"name":"b.txt","type":"file"}]}]}"#,
+                "\n"
+            )
+        );
+    }
+
+    #[test]
+    fn tree_detects_cycles_through_a_linked_root() {
+        let mut fs = MemoryFs::new();
+        fs.add_file("/r/d/f", b"")
+            .add_symlink("/r/d/up", "/r")
+            .add_symlink("/lnk", "/r");
+        let mut ctx = shell(fs);
+        assert_eq!(
+            run(&mut ctx, "tree -l /lnk"),
+            "/lnk\n└── [d]\n    ├── f\n    └── up -> /r  [recursivo, nao seguido]\n\n\
+             2 diretorios, 1 arquivo\n"
+        );
+    }
+
+    #[test]
+    fn tree_lists_every_root() {
+        let mut ctx = docs();
+        assert_eq!(
+            run(&mut ctx, "tree /docs/sub /nope /docs/sub"),
+            "/docs/sub\n└── b.txt\ntree: /nope: Nao encontrado\n/docs/sub\n└── b.txt\n\n\
+             0 diretorios, 2 arquivos\n"
+        );
+        assert_eq!(ctx.last_exit_code, 2);
+        assert_eq!(
+            run(&mut ctx, "tree --json /docs/sub /docs/sub"),
+            concat!(
+                r#"[{"name":"/docs/sub","type":"dir","children":[{"name":"b.txt","type":"file"}]},"#,
+                r#"{"name":"/docs/sub","type":"dir","children":[{"name":"b.txt","type":"file"}]}]"#,
+                "\n"
+            )
+        );
+    }
+
+    #[test]
+    fn stat_does_not_follow_links() {
+        let mut ctx = docs();
+        assert_eq!(
+            run(&mut ctx, "stat /docs/a.txt"),
+            "  Arquivo: /docs/a.txt\n     Tipo: arquivo regular\n  Tamanho: 6 bytes\n     Mode: 420\n"
+        );
+        assert_eq!(
+            run(&mut ctx, "stat /docs/la"),
+            "  Arquivo: /docs/la\n     Tipo: link simbolico\n  Tamanho: 5 bytes\n     Mode: 511\n"
+        );
+    }
+}
@@ -0,0 +1,498 @@
+//! # Hex Dump
+//!
+//! `xxd` e `hexdump -C`: bytes em hexadecimal com a coluna ASCII ao lado,
+//! e o caminho de volta com `xxd -r`.
+//!
+//! ```text
+//! xxd:         00000000: 7f45 4c46 0201 0100 0000 0000 0000 0000  .ELF............
+//! hexdump -C:  00000000  7f 45 4c 46 02 01 01 00  00 00 00 00 00 00 00 00  |.ELF............|
+//! ```
+//!
+//! Na tela, cada byte é colorido pela classe (`--color` como no `ls`):
+//!
+//! | Classe                      | Cor      |
+//! |-----------------------------|----------|
+//! | `00`                        | Cinza    |
+//! | ASCII imprimível            | Verde    |
+//! | Espaço em branco (`\t\n\r`) | Amarelo  |
+//! | Outros controles e `7f`     | Magenta  |
+//! | `80`-`ff`                   | Vermelho |
+
+use super::error_to_str;
+use super::fs::open_input;
+use super::CommandResult;
+use crate::shell::args::Args;
+use crate::shell::ls_colors::ColorMode;
+use crate::shell::path;
+use crate::shell::task::{Task, TaskStatus};
+use crate::shell::vfs::VfsFile;
+use crate::shell::ShellContext;
+use crate::state::terminal::TerminalState;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+/// Bytes lidos por passo da tarefa
+const CHUNK_SIZE: usize = 1024;
+
+/// Maior número de bytes por linha (`-c`)
+const MAX_COLS: u64 = 256;
+
+/// Formato da linha
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Style {
+    /// `xxd`: grupos sem espaço interno, `offset:`
+    Xxd,
+    /// `hexdump -C`: bytes separados, ASCII entre `|`
+    Canonical,
+}
+
+// =============================================================================
+// xxd / hexdump
+// =============================================================================
+
+pub fn cmd_xxd(output: &mut TerminalState, ctx: &mut ShellContext, args: &Args) -> CommandResult {
+    if args.flag("r") {
+        revert(output, ctx, args);
+        return CommandResult::Ok;
+    }
+    dump(output, ctx, args, "xxd", Style::Xxd)
+}
+
+pub fn cmd_hexdump(
+    output: &mut TerminalState,
+    ctx: &mut ShellContext,
+    args: &Args,
+) -> CommandResult {
+    dump(output, ctx, args, "hexdump", Style::Canonical)
+}
+
+fn dump(
+    output: &mut TerminalState,
+    ctx: &mut ShellContext,
+    args: &Args,
+    cmd: &str,
+    style: Style,
+) -> CommandResult {
+    let Some(mode) = ColorMode::parse(args.value("color")) else {
+        return usage(output, ctx, cmd, "valor invalido para --color");
+    };
+
+    let cols = args.number("c").unwrap_or(16);
+    if cols == 0 || cols > MAX_COLS {
+        return usage(output, ctx, cmd, "-c deve estar entre 1 e 256");
+    }
+    let default_group = match style {
+        Style::Xxd => 2,
+        Style::Canonical => 8,
+    };
+    let group = args.number("g").unwrap_or(default_group).min(cols);
+
+    let length = match args.value("n").or(args.value("l")).map(parse_offset) {
+        None => u64::MAX,
+        Some(Some(Offset::Start(n))) => n,
+        Some(_) => return usage(output, ctx, cmd, "tamanho invalido"),
+    };
+    let seek = match args.value("s").map(parse_offset) {
+        None => Offset::Start(0),
+        Some(Some(offset)) => offset,
+        Some(None) => return usage(output, ctx, cmd, "offset invalido"),
+    };
+
+    let Some((file, size)) = open_input(output, ctx, cmd, args.positional.first().copied()) else {
+        return CommandResult::Ok;
+    };
+    let skip = match seek {
+        Offset::Start(n) => n,
+        Offset::FromEnd(n) => size.saturating_sub(n),
+    };
+
+    CommandResult::Task(Box::new(DumpTask {
+        file,
+        format: Format {
+            style,
+            cols: cols as usize,
+            group: group as usize,
+            colors: mode.enabled(output.is_capturing()),
+        },
+        skip,
+        remaining: length,
+        offset: skip,
+        line: Vec::new(),
+        shown: false,
+    }))
+}
+
+/// Escreve um erro de uso, com código de saída 2
+fn usage(
+    output: &mut TerminalState,
+    ctx: &mut ShellContext,
+    cmd: &str,
+    msg: &str,
+) -> CommandResult {
+    output.write_str(cmd);
+    output.write_str(": ");
+    output.write_line(msg);
+    ctx.last_exit_code = 2;
+    CommandResult::Ok
+}
+
+/// Posição de `-s`
+#[derive(Debug, Clone, Copy)]
+enum Offset {
+    /// A partir do início
+    Start(u64),
+    /// `-N`: N bytes antes do fim
+    FromEnd(u64),
+}
+
+/// Decimal ou `0x` hexadecimal; `-` conta do fim
+fn parse_offset(s: &str) -> Option<Offset> {
+    let (from_end, s) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s.strip_prefix('+').unwrap_or(s)),
+    };
+    let n = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16).ok()?,
+        None => s.parse().ok()?,
+    };
+    Some(if from_end {
+        Offset::FromEnd(n)
+    } else {
+        Offset::Start(n)
+    })
+}
+
+/// Layout das linhas
+struct Format {
+    style: Style,
+    /// Bytes por linha
+    cols: usize,
+    /// Bytes por grupo (0 = sem grupos)
+    group: usize,
+    colors: bool,
+}
+
+impl Format {
+    /// Uma linha: offset, hexa (completado até `cols`) e ASCII
+    fn row(&self, offset: u64, bytes: &[u8]) -> String {
+        let mut s = String::new();
+        let _ = match self.style {
+            Style::Xxd => write!(s, "{:08x}: ", offset),
+            Style::Canonical => write!(s, "{:08x}  ", offset),
+        };
+
+        let mut color = None;
+        for i in 0..self.cols {
+            match bytes.get(i) {
+                Some(&b) => {
+                    self.paint(&mut s, &mut color, b);
+                    let _ = write!(s, "{:02x}", b);
+                }
+                None => s.push_str("  "),
+            }
+            let last = i + 1 == self.cols;
+            if self.style == Style::Canonical {
+                s.push(' ');
+            }
+            if self.group > 0 && (i + 1) % self.group == 0 && !last {
+                s.push(' ');
+            }
+        }
+        reset(&mut s, &mut color);
+
+        s.push_str(match self.style {
+            Style::Xxd => "  ",
+            Style::Canonical => " |",
+        });
+        for &b in bytes {
+            self.paint(&mut s, &mut color, b);
+            s.push(if b.is_ascii_graphic() || b == b' ' {
+                b as char
+            } else {
+                '.'
+            });
+        }
+        reset(&mut s, &mut color);
+        if self.style == Style::Canonical {
+            s.push('|');
+        }
+        s
+    }
+
+    /// Troca a cor se a classe do byte mudou
+    fn paint(&self, s: &mut String, color: &mut Option<&'static str>, b: u8) {
+        if !self.colors {
+            return;
+        }
+        let class = byte_color(b);
+        if *color != Some(class) {
+            s.push_str("\x1b[");
+            s.push_str(class);
+            s.push('m');
+            *color = Some(class);
+        }
+    }
+}
+
+fn reset(s: &mut String, color: &mut Option<&'static str>) {
+    if color.take().is_some() {
+        s.push_str("\x1b[0m");
+    }
+}
+
+/// Parâmetros SGR da classe do byte
+fn byte_color(b: u8) -> &'static str {
+    match b {
+        0 => "90",
+        b'\t' | b'\n' | b'\r' | 0x0b | 0x0c => "33",
+        b' '..=b'~' => "32",
+        0x80.. => "31",
+        _ => "35",
+    }
+}
+
+/// Dump em andamento: um bloco por passo, linhas completas escritas logo
+struct DumpTask {
+    file: Box<dyn VfsFile>,
+    format: Format,
+    /// Bytes a descartar antes do início (`-s`)
+    skip: u64,
+    /// Bytes que ainda podem ser mostrados (`-n`)
+    remaining: u64,
+    /// Offset do primeiro byte de `line`
+    offset: u64,
+    /// Bytes da linha ainda incompleta
+    line: Vec<u8>,
+    /// Algum byte foi mostrado
+    shown: bool,
+}
+
+impl DumpTask {
+    /// Escreve a linha incompleta e, no `hexdump`, o offset final
+    fn finish(&mut self, output: &mut TerminalState) -> TaskStatus {
+        if !self.line.is_empty() {
+            output.write_line(&self.format.row(self.offset, &self.line));
+            self.offset += self.line.len() as u64;
+            self.line.clear();
+        }
+        if self.format.style == Style::Canonical && self.shown {
+            output.write_line(&format!("{:08x}", self.offset));
+        }
+        TaskStatus::Done
+    }
+}
+
+impl Task for DumpTask {
+    fn step(&mut self, ctx: &mut ShellContext, output: &mut TerminalState) -> TaskStatus {
+        if self.remaining == 0 {
+            return self.finish(output);
+        }
+
+        let mut buf = [0u8; CHUNK_SIZE];
+        let n = match self.file.read(&mut buf) {
+            Ok(0) => return self.finish(output),
+            Ok(n) => n,
+            Err(e) => {
+                output.write_str("erro ao ler: ");
+                output.write_line(error_to_str(e));
+                ctx.last_exit_code = 1;
+                return self.finish(output);
+            }
+        };
+
+        let mut data = &buf[..n];
+        if self.skip > 0 {
+            let k = (self.skip as usize).min(data.len());
+            data = &data[k..];
+            self.skip -= k as u64;
+        }
+        let take = (data.len() as u64).min(self.remaining) as usize;
+        self.remaining -= take as u64;
+        self.line.extend_from_slice(&data[..take]);
+        self.shown |= take > 0;
+
+        let cols = self.format.cols;
+        while self.line.len() >= cols {
+            output.write_line(&self.format.row(self.offset, &self.line[..cols]));
+            self.line.drain(..cols);
+            self.offset += cols as u64;
+        }
+        TaskStatus::Pending
+    }
+}
+
+// =============================================================================
+// xxd -r - Dump de volta para bytes
+// =============================================================================
+
+/// Lê um dump de `xxd` ou `hexdump -C` e grava os bytes em `destino`, ou
+/// os mostra como texto se não houver destino
+fn revert(output: &mut TerminalState, ctx: &mut ShellContext, args: &Args) {
+    let Some((mut file, _)) = open_input(output, ctx, "xxd", args.positional.first().copied())
+    else {
+        return;
+    };
+
+    let mut text = Vec::new();
+    let mut buf = [0u8; CHUNK_SIZE];
+    loop {
+        match file.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => text.extend_from_slice(&buf[..n]),
+            Err(e) => {
+                output.write_str("xxd: erro ao ler: ");
+                output.write_line(error_to_str(e));
+                ctx.last_exit_code = 1;
+                return;
+            }
+        }
+    }
+
+    let mut data: Vec<u8> = Vec::new();
+    for line in String::from_utf8_lossy(&text).lines() {
+        let Some((offset, bytes)) = parse_dump_line(line) else {
+            continue;
+        };
+        let start = offset as usize;
+        if data.len() < start + bytes.len() {
+            data.resize(start + bytes.len(), 0);
+        }
+        data[start..start + bytes.len()].copy_from_slice(&bytes);
+    }
+
+    let Some(dest) = args.positional.get(1) else {
+        output.write_str(&String::from_utf8_lossy(&data));
+        return;
+    };
+
+    let full_path = path::resolve(&ctx.cwd, dest);
+    let result = ctx.fs.create(&full_path).and_then(|mut out| {
+        let mut written = 0;
+        while written < data.len() {
+            match out.write(&data[written..])? {
+                0 => return Err(redpowder::SysError::IoError),
+                n => written += n,
+            }
+        }
+        Ok(())
+    });
+    if let Err(e) = result {
+        output.write_str("xxd: ");
+        output.write_str(&full_path);
+        output.write_str(": ");
+        output.write_line(error_to_str(e));
+        ctx.last_exit_code = 1;
+    }
+}
+
+/// Offset e bytes de uma linha de dump; `None` para linhas sem dados (o
+/// offset final do `hexdump`, `*`)
+fn parse_dump_line(line: &str) -> Option<(u64, Vec<u8>)> {
+    let line = line.trim_start();
+    let (offset, hex) = match line.split_once(':') {
+        // xxd: `offset: xxxx xxxx  ascii`; o hexa termina em dois espaços.
+        // Decidido pelo offset: a coluna ASCII pode ter `|` e `:`
+        Some((offset, rest)) if offset.bytes().all(|b| b.is_ascii_hexdigit()) => {
+            let rest = rest.strip_prefix(' ').unwrap_or(rest);
+            (offset, rest.split("  ").next().unwrap_or(""))
+        }
+        // hexdump -C: `offset  xx xx ...  |ascii|`
+        _ => line.split_once('|')?.0.split_once(' ')?,
+    };
+    let offset = u64::from_str_radix(offset.trim(), 16).ok()?;
+
+    let digits: Vec<u8> = hex
+        .bytes()
+        .filter(|b| *b != b' ')
+        .map_while(|b| (b as char).to_digit(16).map(|d| d as u8))
+        .collect();
+    let bytes = digits.chunks_exact(2).map(|p| p[0] << 4 | p[1]).collect();
+    Some((offset, bytes))
+}
+
+// =============================================================================
+// TESTES
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::parse_dump_line;
+    use crate::shell::testing::{bytes, run, shell};
+    use crate::shell::vfs::MemoryFs;
+    use crate::shell::ShellContext;
+    use alloc::vec::Vec;
+
+    /// `/bin.dat` com todos os bytes, de 0 a 255
+    fn binary() -> ShellContext {
+        let all: Vec<u8> = (0..=255).collect();
+        let mut fs = MemoryFs::new();
+        fs.add_file("/bin.dat", &all).add_file("/abc", b"abc\n");
+        shell(fs)
+    }
+
+    #[test]
+    fn xxd_and_hexdump_layouts() {
+        let mut ctx = binary();
+        assert_eq!(
+            run(&mut ctx, "xxd /abc"),
+            "00000000: 6162 630a                                abc.\n"
+        );
+        assert_eq!(
+            run(&mut ctx, "hexdump -C /abc"),
+            "00000000  61 62 63 0a                                       |abc.|\n00000004\n"
+        );
+    }
+
+    #[test]
+    fn seek_length_and_grouping() {
+        let mut ctx = binary();
+        assert_eq!(
+            run(&mut ctx, "xxd -s 0x41 -n 3 -g 1 /bin.dat"),
+            "00000041: 41 42 43                                         ABC\n"
+        );
+        assert_eq!(
+            run(&mut ctx, "xxd -s -2 -c 4 -g 0 /bin.dat"),
+            "000000fe: feff      ..\n"
+        );
+        assert_eq!(
+            run(&mut ctx, "xxd -c 0 /abc"),
+            "xxd: -c deve estar entre 1 e 256\n"
+        );
+        assert_eq!(ctx.last_exit_code, 2);
+    }
+
+    #[test]
+    fn revert_round_trips() {
+        let mut ctx = binary();
+        let all: Vec<u8> = (0..=255).collect();
+        for line in [
+            "xxd /bin.dat | xxd -r - /out",
+            "xxd -c 7 -g 0 /bin.dat | xxd -r - /out",
+            "xxd -g 3 /bin.dat | xxd -r - /out",
+            "hexdump -C /bin.dat | xxd -r - /out",
+        ] {
+            assert_eq!(run(&mut ctx, line), "", "{}", line);
+            assert_eq!(bytes(&ctx, "/out").as_deref(), Some(&all[..]), "{}", line);
+        }
+
+        // Offsets de `-s` voltam para o mesmo lugar
+        run(&mut ctx, "xxd -s 1 /abc | xxd -r - /out");
+        assert_eq!(bytes(&ctx, "/out").as_deref(), Some(&b"\0bc\n"[..]));
+    }
+
+    #[test]
+    fn dump_lines_with_separators_in_the_ascii_column() {
+        assert_eq!(
+            parse_dump_line("00000010: 7c3a 20  |: "),
+            Some((0x10, alloc::vec![0x7c, 0x3a, 0x20]))
+        );
+        assert_eq!(
+            parse_dump_line("00000010  7c 3a  |:||"),
+            Some((0x10, alloc::vec![0x7c, 0x3a]))
+        );
+        assert_eq!(parse_dump_line("00000012"), None);
+    }
+}
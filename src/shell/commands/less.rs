@@ -0,0 +1,63 @@
+//! # Less
+//!
+//! `less`/`more`: abre um arquivo ou a saída de um pipeline no pager
+//! (veja [`crate::shell::pager`]).
+
+use super::error_to_str;
+use super::fs::open_input;
+use super::CommandResult;
+use crate::shell::args::{self, Args};
+use crate::shell::pager::Pager;
+use crate::shell::ShellContext;
+use crate::state::terminal::TerminalState;
+use crate::state::utf8::Utf8Decoder;
+use alloc::boxed::Box;
+use alloc::string::String;
+
+const LESS_SYNOPSIS: &str = "less [opcoes] [arquivo]";
+
+pub fn cmd_less(output: &mut TerminalState, ctx: &mut ShellContext, args: &Args) -> CommandResult {
+    if args.positional.len() > 1 {
+        args::report_error(
+            output,
+            ctx,
+            "less",
+            LESS_SYNOPSIS,
+            "apenas um arquivo por vez",
+        );
+        return CommandResult::Ok;
+    }
+
+    let arg = args.positional.first().copied();
+    let Some((mut file, size)) = open_input(output, ctx, "less", arg) else {
+        return CommandResult::Ok;
+    };
+
+    let mut text = String::with_capacity(size as usize);
+    let mut decoder = Utf8Decoder::new();
+    let mut buf = [0u8; 1024];
+    loop {
+        match file.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => decoder.decode(&buf[..n], &mut text),
+            Err(e) => {
+                output.write_str("less: ");
+                output.write_line(error_to_str(e));
+                ctx.last_exit_code = 1;
+                return CommandResult::Ok;
+            }
+        }
+    }
+    decoder.finish(&mut text);
+
+    // Fora da tela (pipeline, `$(...)`) o texto segue adiante, como um `cat`
+    if output.is_capturing() {
+        output.write_str(&text);
+        return CommandResult::Ok;
+    }
+
+    let title = arg.filter(|a| *a != "-").unwrap_or("(entrada)");
+    CommandResult::Screen(Box::new(
+        Pager::new(title, &text).ignore_case(args.flag("i")),
+    ))
+}
@@ -3,13 +3,37 @@
 //! Dispatcher e implementação de comandos do shell.
 
 mod builtin;
+mod data;
+mod edit;
+mod fileops;
+mod find;
 mod fs;
+mod hexdump;
+mod less;
+pub mod registry;
 mod system;
+mod text;
 
+pub use registry::{Builtin, Category, Command, Registry};
+
+use super::args::OptSpec;
+use super::jobs::JobState;
+use super::launcher::{find_executable, Pid, StdinFeed};
+use super::parser::{self, ExpandError};
+use super::path;
+use super::screen::Screen;
+use super::suggest;
+use super::table::PipeData;
+use super::task::{self, Task, TaskStatus};
+use super::vfs::Vfs;
 use super::ShellContext;
+use crate::pty::Signal;
 use crate::state::terminal::TerminalState;
+use crate::state::utf8::Utf8Decoder;
+use alloc::boxed::Box;
 use alloc::string::String;
 use alloc::vec::Vec;
+use core::ops::Range;
 
 // TODO: Revisar no futuro
 #[allow(unused)]
@@ -23,102 +47,1545 @@ pub enum CommandResult {
     Clear,
     /// Erro com mensagem
     Error(String),
+    /// Programa externo iniciado; o terminal deve ligá-lo ao PTY
+    Spawned {
+        /// PID do processo
+        pid: Pid,
+        /// Linha de comando (para a tabela de jobs)
+        command: String,
+        /// Entrada de pipeline ainda não entregue
+        stdin: StdinFeed,
+    },
+    /// Comando longo; o terminal o avança a cada frame
+    Task(Box<dyn Task>),
+    /// Programa em tela cheia (`less`, `edit`); o terminal lhe entrega o
+    /// teclado até ele terminar
+    Screen(Box<dyn Screen>),
+    /// Comandos desconhecidos com correção; o terminal pede confirmação
+    /// (`[s/N]`) antes de executar `line`, a linha digitada com o nome de
+    /// cada um trocado pela sugestão
+    Correct { line: String },
 }
 
 /// Executa um comando
 pub fn execute_command(
-    cmd_line: &str,
+    line: &str,
     ctx: &mut ShellContext,
     output: &mut TerminalState,
 ) -> CommandResult {
     // Fazer trim e verificar se está vazio
-    let cmd_line = cmd_line.trim();
+    let cmd_line = line.trim();
     if cmd_line.is_empty() {
         return CommandResult::Ok;
     }
 
-    // Parsear argumentos (split por espaços, respeitando aspas simples)
-    let args = parse_args(cmd_line);
-    if args.is_empty() {
-        return CommandResult::Ok;
+    // `cmd &` roda em segundo plano
+    let (cmd_line, background) = split_background(cmd_line);
+
+    let stages = match parser::split_pipeline(cmd_line) {
+        Ok(stages) => stages,
+        Err(msg) => {
+            ctx.last_exit_code = 2;
+            return CommandResult::Error(msg);
+        }
+    };
+
+    // Só programas externos vão para segundo plano: um builtin roda dentro
+    // do próprio shell e não teria como virar job
+    if background {
+        let last = stages.last().and_then(|stage| stage.command.clone());
+        if let Some(name) = last
+            .map(|range| &cmd_line[range])
+            .filter(|name| ctx.commands.find(name).is_some())
+        {
+            output.write_line(&alloc::format!(
+                "{}: comando interno nao roda em segundo plano (&)",
+                name
+            ));
+            ctx.last_exit_code = 1;
+            return CommandResult::Ok;
+        }
+    }
+
+    // Com CORRECT definido, oferecer executar a linha corrigida
+    if ctx.var("CORRECT").is_some() && !output.is_capturing() {
+        if let Some(mut fixed) = corrected_line(cmd_line, &stages, ctx, output) {
+            if background {
+                fixed.push_str(" &");
+            }
+            output.write_str("Corrigir para '");
+            output.write_str(&fixed);
+            output.write_str("'? [s/N] ");
+            ctx.last_exit_code = 127;
+            return CommandResult::Correct { line: fixed };
+        }
+    }
+
+    let mut run = Line::new(cmd_line, &stages, background, ctx.stdin.take());
+    match run.resume(ctx, output) {
+        Some(result) => result,
+        None => CommandResult::Task(Box::new(run)),
     }
+}
+
+/// `line` com o comando desconhecido de cada estágio trocado pela sugestão,
+/// ou `None` se não há o que corrigir.
+///
+/// Só vale para nomes digitados como tal: com aspas ou expansões, a
+/// palavra não é o nome que o comando recebe. Se algum comando desconhecido
+/// não tem sugestão, a linha roda como está.
+fn corrected_line(
+    line: &str,
+    stages: &[parser::Stage],
+    ctx: &ShellContext,
+    output: &mut TerminalState,
+) -> Option<String> {
+    let path_var = ctx.var("PATH").unwrap_or_default();
+    let mut fixes: Vec<(Range<usize>, String)> = Vec::new();
+    for range in stages.iter().filter_map(|stage| stage.command.clone()) {
+        let name = &line[range.clone()];
+        if name.starts_with('~')
+            || name.contains(['\'', '"', '$', '\\', '`'])
+            || ctx.commands.find(name).is_some()
+            || find_executable(ctx.launcher.as_ref(), name, &path_var, &ctx.cwd).is_some()
+        {
+            continue;
+        }
+        fixes.push((range, suggest::command(ctx, name)?));
+    }
+    if fixes.is_empty() {
+        return None;
+    }
+
+    let mut fixed = String::new();
+    let mut copied = 0;
+    for (range, to) in fixes {
+        output.write_str("Comando nao encontrado: ");
+        output.write_line(&line[range.clone()]);
+        fixed.push_str(&line[copied..range.start]);
+        fixed.push_str(&to);
+        copied = range.end;
+    }
+    fixed.push_str(&line[copied..]);
+    Some(fixed)
+}
+
+/// Linha em execução: um comando ou um pipeline `a | b | c`.
+///
+/// Cada estágio, menos o último, roda com a saída capturada, que vira a
+/// entrada (`ctx.stdin`) do seguinte. Se o próximo estágio consome tabelas,
+/// o estágio atual entrega registros em `ctx.table_out` em vez de texto.
+///
+/// Quando um estágio ou um `$(...)` dele devolve uma tarefa (um programa
+/// externo numa captura, por exemplo), a linha inteira vira uma [`Task`]:
+/// cada passo avança a tarefa esperada e, quando ela termina, a linha
+/// continua do ponto onde parou.
+struct Line {
+    stages: Vec<String>,
+    /// Palavra do comando de cada estágio, como digitada
+    commands: Vec<Option<String>>,
+    background: bool,
+    /// Estágio atual
+    index: usize,
+    /// Entrada do estágio atual, que filtros como `tr` leem aos poucos:
+    /// fica em `ctx.stdin` só durante os passos do estágio
+    input: Option<PipeData>,
+    /// Expansões já feitas no estágio atual
+    memo: parser::Memo,
+    /// Tarefa pela qual a linha espera
+    waiting: Option<Waiting>,
+    /// Resultado do último estágio, entregue em [`Task::finish`]
+    result: CommandResult,
+}
+
+/// O que a linha espera
+enum Waiting {
+    /// `$(...)` do estágio atual; a saída vai para o `memo`
+    Substitution {
+        task: Box<dyn Task>,
+        text: String,
+        slot: usize,
+    },
+    /// Estágio do meio; a saída vira a entrada do próximo
+    Stage { task: Box<dyn Task>, text: String },
+    /// Último estágio, escrevendo direto na saída
+    Last(Box<dyn Task>),
+}
+
+impl Line {
+    fn new(
+        line: &str,
+        stages: &[parser::Stage],
+        background: bool,
+        input: Option<PipeData>,
+    ) -> Self {
+        Self {
+            stages: stages.iter().map(|s| String::from(s.text)).collect(),
+            commands: stages
+                .iter()
+                .map(|s| s.command.clone().map(|range| String::from(&line[range])))
+                .collect(),
+            background,
+            index: 0,
+            input,
+            memo: parser::Memo::default(),
+            waiting: None,
+            result: CommandResult::Ok,
+        }
+    }
+
+    /// Roda estágios até a linha terminar (`Some`) ou ter que esperar uma
+    /// tarefa (`None`, com `waiting` preenchido)
+    fn resume(
+        &mut self,
+        ctx: &mut ShellContext,
+        output: &mut TerminalState,
+    ) -> Option<CommandResult> {
+        while let Some(stage) = self.stages.get(self.index) {
+            ctx.stdin = self.input.take();
+
+            let Some(next) = self.commands.get(self.index + 1) else {
+                // Último estágio: escreve na saída da linha
+                let result = run_simple(stage, &mut self.memo, ctx, output, self.background);
+                return match result {
+                    Err(pending) => {
+                        self.wait_substitution(pending, ctx);
+                        None
+                    }
+                    Ok(CommandResult::Task(task)) => {
+                        self.input = ctx.stdin.take();
+                        self.waiting = Some(Waiting::Last(task));
+                        None
+                    }
+                    Ok(result) => {
+                        ctx.stdin = None;
+                        Some(result)
+                    }
+                };
+            };
+
+            ctx.pipe_table = takes_table(ctx, next.as_deref());
+            output.begin_capture();
+            let result = run_simple(stage, &mut self.memo, ctx, output, false);
+            let text = output.end_capture();
+            ctx.pipe_table = false;
+
+            match result {
+                Err(pending) => {
+                    self.wait_substitution(pending, ctx);
+                    return None;
+                }
+                Ok(CommandResult::Error(msg)) => {
+                    ctx.stdin = None;
+                    ctx.table_out = None;
+                    return Some(CommandResult::Error(msg));
+                }
+                Ok(CommandResult::Task(task)) => {
+                    self.input = ctx.stdin.take();
+                    self.waiting = Some(Waiting::Stage { task, text });
+                    return None;
+                }
+                Ok(_) => {
+                    ctx.stdin = None;
+                    self.next_stage(ctx, text);
+                }
+            }
+        }
+
+        Some(CommandResult::Ok)
+    }
+
+    /// Guarda a entrada do estágio até o `$(...)` terminar
+    fn wait_substitution(&mut self, pending: parser::Pending, ctx: &mut ShellContext) {
+        self.input = ctx.stdin.take();
+        self.waiting = Some(Waiting::Substitution {
+            task: pending.task,
+            text: pending.output,
+            slot: pending.slot,
+        });
+    }
+
+    /// Passa a saída do estágio atual para o próximo
+    fn next_stage(&mut self, ctx: &mut ShellContext, text: String) {
+        self.input = Some(match ctx.table_out.take() {
+            Some(table) => PipeData::Table(table),
+            None => PipeData::Text(text),
+        });
+        self.index += 1;
+        self.memo = parser::Memo::default();
+    }
+}
+
+impl Task for Line {
+    fn step(&mut self, ctx: &mut ShellContext, output: &mut TerminalState) -> TaskStatus {
+        match self.waiting.take() {
+            Some(Waiting::Last(mut task)) => {
+                ctx.stdin = self.input.take();
+                let status = task.step(ctx, output);
+                self.input = ctx.stdin.take();
+                if status != TaskStatus::Done {
+                    self.waiting = Some(Waiting::Last(task));
+                    return status;
+                }
+                self.result = task.finish();
+                return TaskStatus::Done;
+            }
+            // Saídas capturadas não têm teclado, como em `task::run_to_end`
+            Some(Waiting::Stage { mut task, mut text }) => {
+                ctx.stdin = self.input.take();
+                output.begin_capture();
+                let done = task::step_unattended(task.as_mut(), ctx, output);
+                text.push_str(&output.end_capture());
+                self.input = ctx.stdin.take();
+                if !done {
+                    self.waiting = Some(Waiting::Stage { task, text });
+                    return TaskStatus::Pending;
+                }
+                self.next_stage(ctx, text);
+            }
+            Some(Waiting::Substitution {
+                mut task,
+                mut text,
+                slot,
+            }) => {
+                output.begin_capture();
+                let done = task::step_unattended(task.as_mut(), ctx, output);
+                text.push_str(&output.end_capture());
+                if !done {
+                    self.waiting = Some(Waiting::Substitution { task, text, slot });
+                    return TaskStatus::Pending;
+                }
+                self.memo.fill(slot, text);
+            }
+            None => {}
+        }
+
+        match self.resume(ctx, output) {
+            Some(result) => {
+                self.result = result;
+                TaskStatus::Done
+            }
+            None => TaskStatus::Pending,
+        }
+    }
+
+    fn answer(&mut self, yes: bool) {
+        if let Some(Waiting::Last(task)) = self.waiting.as_mut() {
+            task.answer(yes);
+        }
+    }
+
+    fn input(&mut self, text: Option<&str>) {
+        if let Some(Waiting::Last(task)) = self.waiting.as_mut() {
+            task.input(text);
+        }
+    }
+
+    fn cancel(&mut self, ctx: &mut ShellContext) {
+        match self.waiting.as_mut() {
+            Some(Waiting::Substitution { task, .. } | Waiting::Stage { task, .. }) => {
+                task.cancel(ctx)
+            }
+            Some(Waiting::Last(task)) => task.cancel(ctx),
+            None => {}
+        }
+    }
+
+    fn finish(&mut self) -> CommandResult {
+        core::mem::replace(&mut self.result, CommandResult::Ok)
+    }
+}
+
+/// O estágio começa com um comando que consome tabelas
+fn takes_table(ctx: &ShellContext, command: Option<&str>) -> bool {
+    command
+        .and_then(|name| ctx.commands.find(name))
+        .is_some_and(|command| command.takes_table())
+}
+
+/// Executa um comando simples (sem `|`).
+///
+/// Um `$(...)` que precisa esperar uma tarefa interrompe o comando antes
+/// de ele rodar; as expansões já feitas ficam em `memo`.
+fn run_simple(
+    cmd_line: &str,
+    memo: &mut parser::Memo,
+    ctx: &mut ShellContext,
+    output: &mut TerminalState,
+    background: bool,
+) -> Result<CommandResult, parser::Pending> {
+    // Separar palavras e aplicar expansões ($VAR, $(cmd), $((expr)))
+    let parser::Words { words, assigns } = match parser::expand_line(cmd_line, ctx, output, memo) {
+        Ok(words) => words,
+        Err(ExpandError::Pending(pending)) => return Err(pending),
+        Err(ExpandError::Invalid(msg)) => {
+            ctx.last_exit_code = 1;
+            return Ok(CommandResult::Error(msg));
+        }
+    };
+
+    // Só atribuições (NOME=valor)
+    if assigns == words.len() {
+        for word in &words {
+            if let Some((name, value)) = word.split_once('=') {
+                ctx.set_var(name, value);
+            }
+        }
+        ctx.last_exit_code = 0;
+        return Ok(CommandResult::Ok);
+    }
+
+    // Atribuições antes de um comando valem apenas para ele
+    let mut saved: Vec<(String, Option<String>)> = Vec::new();
+    for word in &words[..assigns] {
+        if let Some((name, value)) = word.split_once('=') {
+            saved.push((String::from(name), ctx.vars.get(name).cloned()));
+            ctx.set_var(name, value);
+        }
+    }
+
+    let cmd = words[assigns].as_str();
+    let args: Vec<&str> = words[assigns + 1..].iter().map(|s| s.as_str()).collect();
+    let mut result = dispatch(cmd, &args, ctx, output, background);
+
+    // Numa captura (pipeline, `$(...)`) não há terminal para o comando: um
+    // processo não pode ser ligado ao PTY e volta para a tabela de jobs em
+    // vez de ficar sem dono
+    if output.is_capturing() {
+        if let CommandResult::Spawned {
+            pid,
+            command,
+            stdin,
+        } = result
+        {
+            let id = ctx.jobs.add(pid, &command, JobState::Running, stdin);
+            output.write_line(&alloc::format!("[{}] {}", id, pid));
+            result = CommandResult::Ok;
+        }
+    }
+
+    for (name, old) in saved.into_iter().rev() {
+        match old {
+            Some(value) => ctx.set_var(&name, &value),
+            None => ctx.unset_var(&name),
+        }
+    }
+
+    Ok(result)
+}
+
+/// Separa o `&` final (segundo plano), ignorando `&&` e `\&`
+fn split_background(cmd_line: &str) -> (&str, bool) {
+    match cmd_line.strip_suffix('&') {
+        Some(rest) if !rest.ends_with('&') && !rest.ends_with('\\') => (rest.trim_end(), true),
+        _ => (cmd_line, false),
+    }
+}
+
+/// Despacha o comando para a implementação correspondente.
+///
+/// `background` só chega verdadeiro para programas externos:
+/// [`execute_command`] recusa `&` em builtins.
+fn dispatch(
+    cmd: &str,
+    args: &[&str],
+    ctx: &mut ShellContext,
+    output: &mut TerminalState,
+    background: bool,
+) -> CommandResult {
+    ctx.last_exit_code = 0;
 
-    let cmd = args[0].as_str();
-    let args: Vec<&str> = args.iter().skip(1).map(|s| s.as_str()).collect();
-
-    // Dispatcher de comandos
-    match cmd {
-        // === BUILTIN ===
-        "help" => builtin::cmd_help(output, &args),
-        "clear" => return CommandResult::Clear,
-        "exit" | "quit" => return CommandResult::Exit,
-        "echo" => builtin::cmd_echo(output, &args),
-        "ver" | "version" => builtin::cmd_version(output),
-
-        // === FILESYSTEM ===
-        "ls" | "dir" => fs::cmd_ls(output, ctx, &args),
-        "cd" => fs::cmd_cd(output, ctx, &args),
-        "pwd" => fs::cmd_pwd(output, ctx),
-        "cat" | "type" => fs::cmd_cat(output, ctx, &args),
-        "tree" => fs::cmd_tree(output, ctx, &args),
-        "mkdir" => fs::cmd_mkdir(output, ctx, &args),
-        "rmdir" => fs::cmd_rmdir(output, ctx, &args),
-        "rm" | "del" => fs::cmd_rm(output, ctx, &args),
-        "cp" | "copy" => fs::cmd_cp(output, ctx, &args),
-        "mv" | "move" | "rename" => fs::cmd_mv(output, ctx, &args),
-        "stat" => fs::cmd_stat(output, ctx, &args),
-
-        // === SYSTEM ===
-        "uptime" => system::cmd_uptime(output),
-        "ps" => system::cmd_ps(output, &args),
-        "kill" => system::cmd_kill(output, &args),
-        "top" => system::cmd_top(output),
-        "jobs" => system::cmd_jobs(output),
-        "sysinfo" => system::cmd_sysinfo(output),
-        "meminfo" => system::cmd_meminfo(output),
-
-        // === DESCONHECIDO ===
-        _ => {
+    match ctx.commands.find(cmd) {
+        Some(command) => command.run(args, ctx, output),
+        None => run_external(cmd, args, ctx, output, background),
+    }
+}
+
+// =============================================================================
+// TABELA DE BUILTINS
+// =============================================================================
+
+/// Executa um comando que não produz resultado especial
+macro_rules! ok {
+    ($e:expr) => {{
+        $e;
+        CommandResult::Ok
+    }};
+}
+
+/// Comandos internos, na ordem do `help`
+pub(crate) static BUILTINS: &[Builtin] = &[
+    // === FILESYSTEM ===
+    Builtin {
+        name: "ls",
+        aliases: &["dir"],
+        category: Category::Files,
+        synopsis: "ls [opcoes] [caminho]...",
+        summary: "Lista arquivos e diretorios",
+        help: "Cores por tipo configuraveis em LS_COLORS (ex.: di=1;34:ex=1;32:*.zip=31);\nsem cores em pipes e capturas.\n\nEXEMPLOS:\n  ls\n  ls /apps\n  ls -lh /system\n  ls -lSr",
+        options: &[
+            OptSpec::flag(Some('l'), None, "Lista detalhada (permissoes, tamanho)"),
+            OptSpec::flag(Some('a'), None, "Mostra arquivos ocultos"),
+            OptSpec::flag(Some('h'), None, "Tamanhos legiveis (1.5K, 23M)"),
+            OptSpec::flag(Some('S'), None, "Ordena por tamanho, maior primeiro"),
+            OptSpec::flag(Some('t'), None, "Ordena por data, mais novo primeiro"),
+            OptSpec::flag(Some('r'), None, "Inverte a ordem"),
+            OptSpec::flag(Some('1'), None, "Um nome por linha"),
+            OptSpec::value(None, Some("color"), "quando", "Cores: auto, always ou never"),
+            OptSpec::flag(None, Some("json"), "Saida em JSON"),
+        ],
+        run: |args, ctx, out| ok!(fs::cmd_ls(out, ctx, args)),
+    },
+    Builtin {
+        name: "cd",
+        aliases: &[],
+        category: Category::Files,
+        synopsis: "cd [opcoes] [caminho]",
+        summary: "Muda diretorio atual",
+        help: "Sem caminho, vai para $HOME. 'cd -' volta ao diretorio anterior\n($OLDPWD). Caminhos relativos sao procurados tambem nos diretorios\nde $CDPATH (separados por ':').\n\nEXEMPLOS:\n  cd /apps\n  cd ..\n  cd -\n  cd ~/projetos",
+        options: &[
+            OptSpec::flag(Some('L'), None, "Segue links logicamente: '..' volta pelo link (padrao)"),
+            OptSpec::flag(Some('P'), None, "Resolve links; PWD fica com o caminho real"),
+        ],
+        run: |args, ctx, out| ok!(fs::cmd_cd(out, ctx, args)),
+    },
+    Builtin {
+        name: "pwd",
+        aliases: &[],
+        category: Category::Files,
+        synopsis: "pwd [opcoes]",
+        summary: "Mostra diretorio atual",
+        help: "",
+        options: &[
+            OptSpec::flag(Some('L'), None, "Caminho logico, com links (padrao)"),
+            OptSpec::flag(Some('P'), None, "Caminho real, sem links"),
+        ],
+        run: |args, ctx, out| ok!(fs::cmd_pwd(out, ctx, args)),
+    },
+    Builtin {
+        name: "pushd",
+        aliases: &[],
+        category: Category::Files,
+        synopsis: "pushd [caminho | +N]",
+        summary: "Empilha o diretorio atual e muda para outro",
+        help: "Sem argumento, troca o diretorio atual com o topo da pilha.\n+N gira a pilha ate a entrada N (ver 'dirs -v') ficar no topo.\n\nEXEMPLOS:\n  pushd /system\n  pushd\n  pushd +2",
+        options: &[],
+        run: |args, ctx, out| ok!(fs::cmd_pushd(out, ctx, args)),
+    },
+    Builtin {
+        name: "popd",
+        aliases: &[],
+        category: Category::Files,
+        synopsis: "popd [+N]",
+        summary: "Volta ao diretorio do topo da pilha",
+        help: "+N remove a entrada N da pilha sem mudar de diretorio.\n\nEXEMPLOS:\n  popd\n  popd +1",
+        options: &[],
+        run: |args, ctx, out| ok!(fs::cmd_popd(out, ctx, args)),
+    },
+    Builtin {
+        name: "dirs",
+        aliases: &[],
+        category: Category::Files,
+        synopsis: "dirs [opcoes]",
+        summary: "Mostra a pilha de diretorios",
+        help: "",
+        options: &[
+            OptSpec::flag(Some('c'), None, "Esvazia a pilha"),
+            OptSpec::flag(Some('l'), None, "Nao abrevia $HOME para ~"),
+            OptSpec::flag(Some('p'), None, "Uma entrada por linha"),
+            OptSpec::flag(Some('v'), None, "Uma entrada por linha, com o indice"),
+        ],
+        run: |args, ctx, out| ok!(fs::cmd_dirs(out, ctx, args)),
+    },
+    Builtin {
+        name: "cat",
+        aliases: &["type"],
+        category: Category::Files,
+        synopsis: "cat [opcoes] [arquivo]...",
+        summary: "Mostra conteudo de arquivo",
+        help: "Sem arquivo, ou com '-', le a entrada do pipeline.\n\nEXEMPLOS:\n  cat /apps/config.txt\n  cat -n notas.txt\n  ls | cat -A",
+        options: &[
+            OptSpec::flag(Some('n'), Some("number"), "Numera as linhas"),
+            OptSpec::flag(Some('s'), Some("squeeze-blank"), "Junta linhas em branco seguidas"),
+            OptSpec::flag(Some('A'), Some("show-all"), "O mesmo que -vET"),
+            OptSpec::flag(Some('v'), Some("show-nonprinting"), "Controles como ^X e DEL como ^?"),
+            OptSpec::flag(Some('E'), Some("show-ends"), "Mostra $ no fim das linhas"),
+            OptSpec::flag(Some('T'), Some("show-tabs"), "Mostra TAB como ^I"),
+        ],
+        run: |args, ctx, out| fs::cmd_cat(out, ctx, args),
+    },
+    Builtin {
+        name: "xxd",
+        aliases: &[],
+        category: Category::Files,
+        synopsis: "xxd [opcoes] [arquivo]  |  xxd -r [dump [destino]]",
+        summary: "Mostra bytes em hexadecimal",
+        help: "Sem arquivo, ou com '-', le a entrada do pipeline. Com -s, um\nvalor negativo conta a partir do fim.\n\n-r converte um dump (de xxd ou hexdump -C) de volta para bytes.\n\nEXEMPLOS:\n  xxd /apps/shell\n  xxd -s 0x40 -l 32 -g 1 dados.bin\n  xxd -r dump.txt dados.bin",
+        options: &[
+            OptSpec::value(Some('s'), Some("seek"), "offset", "Comeca no offset (decimal ou 0x)"),
+            OptSpec::value(Some('l'), Some("len"), "n", "Mostra no maximo n bytes"),
+            OptSpec::value(Some('n'), None, "n", "O mesmo que -l"),
+            OptSpec::number(Some('c'), Some("cols"), "n", "Bytes por linha (padrao 16)"),
+            OptSpec::number(Some('g'), Some("groupsize"), "n", "Bytes por grupo (padrao 2; 0 = sem grupos)"),
+            OptSpec::flag(Some('r'), Some("revert"), "Converte um dump de volta para bytes"),
+            OptSpec::value(None, Some("color"), "quando", "Cores: auto, always ou never"),
+        ],
+        run: |args, ctx, out| hexdump::cmd_xxd(out, ctx, args),
+    },
+    Builtin {
+        name: "hexdump",
+        aliases: &["hd"],
+        category: Category::Files,
+        synopsis: "hexdump [-C] [opcoes] [arquivo]",
+        summary: "Mostra bytes em hexadecimal e ASCII",
+        help: "Formato canonico (-C, o unico suportado): offset, 16 bytes e a\ncoluna ASCII entre '|'. A ultima linha traz o tamanho total.\n\nEXEMPLOS:\n  hexdump -C /apps/shell\n  hexdump -C -s 512 -n 64 disco.img\n  echo oi | hexdump -C",
+        options: &[
+            OptSpec::flag(Some('C'), Some("canonical"), "Hexadecimal + ASCII (padrao)"),
+            OptSpec::value(Some('s'), Some("skip"), "offset", "Pula os primeiros bytes (decimal ou 0x)"),
+            OptSpec::value(Some('n'), Some("length"), "n", "Mostra no maximo n bytes"),
+            OptSpec::number(Some('g'), Some("group"), "n", "Bytes por grupo (padrao 8)"),
+            OptSpec::value(None, Some("color"), "quando", "Cores: auto, always ou never"),
+        ],
+        run: |args, ctx, out| hexdump::cmd_hexdump(out, ctx, args),
+    },
+    Builtin {
+        name: "less",
+        aliases: &["more"],
+        category: Category::Files,
+        synopsis: "less [opcoes] [arquivo]",
+        summary: "Mostra texto em tela cheia, com rolagem e busca",
+        help: "Sem arquivo, ou com '-', le a entrada do pipeline. Num pipeline ou\ncaptura, apenas repassa o texto.\n\nTECLAS:\n  j, Enter, seta abaixo    Desce uma linha\n  k, seta acima            Sobe uma linha\n  Espaco, f, PgDn          Proxima pagina\n  b, PgUp                  Pagina anterior\n  d / u                    Meia pagina abaixo / acima\n  g / G                    Inicio / fim\n  /padrao, ?padrao         Busca para frente / para tras\n  n / N                    Proxima ocorrencia / anterior\n  q                        Sai\n\nEXEMPLOS:\n  less /apps/config.txt\n  tree / | less\n  less -i notas.txt",
+        options: &[OptSpec::flag(Some('i'), Some("ignore-case"), "Busca sem diferenciar maiusculas")],
+        run: |args, ctx, out| less::cmd_less(out, ctx, args),
+    },
+    Builtin {
+        name: "edit",
+        aliases: &[],
+        category: Category::Files,
+        synopsis: "edit <arquivo>",
+        summary: "Edita arquivo de texto em tela cheia",
+        help: "Um arquivo que nao existe e criado ao salvar. Os atalhos ficam no\nrodape da tela.\n\nTECLAS:\n  setas, Home, End      Move o cursor (^A / ^E: inicio / fim da linha)\n  PgUp, PgDn            Pagina anterior / seguinte (^Y / ^V)\n  ^O                    Salva, perguntando o nome\n  ^S                    Salva no mesmo arquivo\n  ^W                    Busca texto (vazio repete a ultima busca)\n  ^K / ^U               Recorta a linha / cola\n  ^C                    Mostra linha e coluna\n  ^X                    Sai (pergunta se ha alteracoes)\n\nEXEMPLOS:\n  edit /apps/config.txt\n  edit notas.txt",
+        options: &[],
+        run: |args, ctx, out| edit::cmd_edit(out, ctx, args),
+    },
+    Builtin {
+        name: "tree",
+        aliases: &[],
+        category: Category::Files,
+        synopsis: "tree [caminho]... [opcoes]",
+        summary: "Mostra arvore de diretorios",
+        help: "EXEMPLOS:\n  tree\n  tree /system\n  tree / -L 2\n  tree -d -L 1 /\n  tree -ah --noreport",
+        options: &[
+            OptSpec::number(Some('L'), Some("depth"), "n", "Profundidade maxima"),
+            OptSpec::flag(Some('a'), None, "Mostra arquivos ocultos"),
+            OptSpec::flag(Some('d'), None, "Lista so diretorios"),
+            OptSpec::flag(Some('l'), None, "Entra em links para diretorios"),
+            OptSpec::flag(Some('s'), None, "Mostra o tamanho de cada arquivo"),
+            OptSpec::flag(Some('h'), None, "Tamanhos legiveis (1.5K, 23M)"),
+            OptSpec::flag(None, Some("noreport"), "Omite o total de diretorios e arquivos"),
+            OptSpec::value(None, Some("color"), "quando", "Cores: auto, always ou never"),
+            OptSpec::flag(None, Some("json"), "Saida em JSON (arvore aninhada)"),
+        ],
+        run: |args, ctx, out| fs::cmd_tree(out, ctx, args),
+    },
+    Builtin {
+        name: "find",
+        aliases: &[],
+        category: Category::Files,
+        synopsis: "find [caminho...] [expressao]",
+        summary: "Procura arquivos por nome, tipo, tamanho...",
+        help: "TESTES:\n  -name glob, -iname glob   Nome casa com o glob (*, ?, [...])\n  -type f|d|l               Arquivo, diretorio ou link\n  -size [+-]N[ckMG]         Tamanho (sem sufixo: blocos de 512 bytes)\n  -newer arquivo            Modificado depois do arquivo\n  -maxdepth n, -mindepth n  Limites de profundidade\n\nACOES:\n  -print, -print0           Escreve o caminho (fim de linha / NUL)\n  -exec cmd {} ;            Roda cmd com {} trocado pelo caminho\n\nOPERADORES: ( expr ), ! expr, -not, -a, -and, -o, -or\nSem acao, os caminhos que passarem na expressao sao impressos.\n\nEXEMPLOS:\n  find /apps -name '*.txt'\n  find . -type d -maxdepth 2\n  find / -size +1M -not -name '*.img'\n  find . ( -name '*.log' -o -name '*.tmp' ) -exec rm {} ;",
+        options: &[],
+        run: |args, ctx, out| find::cmd_find(out, ctx, args),
+    },
+    Builtin {
+        name: "stat",
+        aliases: &[],
+        category: Category::Files,
+        synopsis: "stat [opcoes] <caminho>...",
+        summary: "Mostra informacoes de arquivo",
+        help: "",
+        options: &[OptSpec::flag(None, Some("json"), "Saida em JSON")],
+        run: |args, ctx, out| ok!(fs::cmd_stat(out, ctx, args)),
+    },
+    Builtin {
+        name: "mkdir",
+        aliases: &[],
+        category: Category::Files,
+        synopsis: "mkdir [opcoes] <caminho>...",
+        summary: "Cria diretorio",
+        help: "EXEMPLOS:\n  mkdir /tmp/teste\n  mkdir -p /tmp/a/b/c",
+        options: &[
+            OptSpec::flag(Some('p'), Some("parents"), "Cria os diretorios pais; ignora existentes"),
+            OptSpec::flag(Some('v'), Some("verbose"), "Mostra cada diretorio criado"),
+        ],
+        run: |args, ctx, out| ok!(fileops::cmd_mkdir(out, ctx, args)),
+    },
+    Builtin {
+        name: "rmdir",
+        aliases: &[],
+        category: Category::Files,
+        synopsis: "rmdir [opcoes] <caminho>...",
+        summary: "Remove diretorio vazio",
+        help: "EXEMPLOS:\n  rmdir /tmp/teste\n  rmdir -p /tmp/a/b/c",
+        options: &[
+            OptSpec::flag(Some('p'), Some("parents"), "Remove tambem os pais que ficarem vazios"),
+            OptSpec::flag(Some('v'), Some("verbose"), "Mostra cada diretorio removido"),
+        ],
+        run: |args, ctx, out| ok!(fileops::cmd_rmdir(out, ctx, args)),
+    },
+    Builtin {
+        name: "rm",
+        aliases: &["del"],
+        category: Category::Files,
+        synopsis: "rm [opcoes] <caminho>...",
+        summary: "Remove arquivos e diretorios",
+        help: "Recusa remover '/', '.' e '..'.\n\nEXEMPLOS:\n  rm notas.txt\n  rm -ri /tmp/antigo\n  rm -rf /tmp/cache",
+        options: &[
+            OptSpec::flag(Some('r'), Some("recursive"), "Remove diretorios e seu conteudo"),
+            OptSpec::flag(Some('R'), None, "O mesmo que -r"),
+            OptSpec::flag(Some('f'), Some("force"), "Ignora inexistentes; nunca pergunta"),
+            OptSpec::flag(Some('i'), Some("interactive"), "Pergunta antes de cada remocao"),
+            OptSpec::flag(Some('v'), Some("verbose"), "Mostra cada remocao"),
+        ],
+        run: |args, ctx, out| fileops::cmd_rm(out, ctx, args),
+    },
+    Builtin {
+        name: "cp",
+        aliases: &["copy"],
+        category: Category::Files,
+        synopsis: "cp [opcoes] <origem>... <destino>",
+        summary: "Copia arquivos e diretorios",
+        help: "Com varias origens, o destino deve ser um diretorio.\n\nEXEMPLOS:\n  cp a.txt b.txt\n  cp -r /apps /tmp/apps\n  cp -n rede.cfg tela.cfg /backup",
+        options: &[
+            OptSpec::flag(Some('r'), Some("recursive"), "Copia diretorios e seu conteudo"),
+            OptSpec::flag(Some('R'), None, "O mesmo que -r"),
+            OptSpec::flag(Some('i'), Some("interactive"), "Pergunta antes de sobrescrever"),
+            OptSpec::flag(Some('n'), Some("no-clobber"), "Nunca sobrescreve"),
+            OptSpec::flag(Some('p'), Some("preserve"), "Preserva a data de modificacao"),
+            OptSpec::flag(Some('v'), Some("verbose"), "Mostra cada copia"),
+        ],
+        run: |args, ctx, out| fileops::cmd_cp(out, ctx, args),
+    },
+    Builtin {
+        name: "mv",
+        aliases: &["move", "rename"],
+        category: Category::Files,
+        synopsis: "mv [opcoes] <origem>... <destino>",
+        summary: "Move/renomeia arquivos",
+        help: "Entre sistemas de arquivos diferentes, copia e remove a origem.\n\nEXEMPLOS:\n  mv velho.txt novo.txt\n  mv -i boot.log app.log /tmp",
+        options: &[
+            OptSpec::flag(Some('i'), Some("interactive"), "Pergunta antes de sobrescrever"),
+            OptSpec::flag(Some('n'), Some("no-clobber"), "Nunca sobrescreve"),
+            OptSpec::flag(Some('f'), Some("force"), "Sobrescreve sem perguntar"),
+            OptSpec::flag(Some('v'), Some("verbose"), "Mostra cada movimentacao"),
+        ],
+        run: |args, ctx, out| fileops::cmd_mv(out, ctx, args),
+    },
+    Builtin {
+        name: "touch",
+        aliases: &[],
+        category: Category::Files,
+        synopsis: "touch [opcoes] <arquivo>...",
+        summary: "Cria arquivo vazio ou atualiza a data",
+        help: "EXEMPLOS:\n  touch notas.txt\n  touch -c /apps/config.txt",
+        options: &[OptSpec::flag(Some('c'), Some("no-create"), "Nao cria arquivos que nao existem")],
+        run: |args, ctx, out| ok!(fileops::cmd_touch(out, ctx, args)),
+    },
+    Builtin {
+        name: "truncate",
+        aliases: &[],
+        category: Category::Files,
+        synopsis: "truncate -s <tamanho> <arquivo>...",
+        summary: "Define o tamanho de arquivos",
+        help: "O tamanho aceita os sufixos K, M e G (potencias de 1024). Com '+' ou\n'-', aumenta ou diminui o tamanho atual. O espaco novo e preenchido\ncom zeros.\n\nEXEMPLOS:\n  truncate -s 0 log.txt\n  truncate -s 1M disco.img\n  truncate -s +512 dados.bin",
+        options: &[
+            OptSpec::value(Some('s'), Some("size"), "tamanho", "Novo tamanho, em bytes"),
+            OptSpec::flag(Some('c'), Some("no-create"), "Nao cria arquivos que nao existem"),
+        ],
+        run: |args, ctx, out| fileops::cmd_truncate(out, ctx, args),
+    },
+    Builtin {
+        name: "write",
+        aliases: &[],
+        category: Category::Files,
+        synopsis: "write <arquivo>",
+        summary: "Grava no arquivo o texto digitado",
+        help: "Le linhas do teclado ate Ctrl+D, como 'cat > arquivo'; Ctrl+C\ninterrompe. O arquivo e criado ou sobrescrito. Num pipeline, grava a\nentrada.\n\nEXEMPLOS:\n  write notas.txt\n  ls -l | write lista.txt",
+        options: &[],
+        run: |args, ctx, out| fileops::cmd_write(out, ctx, args),
+    },
+    // === TEXT ===
+    Builtin {
+        name: "head",
+        aliases: &[],
+        category: Category::Text,
+        synopsis: "head [-n linhas] [arquivo]...",
+        summary: "Mostra as primeiras linhas",
+        help: "Sem arquivo, ou com '-', le a entrada do pipeline.\n\nEXEMPLOS:\n  head notas.txt\n  ls -l | head -n 3",
+        options: &[OptSpec::number(Some('n'), Some("lines"), "n", "Linhas a mostrar (padrao 10)")],
+        run: |args, _, _| text::cmd_head(args),
+    },
+    Builtin {
+        name: "tail",
+        aliases: &[],
+        category: Category::Text,
+        synopsis: "tail [-n [+]linhas] [-f] [arquivo]...",
+        summary: "Mostra as ultimas linhas",
+        help: "Com -n +N, mostra a partir da linha N. Com -f, continua mostrando o\nque for acrescentado ao ultimo arquivo ate Ctrl+C.\n\nEXEMPLOS:\n  tail log.txt\n  tail -n +2 dados.csv\n  tail -f /var/log/sistema.log",
+        options: &[
+            OptSpec::value(Some('n'), Some("lines"), "[+]n", "Linhas a mostrar (padrao 10)"),
+            OptSpec::flag(Some('f'), Some("follow"), "Continua lendo o que for acrescentado"),
+        ],
+        run: |args, ctx, out| text::cmd_tail(out, ctx, args),
+    },
+    Builtin {
+        name: "wc",
+        aliases: &[],
+        category: Category::Text,
+        synopsis: "wc [-l] [-w] [-c] [arquivo]...",
+        summary: "Conta linhas, palavras e bytes",
+        help: "Sem opcoes, mostra as tres contagens. Com varios arquivos, a\nultima linha traz o total.\n\nEXEMPLOS:\n  wc notas.txt\n  ls | wc -l",
+        options: &[
+            OptSpec::flag(Some('l'), Some("lines"), "Conta linhas"),
+            OptSpec::flag(Some('w'), Some("words"), "Conta palavras"),
+            OptSpec::flag(Some('c'), Some("bytes"), "Conta bytes"),
+        ],
+        run: |args, _, _| text::cmd_wc(args),
+    },
+    Builtin {
+        name: "sort",
+        aliases: &[],
+        category: Category::Text,
+        synopsis: "sort [opcoes] [arquivo]...",
+        summary: "Ordena linhas",
+        help: "-k N usa do campo N ate o fim da linha; -k N,M, do campo N ao M.\nCampos sao separados por espacos, ou pelo caractere de -t.\n\nEXEMPLOS:\n  sort nomes.txt\n  ls -l | sort -n -r -k 5\n  sort -t : -k 3 -n /etc/passwd",
+        options: &[
+            OptSpec::flag(Some('n'), Some("numeric-sort"), "Compara como numeros"),
+            OptSpec::flag(Some('r'), Some("reverse"), "Ordem inversa"),
+            OptSpec::flag(Some('u'), Some("unique"), "Uma linha por chave"),
+            OptSpec::value(Some('k'), Some("key"), "N[,M]", "Ordena pelos campos N a M"),
+            OptSpec::value(Some('t'), Some("field-separator"), "sep", "Separador de campos"),
+        ],
+        run: |args, ctx, out| text::cmd_sort(out, ctx, args),
+    },
+    Builtin {
+        name: "uniq",
+        aliases: &[],
+        category: Category::Text,
+        synopsis: "uniq [-c] [-d | -u] [-i] [arquivo]...",
+        summary: "Junta linhas repetidas seguidas",
+        help: "So compara linhas vizinhas; use depois de 'sort' para contar todas.\n\nEXEMPLOS:\n  sort nomes.txt | uniq\n  cut -d : -f 1 log.txt | sort | uniq -c",
+        options: &[
+            OptSpec::flag(Some('c'), Some("count"), "Prefixa com o numero de repeticoes"),
+            OptSpec::flag(Some('d'), Some("repeated"), "So linhas repetidas"),
+            OptSpec::flag(Some('u'), Some("unique"), "So linhas sem repeticao"),
+            OptSpec::flag(Some('i'), Some("ignore-case"), "Ignora maiusculas"),
+        ],
+        run: |args, _, _| text::cmd_uniq(args),
+    },
+    Builtin {
+        name: "cut",
+        aliases: &[],
+        category: Category::Text,
+        synopsis: "cut (-f lista [-d delim] [-s] | -c lista) [arquivo]...",
+        summary: "Seleciona campos ou caracteres",
+        help: "Listas: 1,3 (posicoes), 2-4 (intervalo), -3 (ate 3), 5- (de 5 em\ndiante). O delimitador padrao e TAB.\n\nEXEMPLOS:\n  cut -d : -f 1,3 /etc/passwd\n  ls -l | cut -c 1-10",
+        options: &[
+            OptSpec::value(Some('f'), Some("fields"), "lista", "Campos a mostrar"),
+            OptSpec::value(Some('d'), Some("delimiter"), "delim", "Separador de campos (padrao TAB)"),
+            OptSpec::flag(Some('s'), Some("only-delimited"), "Omite linhas sem o delimitador"),
+            OptSpec::value(Some('c'), Some("characters"), "lista", "Caracteres a mostrar"),
+        ],
+        run: |args, ctx, out| text::cmd_cut(out, ctx, args),
+    },
+    Builtin {
+        name: "tr",
+        aliases: &[],
+        category: Category::Text,
+        synopsis: "tr [-d] [-s] conjunto1 [conjunto2]",
+        summary: "Troca ou apaga caracteres",
+        help: "Le so a entrada do pipeline. Conjuntos aceitam intervalos (a-z),\nclasses ([:upper:], [:digit:], [:space:]...) e escapes (\\n, \\t).\n\nEXEMPLOS:\n  cat nomes.txt | tr a-z A-Z\n  echo 'a  b   c' | tr -s ' '\n  cat dados.txt | tr -d '\\r'",
+        options: &[
+            OptSpec::flag(Some('d'), Some("delete"), "Apaga os caracteres do conjunto1"),
+            OptSpec::flag(Some('s'), Some("squeeze-repeats"), "Junta repeticoes seguidas"),
+        ],
+        run: |args, ctx, out| text::cmd_tr(out, ctx, args),
+    },
+    Builtin {
+        name: "grep",
+        aliases: &[],
+        category: Category::Text,
+        synopsis: "grep [opcoes] padrao [arquivo]...",
+        summary: "Busca linhas por expressao regular",
+        help: "Sem -E, o padrao e uma expressao basica: \\( \\) \\| \\{ \\} \\+ \\? sao\noperadores. Com -E, ( ) | { } + ? dispensam a barra. Classes como\n[a-z] e [[:digit:]], ancoras ^ e $ e \\w \\s \\b valem nos dois.\n\nCodigo de saida: 0 se achou, 1 se nao achou, 2 em erro.\n\nEXEMPLOS:\n  grep erro log.txt\n  grep -in 'falha|aviso' -E log.txt\n  grep -rl TODO /apps\n  ps | grep -v shell",
+        options: &[
+            OptSpec::flag(Some('E'), Some("extended-regexp"), "Expressao regular estendida"),
+            OptSpec::flag(Some('i'), Some("ignore-case"), "Ignora maiusculas"),
+            OptSpec::flag(Some('v'), Some("invert-match"), "Seleciona as linhas que nao casam"),
+            OptSpec::flag(Some('n'), Some("line-number"), "Mostra o numero da linha"),
+            OptSpec::flag(Some('r'), Some("recursive"), "Busca dentro dos diretorios"),
+            OptSpec::flag(Some('l'), Some("files-with-matches"), "So os nomes dos arquivos"),
+            OptSpec::flag(Some('c'), Some("count"), "So a contagem de linhas"),
+            OptSpec::value(None, Some("color"), "quando", "Cores: auto, always ou never"),
+        ],
+        run: |args, ctx, out| text::cmd_grep(out, ctx, args),
+    },
+    Builtin {
+        name: "tee",
+        aliases: &[],
+        category: Category::Text,
+        synopsis: "tee [arquivo]...",
+        summary: "Copia a entrada para arquivos",
+        help: "Escreve a entrada do pipeline na saida e em cada arquivo.\n\nEXEMPLOS:\n  ls -l | tee lista.txt\n  ps | tee a.txt b.txt | wc -l",
+        options: &[],
+        run: |args, ctx, out| text::cmd_tee(out, ctx, args),
+    },
+    // === SYSTEM ===
+    Builtin {
+        name: "uptime",
+        aliases: &[],
+        category: Category::System,
+        synopsis: "uptime",
+        summary: "Tempo desde boot",
+        help: "",
+        options: &[],
+        run: |_, _, out| ok!(system::cmd_uptime(out)),
+    },
+    Builtin {
+        name: "ps",
+        aliases: &[],
+        category: Category::System,
+        synopsis: "ps [opcoes]",
+        summary: "Lista processos iniciados pelo shell",
+        help: "Lista os jobs do shell; processos de outros programas ainda nao\nsao visiveis (falta syscall de listagem).",
+        options: &[OptSpec::flag(None, Some("json"), "Saida em JSON")],
+        run: |args, ctx, out| ok!(system::cmd_ps(out, ctx, args)),
+    },
+    Builtin {
+        name: "top",
+        aliases: &[],
+        category: Category::System,
+        synopsis: "top",
+        summary: "Monitor de processos",
+        help: "",
+        options: &[],
+        run: |_, _, out| ok!(system::cmd_top(out)),
+    },
+    Builtin {
+        name: "sysinfo",
+        aliases: &[],
+        category: Category::System,
+        synopsis: "sysinfo",
+        summary: "Informacoes do sistema",
+        help: "",
+        options: &[],
+        run: |_, _, out| ok!(system::cmd_sysinfo(out)),
+    },
+    Builtin {
+        name: "meminfo",
+        aliases: &[],
+        category: Category::System,
+        synopsis: "meminfo",
+        summary: "Informacoes de memoria",
+        help: "",
+        options: &[],
+        run: |_, _, out| ok!(system::cmd_meminfo(out)),
+    },
+    // === JOBS ===
+    Builtin {
+        name: "jobs",
+        aliases: &[],
+        category: Category::Jobs,
+        synopsis: "jobs",
+        summary: "Lista jobs",
+        help: "",
+        options: &[],
+        run: |_, ctx, out| ok!(system::cmd_jobs(out, ctx)),
+    },
+    Builtin {
+        name: "fg",
+        aliases: &[],
+        category: Category::Jobs,
+        synopsis: "fg [%job]",
+        summary: "Traz job para primeiro plano",
+        help: "",
+        options: &[],
+        run: |args, ctx, out| system::cmd_fg(out, ctx, &args.positional),
+    },
+    Builtin {
+        name: "bg",
+        aliases: &[],
+        category: Category::Jobs,
+        synopsis: "bg [%job]...",
+        summary: "Continua job parado em segundo plano",
+        help: "",
+        options: &[],
+        run: |args, ctx, out| ok!(system::cmd_bg(out, ctx, &args.positional)),
+    },
+    // === DATA ===
+    Builtin {
+        name: "where",
+        aliases: &[],
+        category: Category::Data,
+        synopsis: "where <coluna> <op> <valor>",
+        summary: "Filtra linhas da tabela",
+        help: "Operadores: == != > >= < <= =~ (contem) !~ (nao contem)\n\nEXEMPLOS:\n  ls | where size > 1000\n  ls | where type == dir\n  ps | where command =~ app",
+        options: &[],
+        run: |args, ctx, out| ok!(data::cmd_where(out, ctx, args)),
+    },
+    Builtin {
+        name: "sort-by",
+        aliases: &[],
+        category: Category::Data,
+        synopsis: "sort-by [opcoes] <coluna>...",
+        summary: "Ordena a tabela por colunas",
+        help: "EXEMPLOS:\n  ls | sort-by name\n  ls | sort-by -r size",
+        options: &[OptSpec::flag(Some('r'), Some("reverse"), "Ordem decrescente")],
+        run: |args, ctx, out| ok!(data::cmd_sort_by(out, ctx, args)),
+    },
+    Builtin {
+        name: "select",
+        aliases: &[],
+        category: Category::Data,
+        synopsis: "select <coluna>...",
+        summary: "Escolhe colunas da tabela",
+        help: "EXEMPLOS:\n  ls | select name size",
+        options: &[],
+        run: |args, ctx, out| ok!(data::cmd_select(out, ctx, args)),
+    },
+    Builtin {
+        name: "first",
+        aliases: &[],
+        category: Category::Data,
+        synopsis: "first [n]",
+        summary: "Mantem as primeiras n linhas (padrao 1)",
+        help: "EXEMPLOS:\n  ls | sort-by -r size | first 10",
+        options: &[],
+        run: |args, ctx, out| ok!(data::cmd_first(out, ctx, args)),
+    },
+    Builtin {
+        name: "to-json",
+        aliases: &[],
+        category: Category::Data,
+        synopsis: "to-json",
+        summary: "Converte a tabela em JSON",
+        help: "",
+        options: &[],
+        run: |_, ctx, out| ok!(data::cmd_to_json(out, ctx)),
+    },
+    Builtin {
+        name: "to-csv",
+        aliases: &[],
+        category: Category::Data,
+        synopsis: "to-csv",
+        summary: "Converte a tabela em CSV",
+        help: "",
+        options: &[],
+        run: |_, ctx, out| ok!(data::cmd_to_csv(out, ctx)),
+    },
+    // === BUILTIN ===
+    Builtin {
+        name: "help",
+        aliases: &[],
+        category: Category::Other,
+        synopsis: "help [cmd]",
+        summary: "Mostra esta ajuda",
+        help: "",
+        options: &[],
+        run: |args, ctx, out| builtin::cmd_help(out, ctx, &args.positional),
+    },
+    Builtin {
+        name: "clear",
+        aliases: &[],
+        category: Category::Other,
+        synopsis: "clear",
+        summary: "Limpa a tela",
+        help: "",
+        options: &[],
+        run: |_, _, _| CommandResult::Clear,
+    },
+    Builtin {
+        name: "exit",
+        aliases: &["quit"],
+        category: Category::Other,
+        synopsis: "exit",
+        summary: "Sai do terminal",
+        help: "",
+        options: &[],
+        run: |_, _, _| CommandResult::Exit,
+    },
+    Builtin {
+        name: "echo",
+        aliases: &[],
+        category: Category::Other,
+        synopsis: "echo <texto>",
+        summary: "Imprime texto",
+        help: "",
+        options: &[],
+        run: |args, _, out| ok!(builtin::cmd_echo(out, &args.positional)),
+    },
+    Builtin {
+        name: "ver",
+        aliases: &["version"],
+        category: Category::Other,
+        synopsis: "ver",
+        summary: "Versao do sistema",
+        help: "",
+        options: &[],
+        run: |_, _, out| ok!(builtin::cmd_version(out)),
+    },
+];
+
+/// Procura o comando no PATH e o inicia.
+///
+/// Na tela, o processo é devolvido ao terminal (`Spawned`) para rodar ligado
+/// ao PTY, ou vai para a tabela de jobs se `background`. Durante uma captura
+/// (`$(cmd)`), uma [`Capture`] lê a saída até o processo terminar.
+fn run_external(
+    cmd: &str,
+    args: &[&str],
+    ctx: &mut ShellContext,
+    output: &mut TerminalState,
+    background: bool,
+) -> CommandResult {
+    let path_var = ctx.var("PATH").unwrap_or_default();
+    let path = match find_executable(ctx.launcher.as_ref(), cmd, &path_var, &ctx.cwd) {
+        Some(path) => path,
+        None => {
             output.write_str("Comando nao encontrado: ");
             output.write_line(cmd);
-            output.write_line("Digite 'help' para ver comandos disponiveis.");
+            ctx.last_exit_code = 127;
+
+            let Some(suggestion) = suggest::command(ctx, cmd) else {
+                output.write_line("Digite 'help' para ver comandos disponiveis.");
+                return CommandResult::Ok;
+            };
+            output.write_str("Voce quis dizer '");
+            output.write_str(&suggestion);
+            output.write_line("'?");
+            return CommandResult::Ok;
         }
+    };
+
+    let mut argv: Vec<&str> = Vec::with_capacity(args.len() + 1);
+    argv.push(cmd);
+    argv.extend_from_slice(args);
+    let envp = ctx.environ();
+
+    let pid = match ctx.launcher.spawn(&path, &argv, &envp) {
+        Ok(pid) => pid,
+        Err(e) => {
+            output.write_str(cmd);
+            output.write_str(": ");
+            output.write_line(error_to_str(e));
+            ctx.last_exit_code = 126;
+            return CommandResult::Ok;
+        }
+    };
+
+    // Num pipeline, a saída do estágio anterior é o stdin inteiro; o que o
+    // processo não aceitar agora segue com ele (job, terminal ou captura)
+    let mut stdin = match ctx.stdin.take() {
+        Some(input) => StdinFeed::pipe(input.into_text().into_bytes()),
+        None => StdinFeed::default(),
+    };
+
+    if background && !output.is_capturing() {
+        // Sem terminal: o job não lê do teclado
+        stdin.finish();
+        stdin.flush(ctx.launcher.as_mut(), pid);
+        let id = ctx.jobs.add(pid, &argv.join(" "), JobState::Running, stdin);
+        output.write_line(&alloc::format!("[{}] {}", id, pid));
+        return CommandResult::Ok;
+    }
+
+    if !output.is_capturing() {
+        stdin.flush(ctx.launcher.as_mut(), pid);
+        return CommandResult::Spawned {
+            pid,
+            command: argv.join(" "),
+            stdin,
+        };
     }
 
-    CommandResult::Ok
+    // Captura: sem teclado
+    stdin.finish();
+    CommandResult::Task(Box::new(Capture {
+        pid,
+        name: String::from(cmd),
+        stdin,
+        decoder: Utf8Decoder::new(),
+    }))
 }
 
-/// Parseia argumentos de uma linha de comando
-fn parse_args(cmd_line: &str) -> Vec<String> {
-    let mut args = Vec::new();
-    let mut current = String::new();
-    let mut in_quotes = false;
-    let mut quote_char = ' ';
+/// Programa externo numa captura: a cada passo, entrega a entrada
+/// pendente e copia a saída, até o processo terminar
+struct Capture {
+    pid: Pid,
+    /// Nome do comando, para mensagens de erro
+    name: String,
+    stdin: StdinFeed,
+    decoder: Utf8Decoder,
+}
 
-    for c in cmd_line.chars() {
-        match c {
-            '"' | '\'' if !in_quotes => {
-                in_quotes = true;
-                quote_char = c;
-            }
-            c if in_quotes && c == quote_char => {
-                in_quotes = false;
+impl Task for Capture {
+    fn step(&mut self, ctx: &mut ShellContext, output: &mut TerminalState) -> TaskStatus {
+        self.stdin.flush(ctx.launcher.as_mut(), self.pid);
+        let exited = ctx.launcher.try_wait(self.pid);
+
+        let mut buf = [0u8; 512];
+        while let Ok(n @ 1..) = ctx.launcher.read_stdout(self.pid, &mut buf) {
+            self.decoder
+                .decode_with(&buf[..n], |c| output.write_char(c));
+        }
+
+        match exited {
+            Ok(Some(code)) => ctx.last_exit_code = code,
+            Ok(None) => return TaskStatus::Pending,
+            Err(e) => {
+                output.write_str(&self.name);
+                output.write_str(": ");
+                output.write_line(error_to_str(e));
+                ctx.last_exit_code = 1;
             }
-            ' ' | '\t' if !in_quotes => {
-                if !current.is_empty() {
-                    args.push(current.clone());
-                    current.clear();
+        }
+        TaskStatus::Done
+    }
+
+    /// O processo não está na tabela de jobs: sem a tarefa, ficaria solto
+    fn cancel(&mut self, ctx: &mut ShellContext) {
+        let _ = ctx.launcher.signal(self.pid, Signal::Kill);
+    }
+}
+
+/// Converte erro para string
+pub(crate) fn error_to_str(e: redpowder::SysError) -> &'static str {
+    match e {
+        redpowder::SysError::NotFound => "Nao encontrado",
+        redpowder::SysError::PermissionDenied => "Permissao negada",
+        redpowder::SysError::IsDirectory => "E um diretorio",
+        redpowder::SysError::NotDirectory => "Nao e um diretorio",
+        redpowder::SysError::NotEmpty => "Diretorio nao vazio",
+        redpowder::SysError::NotImplemented => "Nao implementado",
+        redpowder::SysError::IoError => "Erro de E/S",
+        _ => "Erro desconhecido",
+    }
+}
+
+/// Motivo de uma falha ao criar ou gravar `path`.
+///
+/// `NotFound` e `NotDirectory` vêm de um diretório no caminho, e a mensagem
+/// aponta qual; `PermissionDenied` inclui o caso comum de um sistema de
+/// arquivos somente leitura.
+pub(crate) fn write_error(fs: &dyn Vfs, path: &str, e: redpowder::SysError) -> String {
+    match e {
+        redpowder::SysError::NotFound | redpowder::SysError::NotDirectory => {
+            let mut dir = String::new();
+            for comp in path::components(path::parent(path)) {
+                dir.push('/');
+                dir.push_str(comp);
+                match fs.metadata(&dir) {
+                    Ok(meta) if meta.is_dir() => {}
+                    Ok(_) => return alloc::format!("{} nao e um diretorio", dir),
+                    Err(_) => return alloc::format!("Diretorio {} nao existe", dir),
                 }
             }
-            _ => {
-                current.push(c);
+            String::from(error_to_str(e))
+        }
+        redpowder::SysError::PermissionDenied => {
+            String::from("Permissao negada (sistema de arquivos somente leitura?)")
+        }
+        e => String::from(error_to_str(e)),
+    }
+}
+
+// =============================================================================
+// TESTES
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{execute_command, CommandResult, JobState};
+    use crate::shell::task::{Task, TaskStatus};
+    use crate::shell::testing::{run, shell, FakeLauncher};
+    use crate::shell::vfs::MemoryFs;
+    use crate::shell::ShellContext;
+    use crate::state::terminal::TerminalState;
+    use alloc::boxed::Box;
+    use alloc::vec;
+
+    fn with_launcher(launcher: FakeLauncher) -> ShellContext {
+        let mut ctx = shell(MemoryFs::new());
+        ctx.launcher = Box::new(launcher);
+        ctx
+    }
+
+    #[test]
+    fn external_program_output_and_exit_code() {
+        let launcher = FakeLauncher::default().program("/apps/greet", "ola\n", 3);
+        let spawned = launcher.spawned.clone();
+        let mut ctx = with_launcher(launcher);
+
+        assert_eq!(run(&mut ctx, "greet -x mundo"), "ola\n");
+        assert_eq!(ctx.last_exit_code, 3);
+        assert_eq!(
+            *spawned.borrow(),
+            vec![vec!["/apps/greet", "greet", "-x", "mundo"]]
+        );
+    }
+
+    #[test]
+    fn external_program_uses_path_variable() {
+        let launcher = FakeLauncher::default().program("/opt/bin/tool", "", 0);
+        let mut ctx = with_launcher(launcher);
+
+        assert!(run(&mut ctx, "tool").starts_with("Comando nao encontrado: tool\n"));
+        ctx.set_var("PATH", "/apps:/opt/bin");
+        assert_eq!(run(&mut ctx, "tool"), "");
+        assert_eq!(ctx.last_exit_code, 0);
+    }
+
+    #[test]
+    fn unknown_command_reports_127() {
+        let mut ctx = with_launcher(FakeLauncher::default());
+        assert_eq!(
+            run(&mut ctx, "qqqqqqqq"),
+            "Comando nao encontrado: qqqqqqqq\nDigite 'help' para ver comandos disponiveis.\n"
+        );
+        assert_eq!(ctx.last_exit_code, 127);
+    }
+
+    /// Filtro `pass` atrás de um pipe que aceita 3 bytes por vez
+    fn small_pipe() -> ShellContext {
+        with_launcher(FakeLauncher::default().stdin_chunk(3).filter("/apps/pass"))
+    }
+
+    #[test]
+    fn captured_program_receives_all_pipeline_input() {
+        let mut ctx = small_pipe();
+        assert_eq!(
+            run(&mut ctx, "echo 0123456789abcdef | pass"),
+            "0123456789abcdef\n"
+        );
+        assert_eq!(ctx.last_exit_code, 0);
+    }
+
+    #[test]
+    fn foreground_program_keeps_unsent_input() {
+        let mut ctx = small_pipe();
+        let mut output = TerminalState::new(80, 24);
+
+        let CommandResult::Spawned { pid, mut stdin, .. } =
+            execute_command("echo 0123456789 | pass", &mut ctx, &mut output)
+        else {
+            panic!("esperava Spawned");
+        };
+        assert!(!stdin.is_done());
+        while !stdin.is_done() {
+            stdin.flush(ctx.launcher.as_mut(), pid);
+        }
+
+        let mut buf = [0u8; 32];
+        let n = ctx.launcher.read_stdout(pid, &mut buf).unwrap();
+        assert_eq!(&buf[..n], b"0123456789\n");
+        assert_eq!(ctx.launcher.try_wait(pid), Ok(Some(0)));
+    }
+
+    #[test]
+    fn background_job_is_fed_on_each_poll() {
+        let mut ctx = small_pipe();
+        let mut output = TerminalState::new(80, 24);
+
+        execute_command("echo abcdefgh | pass &", &mut ctx, &mut output);
+        assert_eq!(ctx.jobs.iter().count(), 1);
+
+        output.begin_capture();
+        for _ in 0..8 {
+            ctx.jobs.poll(ctx.launcher.as_mut(), &mut output);
+        }
+        assert_eq!(output.end_capture(), "abcdefgh\n");
+        assert!(ctx.jobs.iter().all(|job| job.state == JobState::Done(0)));
+    }
+
+    /// `slow` continua rodando nas 3 primeiras consultas
+    fn slow_program() -> ShellContext {
+        with_launcher(
+            FakeLauncher::default()
+                .slow("/apps/slow", "saida\n", 4, 3)
+                .program("/apps/greet", "", 0),
+        )
+    }
+
+    /// Passos até a tarefa terminar
+    fn steps_to_end(
+        task: &mut dyn Task,
+        ctx: &mut ShellContext,
+        output: &mut TerminalState,
+    ) -> usize {
+        let mut steps = 1;
+        while task.step(ctx, output) != TaskStatus::Done {
+            steps += 1;
+            assert!(steps < 100, "a tarefa nao terminou");
+        }
+        steps
+    }
+
+    #[test]
+    fn substitution_waits_for_the_program_step_by_step() {
+        let mut ctx = slow_program();
+        let mut output = TerminalState::new(80, 24);
+        output.begin_capture();
+
+        let CommandResult::Task(mut task) =
+            execute_command("echo [$(slow)]", &mut ctx, &mut output)
+        else {
+            panic!("esperava Task");
+        };
+        assert_eq!(steps_to_end(task.as_mut(), &mut ctx, &mut output), 4);
+        assert_eq!(output.end_capture(), "[saida]\n");
+        assert_eq!(ctx.last_exit_code, 0);
+    }
+
+    #[test]
+    fn pipeline_stage_waits_for_the_program() {
+        let mut ctx = slow_program();
+        assert_eq!(run(&mut ctx, "slow | tr a-z A-Z"), "SAIDA\n");
+        assert_eq!(run(&mut ctx, "echo $(slow | tr a-z A-Z) $?"), "SAIDA 0\n");
+    }
+
+    #[test]
+    fn filter_on_screen_reads_its_pipeline_input() {
+        let mut ctx = slow_program();
+        let mut output = TerminalState::new(80, 24);
+
+        let CommandResult::Task(mut task) =
+            execute_command("echo abc | tr a-z A-Z", &mut ctx, &mut output)
+        else {
+            panic!("esperava Task");
+        };
+        output.begin_capture();
+        steps_to_end(task.as_mut(), &mut ctx, &mut output);
+        assert_eq!(output.end_capture(), "ABC\n");
+    }
+
+    #[test]
+    fn finished_line_hands_over_its_result() {
+        let mut ctx = slow_program();
+        let mut output = TerminalState::new(80, 24);
+
+        let CommandResult::Task(mut task) = execute_command("greet $(slow)", &mut ctx, &mut output)
+        else {
+            panic!("esperava Task");
+        };
+        steps_to_end(task.as_mut(), &mut ctx, &mut output);
+        match task.finish() {
+            CommandResult::Spawned { command, .. } => assert_eq!(command, "greet saida"),
+            _ => panic!("esperava Spawned"),
+        }
+    }
+
+    #[test]
+    fn interrupted_capture_kills_the_program() {
+        let mut ctx = slow_program();
+        let mut output = TerminalState::new(80, 24);
+
+        let CommandResult::Task(mut task) = execute_command("echo $(slow)", &mut ctx, &mut output)
+        else {
+            panic!("esperava Task");
+        };
+        assert_eq!(task.step(&mut ctx, &mut output), TaskStatus::Pending);
+        task.cancel(&mut ctx);
+        assert!(ctx.launcher.try_wait(1).is_err());
+    }
+
+    #[test]
+    fn foreground_program_is_handed_to_the_terminal() {
+        let launcher = FakeLauncher::default().program("/apps/greet", "", 0);
+        let mut ctx = with_launcher(launcher);
+        let mut output = TerminalState::new(80, 24);
+
+        match execute_command("greet a", &mut ctx, &mut output) {
+            CommandResult::Spawned { pid, command, .. } => {
+                assert_eq!(pid, 1);
+                assert_eq!(command, "greet a");
+            }
+            _ => panic!("esperava Spawned"),
+        }
+    }
+
+    #[test]
+    fn builtin_in_background_is_rejected() {
+        let mut ctx = with_launcher(FakeLauncher::default());
+        assert_eq!(
+            run(&mut ctx, "echo oi &"),
+            "echo: comando interno nao roda em segundo plano (&)\n"
+        );
+        assert_eq!(ctx.last_exit_code, 1);
+        assert_eq!(ctx.jobs.iter().count(), 0);
+    }
+
+    #[test]
+    fn fg_inside_a_pipeline_keeps_the_job() {
+        let launcher = FakeLauncher::default().program("/apps/greet", "", 0);
+        let mut ctx = with_launcher(launcher);
+        let mut output = TerminalState::new(80, 24);
+
+        execute_command("greet &", &mut ctx, &mut output);
+        assert_eq!(ctx.jobs.iter().count(), 1);
+
+        assert_eq!(
+            run(&mut ctx, "fg | cat"),
+            "fg: precisa do terminal; nao funciona em pipelines ou capturas\n"
+        );
+        assert!(run(&mut ctx, "echo $(fg)").starts_with("fg: precisa do terminal"));
+        assert_eq!(ctx.jobs.iter().count(), 1);
+    }
+
+    #[test]
+    fn correction_replaces_the_command_word() {
+        let mut ctx = with_launcher(FakeLauncher::default());
+        ctx.set_var("CORRECT", "1");
+        let mut output = TerminalState::new(80, 24);
+
+        for (typed, fixed) in [
+            ("ech ech", "echo ech"),
+            ("A=ech ech ech", "A=ech echo ech"),
+            ("ech a | ech b", "echo a | echo b"),
+            ("echo a |ech b|  cat", "echo a |echo b|  cat"),
+        ] {
+            match execute_command(typed, &mut ctx, &mut output) {
+                CommandResult::Correct { line } => assert_eq!(line, fixed),
+                _ => panic!("esperava Correct para '{}'", typed),
             }
         }
     }
 
-    if !current.is_empty() {
-        args.push(current);
+    #[test]
+    fn correction_needs_a_suggestion_for_every_stage() {
+        let mut ctx = with_launcher(FakeLauncher::default());
+        ctx.set_var("CORRECT", "1");
+        let mut output = TerminalState::new(80, 24);
+
+        let result = execute_command("ech a | zzzzzzzz", &mut ctx, &mut output);
+        assert!(matches!(result, CommandResult::Ok));
+        assert_eq!(ctx.last_exit_code, 127);
+
+        // Numa captura não há a quem perguntar
+        assert_eq!(
+            run(&mut ctx, "ech a"),
+            "Comando nao encontrado: ech\nVoce quis dizer 'echo'?\n"
+        );
     }
 
-    args
+    #[test]
+    fn correction_of_quoted_name_is_declined() {
+        let mut ctx = with_launcher(FakeLauncher::default());
+        ctx.set_var("CORRECT", "1");
+        let mut output = TerminalState::new(80, 24);
+
+        let result = execute_command("\"ech\" x", &mut ctx, &mut output);
+        assert!(matches!(result, CommandResult::Ok));
+        assert_eq!(ctx.last_exit_code, 127);
+    }
 }
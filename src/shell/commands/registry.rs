@@ -0,0 +1,307 @@
+//! # Command Registry
+//!
+//! Registro dos comandos internos do shell.
+//!
+//! Cada comando se descreve (nome, aliases, sinopse, ajuda e opções) e
+//! `help`, `help <cmd>`, o completamento e as sugestões são gerados a partir
+//! do registro. Módulos externos adicionam comandos implementando
+//! [`Command`] e chamando [`Registry::register`] no `ShellContext`.
+
+use super::CommandResult;
+use crate::shell::args::{self, Args, OptSpec};
+use crate::shell::ShellContext;
+use crate::state::terminal::TerminalState;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Grupo do comando na listagem do `help`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    /// Arquivos e diretórios
+    Files,
+    /// Filtros de texto (`head`, `sort`, `tr`...)
+    Text,
+    /// Sistema e processos
+    System,
+    /// Controle de jobs
+    Jobs,
+    /// Transformação de tabelas num pipeline
+    Data,
+    /// Demais comandos
+    Other,
+}
+
+impl Category {
+    /// Ordem de exibição no `help`
+    pub const ALL: [Category; 6] = [
+        Category::Files,
+        Category::Text,
+        Category::System,
+        Category::Jobs,
+        Category::Data,
+        Category::Other,
+    ];
+
+    /// Título da seção
+    pub fn title(&self) -> &'static str {
+        match self {
+            Category::Files => "COMANDOS DE ARQUIVOS:",
+            Category::Text => "PROCESSAMENTO DE TEXTO:",
+            Category::System => "COMANDOS DE SISTEMA:",
+            Category::Jobs => "CONTROLE DE JOBS:",
+            Category::Data => "DADOS ESTRUTURADOS:",
+            Category::Other => "OUTROS:",
+        }
+    }
+
+    /// Linhas extras da seção, que não são comandos
+    pub fn notes(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Category::Jobs => &[
+                ("prog &", "Executa programa externo em segundo plano"),
+                ("Ctrl+Z", "Suspende o processo atual"),
+            ],
+            Category::Data => &[("cmd | cmd", "Passa a saida ao proximo comando")],
+            _ => &[],
+        }
+    }
+}
+
+/// Comando interno do shell
+pub trait Command {
+    /// Nome principal
+    fn name(&self) -> &str;
+
+    /// Nomes alternativos (`dir` para `ls`)
+    fn aliases(&self) -> &[&str] {
+        &[]
+    }
+
+    /// Grupo na listagem do `help`
+    fn category(&self) -> Category {
+        Category::Other
+    }
+
+    /// Forma de uso: `ls [opcoes] [caminho]`
+    fn synopsis(&self) -> &str;
+
+    /// Descrição de uma linha
+    fn summary(&self) -> &str;
+
+    /// Texto adicional de `help <cmd>` (exemplos, observações)
+    fn help(&self) -> &str {
+        ""
+    }
+
+    /// Opções aceitas
+    fn options(&self) -> &[OptSpec] {
+        &[]
+    }
+
+    /// Recebe tabelas num pipeline (o estágio anterior entrega registros
+    /// em vez de texto)
+    fn takes_table(&self) -> bool {
+        self.category() == Category::Data
+    }
+
+    /// Executa o comando
+    fn run(
+        &self,
+        args: &[&str],
+        ctx: &mut ShellContext,
+        output: &mut TerminalState,
+    ) -> CommandResult;
+}
+
+/// Função que executa um comando interno, com os argumentos já separados
+pub type RunFn = fn(&Args, &mut ShellContext, &mut TerminalState) -> CommandResult;
+
+/// Comando descrito por uma tabela estática.
+///
+/// Os argumentos passam pelo parser de opções segundo `options`; sem opções
+/// declaradas, todos chegam como posicionais (inclusive os que começam com
+/// `-`, como em `echo -x`).
+#[derive(Clone, Copy)]
+pub struct Builtin {
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    pub category: Category,
+    pub synopsis: &'static str,
+    pub summary: &'static str,
+    pub help: &'static str,
+    pub options: &'static [OptSpec],
+    pub run: RunFn,
+}
+
+impl Command for Builtin {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn aliases(&self) -> &[&str] {
+        self.aliases
+    }
+
+    fn category(&self) -> Category {
+        self.category
+    }
+
+    fn synopsis(&self) -> &str {
+        self.synopsis
+    }
+
+    fn summary(&self) -> &str {
+        self.summary
+    }
+
+    fn help(&self) -> &str {
+        self.help
+    }
+
+    fn options(&self) -> &[OptSpec] {
+        self.options
+    }
+
+    fn run(
+        &self,
+        args: &[&str],
+        ctx: &mut ShellContext,
+        output: &mut TerminalState,
+    ) -> CommandResult {
+        if self.options.is_empty() {
+            return (self.run)(&Args::raw(args), ctx, output);
+        }
+
+        match args::parse(self.options, args) {
+            Ok(parsed) => (self.run)(&parsed, ctx, output),
+            Err(e) => {
+                args::report_error(output, ctx, self.name, self.synopsis, &e.message());
+                CommandResult::Ok
+            }
+        }
+    }
+}
+
+/// Registro de comandos, na ordem de registro
+pub struct Registry {
+    commands: Vec<Rc<dyn Command>>,
+}
+
+impl Registry {
+    /// Registro vazio
+    pub fn new() -> Self {
+        Self {
+            commands: Vec::new(),
+        }
+    }
+
+    /// Registro com os comandos internos do shell
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        for builtin in super::BUILTINS {
+            registry.register(*builtin);
+        }
+        registry.register(super::system::Kill);
+        registry
+    }
+
+    /// Registra um comando, substituindo outro de mesmo nome
+    pub fn register<C: Command + 'static>(&mut self, command: C) {
+        let command: Rc<dyn Command> = Rc::new(command);
+        match self
+            .commands
+            .iter()
+            .position(|c| c.name() == command.name())
+        {
+            Some(pos) => self.commands[pos] = command,
+            None => self.commands.push(command),
+        }
+    }
+
+    /// Procura pelo nome ou alias
+    pub fn find(&self, name: &str) -> Option<Rc<dyn Command>> {
+        self.commands
+            .iter()
+            .find(|c| c.name() == name || c.aliases().contains(&name))
+            .cloned()
+    }
+
+    /// Comandos na ordem de registro
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Command> {
+        self.commands.iter().map(|c| c.as_ref())
+    }
+
+    /// Todos os nomes aceitos (nomes e aliases)
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.commands
+            .iter()
+            .flat_map(|c| core::iter::once(c.name()).chain(c.aliases().iter().copied()))
+    }
+
+    /// Nomes que começam com `prefix`, ordenados
+    pub fn complete(&self, prefix: &str) -> Vec<&str> {
+        let mut names: Vec<&str> = self.names().filter(|n| n.starts_with(prefix)).collect();
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self::with_builtins()
+    }
+}
+
+/// Escreve a ajuda detalhada de um comando (`help <cmd>`)
+pub fn write_help(output: &mut TerminalState, command: &dyn Command) {
+    let mut line = String::from(command.name());
+    line.push_str(" - ");
+    line.push_str(command.summary());
+    output.write_line(&line);
+    output.write_line("");
+
+    output.write_str("USO: ");
+    output.write_line(command.synopsis());
+
+    if !command.aliases().is_empty() {
+        output.write_str("ALIASES: ");
+        output.write_line(&command.aliases().join(", "));
+    }
+
+    let options = command.options();
+    if !options.is_empty() {
+        output.write_line("");
+        output.write_line("OPCOES:");
+        let usages: Vec<String> = options.iter().map(|o| o.usage()).collect();
+        let width = column_width(usages.iter().map(|u| u.as_str()));
+        for (usage, opt) in usages.iter().zip(options) {
+            write_entry(output, usage, opt.help, width);
+        }
+    }
+
+    let help = command.help();
+    if !help.is_empty() {
+        output.write_line("");
+        for line in help.lines() {
+            output.write_line(line);
+        }
+    }
+}
+
+/// Largura da coluna de uso: a maior entrada mais dois espaços
+pub fn column_width<'a>(usages: impl Iterator<Item = &'a str>) -> usize {
+    usages.map(|u| u.chars().count()).max().unwrap_or(0) + 2
+}
+
+/// Linha de listagem: `  uso              descrição`
+pub fn write_entry(output: &mut TerminalState, usage: &str, text: &str, width: usize) {
+    let mut line = String::from("  ");
+    line.push_str(usage);
+    for _ in usage.chars().count()..width {
+        line.push(' ');
+    }
+    line.push_str(text);
+    output.write_line(&line);
+}
@@ -2,7 +2,17 @@
 //!
 //! Comandos de sistema.
 
+use super::registry::{Category, Command};
+use super::{error_to_str, CommandResult};
+use crate::pty::Signal;
+use crate::shell::args::{self, Args, OptSpec};
+use crate::shell::jobs::{self, JobState};
+use crate::shell::json::JsonWriter;
+use crate::shell::launcher::Pid;
+use crate::shell::table::{self, Table, Value};
+use crate::shell::ShellContext;
 use crate::state::terminal::TerminalState;
+use alloc::format;
 
 // =============================================================================
 // uptime - Tempo desde boot
@@ -36,38 +46,67 @@ pub fn cmd_uptime(output: &mut TerminalState) {
 // ps - Lista processos
 // =============================================================================
 
-pub fn cmd_ps(output: &mut TerminalState, args: &[&str]) {
-    let json_output = args.contains(&"--json");
-
-    if json_output {
-        output.write_line("ps --json: Nao implementado");
-        output.write_line("(Contrato futuro para integracao com ferramentas)");
+pub fn cmd_ps(output: &mut TerminalState, ctx: &mut ShellContext, args: &Args) {
+    // Sem syscall de listagem, só os processos iniciados por este shell
+    if args.flag("json") {
+        // [{"pid", "job", "state", "exit_code", "command"}]
+        let mut json = JsonWriter::new();
+        json.begin_array();
+        for job in ctx.jobs.iter() {
+            json.begin_object();
+            json.field_u64("pid", job.pid);
+            json.field_u64("job", job.id as u64);
+            let (state, code) = match job.state {
+                JobState::Running => ("running", None),
+                JobState::Stopped => ("stopped", None),
+                JobState::Done(code) => ("done", Some(code)),
+            };
+            json.field_str("state", state);
+            json.key("exit_code");
+            match code {
+                Some(code) => json.number(code as i64),
+                None => json.null(),
+            }
+            json.field_str("command", &job.command);
+            json.end_object();
+        }
+        json.end_array();
+        output.write_line(&json.finish());
         return;
     }
 
-    output.write_line("ps: Nao implementado");
-    output.write_line("(Requer syscall de listagem de processos)");
-    output.write_line("");
-    output.write_line("Formato futuro:");
-    output.write_line("  PID  PPID  STATE  NAME");
-    output.write_line("    1     0  R      supervisor");
-    output.write_line("    2     1  R      firefly");
-    output.write_line("    3     2  R      terminal");
-}
-
-// =============================================================================
-// kill - Mata processo
-// =============================================================================
-
-pub fn cmd_kill(output: &mut TerminalState, args: &[&str]) {
-    if args.is_empty() {
-        output.write_line("kill: falta PID");
-        output.write_line("Uso: kill <pid>");
+    if ctx.pipe_table {
+        let mut table = Table::new(&["pid", "job", "state", "exit_code", "command"]);
+        for job in ctx.jobs.iter() {
+            let (state, code) = match job.state {
+                JobState::Running => ("running", Value::Nothing),
+                JobState::Stopped => ("stopped", Value::Nothing),
+                JobState::Done(code) => ("done", Value::Int(code as i64)),
+            };
+            table.push(alloc::vec![
+                Value::from(job.pid),
+                Value::from(job.id as u64),
+                Value::from(state),
+                code,
+                Value::from(job.command.as_str()),
+            ]);
+        }
+        table::emit(ctx, output, table);
         return;
     }
 
-    output.write_line("kill: Nao implementado");
-    output.write_line("(Requer syscall de sinais)");
+    output.write_line("  PID  JOB  STATE  NAME");
+    for job in ctx.jobs.iter() {
+        let state = match job.state {
+            JobState::Running => 'R',
+            JobState::Stopped => 'T',
+            JobState::Done(_) => 'Z',
+        };
+        output.write_line(&format!(
+            "{:>5}  {:>3}  {:<5}  {}",
+            job.pid, job.id, state, job.command
+        ));
+    }
 }
 
 // =============================================================================
@@ -89,9 +128,259 @@ pub fn cmd_top(output: &mut TerminalState) {
 // jobs - Lista jobs
 // =============================================================================
 
-pub fn cmd_jobs(output: &mut TerminalState) {
-    output.write_line("jobs: Nao implementado");
-    output.write_line("(Requer suporte a job control)");
+pub fn cmd_jobs(output: &mut TerminalState, ctx: &mut ShellContext) {
+    for job in ctx.jobs.iter() {
+        jobs::write_job(output, job, ctx.jobs.marker(job.id));
+    }
+}
+
+// =============================================================================
+// fg - Traz job para primeiro plano
+// =============================================================================
+
+pub fn cmd_fg(output: &mut TerminalState, ctx: &mut ShellContext, args: &[&str]) -> CommandResult {
+    // Num pipeline ou `$(...)` não há terminal para devolver o job
+    if output.is_capturing() {
+        output.write_line("fg: precisa do terminal; nao funciona em pipelines ou capturas");
+        ctx.last_exit_code = 1;
+        return CommandResult::Ok;
+    }
+
+    let spec = args.first().copied().unwrap_or("%%");
+    let Some(id) = resolve_job(output, ctx, "fg", spec) else {
+        return CommandResult::Ok;
+    };
+    let Some(job) = ctx.jobs.remove(id) else {
+        return CommandResult::Ok;
+    };
+
+    output.write_line(&job.command);
+
+    match job.state {
+        // Já terminou: só resta o código de saída
+        JobState::Done(code) => {
+            ctx.last_exit_code = code;
+            CommandResult::Ok
+        }
+        JobState::Stopped => {
+            let _ = ctx.launcher.signal(job.pid, Signal::Continue);
+            CommandResult::Spawned {
+                pid: job.pid,
+                command: job.command,
+                stdin: job.stdin,
+            }
+        }
+        JobState::Running => CommandResult::Spawned {
+            pid: job.pid,
+            command: job.command,
+            stdin: job.stdin,
+        },
+    }
+}
+
+// =============================================================================
+// bg - Continua job parado em segundo plano
+// =============================================================================
+
+pub fn cmd_bg(output: &mut TerminalState, ctx: &mut ShellContext, args: &[&str]) {
+    let specs: &[&str] = if args.is_empty() { &["%%"] } else { args };
+
+    for spec in specs {
+        let Some(id) = resolve_job(output, ctx, "bg", spec) else {
+            continue;
+        };
+        let Some(job) = ctx.jobs.get_mut(id) else {
+            continue;
+        };
+
+        if job.state != JobState::Stopped {
+            output.write_line(&format!("bg: job {} ja esta em segundo plano", id));
+            continue;
+        }
+
+        let _ = ctx.launcher.signal(job.pid, Signal::Continue);
+        job.state = JobState::Running;
+        let line = format!("[{}] {} &", id, job.command);
+        ctx.jobs.touch(id);
+        output.write_line(&line);
+    }
+}
+
+// =============================================================================
+// kill - Envia sinal a processo ou job
+// =============================================================================
+
+/// Sinais aceitos por `kill`: (nome, número, sinal)
+const SIGNALS: &[(&str, u32, Signal)] = &[
+    ("INT", 2, Signal::Interrupt),
+    ("QUIT", 3, Signal::Quit),
+    ("KILL", 9, Signal::Kill),
+    ("TERM", 15, Signal::Terminate),
+    ("CONT", 18, Signal::Continue),
+    ("TSTP", 20, Signal::Suspend),
+    ("WINCH", 28, Signal::WindowChange),
+];
+
+/// `kill` aceita `-9` e `-KILL`, que não cabem no parser de opções; por
+/// isso implementa [`Command`] diretamente e separa os argumentos sozinho.
+pub struct Kill;
+
+impl Command for Kill {
+    fn name(&self) -> &str {
+        "kill"
+    }
+
+    fn category(&self) -> Category {
+        Category::Jobs
+    }
+
+    fn synopsis(&self) -> &str {
+        KILL_SYNOPSIS
+    }
+
+    fn summary(&self) -> &str {
+        "Envia sinal a processo ou job"
+    }
+
+    fn help(&self) -> &str {
+        "EXEMPLOS:\n  kill %1\n  kill -9 42\n  kill -s CONT %2"
+    }
+
+    fn options(&self) -> &[OptSpec] {
+        KILL_OPTIONS
+    }
+
+    fn run(
+        &self,
+        args: &[&str],
+        ctx: &mut ShellContext,
+        output: &mut TerminalState,
+    ) -> CommandResult {
+        cmd_kill(output, ctx, args);
+        CommandResult::Ok
+    }
+}
+
+const KILL_SYNOPSIS: &str = "kill [-s SINAL | -SINAL] <pid|%job>...";
+
+const KILL_OPTIONS: &[OptSpec] = &[
+    OptSpec::value(Some('s'), None, "sinal", "Sinal a enviar (padrao TERM)"),
+    OptSpec::flag(Some('l'), None, "Lista os sinais"),
+];
+
+fn cmd_kill(output: &mut TerminalState, ctx: &mut ShellContext, args: &[&str]) {
+    let mut signal = Signal::Terminate;
+    let mut targets = args;
+
+    match targets.first().copied() {
+        Some("-l") => {
+            for (name, number, _) in SIGNALS {
+                output.write_line(&format!("{:>2}) SIG{}", number, name));
+            }
+            return;
+        }
+        Some("-s") => {
+            let Some(name) = targets.get(1) else {
+                args::report_error(
+                    output,
+                    ctx,
+                    "kill",
+                    KILL_SYNOPSIS,
+                    "opcao requer um valor: -s",
+                );
+                return;
+            };
+            match parse_signal(name) {
+                Some(s) => signal = s,
+                None => return invalid_signal(output, ctx, name),
+            }
+            targets = &targets[2..];
+        }
+        Some(arg) if arg.starts_with('-') && arg.len() > 1 => {
+            match parse_signal(&arg[1..]) {
+                Some(s) => signal = s,
+                None => return invalid_signal(output, ctx, &arg[1..]),
+            }
+            targets = &targets[1..];
+        }
+        _ => {}
+    }
+
+    if targets.is_empty() {
+        args::report_error(output, ctx, "kill", KILL_SYNOPSIS, "falta PID");
+        return;
+    }
+
+    for target in targets {
+        // %job ou PID
+        let (pid, job) = if target.starts_with('%') {
+            let Some(id) = resolve_job(output, ctx, "kill", target) else {
+                continue;
+            };
+            match ctx.jobs.get(id) {
+                Some(job) => (job.pid, Some(id)),
+                None => continue,
+            }
+        } else {
+            match target.parse::<Pid>() {
+                Ok(pid) => (pid, ctx.jobs.iter().find(|j| j.pid == pid).map(|j| j.id)),
+                Err(_) => {
+                    output.write_line(&format!("kill: {}: PID invalido", target));
+                    ctx.last_exit_code = 1;
+                    continue;
+                }
+            }
+        };
+
+        if let Err(e) = ctx.launcher.signal(pid, signal) {
+            output.write_line(&format!("kill: ({}) - {}", pid, error_to_str(e)));
+            ctx.last_exit_code = 1;
+            continue;
+        }
+
+        // Manter a tabela coerente com o sinal enviado
+        if let Some(job) = job.and_then(|id| ctx.jobs.get_mut(id)) {
+            match signal {
+                Signal::Continue => job.state = JobState::Running,
+                Signal::Suspend => job.state = JobState::Stopped,
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Converte `9`, `KILL` ou `SIGKILL` em sinal
+fn parse_signal(name: &str) -> Option<Signal> {
+    if let Ok(n) = name.parse::<u32>() {
+        return SIGNALS.iter().find(|(_, num, _)| *num == n).map(|s| s.2);
+    }
+
+    let upper = name.to_ascii_uppercase();
+    let upper = upper.strip_prefix("SIG").unwrap_or(&upper);
+    let upper = if upper == "STOP" { "TSTP" } else { upper };
+    SIGNALS.iter().find(|(n, _, _)| *n == upper).map(|s| s.2)
+}
+
+fn invalid_signal(output: &mut TerminalState, ctx: &mut ShellContext, name: &str) {
+    let message = format!("sinal invalido: {}", name);
+    args::report_error(output, ctx, "kill", KILL_SYNOPSIS, &message);
+}
+
+/// Resolve `%job`, escrevendo o erro se não existir
+fn resolve_job(
+    output: &mut TerminalState,
+    ctx: &mut ShellContext,
+    cmd: &str,
+    spec: &str,
+) -> Option<usize> {
+    match ctx.jobs.find(spec) {
+        Ok(id) => Some(id),
+        Err(msg) => {
+            output.write_line(&format!("{}: {}: {}", cmd, spec, msg));
+            ctx.last_exit_code = 1;
+            None
+        }
+    }
 }
 
 // =============================================================================
@@ -166,3 +455,38 @@ fn write_number(output: &mut TerminalState, n: u64) {
         output.write_str(s);
     }
 }
+
+// =============================================================================
+// TESTES
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::shell::commands::execute_command;
+    use crate::shell::testing::{run, shell, FakeLauncher};
+    use crate::shell::vfs::MemoryFs;
+    use crate::state::terminal::TerminalState;
+    use alloc::boxed::Box;
+
+    #[test]
+    fn ps_json_lists_jobs() {
+        let mut ctx = shell(MemoryFs::new());
+        ctx.launcher = Box::new(FakeLauncher::default().program("/apps/greet", "", 3));
+        assert_eq!(run(&mut ctx, "ps --json"), "[]\n");
+
+        // Fora de captura, `&` cria o job
+        let mut output = TerminalState::new(80, 24);
+        execute_command("greet 'a\"b' &", &mut ctx, &mut output);
+        ctx.jobs.poll(ctx.launcher.as_mut(), &mut output);
+        execute_command("greet ação &", &mut ctx, &mut output);
+
+        assert_eq!(
+            run(&mut ctx, "ps --json"),
+            concat!(
+                r#"[{"pid":1,"job":1,"state":"done","exit_code":3,"command":"greet a\"b"},"#,
+                r#"{"pid":2,"job":2,"state":"running","exit_code":null,"command":"greet ação"}]"#,
+                "\n"
+            )
+        );
+    }
+}
@@ -0,0 +1,1445 @@
+//! # Text Filters
+//!
+//! `head`, `tail`, `wc`, `sort`, `uniq`, `cut`, `tr`, `grep` e `tee`.
+//!
+//! Todos leem arquivos ou, sem arquivo (ou com `-`), a entrada do
+//! pipeline, abertos por [`open_input`]. A leitura é uma só: uma
+//! [`LineTask`] lê um bloco por passo, separa as linhas e as entrega a um
+//! [`Filter`], que decide o que escrever.
+//!
+//! ```text
+//! ls | sort -r | head -n 3
+//! cat log.txt | cut -d : -f 1 | sort | uniq -c
+//! tail -f /var/log/sistema.log
+//! grep -rn 'erro|falha' -E /apps
+//! ```
+
+use super::fs::open_input;
+use super::{error_to_str, CommandResult};
+use crate::shell::args::{self, Args};
+use crate::shell::ls_colors::ColorMode;
+use crate::shell::path;
+use crate::shell::task::{Task, TaskStatus};
+use crate::shell::vfs::{write_all, VfsFile};
+use crate::shell::ShellContext;
+use crate::state::regex::{Regex, RegexOptions, Syntax};
+use crate::state::terminal::TerminalState;
+use crate::state::utf8::Utf8Decoder;
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+/// Bytes lidos por passo da tarefa
+const CHUNK_SIZE: usize = 512;
+
+/// Linhas do `head`/`tail` sem `-n`
+const DEFAULT_LINES: u64 = 10;
+
+// =============================================================================
+// LEITURA POR LINHAS
+// =============================================================================
+
+/// Consumidor das linhas de uma [`LineTask`].
+///
+/// Cada linha chega com o `\n` final; só a última de um arquivo pode vir
+/// sem ele.
+trait Filter {
+    /// Começo de uma entrada (`None` = entrada do pipeline)
+    fn begin(&mut self, _output: &mut TerminalState, _name: Option<&str>) {}
+
+    /// Uma linha; `false` pula o resto da entrada
+    fn line(&mut self, output: &mut TerminalState, line: &[u8]) -> bool;
+
+    /// Fim de uma entrada
+    fn end(&mut self, _output: &mut TerminalState) {}
+
+    /// Fim de todas as entradas
+    fn finish(&mut self, _output: &mut TerminalState) {}
+
+    /// Código de saída; `failed` diz se alguma entrada não pôde ser lida
+    fn exit_code(&self, failed: bool) -> i32 {
+        i32::from(failed)
+    }
+}
+
+/// Lê as entradas em ordem e as passa, linha a linha, ao filtro
+struct LineTask {
+    cmd: &'static str,
+    /// Argumentos ainda não abertos (`-` = entrada do pipeline)
+    sources: VecDeque<String>,
+    /// Entrada sendo lida
+    file: Option<Box<dyn VfsFile>>,
+    /// Início de linha ainda sem `\n`
+    partial: Vec<u8>,
+    filter: Box<dyn Filter>,
+    /// `tail -f`: continua lendo o último arquivo depois do fim
+    follow: bool,
+    /// Já no fim do último arquivo, esperando dados novos
+    following: bool,
+    /// `grep -r`: diretórios são trocados pelo seu conteúdo
+    recursive: bool,
+    decoder: Utf8Decoder,
+    /// Alguma entrada não pôde ser aberta ou lida
+    failed: bool,
+}
+
+impl LineTask {
+    /// Tarefa sobre os argumentos posicionais (ou só a entrada do pipeline)
+    fn new(cmd: &'static str, files: &[&str], filter: Box<dyn Filter>) -> Self {
+        let mut sources: VecDeque<String> = files.iter().map(|f| String::from(*f)).collect();
+        if sources.is_empty() {
+            sources.push_back(String::from("-"));
+        }
+        Self {
+            cmd,
+            sources,
+            file: None,
+            partial: Vec::new(),
+            filter,
+            follow: false,
+            following: false,
+            recursive: false,
+            decoder: Utf8Decoder::new(),
+            failed: false,
+        }
+    }
+
+    /// Abre a próxima entrada; `false` quando não há mais
+    fn open_next(&mut self, ctx: &mut ShellContext, output: &mut TerminalState) -> bool {
+        let Some(arg) = self.sources.pop_front() else {
+            return false;
+        };
+
+        if self.recursive && arg != "-" {
+            let full_path = path::resolve(&ctx.cwd, &arg);
+            if ctx.fs.is_dir(&full_path) {
+                self.expand_dir(ctx, output, &arg, &full_path);
+                return true;
+            }
+        }
+
+        match open_input(output, ctx, self.cmd, Some(&arg)) {
+            Some((file, _)) => {
+                let name = (arg != "-").then_some(arg.as_str());
+                self.filter.begin(output, name);
+                self.file = Some(file);
+                // Seguir só faz sentido num arquivo que pode crescer
+                if name.is_none() && self.sources.is_empty() {
+                    self.follow = false;
+                }
+            }
+            None => self.failed = true,
+        }
+        true
+    }
+
+    /// Põe as entradas do diretório no início da fila, em ordem de nome.
+    ///
+    /// Só links passados como argumento são seguidos; dentro da árvore eles
+    /// são ignorados, o que também evita ciclos.
+    fn expand_dir(
+        &mut self,
+        ctx: &mut ShellContext,
+        output: &mut TerminalState,
+        arg: &str,
+        full_path: &str,
+    ) {
+        let mut entries = match ctx.fs.read_dir(full_path) {
+            Ok(entries) => entries,
+            Err(e) => {
+                output.write_str(self.cmd);
+                output.write_str(": ");
+                output.write_str(arg);
+                output.write_str(": ");
+                output.write_line(error_to_str(e));
+                self.failed = true;
+                return;
+            }
+        };
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+
+        for entry in entries.iter().rev() {
+            let child = path::join(full_path, &entry.name);
+            if ctx.fs.stat(&child).is_ok_and(|meta| !meta.is_symlink()) {
+                self.sources.push_front(path::join(arg, &entry.name));
+            }
+        }
+    }
+
+    /// Entrega as linhas completas de `data`, guardando o resto
+    fn feed(&mut self, output: &mut TerminalState, data: &[u8]) -> bool {
+        self.partial.extend_from_slice(data);
+
+        let mut start = 0;
+        while let Some(i) = self.partial[start..].iter().position(|&b| b == b'\n') {
+            let end = start + i + 1;
+            if !self.filter.line(output, &self.partial[start..end]) {
+                self.partial.clear();
+                return false;
+            }
+            start = end;
+        }
+        self.partial.drain(..start);
+        true
+    }
+
+    /// Fim da entrada atual: a linha sem `\n` também conta
+    fn end_input(&mut self, output: &mut TerminalState) {
+        if !self.partial.is_empty() {
+            let rest = core::mem::take(&mut self.partial);
+            self.filter.line(output, &rest);
+        }
+        self.filter.end(output);
+
+        if self.follow && self.sources.is_empty() {
+            self.following = true;
+        } else {
+            self.file = None;
+        }
+    }
+}
+
+impl Task for LineTask {
+    fn step(&mut self, ctx: &mut ShellContext, output: &mut TerminalState) -> TaskStatus {
+        let Some(file) = &mut self.file else {
+            if self.open_next(ctx, output) {
+                return TaskStatus::Pending;
+            }
+            self.filter.finish(output);
+            ctx.last_exit_code = self.filter.exit_code(self.failed);
+            return TaskStatus::Done;
+        };
+
+        let mut buf = [0u8; CHUNK_SIZE];
+        match file.read(&mut buf) {
+            // No `tail -f`, o fim do arquivo só significa "nada novo ainda";
+            // a tarefa termina com ^C
+            Ok(0) if self.following => {}
+            Ok(0) => self.end_input(output),
+            Ok(n) if self.following => {
+                let mut decoder = core::mem::take(&mut self.decoder);
+                decoder.decode_with(&buf[..n], |c| output.write_char(c));
+                self.decoder = decoder;
+            }
+            Ok(n) => {
+                if !self.feed(output, &buf[..n]) {
+                    self.filter.end(output);
+                    self.file = None;
+                }
+            }
+            Err(e) => {
+                output.write_str(self.cmd);
+                output.write_str(": erro ao ler: ");
+                output.write_line(error_to_str(e));
+                self.failed = true;
+                self.follow = false;
+                self.following = false;
+                self.end_input(output);
+            }
+        }
+        TaskStatus::Pending
+    }
+}
+
+/// Linha como texto (bytes inválidos viram U+FFFD)
+fn text(line: &[u8]) -> Cow<'_, str> {
+    String::from_utf8_lossy(line)
+}
+
+/// Linha sem o `\n` final
+fn chomp(line: &[u8]) -> &[u8] {
+    line.strip_suffix(b"\n").unwrap_or(line)
+}
+
+/// Cabeçalho `==> nome <==` do `head`/`tail` com vários arquivos
+fn write_header(output: &mut TerminalState, first: bool, name: Option<&str>) {
+    if !first {
+        output.write_char('\n');
+    }
+    output.write_str("==> ");
+    output.write_str(name.unwrap_or("entrada do pipeline"));
+    output.write_line(" <==");
+}
+
+// =============================================================================
+// head - Primeiras linhas
+// =============================================================================
+
+pub fn cmd_head(args: &Args) -> CommandResult {
+    let count = args.number("n").unwrap_or(DEFAULT_LINES);
+    let filter = Head {
+        count,
+        seen: 0,
+        headers: args.positional.len() > 1,
+        first: true,
+    };
+    CommandResult::Task(Box::new(LineTask::new(
+        "head",
+        &args.positional,
+        Box::new(filter),
+    )))
+}
+
+struct Head {
+    /// Linhas a mostrar por entrada
+    count: u64,
+    seen: u64,
+    /// Mais de uma entrada: cabeçalho antes de cada uma
+    headers: bool,
+    first: bool,
+}
+
+impl Filter for Head {
+    fn begin(&mut self, output: &mut TerminalState, name: Option<&str>) {
+        if self.headers {
+            write_header(output, self.first, name);
+        }
+        self.first = false;
+        self.seen = 0;
+    }
+
+    fn line(&mut self, output: &mut TerminalState, line: &[u8]) -> bool {
+        if self.seen >= self.count {
+            return false;
+        }
+        output.write_str(&text(line));
+        self.seen += 1;
+        self.seen < self.count
+    }
+}
+
+// =============================================================================
+// tail - Últimas linhas
+// =============================================================================
+
+pub fn cmd_tail(output: &mut TerminalState, ctx: &mut ShellContext, args: &Args) -> CommandResult {
+    // `-n N`: últimas N linhas; `-n +N`: a partir da linha N
+    let start = match args.value("n") {
+        None => TailStart::Last(DEFAULT_LINES),
+        Some(value) => {
+            let parsed = match value.strip_prefix('+') {
+                Some(n) => n.parse().ok().map(TailStart::From),
+                None => value.parse().ok().map(TailStart::Last),
+            };
+            let Some(start) = parsed else {
+                output.write_str("tail: numero de linhas invalido: ");
+                output.write_line(value);
+                ctx.last_exit_code = args::EXIT_USAGE;
+                return CommandResult::Ok;
+            };
+            start
+        }
+    };
+
+    let filter = Tail {
+        start,
+        buffer: VecDeque::new(),
+        line: 0,
+        headers: args.positional.len() > 1,
+        first: true,
+    };
+    let mut task = LineTask::new("tail", &args.positional, Box::new(filter));
+    // Numa captura, seguir o arquivo nunca terminaria
+    task.follow = args.flag("f") && !output.is_capturing();
+    CommandResult::Task(Box::new(task))
+}
+
+/// Onde o `tail` começa
+#[derive(Debug, Clone, Copy)]
+enum TailStart {
+    /// As últimas N linhas
+    Last(u64),
+    /// A partir da linha N (1 = todas)
+    From(u64),
+}
+
+struct Tail {
+    start: TailStart,
+    /// Últimas linhas vistas (`Last`)
+    buffer: VecDeque<Vec<u8>>,
+    /// Linhas vistas na entrada atual
+    line: u64,
+    headers: bool,
+    first: bool,
+}
+
+impl Filter for Tail {
+    fn begin(&mut self, output: &mut TerminalState, name: Option<&str>) {
+        if self.headers {
+            write_header(output, self.first, name);
+        }
+        self.first = false;
+        self.line = 0;
+    }
+
+    fn line(&mut self, output: &mut TerminalState, line: &[u8]) -> bool {
+        self.line += 1;
+        match self.start {
+            TailStart::Last(0) => {}
+            TailStart::Last(n) => {
+                if self.buffer.len() as u64 == n {
+                    self.buffer.pop_front();
+                }
+                self.buffer.push_back(Vec::from(line));
+            }
+            TailStart::From(n) => {
+                if self.line >= n {
+                    output.write_str(&text(line));
+                }
+            }
+        }
+        true
+    }
+
+    fn end(&mut self, output: &mut TerminalState) {
+        for line in self.buffer.drain(..) {
+            output.write_str(&text(&line));
+        }
+    }
+}
+
+// =============================================================================
+// wc - Conta linhas, palavras e bytes
+// =============================================================================
+
+pub fn cmd_wc(args: &Args) -> CommandResult {
+    let mut show = [args.flag("l"), args.flag("w"), args.flag("c")];
+    if show == [false; 3] {
+        show = [true; 3];
+    }
+    let filter = Wc {
+        show,
+        current: [0; 3],
+        total: [0; 3],
+        name: None,
+        inputs: 0,
+    };
+    CommandResult::Task(Box::new(LineTask::new(
+        "wc",
+        &args.positional,
+        Box::new(filter),
+    )))
+}
+
+/// Contagens `[linhas, palavras, bytes]`
+type Counts = [u64; 3];
+
+struct Wc {
+    /// Colunas pedidas, na ordem de `Counts`
+    show: [bool; 3],
+    current: Counts,
+    total: Counts,
+    /// Nome da entrada atual
+    name: Option<String>,
+    /// Entradas contadas
+    inputs: usize,
+}
+
+impl Wc {
+    fn write_row(&self, output: &mut TerminalState, counts: &Counts, name: Option<&str>) {
+        // `ls | wc -l` mostra só o número, como no coreutils
+        let columns = self.show.iter().filter(|s| **s).count();
+        let width = if name.is_none() && columns == 1 { 0 } else { 7 };
+
+        let mut row = String::new();
+        for (count, _) in counts.iter().zip(self.show).filter(|(_, s)| *s) {
+            if !row.is_empty() {
+                row.push(' ');
+            }
+            row.push_str(&format!("{:>width$}", count, width = width));
+        }
+        if let Some(name) = name {
+            row.push(' ');
+            row.push_str(name);
+        }
+        output.write_line(&row);
+    }
+}
+
+impl Filter for Wc {
+    fn begin(&mut self, _output: &mut TerminalState, name: Option<&str>) {
+        self.current = [0; 3];
+        self.name = name.map(String::from);
+    }
+
+    fn line(&mut self, _output: &mut TerminalState, line: &[u8]) -> bool {
+        if line.ends_with(b"\n") {
+            self.current[0] += 1;
+        }
+        self.current[1] += line
+            .split(|b| b.is_ascii_whitespace())
+            .filter(|w| !w.is_empty())
+            .count() as u64;
+        self.current[2] += line.len() as u64;
+        true
+    }
+
+    fn end(&mut self, output: &mut TerminalState) {
+        let name = self.name.take();
+        self.write_row(output, &self.current, name.as_deref());
+        for (total, n) in self.total.iter_mut().zip(self.current) {
+            *total += n;
+        }
+        self.inputs += 1;
+    }
+
+    fn finish(&mut self, output: &mut TerminalState) {
+        if self.inputs > 1 {
+            self.write_row(output, &self.total, Some("total"));
+        }
+    }
+}
+
+// =============================================================================
+// sort - Ordena linhas
+// =============================================================================
+
+pub fn cmd_sort(output: &mut TerminalState, ctx: &mut ShellContext, args: &Args) -> CommandResult {
+    let sep = match args.value("t") {
+        None => None,
+        Some(value) => match single_char(value) {
+            Some(c) => Some(c),
+            None => {
+                output.write_line("sort: o separador deve ser um unico caractere");
+                ctx.last_exit_code = args::EXIT_USAGE;
+                return CommandResult::Ok;
+            }
+        },
+    };
+    let key = match args.value("k") {
+        None => None,
+        Some(value) => match parse_key(value) {
+            Some(key) => Some(key),
+            None => {
+                output.write_str("sort: chave invalida: ");
+                output.write_line(value);
+                ctx.last_exit_code = args::EXIT_USAGE;
+                return CommandResult::Ok;
+            }
+        },
+    };
+
+    let filter = Sort {
+        lines: Vec::new(),
+        numeric: args.flag("n"),
+        reverse: args.flag("r"),
+        unique: args.flag("u"),
+        key,
+        sep,
+    };
+    CommandResult::Task(Box::new(LineTask::new(
+        "sort",
+        &args.positional,
+        Box::new(filter),
+    )))
+}
+
+/// `-k N[,M]`: campos N até M (1 = primeiro; sem M, até o fim da linha)
+fn parse_key(value: &str) -> Option<(usize, usize)> {
+    let (start, end) = match value.split_once(',') {
+        Some((start, end)) => (start.parse().ok()?, end.parse().ok()?),
+        None => (value.parse().ok()?, usize::MAX),
+    };
+    (start > 0 && end >= start).then_some((start, end))
+}
+
+struct Sort {
+    lines: Vec<String>,
+    /// `-n`: compara o número no início da chave
+    numeric: bool,
+    reverse: bool,
+    /// `-u`: uma linha por chave
+    unique: bool,
+    /// `-k`: campos usados na comparação
+    key: Option<(usize, usize)>,
+    /// `-t`: separador de campos (padrão: espaços)
+    sep: Option<char>,
+}
+
+impl Sort {
+    /// Trecho da linha usado na comparação
+    fn key<'a>(&self, line: &'a str) -> &'a str {
+        let Some((first, last)) = self.key else {
+            return line;
+        };
+        let fields = field_spans(line, self.sep);
+        match fields.get(first - 1) {
+            Some(&(start, _)) => {
+                let end = fields
+                    .get(last - 1)
+                    .or(fields.last())
+                    .map_or(start, |f| f.1);
+                &line[start..end]
+            }
+            None => "",
+        }
+    }
+
+    fn compare(&self, a: &str, b: &str) -> Ordering {
+        let (ka, kb) = (self.key(a), self.key(b));
+        let order = if self.numeric {
+            leading_number(ka)
+                .partial_cmp(&leading_number(kb))
+                .unwrap_or(Ordering::Equal)
+        } else {
+            ka.cmp(kb)
+        };
+        if self.reverse {
+            order.reverse()
+        } else {
+            order
+        }
+    }
+}
+
+impl Filter for Sort {
+    fn line(&mut self, _output: &mut TerminalState, line: &[u8]) -> bool {
+        self.lines.push(text(chomp(line)).into_owned());
+        true
+    }
+
+    fn finish(&mut self, output: &mut TerminalState) {
+        let mut lines = core::mem::take(&mut self.lines);
+        // Chaves iguais: desempata pela linha inteira, como o coreutils
+        // (com -u, a primeira linha de cada chave é a que fica)
+        lines.sort_by(|a, b| {
+            self.compare(a, b)
+                .then_with(|| match (self.unique, self.reverse) {
+                    (true, _) => Ordering::Equal,
+                    (false, true) => b.cmp(a),
+                    (false, false) => a.cmp(b),
+                })
+        });
+        if self.unique {
+            lines.dedup_by(|a, b| self.compare(a, b) == Ordering::Equal);
+        }
+        for line in &lines {
+            output.write_line(line);
+        }
+    }
+}
+
+/// Posições `(início, fim)` de cada campo da linha.
+///
+/// Sem separador, campos são sequências sem espaços; com ele, os campos
+/// vazios também contam (`a::b` tem três).
+fn field_spans(line: &str, sep: Option<char>) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    match sep {
+        Some(sep) => {
+            let mut start = 0;
+            for (i, c) in line.char_indices().filter(|(_, c)| *c == sep) {
+                spans.push((start, i));
+                start = i + c.len_utf8();
+            }
+            spans.push((start, line.len()));
+        }
+        None => {
+            let mut start = None;
+            for (i, c) in line.char_indices() {
+                match (c.is_whitespace(), start) {
+                    (true, Some(s)) => {
+                        spans.push((s, i));
+                        start = None;
+                    }
+                    (false, None) => start = Some(i),
+                    _ => {}
+                }
+            }
+            if let Some(s) = start {
+                spans.push((s, line.len()));
+            }
+        }
+    }
+    spans
+}
+
+/// Número no início do texto (`-12.5kg` → -12.5); 0 se não houver
+fn leading_number(s: &str) -> f64 {
+    let s = s.trim_start();
+    let mut end = 0;
+    let mut dot = false;
+    for (i, c) in s.char_indices() {
+        match c {
+            '-' | '+' if i == 0 => {}
+            '.' if !dot => dot = true,
+            '0'..='9' => {}
+            _ => break,
+        }
+        end = i + 1;
+    }
+    s[..end].parse().unwrap_or(0.0)
+}
+
+// =============================================================================
+// uniq - Junta linhas repetidas
+// =============================================================================
+
+pub fn cmd_uniq(args: &Args) -> CommandResult {
+    let filter = Uniq {
+        count: args.flag("c"),
+        repeated: args.flag("d"),
+        unique: args.flag("u"),
+        ignore_case: args.flag("i"),
+        current: None,
+    };
+    CommandResult::Task(Box::new(LineTask::new(
+        "uniq",
+        &args.positional,
+        Box::new(filter),
+    )))
+}
+
+struct Uniq {
+    /// `-c`: prefixa cada linha com o número de repetições
+    count: bool,
+    /// `-d`: só linhas repetidas
+    repeated: bool,
+    /// `-u`: só linhas sem repetição
+    unique: bool,
+    /// `-i`: ignora maiúsculas
+    ignore_case: bool,
+    /// Linha atual e quantas vezes apareceu seguida
+    current: Option<(String, u64)>,
+}
+
+impl Uniq {
+    fn flush(&mut self, output: &mut TerminalState) {
+        let Some((line, n)) = self.current.take() else {
+            return;
+        };
+        if (self.repeated && n < 2) || (self.unique && n > 1) {
+            return;
+        }
+        if self.count {
+            output.write_str(&format!("{:7} ", n));
+        }
+        output.write_line(&line);
+    }
+}
+
+impl Filter for Uniq {
+    fn line(&mut self, output: &mut TerminalState, line: &[u8]) -> bool {
+        let line = text(chomp(line));
+        if let Some((current, n)) = &mut self.current {
+            let same = if self.ignore_case {
+                current.to_lowercase() == line.to_lowercase()
+            } else {
+                *current == line
+            };
+            if same {
+                *n += 1;
+                return true;
+            }
+        }
+        self.flush(output);
+        self.current = Some((line.into_owned(), 1));
+        true
+    }
+
+    fn finish(&mut self, output: &mut TerminalState) {
+        self.flush(output);
+    }
+}
+
+// =============================================================================
+// cut - Seleciona campos ou caracteres
+// =============================================================================
+
+const CUT_SYNOPSIS: &str = "cut (-f lista [-d delim] [-s] | -c lista) [arquivo]...";
+
+pub fn cmd_cut(output: &mut TerminalState, ctx: &mut ShellContext, args: &Args) -> CommandResult {
+    let (list, mode) = match (args.value("f"), args.value("c")) {
+        (Some(list), None) => {
+            let delim = match args.value("d") {
+                None => '\t',
+                Some(value) => match single_char(value) {
+                    Some(c) => c,
+                    None => {
+                        args::report_error(
+                            output,
+                            ctx,
+                            "cut",
+                            CUT_SYNOPSIS,
+                            "o delimitador deve ser um unico caractere",
+                        );
+                        return CommandResult::Ok;
+                    }
+                },
+            };
+            let only_delimited = args.flag("s");
+            (
+                list,
+                CutMode::Fields {
+                    delim,
+                    only_delimited,
+                },
+            )
+        }
+        (None, Some(list)) => (list, CutMode::Chars),
+        _ => {
+            args::report_error(
+                output,
+                ctx,
+                "cut",
+                CUT_SYNOPSIS,
+                "use -f ou -c (um dos dois)",
+            );
+            return CommandResult::Ok;
+        }
+    };
+
+    let Some(ranges) = parse_list(list) else {
+        let message = format!("lista invalida: '{}'", list);
+        args::report_error(output, ctx, "cut", CUT_SYNOPSIS, &message);
+        return CommandResult::Ok;
+    };
+
+    let filter = Cut { mode, ranges };
+    CommandResult::Task(Box::new(LineTask::new(
+        "cut",
+        &args.positional,
+        Box::new(filter),
+    )))
+}
+
+/// Lista de `cut`: `1,3-5,-2,7-` em intervalos `(início, fim)` a partir de 1
+fn parse_list(list: &str) -> Option<Vec<(usize, usize)>> {
+    list.split(',')
+        .map(|item| {
+            let (start, end) = match item.split_once('-') {
+                Some(("", "")) => return None,
+                Some((start, end)) => (
+                    if start.is_empty() {
+                        1
+                    } else {
+                        start.parse().ok()?
+                    },
+                    if end.is_empty() {
+                        usize::MAX
+                    } else {
+                        end.parse().ok()?
+                    },
+                ),
+                None => {
+                    let n = item.parse().ok()?;
+                    (n, n)
+                }
+            };
+            (start > 0 && end >= start).then_some((start, end))
+        })
+        .collect()
+}
+
+/// O que o `cut` seleciona
+enum CutMode {
+    /// `-f`: campos separados por `delim`
+    Fields {
+        delim: char,
+        /// `-s`: omite linhas sem o delimitador
+        only_delimited: bool,
+    },
+    /// `-c`: caracteres
+    Chars,
+}
+
+struct Cut {
+    mode: CutMode,
+    ranges: Vec<(usize, usize)>,
+}
+
+impl Cut {
+    /// A posição `n` (a partir de 1) está na lista
+    fn selected(&self, n: usize) -> bool {
+        self.ranges
+            .iter()
+            .any(|&(start, end)| n >= start && n <= end)
+    }
+}
+
+impl Filter for Cut {
+    fn line(&mut self, output: &mut TerminalState, line: &[u8]) -> bool {
+        let line = text(chomp(line));
+        let mut out = String::new();
+
+        match self.mode {
+            CutMode::Chars => {
+                out.extend(
+                    line.chars()
+                        .enumerate()
+                        .filter(|(i, _)| self.selected(i + 1))
+                        .map(|(_, c)| c),
+                );
+            }
+            CutMode::Fields {
+                delim,
+                only_delimited,
+            } => {
+                // Linhas sem o delimitador passam inteiras
+                if !line.contains(delim) {
+                    if !only_delimited {
+                        output.write_line(&line);
+                    }
+                    return true;
+                }
+                for (i, field) in line.split(delim).enumerate() {
+                    if self.selected(i + 1) {
+                        if !out.is_empty() {
+                            out.push(delim);
+                        }
+                        out.push_str(field);
+                    }
+                }
+            }
+        }
+        output.write_line(&out);
+        true
+    }
+}
+
+// =============================================================================
+// tr - Troca ou apaga caracteres
+// =============================================================================
+
+const TR_SYNOPSIS: &str = "tr [-d] [-s] conjunto1 [conjunto2]";
+
+pub fn cmd_tr(output: &mut TerminalState, ctx: &mut ShellContext, args: &Args) -> CommandResult {
+    let delete = args.flag("d");
+    let squeeze = args.flag("s");
+
+    let sets: Vec<Vec<char>> = args.positional.iter().map(|s| expand_set(s)).collect();
+    let expected = match (delete, squeeze) {
+        (true, false) => 1..=1,
+        (false, true) => 1..=2,
+        _ => 2..=2,
+    };
+    if !expected.contains(&sets.len()) {
+        let message = if sets.len() < *expected.start() {
+            "faltam conjuntos"
+        } else {
+            "conjuntos demais"
+        };
+        args::report_error(output, ctx, "tr", TR_SYNOPSIS, message);
+        return CommandResult::Ok;
+    }
+
+    let mut sets = sets.into_iter();
+    let from = sets.next().unwrap_or_default();
+    let to = sets.next().unwrap_or_default();
+    // Com -d, o segundo conjunto é só o de -s
+    let (to, squeeze_set) = match (delete, squeeze) {
+        (true, true) => (Vec::new(), to),
+        (false, true) if to.is_empty() => (to, from.clone()),
+        (false, true) => (to.clone(), to),
+        _ => (to, Vec::new()),
+    };
+
+    let filter = Tr {
+        from,
+        to,
+        delete,
+        squeeze: squeeze_set,
+        last: None,
+    };
+    // Só a entrada do pipeline, como no coreutils
+    CommandResult::Task(Box::new(LineTask::new("tr", &[], Box::new(filter))))
+}
+
+/// Expande um conjunto do `tr`: escapes (`\n`, `\t`, `\\`, `\NNN`),
+/// intervalos (`a-z`) e classes (`[:upper:]`)
+fn expand_set(set: &str) -> Vec<char> {
+    let mut chars: Vec<char> = Vec::new();
+    let mut rest = set;
+
+    while let Some(c) = rest.chars().next() {
+        if let Some(class) = rest.strip_prefix("[:").and_then(|r| r.split_once(":]")) {
+            if let Some(members) = class_members(class.0) {
+                chars.extend(members);
+                rest = class.1;
+                continue;
+            }
+        }
+
+        let (first, after) = set_char(rest, c);
+        // `a-z`: o `-` no fim é literal
+        if let Some(tail) = after.strip_prefix('-').filter(|t| !t.is_empty()) {
+            let (last, after_range) = set_char(tail, tail.chars().next().unwrap_or('-'));
+            if last >= first {
+                chars.extend(first..=last);
+                rest = after_range;
+                continue;
+            }
+        }
+        chars.push(first);
+        rest = after;
+    }
+    chars
+}
+
+/// Um caractere do conjunto, com escape; devolve o resto do texto
+fn set_char(s: &str, c: char) -> (char, &str) {
+    let after = &s[c.len_utf8()..];
+    if c != '\\' {
+        return (c, after);
+    }
+    let Some(e) = after.chars().next() else {
+        return ('\\', after);
+    };
+    let rest = &after[e.len_utf8()..];
+    match e {
+        'n' => ('\n', rest),
+        't' => ('\t', rest),
+        'r' => ('\r', rest),
+        '0'..='7' => {
+            let digits = after
+                .bytes()
+                .take(3)
+                .take_while(|b| (b'0'..=b'7').contains(b))
+                .count();
+            let value = u32::from_str_radix(&after[..digits], 8).unwrap_or(0);
+            (char::from_u32(value).unwrap_or('\0'), &after[digits..])
+        }
+        other => (other, rest),
+    }
+}
+
+/// Membros de `[:nome:]`, em ordem (`[:lower:]` e `[:upper:]` alinham)
+fn class_members(name: &str) -> Option<Vec<char>> {
+    let test: fn(&char) -> bool = match name {
+        "alpha" => |c| c.is_ascii_alphabetic(),
+        "digit" => |c| c.is_ascii_digit(),
+        "alnum" => |c| c.is_ascii_alphanumeric(),
+        "lower" => |c| c.is_ascii_lowercase(),
+        "upper" => |c| c.is_ascii_uppercase(),
+        "space" => |c| c.is_ascii_whitespace() || *c == '\x0b',
+        "blank" => |c| *c == ' ' || *c == '\t',
+        "punct" => |c| c.is_ascii_punctuation(),
+        "cntrl" => |c| c.is_ascii_control(),
+        _ => return None,
+    };
+    Some(('\0'..='\x7f').filter(test).collect())
+}
+
+/// `-d`/`-t`: um caractere, aceitando escapes como `\t`
+fn single_char(value: &str) -> Option<char> {
+    match expand_set(value).as_slice() {
+        [c] => Some(*c),
+        _ => None,
+    }
+}
+
+struct Tr {
+    from: Vec<char>,
+    /// Substitutos; o último se repete se for mais curto que `from`
+    to: Vec<char>,
+    /// `-d`: apaga os caracteres de `from`
+    delete: bool,
+    /// `-s`: repetições seguidas destes viram uma só
+    squeeze: Vec<char>,
+    /// Último caractere escrito (para `-s` entre linhas)
+    last: Option<char>,
+}
+
+impl Filter for Tr {
+    fn line(&mut self, output: &mut TerminalState, line: &[u8]) -> bool {
+        let mut out = String::new();
+        for c in text(line).chars() {
+            if self.delete && self.from.contains(&c) {
+                continue;
+            }
+            let c = match self.from.iter().rposition(|f| *f == c) {
+                Some(i) if !self.to.is_empty() => self.to[i.min(self.to.len() - 1)],
+                _ => c,
+            };
+            if self.last == Some(c) && self.squeeze.contains(&c) {
+                continue;
+            }
+            out.push(c);
+            self.last = Some(c);
+        }
+        output.write_str(&out);
+        true
+    }
+}
+
+// =============================================================================
+// grep - Busca linhas por expressão regular
+// =============================================================================
+
+const GREP_SYNOPSIS: &str = "grep [opcoes] padrao [arquivo]...";
+
+/// Cores da saída, como as padrão do GNU grep
+const GREP_MATCH: &str = "\x1b[1;31m";
+const GREP_NAME: &str = "\x1b[35m";
+const GREP_LINE: &str = "\x1b[32m";
+const GREP_SEP: &str = "\x1b[36m";
+const GREP_RESET: &str = "\x1b[0m";
+
+pub fn cmd_grep(output: &mut TerminalState, ctx: &mut ShellContext, args: &Args) -> CommandResult {
+    let Some(mode) = ColorMode::parse(args.value("color")) else {
+        output.write_str("grep: valor invalido para --color: ");
+        output.write_line(args.value("color").unwrap_or_default());
+        ctx.last_exit_code = args::EXIT_USAGE;
+        return CommandResult::Ok;
+    };
+    let Some((pattern, files)) = args.positional.split_first() else {
+        args::report_error(output, ctx, "grep", GREP_SYNOPSIS, "falta o padrao");
+        return CommandResult::Ok;
+    };
+
+    let options = RegexOptions {
+        syntax: if args.flag("E") {
+            Syntax::Extended
+        } else {
+            Syntax::Basic
+        },
+        ignore_case: args.flag("i"),
+    };
+    let regex = match Regex::with_options(pattern, options) {
+        Ok(regex) => regex,
+        Err(e) => {
+            output.write_str("grep: ");
+            output.write_line(&e.message());
+            ctx.last_exit_code = args::EXIT_USAGE;
+            return CommandResult::Ok;
+        }
+    };
+
+    let recursive = args.flag("r");
+    // `grep -r padrao` procura no diretório atual
+    let files: &[&str] = if recursive && files.is_empty() {
+        &["."]
+    } else {
+        files
+    };
+    let invert = args.flag("v");
+    let filter = Grep {
+        regex,
+        invert,
+        line_numbers: args.flag("n"),
+        files_only: args.flag("l"),
+        count: args.flag("c"),
+        with_names: recursive || files.len() > 1,
+        colors: mode.enabled(output.is_capturing()),
+        name: None,
+        line: 0,
+        selected: 0,
+        any: false,
+    };
+    let mut task = LineTask::new("grep", files, Box::new(filter));
+    task.recursive = recursive;
+    CommandResult::Task(Box::new(task))
+}
+
+struct Grep {
+    regex: Regex,
+    /// `-v`: seleciona as linhas que não casam
+    invert: bool,
+    /// `-n`
+    line_numbers: bool,
+    /// `-l`: só o nome de cada entrada com alguma linha
+    files_only: bool,
+    /// `-c`: só a contagem por entrada
+    count: bool,
+    /// Prefixa as linhas com o nome da entrada
+    with_names: bool,
+    colors: bool,
+    /// Entrada atual, como foi passada
+    name: Option<String>,
+    /// Linha atual
+    line: u64,
+    /// Linhas selecionadas na entrada atual
+    selected: u64,
+    /// Alguma linha foi selecionada (código de saída 0)
+    any: bool,
+}
+
+impl Grep {
+    fn name(&self) -> &str {
+        self.name.as_deref().unwrap_or("(entrada)")
+    }
+
+    /// `nome:` e `linha:` conforme as opções
+    fn write_prefix(&self, output: &mut TerminalState, line: Option<u64>) {
+        let mut fields: Vec<(&str, String)> = Vec::new();
+        if self.with_names {
+            fields.push((GREP_NAME, String::from(self.name())));
+        }
+        if let Some(n) = line {
+            fields.push((GREP_LINE, format!("{}", n)));
+        }
+        for (color, field) in fields {
+            if self.colors {
+                output.write_str(&format!(
+                    "{}{}{}{}:{}",
+                    color, field, GREP_RESET, GREP_SEP, GREP_RESET
+                ));
+            } else {
+                output.write_str(&field);
+                output.write_char(':');
+            }
+        }
+    }
+
+    /// A linha com os casamentos destacados
+    fn write_matches(&self, output: &mut TerminalState, line: &str) {
+        if !self.colors || self.invert {
+            output.write_line(line);
+            return;
+        }
+        let mut last = 0;
+        for (start, end) in self.regex.find_iter(line).filter(|(s, e)| s < e) {
+            output.write_str(&line[last..start]);
+            output.write_str(GREP_MATCH);
+            output.write_str(&line[start..end]);
+            output.write_str(GREP_RESET);
+            last = end;
+        }
+        output.write_line(&line[last..]);
+    }
+}
+
+impl Filter for Grep {
+    fn begin(&mut self, _output: &mut TerminalState, name: Option<&str>) {
+        self.name = name.map(String::from);
+        self.line = 0;
+        self.selected = 0;
+    }
+
+    fn line(&mut self, output: &mut TerminalState, line: &[u8]) -> bool {
+        self.line += 1;
+        let bytes = chomp(line);
+        let text = text(bytes);
+        if self.regex.is_match(&text) == self.invert {
+            return true;
+        }
+        self.selected += 1;
+        self.any = true;
+
+        if self.files_only {
+            output.write_line(self.name());
+            return false;
+        }
+        if self.count {
+            return true;
+        }
+        // NUL só aparece em binários: avisa uma vez e passa ao próximo
+        if bytes.contains(&0) {
+            output.write_str("grep: ");
+            output.write_str(self.name());
+            output.write_line(": arquivo binario corresponde");
+            return false;
+        }
+
+        self.write_prefix(output, self.line_numbers.then_some(self.line));
+        self.write_matches(output, &text);
+        true
+    }
+
+    fn end(&mut self, output: &mut TerminalState) {
+        if self.count {
+            self.write_prefix(output, None);
+            output.write_line(&format!("{}", self.selected));
+        }
+    }
+
+    fn exit_code(&self, failed: bool) -> i32 {
+        // 0: achou, 1: não achou, 2: erro
+        if failed {
+            2
+        } else {
+            i32::from(!self.any)
+        }
+    }
+}
+
+// =============================================================================
+// tee - Copia a entrada para arquivos
+// =============================================================================
+
+pub fn cmd_tee(output: &mut TerminalState, ctx: &mut ShellContext, args: &Args) -> CommandResult {
+    let mut files = Vec::new();
+    let mut failed = false;
+    for arg in &args.positional {
+        let full_path = path::resolve(&ctx.cwd, arg);
+        match ctx.fs.create(&full_path) {
+            Ok(file) => files.push((full_path, file)),
+            Err(e) => {
+                output.write_str("tee: ");
+                output.write_str(&full_path);
+                output.write_str(": ");
+                output.write_line(error_to_str(e));
+                failed = true;
+            }
+        }
+    }
+
+    let filter = Tee { files, failed };
+    // Os argumentos são destinos; a origem é sempre o pipeline
+    CommandResult::Task(Box::new(LineTask::new("tee", &[], Box::new(filter))))
+}
+
+struct Tee {
+    files: Vec<(String, Box<dyn VfsFile>)>,
+    failed: bool,
+}
+
+impl Filter for Tee {
+    fn line(&mut self, output: &mut TerminalState, line: &[u8]) -> bool {
+        output.write_str(&text(line));
+
+        // Um destino que falha é descartado; os outros continuam
+        let mut failed = false;
+        self.files
+            .retain_mut(|(full_path, file)| match write_all(file.as_mut(), line) {
+                Ok(()) => true,
+                Err(e) => {
+                    output.write_str("tee: ");
+                    output.write_str(full_path);
+                    output.write_str(": ");
+                    output.write_line(error_to_str(e));
+                    failed = true;
+                    false
+                }
+            });
+        self.failed |= failed;
+        true
+    }
+
+    fn exit_code(&self, failed: bool) -> i32 {
+        i32::from(failed || self.failed)
+    }
+}
+
+// =============================================================================
+// TESTES
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::shell::testing::{contents, run, shell};
+    use crate::shell::vfs::MemoryFs;
+    use crate::shell::ShellContext;
+
+    fn files() -> ShellContext {
+        let mut fs = MemoryFs::new();
+        fs.add_file("/n.txt", b"1\n2\n3\n4\n5\n")
+            .add_file("/sem-fim.txt", b"um dois\ntres")
+            .add_file(
+                "/passwd",
+                b"root:x:0:sh\nana:x:1000:zsh\nbia:x:20:sh\nana:y:7:sh\n",
+            )
+            .add_file("/rep.txt", b"a\na\nb\nA\na\n");
+        shell(fs)
+    }
+
+    #[test]
+    fn head_takes_the_first_lines() {
+        let mut ctx = files();
+        assert_eq!(run(&mut ctx, "head -n 2 /n.txt"), "1\n2\n");
+        assert_eq!(run(&mut ctx, "head -n 9 /sem-fim.txt"), "um dois\ntres");
+    }
+
+    #[test]
+    fn tail_counts_from_the_end_or_from_a_line() {
+        let mut ctx = files();
+        assert_eq!(run(&mut ctx, "tail -n 2 /n.txt"), "4\n5\n");
+        assert_eq!(run(&mut ctx, "tail -n +4 /n.txt"), "4\n5\n");
+        assert_eq!(run(&mut ctx, "tail -n +1 /n.txt"), "1\n2\n3\n4\n5\n");
+        assert_eq!(run(&mut ctx, "cat /n.txt | tail -n 1"), "5\n");
+    }
+
+    #[test]
+    fn sort_by_key_with_separator() {
+        let mut ctx = files();
+        assert_eq!(
+            run(&mut ctx, "sort -t : -k 3,3 -n /passwd"),
+            "root:x:0:sh\nana:y:7:sh\nbia:x:20:sh\nana:x:1000:zsh\n"
+        );
+        assert_eq!(
+            run(&mut ctx, "sort -t : -k 1,1 -u /passwd"),
+            "ana:x:1000:zsh\nbia:x:20:sh\nroot:x:0:sh\n"
+        );
+        assert_eq!(
+            run(&mut ctx, "sort -t : -k 4 -r /passwd"),
+            "ana:x:1000:zsh\nroot:x:0:sh\nbia:x:20:sh\nana:y:7:sh\n"
+        );
+        assert_eq!(run(&mut ctx, "sort -n -r /n.txt"), "5\n4\n3\n2\n1\n");
+    }
+
+    #[test]
+    fn uniq_counts_adjacent_repeats() {
+        let mut ctx = files();
+        assert_eq!(
+            run(&mut ctx, "uniq -c /rep.txt"),
+            "      2 a\n      1 b\n      1 A\n      1 a\n"
+        );
+        assert_eq!(run(&mut ctx, "uniq -d /rep.txt"), "a\n");
+        assert_eq!(run(&mut ctx, "uniq -i -u /rep.txt"), "b\n");
+    }
+
+    #[test]
+    fn cut_fields_and_ranges() {
+        let mut ctx = files();
+        assert_eq!(
+            run(&mut ctx, "cut -d : -f 1,3 /passwd"),
+            "root:0\nana:1000\nbia:20\nana:7\n"
+        );
+        assert_eq!(
+            run(&mut ctx, "head -n 1 /passwd | cut -d : -f 2-"),
+            "x:0:sh\n"
+        );
+        assert_eq!(
+            run(&mut ctx, "head -n 1 /passwd | cut -d : -f -2"),
+            "root:x\n"
+        );
+        assert_eq!(run(&mut ctx, "echo ação | cut -c 2-3"), "çã\n");
+        assert_eq!(run(&mut ctx, "echo sem | cut -d : -f 2 -s"), "");
+    }
+
+    #[test]
+    fn tr_ranges_and_delete() {
+        let mut ctx = files();
+        assert_eq!(run(&mut ctx, "echo Ola Mundo | tr a-z A-Z"), "OLA MUNDO\n");
+        assert_eq!(run(&mut ctx, "echo a1b2c3 | tr -d 0-9"), "abc\n");
+        assert_eq!(run(&mut ctx, "echo 'a   b' | tr -s ' '"), "a b\n");
+        assert_eq!(run(&mut ctx, "echo abc | tr abc x"), "xxx\n");
+    }
+
+    #[test]
+    fn wc_counts_a_last_line_without_newline() {
+        let mut ctx = files();
+        assert_eq!(
+            run(&mut ctx, "wc /sem-fim.txt"),
+            "      1       3      12 /sem-fim.txt\n"
+        );
+        assert_eq!(
+            run(&mut ctx, "wc -l /sem-fim.txt"),
+            "      1 /sem-fim.txt\n"
+        );
+        assert_eq!(
+            run(&mut ctx, "wc -w /sem-fim.txt"),
+            "      3 /sem-fim.txt\n"
+        );
+        assert_eq!(
+            run(&mut ctx, "wc -c /sem-fim.txt"),
+            "     12 /sem-fim.txt\n"
+        );
+        assert_eq!(run(&mut ctx, "cat /n.txt | wc -l"), "5\n");
+    }
+
+    #[test]
+    fn tee_copies_to_files_and_output() {
+        let mut ctx = files();
+        assert_eq!(
+            run(&mut ctx, "cat /n.txt | head -n 2 | tee /a /b"),
+            "1\n2\n"
+        );
+        assert_eq!(contents(&ctx, "/a").as_deref(), Some("1\n2\n"));
+        assert_eq!(contents(&ctx, "/b").as_deref(), Some("1\n2\n"));
+    }
+}
@@ -2,7 +2,18 @@
 //!
 //! Contexto compartilhado do shell.
 
-use alloc::string::String;
+use super::commands::Registry;
+use super::jobs::JobTable;
+use super::launcher::{ProcessLauncher, RedpowderLauncher, DEFAULT_PATH};
+use super::path;
+use super::prompt;
+use super::table::{PipeData, Table};
+use super::vfs::{RedpowderFs, Vfs};
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
 
 // TODO: Revisar no futuro
 #[allow(unused)]
@@ -16,29 +27,113 @@ pub struct ShellContext {
     pub username: String,
     /// Hostname
     pub hostname: String,
+    /// Variáveis do shell (`NOME=valor`)
+    pub vars: BTreeMap<String, String>,
+    /// Lançador de programas externos
+    pub launcher: Box<dyn ProcessLauncher>,
+    /// Sistema de arquivos usado pelos comandos
+    pub fs: Box<dyn Vfs>,
+    /// Jobs em segundo plano ou parados
+    pub jobs: JobTable,
+    /// Pilha do `pushd`/`popd`, topo primeiro (sem o diretório atual)
+    pub dir_stack: Vec<String>,
+    /// Comandos internos disponíveis
+    pub commands: Registry,
+    /// Saída do estágio anterior do pipeline, ainda não lida
+    pub stdin: Option<PipeData>,
+    /// O próximo estágio do pipeline consome tabelas
+    pub pipe_table: bool,
+    /// Tabela entregue ao próximo estágio
+    pub table_out: Option<Table>,
 }
 
 impl ShellContext {
     /// Cria novo contexto
     pub fn new() -> Self {
+        Self::with_launcher(Box::new(RedpowderLauncher))
+    }
+
+    /// Cria contexto com um lançador de processos específico
+    pub fn with_launcher(launcher: Box<dyn ProcessLauncher>) -> Self {
+        let mut vars = BTreeMap::new();
+        vars.insert(String::from("PATH"), String::from(DEFAULT_PATH));
+
         Self {
             cwd: String::from("/"),
             last_exit_code: 0,
             username: String::from("redstone"),
             hostname: String::from("localhost"),
+            vars,
+            launcher,
+            fs: Box::new(RedpowderFs),
+            jobs: JobTable::new(),
+            dir_stack: Vec::new(),
+            commands: Registry::with_builtins(),
+            stdin: None,
+            pipe_table: false,
+            table_out: None,
         }
     }
 
-    /// Retorna o prompt formatado
+    /// Retorna o prompt formatado a partir de `PS1`
     pub fn prompt(&self) -> String {
-        use alloc::format;
-        format!("{}@{}:{}$ ", self.username, self.hostname, self.cwd)
+        match self.vars.get("PS1") {
+            Some(ps1) => prompt::render(ps1, self),
+            None => prompt::render(prompt::DEFAULT_PS1, self),
+        }
     }
 
-    /// Atualiza CWD
+    /// Atualiza CWD (normalizado), guardando o anterior em `OLDPWD`
     pub fn set_cwd(&mut self, path: &str) {
-        self.cwd.clear();
-        self.cwd.push_str(path);
+        let old = core::mem::replace(&mut self.cwd, path::normalize(path));
+        self.set_var("OLDPWD", &old);
+    }
+
+    /// Retorna o valor de uma variável.
+    ///
+    /// Além das variáveis definidas pelo usuário, resolve as especiais
+    /// `?` (último código de saída), `PWD`, `USER` e `HOSTNAME`.
+    pub fn var(&self, name: &str) -> Option<String> {
+        if let Some(value) = self.vars.get(name) {
+            return Some(value.clone());
+        }
+
+        match name {
+            "?" => Some(self.last_exit_code.to_string()),
+            "PWD" => Some(self.cwd.clone()),
+            "USER" => Some(self.username.clone()),
+            "HOSTNAME" => Some(self.hostname.clone()),
+            _ => None,
+        }
+    }
+
+    /// Define uma variável
+    pub fn set_var(&mut self, name: &str, value: &str) {
+        self.vars.insert(String::from(name), String::from(value));
+    }
+
+    /// Ambiente repassado a programas externos (`NOME=valor`)
+    pub fn environ(&self) -> Vec<String> {
+        let mut env: Vec<String> = self
+            .vars
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect();
+
+        for name in ["PWD", "USER", "HOSTNAME"] {
+            if !self.vars.contains_key(name) {
+                if let Some(value) = self.var(name) {
+                    env.push(format!("{}={}", name, value));
+                }
+            }
+        }
+
+        env
+    }
+
+    /// Remove uma variável
+    pub fn unset_var(&mut self, name: &str) {
+        self.vars.remove(name);
     }
 }
 
@@ -0,0 +1,845 @@
+//! # Editor
+//!
+//! Editor de texto em tela cheia no estilo do nano (`edit <arquivo>`).
+//!
+//! ```text
+//!   edit  /apps/config.txt  [Modificado]       ← título
+//!   1 cor=azul                                 ← texto, com número da linha
+//!   2 tamanho=12
+//!             [ 2 linhas gravadas ]            ← status e perguntas
+//! ^O Salvar    ^W Buscar    ^K Recortar ...    ← ajuda
+//! ^X Sair      ^C Posicao   ^A Inicio   ...
+//! ```
+//!
+//! O texto fica em memória como linhas UTF-8; o cursor anda por
+//! caracteres, não por bytes. A leitura e a gravação passam pelo [`Vfs`]
+//! do shell (no RedstoneOS, `redpowder::fs::File`).
+//!
+//! ## Teclas
+//!
+//! | Tecla               | Ação                                       |
+//! |---------------------|--------------------------------------------|
+//! | setas, `Home`/`End` | Move o cursor (`^A`/`^E`: início/fim)      |
+//! | `PgUp`/`PgDn`       | Página anterior/seguinte (`^Y`/`^V`)       |
+//! | `^O`                | Salva, perguntando o nome                  |
+//! | `^S`                | Salva no mesmo arquivo                     |
+//! | `^W`                | Busca texto (vazio repete a última)        |
+//! | `^K` / `^U`         | Recorta a linha (seguidos acumulam) / cola |
+//! | `^C`                | Mostra a posição do cursor                 |
+//! | `^X`                | Sai, perguntando se há alterações          |
+
+use super::commands::write_error;
+use super::path;
+use super::screen::{self, move_to, Key, Screen};
+use super::vfs::{write_all, Vfs};
+use super::ShellContext;
+use crate::state::terminal::TerminalState;
+use crate::state::utf8::Utf8Decoder;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use redpowder::SysError;
+
+/// Largura da tabulação na exibição
+const TAB_WIDTH: usize = 8;
+
+/// Linhas da tela fora do texto: título, status e as duas de ajuda
+const CHROME_ROWS: usize = 4;
+
+/// Barra de ajuda: duas linhas de `(tecla, ação)`
+const HELP_BAR: [[(&str, &str); 4]; 2] = [
+    [
+        ("^O", "Salvar"),
+        ("^W", "Buscar"),
+        ("^K", "Recortar"),
+        ("^U", "Colar"),
+    ],
+    [
+        ("^X", "Sair"),
+        ("^C", "Posicao"),
+        ("^A", "Inicio"),
+        ("^E", "Fim"),
+    ],
+];
+
+/// Largura de cada entrada da barra de ajuda
+const HELP_WIDTH: usize = 14;
+
+/// Pergunta na linha de status
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Prompt {
+    /// Nome do arquivo para `^O`
+    SaveAs,
+    /// Texto para `^W`
+    Search,
+    /// `^X` com alterações não salvas
+    ConfirmQuit,
+}
+
+/// Arquivo aberto no editor
+pub struct Editor {
+    /// Caminho absoluto do arquivo
+    path: String,
+    /// Texto, uma entrada por linha, sem o `\n`
+    lines: Vec<String>,
+    /// Linha do cursor
+    row: usize,
+    /// Coluna do cursor, em caracteres
+    col: usize,
+    /// Primeira linha na tela
+    top: usize,
+    /// Primeira coluna de exibição na tela (rolagem horizontal)
+    left: usize,
+    /// Há alterações não salvas
+    modified: bool,
+    /// Linhas recortadas com `^K`
+    cut: Vec<String>,
+    /// A tecla anterior foi `^K` (o próximo recorte acumula)
+    cutting: bool,
+    /// Último texto buscado
+    search: String,
+    /// Pergunta em andamento e a resposta digitada até agora
+    prompt: Option<(Prompt, String)>,
+    /// Aviso na linha de status, até a próxima tecla
+    message: Option<String>,
+}
+
+impl Editor {
+    /// Abre `path` (absoluto); um arquivo que não existe começa vazio e
+    /// é criado ao salvar
+    pub fn open(fs: &dyn Vfs, path: &str) -> Result<Self, SysError> {
+        let (text, message) = match fs.metadata(path) {
+            Ok(meta) if meta.is_dir() => return Err(SysError::IsDirectory),
+            Ok(_) => read_text(fs, path)?,
+            Err(SysError::NotFound) => (String::new(), String::from("[ Arquivo novo ]")),
+            Err(e) => return Err(e),
+        };
+
+        let mut lines: Vec<String> = text.split('\n').map(String::from).collect();
+        // O `\n` final não abre uma linha a mais
+        if lines.len() > 1 && text.ends_with('\n') {
+            lines.pop();
+        }
+
+        Ok(Self {
+            path: String::from(path),
+            lines,
+            row: 0,
+            col: 0,
+            top: 0,
+            left: 0,
+            modified: false,
+            cut: Vec::new(),
+            cutting: false,
+            search: String::new(),
+            prompt: None,
+            message: Some(message),
+        })
+    }
+
+    /// Linha do cursor
+    fn line(&self) -> &String {
+        &self.lines[self.row]
+    }
+
+    /// Caracteres na linha do cursor
+    fn line_len(&self) -> usize {
+        self.line().chars().count()
+    }
+
+    // =========================================================================
+    // EDIÇÃO
+    // =========================================================================
+
+    /// Insere um caractere no cursor
+    fn insert(&mut self, c: char) {
+        let at = byte_at(self.line(), self.col);
+        self.lines[self.row].insert(at, c);
+        self.col += 1;
+        self.modified = true;
+    }
+
+    /// Quebra a linha no cursor
+    fn newline(&mut self) {
+        let at = byte_at(self.line(), self.col);
+        let rest = self.lines[self.row].split_off(at);
+        self.lines.insert(self.row + 1, rest);
+        self.row += 1;
+        self.col = 0;
+        self.modified = true;
+    }
+
+    /// Apaga o caractere antes do cursor, juntando com a linha anterior no
+    /// início da linha
+    fn backspace(&mut self) {
+        if self.col > 0 {
+            self.col -= 1;
+            self.delete();
+        } else if self.row > 0 {
+            let line = self.lines.remove(self.row);
+            self.row -= 1;
+            self.col = self.line_len();
+            self.lines[self.row].push_str(&line);
+            self.modified = true;
+        }
+    }
+
+    /// Apaga o caractere no cursor, juntando com a próxima linha no fim
+    fn delete(&mut self) {
+        if self.col < self.line_len() {
+            let at = byte_at(self.line(), self.col);
+            self.lines[self.row].remove(at);
+            self.modified = true;
+        } else if self.row + 1 < self.lines.len() {
+            let next = self.lines.remove(self.row + 1);
+            self.lines[self.row].push_str(&next);
+            self.modified = true;
+        }
+    }
+
+    /// Recorta a linha do cursor; recortes seguidos vão juntos
+    fn cut_line(&mut self, append: bool) {
+        if !append {
+            self.cut.clear();
+        }
+
+        if self.lines.len() == 1 {
+            self.cut.push(core::mem::take(&mut self.lines[0]));
+        } else {
+            self.cut.push(self.lines.remove(self.row));
+            self.row = self.row.min(self.lines.len() - 1);
+        }
+        self.col = 0;
+        self.cutting = true;
+        self.modified = true;
+    }
+
+    /// Cola as linhas recortadas antes da linha do cursor
+    fn paste(&mut self) {
+        if self.cut.is_empty() {
+            self.message = Some(String::from("[ Nada para colar ]"));
+            return;
+        }
+
+        for (i, line) in self.cut.iter().enumerate() {
+            self.lines.insert(self.row + i, line.clone());
+        }
+        self.row += self.cut.len();
+        self.col = 0;
+        self.modified = true;
+    }
+
+    // =========================================================================
+    // MOVIMENTO
+    // =========================================================================
+
+    /// Sobe (negativo) ou desce `n` linhas, mantendo a coluna se couber
+    fn move_rows(&mut self, n: isize) {
+        let last = self.lines.len() - 1;
+        self.row = self.row.saturating_add_signed(n).min(last);
+        self.col = self.col.min(self.line_len());
+    }
+
+    fn move_left(&mut self) {
+        if self.col > 0 {
+            self.col -= 1;
+        } else if self.row > 0 {
+            self.row -= 1;
+            self.col = self.line_len();
+        }
+    }
+
+    fn move_right(&mut self) {
+        if self.col < self.line_len() {
+            self.col += 1;
+        } else if self.row + 1 < self.lines.len() {
+            self.row += 1;
+            self.col = 0;
+        }
+    }
+
+    /// Primeira linha e coluna na tela para que o cursor fique visível
+    fn view(&self, output: &TerminalState) -> (usize, usize) {
+        let rows = text_rows(output);
+        let mut top = self.top.min(self.row);
+        if self.row >= top + rows {
+            top = self.row + 1 - rows;
+        }
+
+        let width = self.text_width(output);
+        let x = display_col(self.line(), self.col);
+        let mut left = self.left.min(x);
+        if x >= left + width {
+            left = x + 1 - width;
+        }
+        (top, left)
+    }
+
+    // =========================================================================
+    // BUSCA E GRAVAÇÃO
+    // =========================================================================
+
+    /// Leva o cursor à próxima ocorrência de `pattern`, voltando ao início
+    /// do texto se preciso
+    fn find(&mut self, pattern: &str) {
+        let total = self.lines.len();
+        let start = self.row;
+        for step in 0..=total {
+            let index = (self.row + step) % total;
+            let line = &self.lines[index];
+            let from = if step == 0 {
+                byte_at(line, self.col + 1)
+            } else {
+                0
+            };
+
+            if let Some(at) = line[from..].find(pattern) {
+                self.col = line[..from + at].chars().count();
+                self.row = index;
+                if start + step >= total {
+                    self.message = Some(String::from("[ Busca recomecou do inicio ]"));
+                }
+                return;
+            }
+        }
+
+        self.message = Some(format!("[ \"{}\" nao encontrado ]", pattern));
+    }
+
+    /// Grava o texto em `path`; `false` se não deu certo
+    fn save(&mut self, ctx: &mut ShellContext, path: String) -> bool {
+        let mut data = self.lines.join("\n");
+        if self.lines.len() > 1 || !self.lines[0].is_empty() {
+            data.push('\n');
+        }
+
+        let result = ctx
+            .fs
+            .create(&path)
+            .and_then(|mut file| write_all(file.as_mut(), data.as_bytes()));
+
+        match result {
+            Ok(()) => {
+                self.message = Some(format!("[ {} linhas gravadas ]", self.lines.len()));
+                self.path = path;
+                self.modified = false;
+                true
+            }
+            Err(e) => {
+                let reason = write_error(ctx.fs.as_ref(), &path, e);
+                self.message = Some(format!("[ Erro ao gravar {}: {} ]", path, reason));
+                false
+            }
+        }
+    }
+
+    /// Resposta da pergunta na linha de status; `false` quando o editor
+    /// deve fechar
+    fn prompt_key(
+        &mut self,
+        kind: Prompt,
+        mut text: String,
+        key: Key,
+        ctx: &mut ShellContext,
+    ) -> bool {
+        if matches!(key, Key::Ctrl('c') | Key::Escape) {
+            self.message = Some(String::from("[ Cancelado ]"));
+            return true;
+        }
+
+        if kind == Prompt::ConfirmQuit {
+            match key {
+                Key::Char('s' | 'S' | 'y' | 'Y') => {
+                    let path = self.path.clone();
+                    return !self.save(ctx, path);
+                }
+                Key::Char('n' | 'N') => return false,
+                _ => self.prompt = Some((kind, text)),
+            }
+            return true;
+        }
+
+        match key {
+            Key::Enter if kind == Prompt::SaveAs => {
+                if text.is_empty() {
+                    self.message = Some(String::from("[ Cancelado ]"));
+                } else {
+                    let path = path::resolve(&ctx.cwd, &text);
+                    self.save(ctx, path);
+                }
+            }
+            Key::Enter => {
+                if !text.is_empty() {
+                    self.search = text;
+                }
+                if self.search.is_empty() {
+                    self.message = Some(String::from("[ Cancelado ]"));
+                } else {
+                    let pattern = self.search.clone();
+                    self.find(&pattern);
+                }
+            }
+            Key::Backspace => {
+                text.pop();
+                self.prompt = Some((kind, text));
+            }
+            Key::Char(c) => {
+                text.push(c);
+                self.prompt = Some((kind, text));
+            }
+            _ => self.prompt = Some((kind, text)),
+        }
+        true
+    }
+
+    // =========================================================================
+    // DESENHO
+    // =========================================================================
+
+    /// Largura da coluna de números de linha, com o espaço depois
+    fn gutter(&self) -> usize {
+        let mut digits = 1;
+        let mut n = self.lines.len();
+        while n >= 10 {
+            n /= 10;
+            digits += 1;
+        }
+        digits.max(3) + 1
+    }
+
+    /// Colunas disponíveis para o texto
+    fn text_width(&self, output: &TerminalState) -> usize {
+        (output.cols as usize).saturating_sub(self.gutter()).max(1)
+    }
+
+    /// Texto da pergunta em andamento
+    fn prompt_label(&self, kind: Prompt) -> String {
+        match kind {
+            Prompt::SaveAs => String::from("Salvar como: "),
+            Prompt::Search if self.search.is_empty() => String::from("Buscar: "),
+            Prompt::Search => format!("Buscar [{}]: ", self.search),
+            Prompt::ConfirmQuit => String::from("Salvar alteracoes? (s/n, ^C cancela) "),
+        }
+    }
+
+    /// Duas linhas de atalhos no rodapé
+    fn render_help(&self, output: &mut TerminalState, first_row: usize) {
+        let cols = output.cols as usize;
+        for (i, entries) in HELP_BAR.iter().enumerate() {
+            move_to(output, first_row + i);
+            let mut used = 0;
+            for (key, label) in entries {
+                // Sem chegar à última coluna, que faria a tela rolar
+                if used + HELP_WIDTH >= cols {
+                    break;
+                }
+                output.write_str("\x1b[7m");
+                output.write_str(key);
+                output.write_str("\x1b[27m ");
+                output.write_str(label);
+                for _ in key.len() + 1 + label.len()..HELP_WIDTH {
+                    output.write_char(' ');
+                }
+                used += HELP_WIDTH;
+            }
+        }
+    }
+}
+
+impl Screen for Editor {
+    fn render(&self, output: &mut TerminalState) {
+        let rows = output.rows as usize;
+        let (top, left) = self.view(output);
+        output.write_str("\x1b[0m\x1b[H\x1b[2J");
+
+        let flag = if self.modified { "  [Modificado]" } else { "" };
+        screen::write_bar(output, 0, &format!("  edit  {}{}", self.path, flag));
+
+        let gutter = self.gutter();
+        let width = self.text_width(output);
+        for (screen_row, index) in (top..self.lines.len()).take(text_rows(output)).enumerate() {
+            move_to(output, screen_row + 1);
+            output.write_str(&format!("\x1b[90m{:>1$}\x1b[39m ", index + 1, gutter - 1));
+            let cells: String = display_cells(&self.lines[index])
+                .into_iter()
+                .skip(left)
+                .take(width)
+                .collect();
+            output.write_str(&cells);
+        }
+
+        let status_row = rows.saturating_sub(3);
+        self.render_help(output, rows.saturating_sub(2));
+
+        // Cursor: na pergunta, depois da resposta; senão, no texto
+        let (y, x) = match &self.prompt {
+            Some((kind, text)) => {
+                let mut line = self.prompt_label(*kind);
+                line.push_str(text);
+                let line = screen::fit(&line, (output.cols as usize).saturating_sub(1));
+                move_to(output, status_row);
+                output.write_str(&line);
+                (status_row, line.chars().count())
+            }
+            None => {
+                if let Some(message) = &self.message {
+                    // Centralizada, como no nano
+                    let pad = (output.cols as usize).saturating_sub(message.chars().count()) / 2;
+                    move_to(output, status_row);
+                    for _ in 0..pad {
+                        output.write_char(' ');
+                    }
+                    output.write_str("\x1b[7m");
+                    output.write_str(&screen::fit(message, output.cols as usize - pad - 1));
+                    output.write_str("\x1b[27m");
+                }
+                let x = display_col(self.line(), self.col) - left;
+                (self.row - top + 1, gutter + x)
+            }
+        };
+
+        output.write_str(&format!("\x1b[{};{}H\x1b[?25h", y + 1, x + 1));
+    }
+
+    fn key(&mut self, key: Key, ctx: &mut ShellContext, output: &mut TerminalState) -> bool {
+        self.message = None;
+
+        if let Some((kind, text)) = self.prompt.take() {
+            if !self.prompt_key(kind, text, key, ctx) {
+                return false;
+            }
+        } else {
+            let append = core::mem::take(&mut self.cutting);
+            let page = text_rows(output) as isize;
+            match key {
+                Key::Ctrl('x') if !self.modified => return false,
+                Key::Ctrl('x') => self.prompt = Some((Prompt::ConfirmQuit, String::new())),
+                Key::Ctrl('o') => self.prompt = Some((Prompt::SaveAs, self.path.clone())),
+                Key::Ctrl('s') => {
+                    let path = self.path.clone();
+                    self.save(ctx, path);
+                }
+                Key::Ctrl('w') => self.prompt = Some((Prompt::Search, String::new())),
+                Key::Ctrl('k') => self.cut_line(append),
+                Key::Ctrl('u') => self.paste(),
+                Key::Ctrl('c') => {
+                    self.message = Some(format!(
+                        "[ linha {}/{}, coluna {}/{} ]",
+                        self.row + 1,
+                        self.lines.len(),
+                        self.col + 1,
+                        self.line_len() + 1
+                    ));
+                }
+                Key::Ctrl('a') | Key::Home => self.col = 0,
+                Key::Ctrl('e') | Key::End => self.col = self.line_len(),
+                Key::Ctrl('y') | Key::PageUp => self.move_rows(-page),
+                Key::Ctrl('v') | Key::PageDown => self.move_rows(page),
+                Key::Up => self.move_rows(-1),
+                Key::Down => self.move_rows(1),
+                Key::Left => self.move_left(),
+                Key::Right => self.move_right(),
+                Key::Enter => self.newline(),
+                Key::Backspace => self.backspace(),
+                Key::Delete => self.delete(),
+                Key::Char(c) if c != '\n' && c != '\r' => self.insert(c),
+                _ => {}
+            }
+        }
+
+        (self.top, self.left) = self.view(output);
+        self.render(output);
+        true
+    }
+}
+
+/// Lê o arquivo inteiro; bytes fora do UTF-8 viram U+FFFD, com aviso
+fn read_text(fs: &dyn Vfs, path: &str) -> Result<(String, String), SysError> {
+    let mut file = fs.open(path)?;
+    let mut bytes = Vec::new();
+    let mut buf = [0u8; 1024];
+    loop {
+        match file.read(&mut buf)? {
+            0 => break,
+            n => bytes.extend_from_slice(&buf[..n]),
+        }
+    }
+
+    match String::from_utf8(bytes) {
+        Ok(text) => {
+            let count = text.lines().count();
+            Ok((text, format!("[ {} linhas lidas ]", count)))
+        }
+        Err(e) => {
+            let mut text = String::new();
+            let mut decoder = Utf8Decoder::new();
+            decoder.decode(e.as_bytes(), &mut text);
+            decoder.finish(&mut text);
+            let message = String::from("[ UTF-8 invalido: bytes trocados por U+FFFD ]");
+            Ok((text, message))
+        }
+    }
+}
+
+/// Linhas da tela para o texto
+fn text_rows(output: &TerminalState) -> usize {
+    (output.rows as usize).saturating_sub(CHROME_ROWS).max(1)
+}
+
+/// Índice em bytes do caractere `col` (ou o fim da linha)
+fn byte_at(line: &str, col: usize) -> usize {
+    line.char_indices().nth(col).map_or(line.len(), |(i, _)| i)
+}
+
+/// Coluna de exibição do caractere `col`, com tabs expandidas
+fn display_col(line: &str, col: usize) -> usize {
+    line.chars().take(col).fold(0, |x, c| x + char_width(c, x))
+}
+
+/// Colunas ocupadas por `c` quando começa na coluna `x`
+fn char_width(c: char, x: usize) -> usize {
+    match c {
+        '\t' => TAB_WIDTH - x % TAB_WIDTH,
+        c if (c as u32) < 0x20 || c == '\x7f' => 2,
+        _ => 1,
+    }
+}
+
+/// Caracteres exibidos para a linha: tabs viram espaços e controles `^X`
+fn display_cells(line: &str) -> Vec<char> {
+    let mut cells = Vec::with_capacity(line.len());
+    for c in line.chars() {
+        match char_width(c, cells.len()) {
+            1 => cells.push(c),
+            _ if c == '\t' => {
+                let width = char_width(c, cells.len());
+                cells.extend(core::iter::repeat_n(' ', width));
+            }
+            _ => {
+                cells.push('^');
+                cells.push(char::from(c as u8 ^ 0x40));
+            }
+        }
+    }
+    cells
+}
+
+// =============================================================================
+// TESTES
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::Editor;
+    use crate::shell::screen::{Key, Screen};
+    use crate::shell::testing::{contents, run, shell};
+    use crate::shell::vfs::MemoryFs;
+    use crate::shell::ShellContext;
+    use crate::state::terminal::TerminalState;
+
+    /// Editor aberto em `path`, numa tela de 40x10
+    fn open(fs: MemoryFs, path: &str) -> (Editor, ShellContext, TerminalState) {
+        let ctx = shell(fs);
+        let editor = Editor::open(ctx.fs.as_ref(), path).unwrap();
+        (editor, ctx, TerminalState::new(40, 10))
+    }
+
+    fn press(
+        editor: &mut Editor,
+        ctx: &mut ShellContext,
+        output: &mut TerminalState,
+        keys: &[Key],
+    ) {
+        for key in keys {
+            assert!(editor.key(*key, ctx, output));
+        }
+    }
+
+    fn typed(text: &str) -> impl Iterator<Item = Key> + '_ {
+        text.chars().map(Key::Char)
+    }
+
+    #[test]
+    fn cursor_moves_by_characters() {
+        let mut fs = MemoryFs::new();
+        fs.add_file("/a.txt", "ação\né\n".as_bytes());
+        let (mut editor, mut ctx, mut output) = open(fs, "/a.txt");
+        assert_eq!(editor.message.as_deref(), Some("[ 2 linhas lidas ]"));
+
+        press(
+            &mut editor,
+            &mut ctx,
+            &mut output,
+            &[Key::Right, Key::Right, Key::Char('X')],
+        );
+        assert_eq!(editor.lines[0], "açXão");
+        press(
+            &mut editor,
+            &mut ctx,
+            &mut output,
+            &[Key::End, Key::Backspace, Key::Delete],
+        );
+        assert_eq!(editor.lines, ["açXãé"]);
+        assert_eq!(editor.col, 4);
+
+        // A quebra cai entre caracteres, nunca dentro de um
+        press(&mut editor, &mut ctx, &mut output, &[Key::Left, Key::Enter]);
+        assert_eq!(editor.lines, ["açX", "ãé"]);
+        press(&mut editor, &mut ctx, &mut output, &[Key::Left]);
+        assert_eq!((editor.row, editor.col), (0, 3));
+        press(
+            &mut editor,
+            &mut ctx,
+            &mut output,
+            &[Key::Right, Key::Right],
+        );
+        assert_eq!((editor.row, editor.col), (1, 1));
+        press(
+            &mut editor,
+            &mut ctx,
+            &mut output,
+            &[Key::Home, Key::Backspace],
+        );
+        assert_eq!(editor.lines, ["açXãé"]);
+        assert_eq!(editor.col, 3);
+        assert!(editor.modified);
+    }
+
+    #[test]
+    fn vertical_moves_keep_the_column_when_it_fits() {
+        let mut fs = MemoryFs::new();
+        fs.add_file("/a.txt", "çççççç\nç\nçççç\n".as_bytes());
+        let (mut editor, mut ctx, mut output) = open(fs, "/a.txt");
+        press(&mut editor, &mut ctx, &mut output, &[Key::End, Key::Down]);
+        assert_eq!((editor.row, editor.col), (1, 1));
+        press(
+            &mut editor,
+            &mut ctx,
+            &mut output,
+            &[Key::Down, Key::Char('!')],
+        );
+        assert_eq!(editor.lines[2], "ç!ççç");
+    }
+
+    #[test]
+    fn invalid_utf8_is_replaced_with_a_warning() {
+        let mut fs = MemoryFs::new();
+        fs.add_file("/bin", b"a\xffb\n");
+        let (editor, _, _) = open(fs, "/bin");
+        assert_eq!(editor.lines, ["a\u{FFFD}b"]);
+        assert_eq!(
+            editor.message.as_deref(),
+            Some("[ UTF-8 invalido: bytes trocados por U+FFFD ]")
+        );
+    }
+
+    #[test]
+    fn new_file_is_created_on_save() {
+        let mut fs = MemoryFs::new();
+        fs.add_dir("/tmp");
+        let (mut editor, mut ctx, mut output) = open(fs, "/tmp/novo.txt");
+        assert_eq!(editor.message.as_deref(), Some("[ Arquivo novo ]"));
+
+        let keys: alloc::vec::Vec<Key> = typed("olá")
+            .chain([Key::Enter])
+            .chain(typed("fim"))
+            .collect();
+        press(&mut editor, &mut ctx, &mut output, &keys);
+        press(&mut editor, &mut ctx, &mut output, &[Key::Ctrl('s')]);
+        assert_eq!(editor.message.as_deref(), Some("[ 2 linhas gravadas ]"));
+        assert!(!editor.modified);
+        assert_eq!(
+            contents(&ctx, "/tmp/novo.txt").as_deref(),
+            Some("olá\nfim\n")
+        );
+        assert!(!editor.key(Key::Ctrl('x'), &mut ctx, &mut output));
+    }
+
+    #[test]
+    fn saving_to_a_read_only_filesystem_keeps_the_editor_open() {
+        let mut fs = MemoryFs::new();
+        fs.add_file("/a.txt", b"x\n").read_only();
+        let (mut editor, mut ctx, mut output) = open(fs, "/a.txt");
+
+        press(
+            &mut editor,
+            &mut ctx,
+            &mut output,
+            &[Key::Char('y'), Key::Ctrl('s')],
+        );
+        assert_eq!(
+            editor.message.as_deref(),
+            Some("[ Erro ao gravar /a.txt: Permissao negada (sistema de arquivos somente leitura?) ]")
+        );
+        assert!(editor.modified);
+
+        // Sair salvando também falha, e o texto não se perde
+        press(
+            &mut editor,
+            &mut ctx,
+            &mut output,
+            &[Key::Ctrl('x'), Key::Char('s')],
+        );
+        assert!(editor
+            .message
+            .as_deref()
+            .unwrap()
+            .starts_with("[ Erro ao gravar"));
+        assert_eq!(contents(&ctx, "/a.txt").as_deref(), Some("x\n"));
+        press(&mut editor, &mut ctx, &mut output, &[Key::Ctrl('x')]);
+        assert!(!editor.key(Key::Char('n'), &mut ctx, &mut output));
+    }
+
+    #[test]
+    fn save_as_reports_a_missing_directory() {
+        let (mut editor, mut ctx, mut output) = open(MemoryFs::new(), "/a.txt");
+        press(&mut editor, &mut ctx, &mut output, &[Key::Ctrl('o')]);
+        // A resposta começa com o nome atual
+        let erase = [Key::Backspace; 6];
+        press(&mut editor, &mut ctx, &mut output, &erase);
+        let keys: alloc::vec::Vec<Key> = typed("nada/b.txt").chain([Key::Enter]).collect();
+        press(&mut editor, &mut ctx, &mut output, &keys);
+        assert_eq!(
+            editor.message.as_deref(),
+            Some("[ Erro ao gravar /nada/b.txt: Diretorio /nada nao existe ]")
+        );
+        assert_eq!(editor.path, "/a.txt");
+    }
+
+    #[test]
+    fn search_wraps_around() {
+        let mut fs = MemoryFs::new();
+        fs.add_file("/a.txt", "pé\nxx\npé de moleque\n".as_bytes());
+        let (mut editor, mut ctx, mut output) = open(fs, "/a.txt");
+        press(
+            &mut editor,
+            &mut ctx,
+            &mut output,
+            &[Key::Down, Key::Ctrl('w')],
+        );
+        let keys: alloc::vec::Vec<Key> = typed("de").chain([Key::Enter]).collect();
+        press(&mut editor, &mut ctx, &mut output, &keys);
+        assert_eq!((editor.row, editor.col), (2, 3));
+        press(
+            &mut editor,
+            &mut ctx,
+            &mut output,
+            &[Key::Ctrl('w'), Key::Enter],
+        );
+        assert_eq!((editor.row, editor.col), (2, 3));
+        assert_eq!(
+            editor.message.as_deref(),
+            Some("[ Busca recomecou do inicio ]")
+        );
+    }
+
+    #[test]
+    fn edit_needs_the_terminal() {
+        let mut ctx = shell(MemoryFs::new());
+        assert_eq!(
+            run(&mut ctx, "edit /a.txt"),
+            "edit: precisa do terminal; nao funciona em pipelines ou capturas\n"
+        );
+        assert!(run(&mut ctx, "edit").starts_with("edit: falta o arquivo\n"));
+    }
+}
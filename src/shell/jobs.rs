@@ -0,0 +1,229 @@
+//! # Job Control
+//!
+//! Tabela de jobs do shell: processos externos em segundo plano ou parados.
+//!
+//! O processo em primeiro plano pertence ao terminal (ligado ao PTY) e só
+//! entra na tabela quando é suspenso com ^Z. `fg` o devolve ao terminal.
+//!
+//! ## Especificação de job
+//!
+//! | Forma          | Job                                   |
+//! |----------------|---------------------------------------|
+//! | `%n`           | Job número `n`                        |
+//! | `%%`, `%+`     | Job atual (o mais recente)            |
+//! | `%-`           | Job anterior                          |
+//! | `%texto`       | Job cujo comando começa com `texto`   |
+
+use super::launcher::{Pid, ProcessLauncher, StdinFeed};
+use crate::state::terminal::TerminalState;
+use crate::state::utf8::Utf8Decoder;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Estado de um job
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobState {
+    /// Executando em segundo plano
+    Running,
+    /// Suspenso (^Z)
+    Stopped,
+    /// Terminou com o código dado
+    Done(i32),
+}
+
+impl JobState {
+    /// Nome exibido por `jobs`
+    pub fn label(&self) -> String {
+        match self {
+            JobState::Running => String::from("Executando"),
+            JobState::Stopped => String::from("Parado"),
+            JobState::Done(0) => String::from("Concluido"),
+            JobState::Done(code) => alloc::format!("Saida {}", code),
+        }
+    }
+}
+
+/// Job da tabela
+pub struct Job {
+    /// Número do job (`%n`)
+    pub id: usize,
+    /// PID do processo
+    pub pid: Pid,
+    /// Linha de comando
+    pub command: String,
+    /// Estado atual
+    pub state: JobState,
+    /// Entrada ainda não entregue ao processo
+    pub stdin: StdinFeed,
+    /// Decodificador da saída do processo
+    decoder: Utf8Decoder,
+}
+
+/// Tabela de jobs.
+///
+/// A ordem do vetor é a de uso: o último é o job atual (`+`), o penúltimo
+/// o anterior (`-`).
+#[derive(Default)]
+pub struct JobTable {
+    jobs: Vec<Job>,
+}
+
+impl JobTable {
+    /// Cria tabela vazia
+    pub fn new() -> Self {
+        Self { jobs: Vec::new() }
+    }
+
+    /// Adiciona um job, retornando seu número
+    pub fn add(&mut self, pid: Pid, command: &str, state: JobState, stdin: StdinFeed) -> usize {
+        // Menor número livre
+        let mut id = 1;
+        while self.jobs.iter().any(|j| j.id == id) {
+            id += 1;
+        }
+
+        self.jobs.push(Job {
+            id,
+            pid,
+            command: String::from(command),
+            state,
+            stdin,
+            decoder: Utf8Decoder::new(),
+        });
+        id
+    }
+
+    /// Jobs na ordem de uso
+    pub fn iter(&self) -> impl Iterator<Item = &Job> {
+        self.jobs.iter()
+    }
+
+    /// Resolve uma especificação de job (`%n`, `%%`, `%-`, `%texto`)
+    pub fn find(&self, spec: &str) -> Result<usize, &'static str> {
+        let current = |offset: usize| {
+            self.jobs
+                .len()
+                .checked_sub(1 + offset)
+                .map(|i| self.jobs[i].id)
+                .ok_or("job atual inexistente")
+        };
+
+        let spec = spec.strip_prefix('%').unwrap_or(spec);
+        match spec {
+            "" | "%" | "+" => current(0),
+            "-" => current(1),
+            _ => {
+                if let Ok(n) = spec.parse::<usize>() {
+                    return self
+                        .jobs
+                        .iter()
+                        .find(|j| j.id == n)
+                        .map(|j| j.id)
+                        .ok_or("job inexistente");
+                }
+
+                let mut matches = self.jobs.iter().filter(|j| j.command.starts_with(spec));
+                match (matches.next(), matches.next()) {
+                    (Some(job), None) => Ok(job.id),
+                    (Some(_), Some(_)) => Err("especificacao ambigua"),
+                    (None, _) => Err("job inexistente"),
+                }
+            }
+        }
+    }
+
+    /// Job pelo número
+    pub fn get(&self, id: usize) -> Option<&Job> {
+        self.jobs.iter().find(|j| j.id == id)
+    }
+
+    /// Job pelo número (mutável)
+    pub fn get_mut(&mut self, id: usize) -> Option<&mut Job> {
+        self.jobs.iter_mut().find(|j| j.id == id)
+    }
+
+    /// Torna o job o atual (`+`)
+    pub fn touch(&mut self, id: usize) {
+        if let Some(pos) = self.jobs.iter().position(|j| j.id == id) {
+            let job = self.jobs.remove(pos);
+            self.jobs.push(job);
+        }
+    }
+
+    /// Remove o job da tabela
+    pub fn remove(&mut self, id: usize) -> Option<Job> {
+        let pos = self.jobs.iter().position(|j| j.id == id)?;
+        Some(self.jobs.remove(pos))
+    }
+
+    /// Marcador do job: `+` atual, `-` anterior
+    pub fn marker(&self, id: usize) -> char {
+        let n = self.jobs.len();
+        match self.jobs.iter().position(|j| j.id == id) {
+            Some(pos) if pos + 1 == n => '+',
+            Some(pos) if pos + 2 == n => '-',
+            _ => ' ',
+        }
+    }
+
+    /// Atualiza os jobs: entrega a entrada pendente, copia a saída para o
+    /// terminal e detecta término.
+    ///
+    /// Retorna `true` se algo foi escrito em `output`.
+    pub fn poll(&mut self, launcher: &mut dyn ProcessLauncher, output: &mut TerminalState) -> bool {
+        let mut wrote = false;
+        let mut buf = [0u8; 512];
+
+        for job in self.jobs.iter_mut() {
+            if let JobState::Done(_) = job.state {
+                continue;
+            }
+
+            job.stdin.flush(launcher, job.pid);
+            let exited = launcher.try_wait(job.pid);
+
+            while let Ok(n @ 1..) = launcher.read_stdout(job.pid, &mut buf) {
+                job.decoder.decode_with(&buf[..n], |c| output.write_char(c));
+                wrote = true;
+            }
+
+            match exited {
+                Ok(Some(code)) => job.state = JobState::Done(code),
+                Ok(None) => {}
+                Err(_) => job.state = JobState::Done(1),
+            }
+        }
+
+        wrote
+    }
+
+    /// Remove e escreve os jobs concluídos (avisos antes do próximo prompt)
+    pub fn report_finished(&mut self, output: &mut TerminalState) {
+        let mut i = 0;
+        while i < self.jobs.len() {
+            if let JobState::Done(_) = self.jobs[i].state {
+                let marker = self.marker(self.jobs[i].id);
+                let job = self.jobs.remove(i);
+                write_job(output, &job, marker);
+            } else {
+                i += 1;
+            }
+        }
+    }
+}
+
+/// Escreve a linha de um job: `[1]+  Executando    comando`
+pub fn write_job(output: &mut TerminalState, job: &Job, marker: char) {
+    use core::fmt::Write;
+
+    let mut line = String::new();
+    let _ = write!(
+        line,
+        "[{}]{}  {:<12}  {}",
+        job.id,
+        marker,
+        job.state.label(),
+        job.command
+    );
+    output.write_line(&line);
+}
@@ -0,0 +1,229 @@
+//! # JSON Writer
+//!
+//! Escritor de JSON incremental, sem dependências, para a saída `--json`
+//! dos comandos.
+//!
+//! As vírgulas entre elementos são inseridas automaticamente; o documento
+//! sai compacto, numa linha só:
+//!
+//! ```text
+//! let mut json = JsonWriter::new();
+//! json.begin_object();
+//! json.key("name");
+//! json.string("a \"b\"");
+//! json.end_object();
+//! // {"name":"a \"b\""}
+//! ```
+//!
+//! O texto pode ser retirado aos poucos com [`JsonWriter::take`], o que
+//! permite escrever documentos grandes (`tree --json`) em partes.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+/// Escritor de JSON
+pub struct JsonWriter {
+    out: String,
+    /// Um nível por objeto/array aberto: `true` se ainda está vazio
+    stack: Vec<bool>,
+    /// Acabou de escrever uma chave; o próximo valor não leva vírgula
+    after_key: bool,
+}
+
+impl JsonWriter {
+    /// Cria um escritor vazio
+    pub fn new() -> Self {
+        Self {
+            out: String::new(),
+            stack: Vec::new(),
+            after_key: false,
+        }
+    }
+
+    /// Vírgula antes de um novo elemento, se não for o primeiro
+    fn separator(&mut self) {
+        if self.after_key {
+            self.after_key = false;
+            return;
+        }
+        if let Some(empty) = self.stack.last_mut() {
+            if !*empty {
+                self.out.push(',');
+            }
+            *empty = false;
+        }
+    }
+
+    /// Abre um objeto `{`
+    pub fn begin_object(&mut self) {
+        self.separator();
+        self.out.push('{');
+        self.stack.push(true);
+    }
+
+    /// Fecha o objeto `}`
+    pub fn end_object(&mut self) {
+        self.stack.pop();
+        self.out.push('}');
+    }
+
+    /// Abre um array `[`
+    pub fn begin_array(&mut self) {
+        self.separator();
+        self.out.push('[');
+        self.stack.push(true);
+    }
+
+    /// Fecha o array `]`
+    pub fn end_array(&mut self) {
+        self.stack.pop();
+        self.out.push(']');
+    }
+
+    /// Chave do próximo valor (dentro de objeto)
+    pub fn key(&mut self, key: &str) {
+        self.separator();
+        escape_into(&mut self.out, key);
+        self.out.push(':');
+        self.after_key = true;
+    }
+
+    /// Valor string
+    pub fn string(&mut self, value: &str) {
+        self.separator();
+        escape_into(&mut self.out, value);
+    }
+
+    /// Valor numérico
+    pub fn number(&mut self, value: i64) {
+        self.separator();
+        let _ = write!(self.out, "{}", value);
+    }
+
+    /// Valor numérico sem sinal
+    pub fn unsigned(&mut self, value: u64) {
+        self.separator();
+        let _ = write!(self.out, "{}", value);
+    }
+
+    // TODO: Revisar no futuro
+    #[allow(unused)]
+    /// Valor booleano
+    pub fn boolean(&mut self, value: bool) {
+        self.separator();
+        self.out.push_str(if value { "true" } else { "false" });
+    }
+
+    /// Valor `null`
+    pub fn null(&mut self) {
+        self.separator();
+        self.out.push_str("null");
+    }
+
+    /// Atalho: chave com valor string
+    pub fn field_str(&mut self, key: &str, value: &str) {
+        self.key(key);
+        self.string(value);
+    }
+
+    /// Atalho: chave com valor numérico sem sinal
+    pub fn field_u64(&mut self, key: &str, value: u64) {
+        self.key(key);
+        self.unsigned(value);
+    }
+
+    /// Retira o texto escrito até agora, mantendo o estado de aninhamento
+    pub fn take(&mut self) -> String {
+        core::mem::take(&mut self.out)
+    }
+
+    /// Finaliza e retorna o texto restante
+    pub fn finish(self) -> String {
+        self.out
+    }
+}
+
+impl Default for JsonWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Escreve `value` como string JSON, com aspas e escapes
+pub fn escape_into(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{08}' => out.push_str("\\b"),
+            '\u{0C}' => out.push_str("\\f"),
+            c if (c as u32) < 0x20 || c == '\u{7F}' => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+// =============================================================================
+// TESTES
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{escape_into, JsonWriter};
+    use alloc::string::String;
+
+    fn escaped(value: &str) -> String {
+        let mut out = String::new();
+        escape_into(&mut out, value);
+        out
+    }
+
+    #[test]
+    fn quotes_and_backslashes_are_escaped() {
+        assert_eq!(escaped(r#"a "b" \c"#), r#""a \"b\" \\c""#);
+        assert_eq!(escaped(""), "\"\"");
+    }
+
+    #[test]
+    fn control_characters_are_escaped() {
+        assert_eq!(escaped("a\nb\r\tc"), r#""a\nb\r\tc""#);
+        assert_eq!(escaped("\u{08}\u{0C}"), r#""\b\f""#);
+        assert_eq!(escaped("\u{01}\u{1F}\u{7F}"), r#""\u0001\u001f\u007f""#);
+    }
+
+    #[test]
+    fn non_ascii_passes_through() {
+        assert_eq!(escaped("ação 日本 😀"), "\"ação 日本 😀\"");
+        assert_eq!(escaped("\u{80}\u{2028}"), "\"\u{80}\u{2028}\"");
+    }
+
+    #[test]
+    fn writer_separates_nested_values() {
+        let mut json = JsonWriter::new();
+        json.begin_array();
+        json.begin_object();
+        json.field_str("nome", "x\"y");
+        json.key("filhos");
+        json.begin_array();
+        json.number(-1);
+        json.null();
+        json.end_array();
+        json.end_object();
+        let head = json.take();
+        json.begin_object();
+        json.field_u64("n", 2);
+        json.end_object();
+        json.end_array();
+
+        assert_eq!(head, r#"[{"nome":"x\"y","filhos":[-1,null]}"#);
+        assert_eq!(json.finish(), r#",{"n":2}]"#);
+    }
+}
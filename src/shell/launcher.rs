@@ -0,0 +1,269 @@
+//! # Process Launcher
+//!
+//! Busca e execução de programas externos.
+//!
+//! O shell não chama a API de processos diretamente: tudo passa pelo
+//! trait [`ProcessLauncher`], o que permite substituir o lançador real
+//! ([`RedpowderLauncher`]) por um falso em testes.
+
+use super::path;
+use crate::pty::Signal;
+use alloc::string::String;
+use alloc::vec::Vec;
+use redpowder::SysError;
+
+/// PATH usado quando a variável não está definida
+pub const DEFAULT_PATH: &str = "/apps:/system/bin";
+
+/// Identificador de processo
+pub type Pid = u64;
+
+/// Lançador de processos externos
+pub trait ProcessLauncher {
+    /// Verifica se `path` é um arquivo executável
+    fn is_executable(&self, path: &str) -> bool;
+
+    /// Inicia o programa em `path` com os argumentos e o ambiente dados.
+    ///
+    /// `argv[0]` é o nome do programa; `envp` contém entradas `NOME=valor`.
+    fn spawn(&mut self, path: &str, argv: &[&str], envp: &[String]) -> Result<Pid, SysError>;
+
+    /// Verifica sem bloquear se o processo terminou, retornando seu código de saída
+    fn try_wait(&mut self, pid: Pid) -> Result<Option<i32>, SysError>;
+
+    /// Envia bytes para o stdin do processo
+    fn write_stdin(&mut self, pid: Pid, data: &[u8]) -> Result<usize, SysError>;
+
+    /// Fecha o stdin do processo (fim de arquivo)
+    fn close_stdin(&mut self, pid: Pid) -> Result<(), SysError>;
+
+    /// Lê a saída pendente do processo sem bloquear (0 se não há nada)
+    fn read_stdout(&mut self, pid: Pid, buf: &mut [u8]) -> Result<usize, SysError>;
+
+    /// Entrega um sinal ao processo
+    fn signal(&mut self, pid: Pid, signal: Signal) -> Result<(), SysError>;
+}
+
+/// Procura `name` nos diretórios de `path_var` (separados por `:`).
+///
+/// Nomes com `/` não passam pela busca: são resolvidos em relação a `cwd`.
+/// Um diretório vazio em `PATH` significa o diretório atual.
+pub fn find_executable(
+    launcher: &dyn ProcessLauncher,
+    name: &str,
+    path_var: &str,
+    cwd: &str,
+) -> Option<String> {
+    if name.is_empty() {
+        return None;
+    }
+
+    if name.contains('/') {
+        let path = path::resolve(cwd, name);
+        return launcher.is_executable(&path).then_some(path);
+    }
+
+    path_var
+        .split(':')
+        .map(|dir| if dir.is_empty() { cwd } else { dir })
+        .map(|dir| path::join(dir, name))
+        .find(|candidate| launcher.is_executable(candidate))
+}
+
+// =============================================================================
+// STDIN
+// =============================================================================
+
+/// Entrada de um processo ainda não aceita pelo seu stdin.
+///
+/// O pipe do filho pode estar cheio: o que `write_stdin` não aceitou fica
+/// aqui e é reenviado em [`StdinFeed::flush`] nas próximas voltas. O stdin
+/// só é fechado depois que tudo foi entregue.
+#[derive(Debug, Default)]
+pub struct StdinFeed {
+    data: Vec<u8>,
+    /// Fechar o stdin quando `data` esvaziar
+    eof: bool,
+    /// O stdin já foi fechado
+    closed: bool,
+}
+
+impl StdinFeed {
+    /// Entrada vinda de um pipeline: os dados e depois fim de arquivo
+    pub fn pipe(data: Vec<u8>) -> Self {
+        Self {
+            data,
+            eof: true,
+            closed: false,
+        }
+    }
+
+    /// Acrescenta bytes; depois do fim de arquivo, são descartados
+    pub fn push(&mut self, bytes: &[u8]) {
+        if !self.eof {
+            self.data.extend_from_slice(bytes);
+        }
+    }
+
+    /// Fim de arquivo depois do que já está pendente
+    pub fn finish(&mut self) {
+        self.eof = true;
+    }
+
+    /// Não há mais nada a entregar nem a fechar
+    pub fn is_done(&self) -> bool {
+        self.data.is_empty() && (!self.eof || self.closed)
+    }
+
+    /// Envia o que o processo aceitar agora.
+    ///
+    /// Uma escrita de 0 bytes (pipe cheio) ou com erro deixa o resto para
+    /// a próxima chamada; quando o processo termina, o que sobrou é
+    /// descartado junto com ele.
+    pub fn flush(&mut self, launcher: &mut dyn ProcessLauncher, pid: Pid) {
+        while !self.data.is_empty() {
+            match launcher.write_stdin(pid, &self.data) {
+                Ok(n @ 1..) => {
+                    self.data.drain(..n.min(self.data.len()));
+                }
+                _ => return,
+            }
+        }
+        if self.eof && !self.closed && launcher.close_stdin(pid).is_ok() {
+            self.closed = true;
+        }
+    }
+}
+
+// =============================================================================
+// REDPOWDER
+// =============================================================================
+
+/// Lançador que usa a API de processos do redpowder
+#[derive(Debug, Default)]
+pub struct RedpowderLauncher;
+
+impl ProcessLauncher for RedpowderLauncher {
+    fn is_executable(&self, path: &str) -> bool {
+        match redpowder::fs::stat(path) {
+            // Arquivo regular com bit de execução. Sistemas de arquivos que
+            // não registram permissões (mode 0) aceitam qualquer arquivo.
+            Ok(info) => info.file_type == 1 && (info.mode == 0 || info.mode & 0o111 != 0),
+            Err(_) => false,
+        }
+    }
+
+    fn spawn(&mut self, path: &str, argv: &[&str], envp: &[String]) -> Result<Pid, SysError> {
+        let envp: Vec<&str> = envp.iter().map(|s| s.as_str()).collect();
+        redpowder::process::spawn(path, argv, &envp)
+    }
+
+    fn try_wait(&mut self, pid: Pid) -> Result<Option<i32>, SysError> {
+        redpowder::process::try_wait(pid)
+    }
+
+    fn write_stdin(&mut self, pid: Pid, data: &[u8]) -> Result<usize, SysError> {
+        redpowder::process::write_stdin(pid, data)
+    }
+
+    fn close_stdin(&mut self, pid: Pid) -> Result<(), SysError> {
+        redpowder::process::close_stdin(pid)
+    }
+
+    fn read_stdout(&mut self, pid: Pid, buf: &mut [u8]) -> Result<usize, SysError> {
+        redpowder::process::read_stdout(pid, buf)
+    }
+
+    fn signal(&mut self, pid: Pid, signal: Signal) -> Result<(), SysError> {
+        let signo = match signal {
+            Signal::Interrupt => 2,
+            Signal::Quit => 3,
+            Signal::Suspend => 20,
+            Signal::WindowChange => 28,
+            Signal::Continue => 18,
+            Signal::Terminate => 15,
+            Signal::Kill => 9,
+        };
+        redpowder::process::kill(pid, signo)
+    }
+}
+
+// =============================================================================
+// TESTES
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{find_executable, ProcessLauncher, StdinFeed};
+    use crate::shell::testing::FakeLauncher;
+
+    fn launcher() -> FakeLauncher {
+        FakeLauncher::default()
+            .program("/apps/hello", "", 0)
+            .program("/system/bin/hello", "", 0)
+            .program("/system/bin/ps", "", 0)
+            .program("/home/tool", "", 0)
+    }
+
+    #[test]
+    fn path_is_searched_in_order() {
+        let l = launcher();
+        let found = |name| find_executable(&l, name, "/apps:/system/bin", "/");
+        assert_eq!(found("hello").as_deref(), Some("/apps/hello"));
+        assert_eq!(found("ps").as_deref(), Some("/system/bin/ps"));
+        assert_eq!(found("nada"), None);
+        assert_eq!(found(""), None);
+    }
+
+    #[test]
+    fn empty_path_entry_is_the_current_directory() {
+        let l = launcher();
+        assert_eq!(find_executable(&l, "tool", "/apps", "/home"), None);
+        assert_eq!(
+            find_executable(&l, "tool", "/apps::", "/home").as_deref(),
+            Some("/home/tool")
+        );
+    }
+
+    #[test]
+    fn names_with_slash_skip_the_search() {
+        let l = launcher();
+        assert_eq!(
+            find_executable(&l, "./tool", "/apps", "/home").as_deref(),
+            Some("/home/tool")
+        );
+        assert_eq!(
+            find_executable(&l, "/system/bin/ps", "", "/").as_deref(),
+            Some("/system/bin/ps")
+        );
+        assert_eq!(
+            find_executable(&l, "bin/ps", "/apps", "/system").as_deref(),
+            Some("/system/bin/ps")
+        );
+        assert_eq!(find_executable(&l, "/apps/ps", "/system/bin", "/"), None);
+    }
+
+    #[test]
+    fn stdin_feed_retries_until_everything_is_accepted() {
+        let mut l = FakeLauncher::default().stdin_chunk(2).filter("/apps/pass");
+        let pid = l.spawn("/apps/pass", &["pass"], &[]).unwrap();
+        let mut feed = StdinFeed::default();
+
+        // Teclas chegando com o pipe cheio não se perdem
+        feed.push(b"abc");
+        feed.flush(&mut l, pid);
+        feed.push(b"de\n");
+        feed.finish();
+        feed.push(b"ignorado");
+        assert!(!feed.is_done());
+        assert_eq!(l.try_wait(pid), Ok(None));
+
+        while !feed.is_done() {
+            feed.flush(&mut l, pid);
+        }
+        let mut buf = [0u8; 16];
+        let n = l.read_stdout(pid, &mut buf).unwrap();
+        assert_eq!(&buf[..n], b"abcde\n");
+        assert_eq!(l.try_wait(pid), Ok(Some(0)));
+    }
+}
@@ -0,0 +1,152 @@
+//! # LS Colors
+//!
+//! Cores de nomes de arquivo para `ls` e `tree`, configuráveis pela
+//! variável `LS_COLORS` no mesmo formato do GNU:
+//!
+//! ```text
+//! LS_COLORS='di=1;34:ln=1;36:ex=1;32:hi=90:*.zip=31'
+//! ```
+//!
+//! | Chave    | Aplica-se a                    |
+//! |----------|--------------------------------|
+//! | `di`     | Diretórios                     |
+//! | `ln`     | Links simbólicos               |
+//! | `ex`     | Arquivos executáveis           |
+//! | `fi`     | Demais arquivos regulares      |
+//! | `hi`     | Arquivos ocultos (`.nome`)     |
+//! | `*.ext`  | Arquivos com a extensão        |
+//!
+//! Os valores são parâmetros SGR (`1;34` = azul em negrito). Entradas da
+//! variável substituem as padrão; as demais padrão continuam valendo.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Cores usadas quando `LS_COLORS` não define a chave
+const DEFAULT_COLORS: &str = "di=1;34:ln=1;36:ex=1;32:hi=90:\
+    *.tar=31:*.gz=31:*.zip=31:*.xz=31:\
+    *.png=35:*.jpg=35:*.bmp=35:*.ppm=35:\
+    *.toml=33:*.cfg=33:*.ini=33:*.conf=33";
+
+/// Cores por tipo e extensão
+#[derive(Debug, Clone)]
+pub struct LsColors {
+    /// (chave, parâmetros SGR), na ordem em que foram definidas
+    entries: Vec<(String, String)>,
+}
+
+impl LsColors {
+    /// Cores padrão, com `spec` (conteúdo de `LS_COLORS`) por cima
+    pub fn new(spec: Option<&str>) -> Self {
+        let mut colors = Self {
+            entries: Vec::new(),
+        };
+        colors.merge(DEFAULT_COLORS);
+        if let Some(spec) = spec {
+            colors.merge(spec);
+        }
+        colors
+    }
+
+    /// Adiciona as entradas `chave=valor` de `spec`, ignorando as inválidas
+    fn merge(&mut self, spec: &str) {
+        for item in spec.split(':') {
+            let Some((key, value)) = item.split_once('=') else {
+                continue;
+            };
+            if key.is_empty() || !value.chars().all(|c| c.is_ascii_digit() || c == ';') {
+                continue;
+            }
+
+            match self.entries.iter_mut().find(|(k, _)| k == key) {
+                Some(entry) => entry.1 = String::from(value),
+                None => self.entries.push((String::from(key), String::from(value))),
+            }
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+            .filter(|v| !v.is_empty())
+    }
+
+    /// Parâmetros SGR para uma entrada (`file_type` como em `stat`:
+    /// 1 arquivo, 2 diretório, 3 link)
+    pub fn lookup(&self, name: &str, file_type: u8, mode: u32) -> Option<&str> {
+        match file_type {
+            3 => return self.get("ln"),
+            2 => return self.get("di"),
+            _ => {}
+        }
+
+        if mode & 0o111 != 0 {
+            if let Some(color) = self.get("ex") {
+                return Some(color);
+            }
+        }
+
+        if let Some(dot) = name.rfind('.').filter(|&i| i > 0) {
+            let mut key = String::from("*");
+            key.push_str(&name[dot..]);
+            if let Some(color) = self.get(&key) {
+                return Some(color);
+            }
+        }
+
+        if name.starts_with('.') {
+            if let Some(color) = self.get("hi") {
+                return Some(color);
+            }
+        }
+
+        self.get("fi")
+    }
+
+    /// `text` entre a cor da entrada e o reset
+    pub fn paint(&self, text: &str, name: &str, file_type: u8, mode: u32) -> String {
+        match self.lookup(name, file_type, mode) {
+            Some(color) => {
+                let mut s = String::from("\x1b[");
+                s.push_str(color);
+                s.push('m');
+                s.push_str(text);
+                s.push_str("\x1b[0m");
+                s
+            }
+            None => String::from(text),
+        }
+    }
+}
+
+/// Quando colorir: `--color=auto|always|never`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorMode {
+    /// Só na tela, não em pipes e capturas
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorMode {
+    /// Interpreta o valor de `--color` (sem valor = `auto`)
+    pub fn parse(value: Option<&str>) -> Option<Self> {
+        match value {
+            None | Some("auto") | Some("tty") => Some(ColorMode::Auto),
+            Some("always") | Some("yes") | Some("force") => Some(ColorMode::Always),
+            Some("never") | Some("no") | Some("none") => Some(ColorMode::Never),
+            Some(_) => None,
+        }
+    }
+
+    /// Decide se colore, sabendo se a saída é capturada (pipe, `$(...)`)
+    pub fn enabled(&self, capturing: bool) -> bool {
+        match self {
+            ColorMode::Auto => !capturing,
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+        }
+    }
+}
@@ -11,24 +11,70 @@
 //! | `pwd`     | Mostra diretório atual       | 🟢 |
 //! | `cat`     | Mostra conteúdo de arquivo   | 🟢 |
 //! | `tree`    | Mostra árvore de diretórios  | 🟢 |
-//! | `mkdir`   | Cria diretório               | ⚪ |
-//! | `rmdir`   | Remove diretório             | ⚪ |
-//! | `rm`      | Remove arquivo               | ⚪ |
-//! | `cp`      | Copia arquivo                | ⚪ |
-//! | `mv`      | Move/renomeia arquivo        | ⚪ |
+//! | `find`    | Procura arquivos             | 🟢 |
+//! | `xxd`     | Dump hexadecimal (e reverso) | 🟢 |
+//! | `hexdump` | Dump hexadecimal canônico    | 🟢 |
+//! | `less`    | Pager em tela cheia          | 🟢 |
+//! | `edit`    | Editor de texto              | 🟢 |
+//! | `pushd`   | Empilha e muda diretório     | 🟢 |
+//! | `popd`    | Desempilha diretório         | 🟢 |
+//! | `dirs`    | Mostra pilha de diretórios   | 🟢 |
+//! | `mkdir`   | Cria diretório               | 🟢 |
+//! | `rmdir`   | Remove diretório             | 🟢 |
+//! | `rm`      | Remove arquivo               | 🟢 |
+//! | `cp`      | Copia arquivo                | 🟢 |
+//! | `mv`      | Move/renomeia arquivo        | 🟢 |
+//! | `touch`   | Cria arquivo / atualiza data | 🟢 |
+//! | `truncate`| Define tamanho do arquivo    | 🟢 |
+//! | `write`   | Grava texto digitado         | 🟢 |
+//! | `head`    | Primeiras linhas             | 🟢 |
+//! | `tail`    | Últimas linhas (e `-f`)      | 🟢 |
+//! | `wc`      | Conta linhas/palavras/bytes  | 🟢 |
+//! | `sort`    | Ordena linhas                | 🟢 |
+//! | `uniq`    | Junta linhas repetidas       | 🟢 |
+//! | `cut`     | Seleciona campos             | 🟢 |
+//! | `tr`      | Troca/apaga caracteres       | 🟢 |
+//! | `grep`    | Busca por expressão regular  | 🟢 |
+//! | `tee`     | Copia entrada para arquivos  | 🟢 |
 //! | `clear`   | Limpa tela                   | 🟢 |
 //! | `exit`    | Sai do terminal              | 🟢 |
 //! | `help`    | Mostra ajuda                 | 🟢 |
 //! | `uptime`  | Tempo desde boot             | 🟡 |
-//! | `ps`      | Lista processos              | ⚪ |
-//! | `kill`    | Mata processo                | ⚪ |
+//! | `ps`      | Lista processos do shell     | 🟡 |
+//! | `kill`    | Envia sinal a processo/job   | 🟢 |
 //! | `top`     | Monitor de processos         | ⚪ |
-//! | `jobs`    | Lista jobs                   | ⚪ |
+//! | `jobs`    | Lista jobs                   | 🟢 |
+//! | `fg`      | Job em primeiro plano        | 🟢 |
+//! | `bg`      | Job em segundo plano         | 🟢 |
 //! | `sysinfo` | Info do sistema              | ⚪ |
 //! | `meminfo` | Info de memória              | ⚪ |
+//! | `where`   | Filtra linhas de tabela      | 🟢 |
+//! | `sort-by` | Ordena tabela por colunas    | 🟢 |
+//! | `select`  | Escolhe colunas da tabela    | 🟢 |
+//! | `first`   | Primeiras linhas da tabela   | 🟢 |
+//! | `to-json` | Converte tabela em JSON      | 🟢 |
+//! | `to-csv`  | Converte tabela em CSV       | 🟢 |
 
+pub mod args;
+mod arith;
 pub mod commands;
 mod context;
+pub mod editor;
+pub mod jobs;
+pub mod json;
+pub mod launcher;
+pub mod ls_colors;
+pub mod pager;
+mod parser;
+pub mod path;
+mod prompt;
+pub mod screen;
+pub mod suggest;
+pub mod table;
+pub mod task;
+#[cfg(test)]
+pub(crate) mod testing;
+pub mod vfs;
 
 pub use commands::execute_command;
 pub use context::ShellContext;
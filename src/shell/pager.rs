@@ -0,0 +1,577 @@
+//! # Pager
+//!
+//! Visualizador de texto em tela cheia: `less`/`more`, e o `help` quando a
+//! saída não cabe na tela.
+//!
+//! É um [`Screen`]: desenha na tela alternativa com sequências de escape e
+//! recebe o teclado da janela até `q`; ao sair, a tela principal volta como
+//! estava.
+//!
+//! ## Teclas
+//!
+//! | Tecla                     | Ação                                  |
+//! |---------------------------|---------------------------------------|
+//! | `j`, `Enter`, `↓`         | Desce uma linha                       |
+//! | `k`, `↑`                  | Sobe uma linha                        |
+//! | `Espaço`, `f`, `PgDn`     | Próxima página                        |
+//! | `b`, `PgUp`               | Página anterior                       |
+//! | `d` / `u`                 | Meia página abaixo / acima            |
+//! | `g`, `Home` / `G`, `End`  | Início / fim do texto                 |
+//! | `/padrão`, `?padrão`      | Busca para frente / para trás         |
+//! | `n` / `N`                 | Repete a busca / no sentido contrário |
+//! | `q`, `Esc`, ^C            | Sai                                   |
+
+use super::commands::CommandResult;
+use super::screen::{self, move_to, Key, Screen};
+use super::ShellContext;
+use crate::state::regex::{Regex, RegexOptions};
+use crate::state::terminal::TerminalState;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Largura da tabulação na exibição
+const TAB_WIDTH: usize = 8;
+
+/// Busca ativa
+struct Search {
+    regex: Regex,
+    /// `/` (para frente) ou `?` (para trás)
+    forward: bool,
+}
+
+/// Texto aberto no pager
+pub struct Pager {
+    /// Nome mostrado na linha de status
+    title: String,
+    /// Linhas prontas para exibição (tabs expandidas, controles como `^X`)
+    lines: Vec<String>,
+    /// Primeira linha na tela
+    top: usize,
+    /// Buscas ignoram maiúsculas/minúsculas
+    ignore_case: bool,
+    /// Última busca, destacada na tela
+    search: Option<Search>,
+    /// Padrão sendo digitado depois de `/` ou `?`
+    prompt: Option<(char, String)>,
+    /// Aviso na linha de status, até a próxima tecla
+    message: Option<String>,
+}
+
+impl Pager {
+    /// Pager com o texto dado
+    pub fn new(title: &str, text: &str) -> Self {
+        Self {
+            title: String::from(title),
+            lines: text.lines().map(display_line).collect(),
+            top: 0,
+            ignore_case: false,
+            search: None,
+            prompt: None,
+            message: None,
+        }
+    }
+
+    /// Buscas sem diferenciar maiúsculas/minúsculas (`less -i`)
+    pub fn ignore_case(mut self, on: bool) -> Self {
+        self.ignore_case = on;
+        self
+    }
+
+    /// O texto cabe numa tela, sem precisar de pager
+    pub fn fits(&self, output: &TerminalState) -> bool {
+        let cols = output.cols as usize;
+        let rows: usize = self.lines.iter().map(|l| height(l, cols)).sum();
+        rows <= page_size(output)
+    }
+}
+
+impl Screen for Pager {
+    /// Desenha a página atual e a linha de status
+    fn render(&self, output: &mut TerminalState) {
+        let cols = output.cols as usize;
+        let page = page_size(output);
+        output.write_str("\x1b[0m\x1b[H\x1b[2J");
+
+        let mut row = 0;
+        let mut index = self.top;
+        while row < page {
+            let Some(line) = self.lines.get(index) else {
+                // Depois do fim do texto, como no less
+                move_to(output, row);
+                output.write_char('~');
+                row += 1;
+                continue;
+            };
+            index += 1;
+
+            let marks: Vec<(usize, usize)> = match &self.search {
+                Some(search) => search
+                    .regex
+                    .find_iter(line)
+                    .filter(|(start, end)| end > start)
+                    .collect(),
+                None => Vec::new(),
+            };
+
+            let chars: Vec<(usize, char)> = line.char_indices().collect();
+            if chars.is_empty() {
+                row += 1;
+                continue;
+            }
+
+            for chunk in chars.chunks(cols.max(1)) {
+                if row >= page {
+                    break;
+                }
+                move_to(output, row);
+
+                let mut marked = false;
+                for &(at, c) in chunk {
+                    let inside = marks.iter().any(|&(start, end)| at >= start && at < end);
+                    if inside != marked {
+                        output.write_str(if inside { "\x1b[7m" } else { "\x1b[27m" });
+                        marked = inside;
+                    }
+                    output.write_char(c);
+                }
+                if marked {
+                    output.write_str("\x1b[27m");
+                }
+                row += 1;
+            }
+        }
+
+        self.render_status(output, index.min(self.lines.len()));
+    }
+
+    /// Trata uma tecla e redesenha; `false` quando o pager deve fechar
+    fn key(&mut self, key: Key, _ctx: &mut ShellContext, output: &mut TerminalState) -> bool {
+        self.message = None;
+
+        if let Some((kind, pattern)) = self.prompt.take() {
+            self.prompt_key(kind, pattern, key);
+            self.render(output);
+            return true;
+        }
+
+        let cols = output.cols as usize;
+        let page = page_size(output);
+        match key {
+            Key::Char('q' | 'Q') | Key::Ctrl('c') | Key::Escape => return false,
+            Key::Char('j' | 'e') | Key::Enter | Key::Down => self.scroll_down(1, cols, page),
+            Key::Char('k' | 'y') | Key::Up => self.scroll_up(1),
+            Key::Char(' ' | 'f') | Key::PageDown => self.scroll_down(page, cols, page),
+            Key::Char('b') | Key::PageUp => self.scroll_up(page),
+            Key::Char('d') => self.scroll_down((page / 2).max(1), cols, page),
+            Key::Char('u') => self.scroll_up((page / 2).max(1)),
+            Key::Char('g' | '<') | Key::Home => self.top = 0,
+            Key::Char('G' | '>') | Key::End => self.top = self.last_top(cols, page),
+            Key::Char(c @ ('/' | '?')) => self.prompt = Some((c, String::new())),
+            Key::Char('n') => self.repeat(false),
+            Key::Char('N') => self.repeat(true),
+            _ => {}
+        }
+
+        self.render(output);
+        true
+    }
+}
+
+impl Pager {
+    /// Tecla enquanto o padrão da busca é digitado
+    fn prompt_key(&mut self, kind: char, mut pattern: String, key: Key) {
+        match key {
+            Key::Enter if pattern.is_empty() => {
+                // Padrão vazio repete a última busca no sentido pedido
+                if let Some(search) = self.search.as_mut() {
+                    search.forward = kind == '/';
+                }
+                self.repeat(false);
+            }
+            Key::Enter => self.start_search(kind == '/', &pattern),
+            Key::Escape | Key::Ctrl('c') => {}
+            Key::Backspace => {
+                // Apagar com o padrão vazio desiste da busca
+                if pattern.pop().is_some() {
+                    self.prompt = Some((kind, pattern));
+                }
+            }
+            Key::Char(c) => {
+                pattern.push(c);
+                self.prompt = Some((kind, pattern));
+            }
+            _ => self.prompt = Some((kind, pattern)),
+        }
+    }
+
+    // =========================================================================
+    // ROLAGEM
+    // =========================================================================
+
+    /// Desce `n` linhas, sem passar da última página
+    fn scroll_down(&mut self, n: usize, cols: usize, page: usize) {
+        let last = self.last_top(cols, page);
+        // Uma busca pode ter deixado o topo além da última página
+        if self.top < last {
+            self.top = (self.top + n).min(last);
+        }
+    }
+
+    /// Sobe `n` linhas
+    fn scroll_up(&mut self, n: usize) {
+        self.top = self.top.saturating_sub(n);
+    }
+
+    /// Topo que mostra o fim do texto na última linha da página
+    fn last_top(&self, cols: usize, page: usize) -> usize {
+        let mut used = 0;
+        let mut top = self.lines.len();
+        while top > 0 {
+            let h = height(&self.lines[top - 1], cols);
+            if used + h > page {
+                break;
+            }
+            used += h;
+            top -= 1;
+        }
+        // Uma única linha maior que a página fica no topo
+        top.min(self.lines.len().saturating_sub(1))
+    }
+
+    // =========================================================================
+    // BUSCA
+    // =========================================================================
+
+    /// Compila o padrão e procura a primeira ocorrência
+    fn start_search(&mut self, forward: bool, pattern: &str) {
+        let options = RegexOptions {
+            ignore_case: self.ignore_case,
+            ..RegexOptions::default()
+        };
+        match Regex::with_options(pattern, options) {
+            Ok(regex) => {
+                self.search = Some(Search { regex, forward });
+                self.find(forward);
+            }
+            Err(e) => self.message = Some(format!("Padrao invalido: {}", e.message())),
+        }
+    }
+
+    /// `n` (mesmo sentido da busca) ou `N` (sentido contrário)
+    fn repeat(&mut self, reverse: bool) {
+        match &self.search {
+            Some(search) => self.find(search.forward != reverse),
+            None => self.message = Some(String::from("Nenhuma busca anterior")),
+        }
+    }
+
+    /// Leva ao topo a próxima linha que casa, a partir da linha do topo
+    fn find(&mut self, forward: bool) {
+        let Some(search) = &self.search else {
+            return;
+        };
+
+        let matches = |i: &usize| search.regex.is_match(&self.lines[*i]);
+        let found = if forward {
+            (self.top + 1..self.lines.len()).find(matches)
+        } else {
+            (0..self.top).rev().find(matches)
+        };
+
+        match found {
+            Some(i) => self.top = i,
+            None => self.message = Some(String::from("Padrao nao encontrado")),
+        }
+    }
+
+    // =========================================================================
+    // DESENHO
+    // =========================================================================
+
+    /// Última linha da tela: padrão em digitação, aviso ou posição
+    fn render_status(&self, output: &mut TerminalState, shown: usize) {
+        let last_row = output.rows.saturating_sub(1) as usize;
+
+        if let Some((kind, pattern)) = &self.prompt {
+            move_to(output, last_row);
+            output.write_char(*kind);
+            output.write_str(&screen::fit(pattern, output.cols as usize - 2));
+            output.write_str("\x1b[?25h");
+            return;
+        }
+        output.write_str("\x1b[?25l");
+
+        let status = match &self.message {
+            Some(message) => message.clone(),
+            None => {
+                let total = self.lines.len();
+                let position = if shown >= total {
+                    String::from("(FIM)")
+                } else {
+                    format!("{}%", shown * 100 / total)
+                };
+                format!(
+                    "{}  linhas {}-{} de {}  {}  (q sai, / busca)",
+                    self.title,
+                    (self.top + 1).min(total),
+                    shown,
+                    total,
+                    position
+                )
+            }
+        };
+
+        screen::write_bar(output, last_row, &status);
+    }
+}
+
+/// Mostra `write` no pager se a saída não couber na tela.
+///
+/// Em capturas e pipelines a saída segue direto, sem pager.
+pub fn page_output(
+    output: &mut TerminalState,
+    title: &str,
+    write: impl FnOnce(&mut TerminalState),
+) -> CommandResult {
+    if output.is_capturing() {
+        write(output);
+        return CommandResult::Ok;
+    }
+
+    output.begin_capture();
+    write(output);
+    let text = output.end_capture();
+
+    let pager = Pager::new(title, &text);
+    if pager.fits(output) {
+        output.write_str(&text);
+        return CommandResult::Ok;
+    }
+    CommandResult::Screen(Box::new(pager))
+}
+
+/// Linhas da tela para o texto; a última fica para o status
+fn page_size(output: &TerminalState) -> usize {
+    (output.rows as usize).saturating_sub(1).max(1)
+}
+
+/// Linhas de tela ocupadas por uma linha do texto
+fn height(line: &str, cols: usize) -> usize {
+    line.chars().count().div_ceil(cols.max(1)).max(1)
+}
+
+/// Expande tabs e troca caracteres de controle por `^X`
+fn display_line(raw: &str) -> String {
+    let mut line = String::with_capacity(raw.len());
+    let mut col = 0;
+
+    for c in raw.chars() {
+        match c {
+            '\t' => {
+                let next = (col / TAB_WIDTH + 1) * TAB_WIDTH;
+                while col < next {
+                    line.push(' ');
+                    col += 1;
+                }
+            }
+            c if (c as u32) < 0x20 || c == '\x7f' => {
+                line.push('^');
+                line.push(char::from(c as u8 ^ 0x40));
+                col += 2;
+            }
+            c => {
+                line.push(c);
+                col += 1;
+            }
+        }
+    }
+
+    line
+}
+
+// =============================================================================
+// TESTES
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{display_line, page_output, Pager};
+    use crate::shell::commands::CommandResult;
+    use crate::shell::screen::{Key, Screen};
+    use crate::shell::testing::{run, shell};
+    use crate::shell::vfs::MemoryFs;
+    use crate::shell::ShellContext;
+    use crate::state::terminal::{Cell, TerminalState};
+    use alloc::format;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    /// "linha 1" a "linha n", numa tela de 20x5 (quatro linhas de texto)
+    fn pager(n: usize) -> (Pager, ShellContext, TerminalState) {
+        let text: Vec<String> = (1..=n).map(|i| format!("linha {}", i)).collect();
+        let pager = Pager::new("t", &text.join("\n"));
+        (pager, shell(MemoryFs::new()), TerminalState::new(20, 5))
+    }
+
+    fn press(pager: &mut Pager, ctx: &mut ShellContext, output: &mut TerminalState, keys: &[Key]) {
+        for key in keys {
+            assert!(pager.key(*key, ctx, output));
+        }
+    }
+
+    fn typed(text: &str) -> Vec<Key> {
+        text.chars().map(Key::Char).chain([Key::Enter]).collect()
+    }
+
+    /// Texto de uma linha da tela, sem os espaços do fim
+    fn row(output: &TerminalState, row: u32) -> String {
+        let cells = output.get_visible_line(row).unwrap();
+        let text: String = cells.iter().map(|c| c.ch).collect();
+        String::from(text.trim_end())
+    }
+
+    #[test]
+    fn renders_a_page_and_the_status() {
+        let (pager, _, mut output) = pager(2);
+        pager.render(&mut output);
+        assert_eq!(row(&output, 0), "linha 1");
+        assert_eq!(row(&output, 1), "linha 2");
+        assert_eq!(row(&output, 2), "~");
+        assert_eq!(row(&output, 4), "t  linhas 1-2 de 2");
+    }
+
+    #[test]
+    fn scrolling_stops_at_the_last_page() {
+        let (mut pager, mut ctx, mut output) = pager(10);
+        press(
+            &mut pager,
+            &mut ctx,
+            &mut output,
+            &[Key::Down, Key::Char('j')],
+        );
+        assert_eq!(pager.top, 2);
+        press(
+            &mut pager,
+            &mut ctx,
+            &mut output,
+            &[Key::Char(' '), Key::Char(' ')],
+        );
+        assert_eq!(pager.top, 6);
+        assert_eq!(row(&output, 3), "linha 10");
+        press(
+            &mut pager,
+            &mut ctx,
+            &mut output,
+            &[Key::Char('b'), Key::Char('k')],
+        );
+        assert_eq!(pager.top, 1);
+        press(&mut pager, &mut ctx, &mut output, &[Key::Char('G')]);
+        assert_eq!(pager.top, 6);
+        press(&mut pager, &mut ctx, &mut output, &[Key::Char('g')]);
+        assert_eq!(pager.top, 0);
+        assert!(!pager.key(Key::Char('q'), &mut ctx, &mut output));
+    }
+
+    #[test]
+    fn wrapped_lines_take_several_rows() {
+        let mut pager = Pager::new("t", "curta\numa linha com mais de vinte colunas\nfim");
+        let mut ctx = shell(MemoryFs::new());
+        let mut output = TerminalState::new(20, 4);
+        assert!(!pager.fits(&output));
+        pager.render(&mut output);
+        assert_eq!(row(&output, 1), "uma linha com mais d");
+        assert_eq!(row(&output, 2), "e vinte colunas");
+        // A última página já mostra o fim a partir da linha longa
+        press(&mut pager, &mut ctx, &mut output, &[Key::End]);
+        assert_eq!(pager.top, 1);
+    }
+
+    #[test]
+    fn search_moves_and_highlights() {
+        let (mut pager, mut ctx, mut output) = pager(12);
+        press(&mut pager, &mut ctx, &mut output, &[Key::Char('/')]);
+        press(&mut pager, &mut ctx, &mut output, &typed("1$"));
+        assert_eq!(pager.top, 10);
+        assert_eq!(row(&output, 0), "linha 11");
+        // Só o "1" do fim fica em vídeo reverso
+        let cells = output.get_visible_line(0).unwrap();
+        let marked: Vec<bool> = cells[..8].iter().map(|c| c.bg != Cell::BLANK.bg).collect();
+        assert_eq!(
+            marked,
+            [false, false, false, false, false, false, false, true]
+        );
+
+        press(&mut pager, &mut ctx, &mut output, &[Key::Char('N')]);
+        assert_eq!(pager.top, 0);
+        press(&mut pager, &mut ctx, &mut output, &[Key::Char('n')]);
+        assert_eq!(pager.top, 10);
+        press(&mut pager, &mut ctx, &mut output, &[Key::Char('n')]);
+        assert_eq!(pager.message.as_deref(), Some("Padrao nao encontrado"));
+
+        press(&mut pager, &mut ctx, &mut output, &[Key::Char('?')]);
+        press(&mut pager, &mut ctx, &mut output, &typed("^linha 2$"));
+        assert_eq!(pager.top, 1);
+    }
+
+    #[test]
+    fn search_errors_are_shown_in_the_status() {
+        let (mut pager, mut ctx, mut output) = pager(3);
+        press(&mut pager, &mut ctx, &mut output, &[Key::Char('n')]);
+        assert_eq!(pager.message.as_deref(), Some("Nenhuma busca anterior"));
+        press(&mut pager, &mut ctx, &mut output, &[Key::Char('/')]);
+        press(&mut pager, &mut ctx, &mut output, &typed("("));
+        assert!(row(&output, 4).starts_with("Padrao invalido: "));
+
+        // Esc desiste da busca sem sair do pager
+        press(
+            &mut pager,
+            &mut ctx,
+            &mut output,
+            &[Key::Char('/'), Key::Char('x'), Key::Escape],
+        );
+        assert!(pager.prompt.is_none());
+        assert!(pager.search.is_none());
+    }
+
+    #[test]
+    fn tabs_and_control_characters_are_made_visible() {
+        assert_eq!(display_line("a\tb"), "a       b");
+        assert_eq!(display_line("\x1b[1m\x7f"), "^[[1m^?");
+    }
+
+    #[test]
+    fn page_output_only_pages_what_does_not_fit() {
+        let mut output = TerminalState::new(20, 5);
+        let result = page_output(&mut output, "t", |out| out.write_line("curto"));
+        assert!(matches!(result, CommandResult::Ok));
+        assert_eq!(row(&output, 0), "curto");
+
+        let long = |out: &mut TerminalState| {
+            for i in 0..10 {
+                out.write_line(&format!("{}", i));
+            }
+        };
+        assert!(matches!(
+            page_output(&mut output, "t", long),
+            CommandResult::Screen(_)
+        ));
+
+        output.begin_capture();
+        assert!(matches!(
+            page_output(&mut output, "t", long),
+            CommandResult::Ok
+        ));
+        assert_eq!(output.end_capture().lines().count(), 10);
+    }
+
+    #[test]
+    fn less_passes_text_through_captures() {
+        let mut fs = MemoryFs::new();
+        fs.add_file("/a", b"um\ndois\n");
+        let mut ctx = shell(fs);
+        assert_eq!(run(&mut ctx, "less /a | wc -l"), "2\n");
+        assert!(run(&mut ctx, "less /a /a").starts_with("less: apenas um arquivo por vez\n"));
+    }
+}
@@ -0,0 +1,765 @@
+//! # Shell Parser
+//!
+//! Separação de palavras e expansões da linha de comando.
+//!
+//! ## Expansões
+//!
+//! | Sintaxe          | Resultado                                   |
+//! |------------------|---------------------------------------------|
+//! | `$NOME`, `${NOME}` | Valor da variável                         |
+//! | `$?`             | Código de saída do último comando           |
+//! | `$(cmd)`, `` `cmd` `` | Saída do comando (sem `\n` finais)     |
+//! | `$((expr))`      | Resultado da expressão aritmética           |
+//! | `~`, `~usuario`  | Diretório pessoal (no início da palavra)    |
+//! | `~+`, `~-`       | `$PWD` / `$OLDPWD`                          |
+//!
+//! Aspas simples preservam o texto literalmente; aspas duplas permitem
+//! expansões mas impedem a separação do resultado em várias palavras.
+//!
+//! As atribuições `NOME=valor` no início do comando são reconhecidas pelo
+//! texto digitado, antes das expansões: `'A=b'` e `$(echo A=1)` são
+//! palavras comuns. O valor de uma atribuição não é separado em palavras.
+//!
+//! Antes das expansões, a linha é dividida nos `|` de um pipeline
+//! ([`split_pipeline`]); cada estágio é expandido quando vai rodar.
+//!
+//! Um `$(...)` cujo comando vira tarefa (um programa externo, por exemplo)
+//! interrompe a expansão com [`ExpandError::Pending`]. Quando a tarefa
+//! termina, o estágio é expandido de novo com o mesmo [`Memo`]: o que já
+//! foi expandido se repete de lá, sem rodar nem avaliar outra vez.
+
+use super::arith;
+use super::commands::{execute_command, CommandResult};
+use super::path;
+use super::task::Task;
+use super::ShellContext;
+use crate::state::terminal::TerminalState;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::ops::Range;
+
+/// Expansão interrompida
+pub enum ExpandError {
+    /// Erro de sintaxe ou de avaliação
+    Invalid(String),
+    /// `$(...)` esperando uma tarefa
+    Pending(Pending),
+}
+
+impl From<String> for ExpandError {
+    fn from(msg: String) -> Self {
+        Self::Invalid(msg)
+    }
+}
+
+/// `$(...)` cujo comando continua em uma tarefa
+pub struct Pending {
+    pub task: Box<dyn Task>,
+    /// Saída que o comando já escreveu
+    pub output: String,
+    /// Posição no [`Memo`] que recebe a saída completa
+    pub slot: usize,
+}
+
+/// Resultados das expansões de um estágio, na ordem em que aparecem
+#[derive(Default)]
+pub struct Memo {
+    /// Texto expandido e a posição da próxima expansão depois dele
+    slots: Vec<Option<(String, usize)>>,
+}
+
+impl Memo {
+    /// Guarda a saída do `$(...)` que estava esperando
+    pub fn fill(&mut self, slot: usize, text: String) {
+        self.slots[slot] = Some((trim_newlines(text), slot + 1));
+    }
+}
+
+/// Linha expandida
+pub struct Words {
+    pub words: Vec<String>,
+    /// Quantas das primeiras palavras são atribuições `NOME=valor`
+    pub assigns: usize,
+}
+
+/// Expande uma linha de comando em palavras
+pub fn expand_line(
+    line: &str,
+    ctx: &mut ShellContext,
+    output: &mut TerminalState,
+    memo: &mut Memo,
+) -> Result<Words, ExpandError> {
+    let mut expander = Expander::new(line, ctx, output, memo);
+    expander.run()?;
+    Ok(Words {
+        words: expander.words,
+        assigns: expander.assigns,
+    })
+}
+
+/// Verifica se o texto digitado é uma atribuição `NOME=valor`
+pub fn is_assignment(word: &str) -> bool {
+    match word.find('=') {
+        Some(eq) => is_valid_name(&word[..eq]),
+        None => false,
+    }
+}
+
+/// Verifica se é um nome de variável válido
+pub fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Estágio de um pipeline, como digitado
+pub struct Stage<'a> {
+    /// Texto do estágio, sem os espaços das pontas
+    pub text: &'a str,
+    /// Bytes da palavra do comando na linha, depois das atribuições
+    pub command: Option<Range<usize>>,
+}
+
+/// Divide a linha nos `|` fora de aspas, `$(...)` e crases.
+///
+/// `||` não é separador: fica no estágio como texto.
+pub fn split_pipeline(line: &str) -> Result<Vec<Stage<'_>>, String> {
+    let bytes = line.as_bytes();
+    let mut stages = Vec::new();
+    let mut start = 0;
+    let mut quote: Option<u8> = None;
+    // Profundidade de `$(` e crase aberta
+    let mut depth = 0usize;
+    let mut backtick = false;
+    // Palavra sendo lida e palavra do comando do estágio
+    let mut word: Option<usize> = None;
+    let mut command: Option<Range<usize>> = None;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let b = bytes[i];
+        let top = quote.is_none() && depth == 0 && !backtick;
+        if top && (b.is_ascii_whitespace() || b == b'|') {
+            if let Some(w) = word.take() {
+                end_word(line, w..i, &mut command);
+            }
+        } else if word.is_none() {
+            word = Some(i);
+        }
+
+        match (quote, b) {
+            (Some(b'\''), b'\'') => quote = None,
+            (Some(b'\''), _) => {}
+            (_, b'\\') => i += 1,
+            (Some(b'"'), b'"') => quote = None,
+            (None, b'\'' | b'"') => quote = Some(b),
+            (_, b'$') if bytes.get(i + 1) == Some(&b'(') => {
+                depth += 1;
+                i += 1;
+            }
+            (_, b'(') if depth > 0 => depth += 1,
+            (_, b')') if depth > 0 => depth -= 1,
+            (_, b'`') => backtick = !backtick,
+            (None, b'|') if depth == 0 && !backtick => {
+                if bytes.get(i + 1) == Some(&b'|') {
+                    // `||` é texto: uma palavra, como qualquer outra
+                    word = Some(i);
+                    i += 1;
+                } else {
+                    stages.push(Stage {
+                        text: line[start..i].trim(),
+                        command: command.take(),
+                    });
+                    start = i + 1;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    if let Some(w) = word {
+        end_word(line, w..bytes.len(), &mut command);
+    }
+    stages.push(Stage {
+        text: line[start..].trim(),
+        command,
+    });
+
+    if stages.len() > 1 && stages.iter().any(|s| s.text.is_empty()) {
+        return Err(String::from("erro de sintaxe perto de '|'"));
+    }
+    Ok(stages)
+}
+
+/// Fim de uma palavra do estágio: a primeira que não é atribuição é o
+/// comando
+fn end_word(line: &str, range: Range<usize>, command: &mut Option<Range<usize>>) {
+    if command.is_none() && !is_assignment(&line[range.clone()]) {
+        *command = Some(range);
+    }
+}
+
+/// Estado da expansão de uma linha
+struct Expander<'a> {
+    chars: Vec<char>,
+    pos: usize,
+    /// Palavras já finalizadas
+    words: Vec<String>,
+    /// Palavra em construção
+    current: String,
+    /// A palavra atual já começou (permite argumentos vazios como `""`)
+    in_word: bool,
+    /// Atribuições no início de `words`
+    assigns: usize,
+    /// A palavra atual é uma atribuição
+    assigning: bool,
+    ctx: &'a mut ShellContext,
+    output: &'a mut TerminalState,
+    memo: &'a mut Memo,
+    /// Posição da próxima expansão no `memo`
+    next: usize,
+}
+
+impl<'a> Expander<'a> {
+    fn new(
+        text: &str,
+        ctx: &'a mut ShellContext,
+        output: &'a mut TerminalState,
+        memo: &'a mut Memo,
+    ) -> Self {
+        Self {
+            chars: text.chars().collect(),
+            pos: 0,
+            words: Vec::new(),
+            current: String::new(),
+            in_word: false,
+            assigns: 0,
+            assigning: false,
+            ctx,
+            output,
+            memo,
+            next: 0,
+        }
+    }
+
+    /// Processa a linha inteira, separando palavras
+    fn run(&mut self) -> Result<(), ExpandError> {
+        while let Some(c) = self.peek(0) {
+            // Só conta como atribuição o que vem antes do comando
+            if !self.in_word && self.words.len() == self.assigns {
+                self.assigning = self.raw_assignment();
+            }
+
+            match c {
+                ' ' | '\t' | '\n' => {
+                    self.pos += 1;
+                    self.finish_word();
+                }
+                '\\' => {
+                    self.pos += 1;
+                    if let Some(next) = self.peek(0) {
+                        self.pos += 1;
+                        self.current.push(next);
+                    }
+                    self.in_word = true;
+                }
+                '\'' => {
+                    self.pos += 1;
+                    let end = self
+                        .find_char('\'', self.pos)
+                        .ok_or_else(|| String::from("aspas simples nao fechadas"))?;
+                    self.current.extend(&self.chars[self.pos..end]);
+                    self.pos = end + 1;
+                    self.in_word = true;
+                }
+                '"' => {
+                    self.pos += 1;
+                    self.read_quoted(Some('"'))?;
+                    self.in_word = true;
+                }
+                '$' | '`' if self.assigning => {
+                    let text = self.expansion()?;
+                    self.current.push_str(&text);
+                }
+                '$' | '`' => {
+                    let text = self.expansion()?;
+                    self.push_split(&text);
+                }
+                '~' if !self.in_word && self.tilde_end().is_some() => self.tilde()?,
+                _ => {
+                    self.pos += 1;
+                    self.current.push(c);
+                    self.in_word = true;
+                }
+            }
+        }
+
+        self.finish_word();
+        Ok(())
+    }
+
+    /// Lê texto entre aspas duplas (ou até o fim, se `terminator` for `None`)
+    fn read_quoted(&mut self, terminator: Option<char>) -> Result<(), ExpandError> {
+        while let Some(c) = self.peek(0) {
+            if Some(c) == terminator {
+                self.pos += 1;
+                return Ok(());
+            }
+
+            match c {
+                '\\' => {
+                    self.pos += 1;
+                    match self.peek(0) {
+                        Some(next @ ('$' | '`' | '"' | '\\')) => {
+                            self.pos += 1;
+                            self.current.push(next);
+                        }
+                        _ => self.current.push('\\'),
+                    }
+                }
+                '$' | '`' => {
+                    let text = self.expansion()?;
+                    self.current.push_str(&text);
+                }
+                _ => {
+                    self.pos += 1;
+                    self.current.push(c);
+                }
+            }
+        }
+
+        match terminator {
+            Some(_) => Err(String::from("aspas duplas nao fechadas").into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Expande `$...` ou `` `...` `` na posição atual
+    fn expansion(&mut self) -> Result<String, ExpandError> {
+        if self.peek(0) == Some('`') {
+            let start = self.pos + 1;
+            let end = self
+                .find_char('`', start)
+                .ok_or_else(|| String::from("crase nao fechada"))?;
+            let cmd: String = self.chars[start..end].iter().collect();
+            self.pos = end + 1;
+            return self.remember(|this, slot| this.substitute(&cmd, slot));
+        }
+
+        // Pular o '$'
+        self.pos += 1;
+
+        match self.peek(0) {
+            Some('(') if self.peek(1) == Some('(') => {
+                let start = self.pos + 2;
+                let end = self.find_arith_end(start)?;
+                let expr: String = self.chars[start..end].iter().collect();
+                self.pos = end + 2;
+                self.remember(|this, _| this.arithmetic(&expr))
+            }
+            Some('(') => {
+                let start = self.pos + 1;
+                let end = self.find_paren_end(start)?;
+                let cmd: String = self.chars[start..end].iter().collect();
+                self.pos = end + 1;
+                self.remember(|this, slot| this.substitute(&cmd, slot))
+            }
+            Some('{') => {
+                let start = self.pos + 1;
+                let end = self
+                    .find_char('}', start)
+                    .ok_or_else(|| String::from("'${' sem '}'"))?;
+                let name: String = self.chars[start..end].iter().collect();
+                self.pos = end + 1;
+                if name != "?" && !is_valid_name(&name) {
+                    return Err(format!("${{{}}}: substituicao invalida", name).into());
+                }
+                self.remember(|this, _| Ok(this.ctx.var(&name).unwrap_or_default()))
+            }
+            Some('?') => {
+                self.pos += 1;
+                self.remember(|this, _| Ok(this.ctx.last_exit_code.to_string()))
+            }
+            Some(c) if c.is_ascii_alphabetic() || c == '_' => {
+                let start = self.pos;
+                while matches!(self.peek(0), Some(c) if c.is_ascii_alphanumeric() || c == '_') {
+                    self.pos += 1;
+                }
+                let name: String = self.chars[start..self.pos].iter().collect();
+                self.remember(|this, _| Ok(this.ctx.var(&name).unwrap_or_default()))
+            }
+            // '$' sozinho é literal
+            _ => Ok(String::from("$")),
+        }
+    }
+
+    /// Fim do `~nome` na posição atual, se ele tiver a forma de uma expansão
+    fn tilde_end(&self) -> Option<usize> {
+        let mut end = self.pos + 1;
+        while let Some(&c) = self.chars.get(end) {
+            if !(c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '+')) {
+                break;
+            }
+            end += 1;
+        }
+        match self.chars.get(end) {
+            None | Some('/' | ' ' | '\t' | '\n') => Some(end),
+            _ => None,
+        }
+    }
+
+    /// Expande `~nome` no início da palavra, até a primeira `/`.
+    ///
+    /// Sem diretório para o nome, o `~nome` fica literal.
+    fn tilde(&mut self) -> Result<(), ExpandError> {
+        let end = self.tilde_end().unwrap_or(self.pos + 1);
+        let name: String = self.chars[self.pos + 1..end].iter().collect();
+        let dir = self.remember(|this, _| {
+            Ok(path::tilde(this.ctx, &name).unwrap_or_else(|| format!("~{}", name)))
+        })?;
+        // `~/x` com HOME=/ vira `/x`, não `//x`
+        if self.chars.get(end) == Some(&'/') {
+            self.current.push_str(dir.trim_end_matches('/'));
+        } else {
+            self.current.push_str(&dir);
+        }
+        self.pos = end;
+        self.in_word = true;
+        Ok(())
+    }
+
+    /// Avalia uma expansão, ou repete o resultado guardado no `memo`.
+    ///
+    /// `eval` recebe a posição reservada para o resultado.
+    fn remember(
+        &mut self,
+        eval: impl FnOnce(&mut Self, usize) -> Result<String, ExpandError>,
+    ) -> Result<String, ExpandError> {
+        let slot = self.next;
+        if let Some(Some((text, end))) = self.memo.slots.get(slot) {
+            self.next = *end;
+            return Ok(text.clone());
+        }
+        if self.memo.slots.len() == slot {
+            self.memo.slots.push(None);
+        }
+        self.next += 1;
+
+        let text = eval(self, slot)?;
+        self.memo.slots[slot] = Some((text.clone(), self.next));
+        Ok(text)
+    }
+
+    /// Executa um comando capturando sua saída
+    fn substitute(&mut self, cmd: &str, slot: usize) -> Result<String, ExpandError> {
+        // O comando lê a mesma entrada do estágio, sem consumi-la
+        let stdin = self.ctx.stdin.clone();
+        self.output.begin_capture();
+        let result = execute_command(cmd, self.ctx, self.output);
+        let text = self.output.end_capture();
+        self.ctx.stdin = stdin;
+
+        match result {
+            CommandResult::Error(msg) => Err(ExpandError::Invalid(msg)),
+            CommandResult::Task(task) => Err(ExpandError::Pending(Pending {
+                task,
+                output: text,
+                slot,
+            })),
+            _ => Ok(trim_newlines(text)),
+        }
+    }
+
+    /// Avalia `$((expr))`; a expressão passa antes pelas expansões de `$`
+    fn arithmetic(&mut self, expr: &str) -> Result<String, ExpandError> {
+        let mut inner = Expander::new(expr, self.ctx, self.output, self.memo);
+        inner.next = self.next;
+        let result = inner.read_quoted(None);
+        self.next = inner.next;
+        result?;
+        let expr = inner.current;
+
+        let n = arith::eval(&expr, &mut self.ctx.vars)
+            .map_err(|e| format!("{}: {}", expr.trim(), e.as_str()))?;
+        Ok(n.to_string())
+    }
+
+    /// Adiciona resultado de expansão sem aspas, separando em palavras
+    fn push_split(&mut self, text: &str) {
+        for c in text.chars() {
+            if c == ' ' || c == '\t' || c == '\n' {
+                self.finish_word();
+            } else {
+                self.current.push(c);
+                self.in_word = true;
+            }
+        }
+    }
+
+    fn finish_word(&mut self) {
+        if self.in_word {
+            self.words.push(core::mem::take(&mut self.current));
+            self.in_word = false;
+            if self.assigning {
+                self.assigns += 1;
+            }
+        }
+        self.assigning = false;
+    }
+
+    /// O texto a partir da posição atual começa com `NOME=`, sem aspas,
+    /// escapes ou expansões no nome
+    fn raw_assignment(&self) -> bool {
+        let rest = &self.chars[self.pos..];
+        match rest.iter().position(|&c| c == '=') {
+            Some(eq) => is_valid_name(&rest[..eq].iter().collect::<String>()),
+            None => false,
+        }
+    }
+
+    fn peek(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    /// Procura `c` a partir de `start`, respeitando escapes com `\`
+    fn find_char(&self, c: char, start: usize) -> Option<usize> {
+        let mut i = start;
+        while i < self.chars.len() {
+            if self.chars[i] == c {
+                return Some(i);
+            }
+            if self.chars[i] == '\\' && c != '\'' {
+                i += 1;
+            }
+            i += 1;
+        }
+        None
+    }
+
+    /// Encontra o `)` que fecha um `$(`, respeitando aninhamento e aspas
+    fn find_paren_end(&self, start: usize) -> Result<usize, String> {
+        let mut depth = 1;
+        let mut i = start;
+
+        while i < self.chars.len() {
+            match self.chars[i] {
+                '\\' => i += 1,
+                '\'' | '"' => {
+                    i = self
+                        .find_char(self.chars[i], i + 1)
+                        .ok_or_else(|| String::from("aspas nao fechadas em '$('"))?;
+                }
+                '(' => depth += 1,
+                ')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(i);
+                    }
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+
+        Err(String::from("'$(' sem ')'"))
+    }
+
+    /// Encontra o `))` que fecha um `$((`
+    fn find_arith_end(&self, start: usize) -> Result<usize, String> {
+        let mut depth = 0;
+
+        for i in start..self.chars.len() {
+            match self.chars[i] {
+                '(' => depth += 1,
+                ')' if depth > 0 => depth -= 1,
+                ')' if self.chars.get(i + 1) == Some(&')') => return Ok(i),
+                ')' => break,
+                _ => {}
+            }
+        }
+
+        Err(String::from("'$((' sem '))'"))
+    }
+}
+
+/// Saída de `$(...)`, sem os `\n` finais
+fn trim_newlines(mut text: String) -> String {
+    while text.ends_with('\n') {
+        text.pop();
+    }
+    text
+}
+
+// =============================================================================
+// TESTES
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::split_pipeline;
+    use crate::shell::commands::{execute_command, CommandResult};
+    use crate::shell::testing::{run, shell};
+    use crate::shell::vfs::MemoryFs;
+    use crate::shell::ShellContext;
+    use crate::state::terminal::TerminalState;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    fn sh() -> ShellContext {
+        shell(MemoryFs::new())
+    }
+
+    /// Mensagem de erro da linha
+    fn error(ctx: &mut ShellContext, line: &str) -> String {
+        let mut output = TerminalState::new(80, 24);
+        match execute_command(line, ctx, &mut output) {
+            CommandResult::Error(msg) => msg,
+            _ => panic!("esperava erro em {}", line),
+        }
+    }
+
+    /// Texto e palavra do comando de cada estágio
+    fn stages(line: &str) -> Vec<(&str, Option<&str>)> {
+        split_pipeline(line)
+            .unwrap()
+            .into_iter()
+            .map(|stage| (stage.text, stage.command.map(|range| &line[range])))
+            .collect()
+    }
+
+    #[test]
+    fn pipeline_stages_and_command_words() {
+        assert_eq!(
+            stages("A=1 ls -l | sort  -k2 |wc"),
+            [
+                ("A=1 ls -l", Some("ls")),
+                ("sort  -k2", Some("sort")),
+                ("wc", Some("wc"))
+            ]
+        );
+        assert_eq!(
+            stages("echo '|' \\| $(a | b) `c | d` || x"),
+            [("echo '|' \\| $(a | b) `c | d` || x", Some("echo"))]
+        );
+        assert_eq!(
+            stages("\"a b\" c | 'A=1' d | A=\"x y\""),
+            [
+                ("\"a b\" c", Some("\"a b\"")),
+                ("'A=1' d", Some("'A=1'")),
+                ("A=\"x y\"", None)
+            ]
+        );
+        assert!(split_pipeline("a | | b").is_err());
+    }
+
+    #[test]
+    fn assignments_are_decided_before_expansion() {
+        let mut ctx = sh();
+        assert_eq!(run(&mut ctx, "A=b"), "");
+        assert_eq!(ctx.var("A").as_deref(), Some("b"));
+
+        assert!(run(&mut ctx, "'B=1' echo x").starts_with("Comando nao encontrado: B=1\n"));
+        assert!(run(&mut ctx, "$(echo B=1)").starts_with("Comando nao encontrado: B=1\n"));
+        assert!(run(&mut ctx, "\\B=1").starts_with("Comando nao encontrado: B=1\n"));
+        assert!(run(&mut ctx, "\"B\"=1").starts_with("Comando nao encontrado: B=1\n"));
+        assert_eq!(ctx.var("B"), None);
+
+        // Depois do comando, `NOME=valor` é argumento
+        assert_eq!(run(&mut ctx, "echo C=1"), "C=1\n");
+        assert_eq!(ctx.var("C"), None);
+    }
+
+    #[test]
+    fn assignment_value_is_not_split() {
+        let mut ctx = sh();
+        run(&mut ctx, "A='x  y'");
+        run(&mut ctx, "B=$A C=$(echo 1 2)");
+        assert_eq!(run(&mut ctx, "echo \"[$B]\" \"[$C]\""), "[x  y] [1 2]\n");
+    }
+
+    #[test]
+    fn prefix_assignment_is_temporary() {
+        let mut ctx = sh();
+        assert_eq!(run(&mut ctx, "X=tmp echo oi"), "oi\n");
+        assert_eq!(ctx.var("X"), None);
+        run(&mut ctx, "X=1");
+        run(&mut ctx, "X=2 echo oi");
+        assert_eq!(ctx.var("X").as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn command_substitution() {
+        let mut ctx = sh();
+        assert_eq!(run(&mut ctx, "echo $(echo a   b)"), "a b\n");
+        assert_eq!(run(&mut ctx, "echo \"$(echo '  a  ')\""), "  a  \n");
+        assert_eq!(run(&mut ctx, "echo [$(echo $(echo x))]"), "[x]\n");
+        assert_eq!(run(&mut ctx, "echo `echo y`z"), "yz\n");
+        assert_eq!(run(&mut ctx, "echo $(echo a | tr a b)"), "b\n");
+        assert_eq!(error(&mut ctx, "echo $(echo"), "'$(' sem ')'");
+    }
+
+    #[test]
+    fn exit_status_expansion() {
+        let mut ctx = sh();
+        run(&mut ctx, "qqqq");
+        assert_eq!(run(&mut ctx, "echo $? ${?}"), "127 127\n");
+    }
+
+    #[test]
+    fn arithmetic_expansion() {
+        let mut ctx = sh();
+        assert_eq!(run(&mut ctx, "echo $((2+3*4)) $(( (2+3)*4 ))"), "14 20\n");
+        run(&mut ctx, "N=3");
+        assert_eq!(
+            run(&mut ctx, "echo $((N*2)) $(($N+1)) $((N+=1))"),
+            "6 4 4\n"
+        );
+        assert_eq!(ctx.var("N").as_deref(), Some("4"));
+        assert_eq!(run(&mut ctx, "echo $(($(echo 6)/4))"), "1\n");
+        assert_eq!(
+            run(&mut ctx, "echo $((9223372036854775807 + 1))"),
+            "-9223372036854775808\n"
+        );
+    }
+
+    #[test]
+    fn arithmetic_errors() {
+        let mut ctx = sh();
+        assert_eq!(error(&mut ctx, "echo $((1/0))"), "1/0: divisao por zero");
+        assert_eq!(ctx.last_exit_code, 1);
+        assert_eq!(
+            error(&mut ctx, "echo $((2 +))"),
+            "2 +: erro de sintaxe na expressao"
+        );
+        assert_eq!(error(&mut ctx, "echo $((1+2)"), "'$((' sem '))'");
+    }
+
+    #[test]
+    fn quotes_and_escapes() {
+        let mut ctx = sh();
+        run(&mut ctx, "X=1");
+        assert_eq!(
+            run(&mut ctx, "echo 'a  $X' \"b  $X\" c\\ d"),
+            "a  $X b  1 c d\n"
+        );
+        assert_eq!(run(&mut ctx, "echo \"\\$X \\\\\" $"), "$X \\ $\n");
+        assert_eq!(error(&mut ctx, "echo 'a"), "aspas simples nao fechadas");
+        assert_eq!(error(&mut ctx, "echo \"a"), "aspas duplas nao fechadas");
+    }
+
+    #[test]
+    fn tilde_expands_at_word_start() {
+        let mut ctx = sh();
+        ctx.set_var("HOME", "/home/u");
+        assert_eq!(
+            run(&mut ctx, "echo ~ ~/x a~ '~'"),
+            "/home/u /home/u/x a~ ~\n"
+        );
+        assert_eq!(run(&mut ctx, "echo ~ninguem/x"), "~ninguem/x\n");
+    }
+}
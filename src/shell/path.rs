@@ -0,0 +1,244 @@
+//! # Paths
+//!
+//! Caminhos absolutos usados pelo shell.
+//!
+//! Há duas formas de canonizar um caminho:
+//!
+//! - **Lexical** ([`normalize`], [`resolve`]): `.`, `..` e barras repetidas
+//!   são tratados só pelo texto, sem consultar o sistema de arquivos. É o
+//!   que `cd` usa por padrão (`-L`): `cd link/..` volta para onde estava.
+//! - **Física** ([`canonicalize`]): links simbólicos são seguidos componente
+//!   a componente, e `..` sobe a partir do destino real (`cd -P`).
+//!
+//! Nas duas, `..` acima da raiz fica na raiz e barras finais são
+//! descartadas.
+
+use super::vfs::Vfs;
+use super::ShellContext;
+use alloc::string::String;
+use alloc::vec::Vec;
+use redpowder::SysError;
+
+/// Links seguidos antes de desistir (evita ciclos)
+pub const MAX_LINK_HOPS: usize = 8;
+
+/// Normaliza um caminho absoluto (remove `//`, `.`, `..` e `/` final)
+pub fn normalize(path: &str) -> String {
+    let mut components: Vec<&str> = Vec::new();
+
+    for comp in path.split('/') {
+        match comp {
+            "" | "." => continue,
+            ".." => {
+                components.pop();
+            }
+            _ => components.push(comp),
+        }
+    }
+
+    let mut result = String::from("/");
+    result.push_str(&components.join("/"));
+    result
+}
+
+/// Resolve `path` relativo a `cwd`, lexicalmente
+pub fn resolve(cwd: &str, path: &str) -> String {
+    if path.starts_with('/') {
+        normalize(path)
+    } else {
+        normalize(&join(cwd, path))
+    }
+}
+
+/// Junta um diretório e um nome, sem normalizar
+pub fn join(base: &str, child: &str) -> String {
+    let mut path = String::from(base.trim_end_matches('/'));
+    path.push('/');
+    path.push_str(child);
+    path
+}
+
+/// Diretório pai de um caminho absoluto normalizado (`/` para a raiz)
+pub fn parent(path: &str) -> &str {
+    match path.trim_end_matches('/').rfind('/') {
+        Some(0) | None => "/",
+        Some(i) => &path[..i],
+    }
+}
+
+/// Último componente do caminho (vazio para a raiz)
+pub fn file_name(path: &str) -> &str {
+    let path = path.trim_end_matches('/');
+    &path[path.rfind('/').map_or(0, |i| i + 1)..]
+}
+
+/// `path` é `dir` ou está dentro dele (ambos normalizados)
+pub fn is_inside(path: &str, dir: &str) -> bool {
+    dir == "/"
+        || path
+            .strip_prefix(dir)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Caminho físico: resolve `path` contra `cwd` seguindo todos os links.
+///
+/// Todos os componentes precisam existir, como no `realpath`.
+pub fn canonicalize(fs: &dyn Vfs, cwd: &str, path: &str) -> Result<String, SysError> {
+    let start = if path.starts_with('/') {
+        String::from(path)
+    } else {
+        join(cwd, path)
+    };
+
+    // Componentes ainda não visitados, do último para o primeiro
+    let mut pending: Vec<String> = components(&start).rev().map(String::from).collect();
+    let mut current = String::from("/");
+    let mut hops = 0;
+
+    while let Some(comp) = pending.pop() {
+        if comp == ".." {
+            current = String::from(parent(&current));
+            continue;
+        }
+
+        let next = join(&current, &comp);
+        let info = fs.stat(&next)?;
+        if info.is_symlink() {
+            hops += 1;
+            if hops > MAX_LINK_HOPS {
+                return Err(SysError::IoError);
+            }
+            let target = fs.read_link(&next)?;
+            if target.starts_with('/') {
+                current = String::from("/");
+            }
+            pending.extend(components(&target).rev().map(String::from));
+        } else if !pending.is_empty() && !info.is_dir() {
+            return Err(SysError::NotDirectory);
+        } else {
+            current = next;
+        }
+    }
+    Ok(current)
+}
+
+/// Componentes não vazios, sem `.`
+pub fn components(path: &str) -> impl DoubleEndedIterator<Item = &str> {
+    path.split('/').filter(|c| !c.is_empty() && *c != ".")
+}
+
+// =============================================================================
+// EXPANSÃO DE ~
+// =============================================================================
+
+/// Diretório de `~nome`, sem o `~`.
+///
+/// | Prefixo   | Resultado                                   |
+/// |-----------|---------------------------------------------|
+/// | `~`       | `$HOME` (ou `/`, se não definida)           |
+/// | `~+`      | Diretório atual                             |
+/// | `~-`      | `$OLDPWD`                                   |
+/// | `~usuario`| `/home/usuario`, se existir                 |
+///
+/// `None` deixa o texto como está.
+pub fn tilde(ctx: &ShellContext, name: &str) -> Option<String> {
+    match name {
+        "" => Some(ctx.var("HOME").unwrap_or_else(|| String::from("/"))),
+        "+" => Some(ctx.cwd.clone()),
+        "-" => ctx.var("OLDPWD"),
+        user if user == ctx.username => tilde(ctx, ""),
+        user => {
+            let home = join("/home", user);
+            ctx.fs.is_dir(&home).then_some(home)
+        }
+    }
+}
+
+/// `path` com `$HOME` abreviado para `~`
+pub fn abbreviate_home(ctx: &ShellContext, path: &str) -> String {
+    if let Some(home) = ctx.var("HOME") {
+        let home = home.trim_end_matches('/');
+        if !home.is_empty() {
+            if let Some(rest) = path.strip_prefix(home) {
+                if rest.is_empty() || rest.starts_with('/') {
+                    let mut s = String::from("~");
+                    s.push_str(rest);
+                    return s;
+                }
+            }
+        }
+    }
+    String::from(path)
+}
+
+// =============================================================================
+// TESTES
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{abbreviate_home, canonicalize, file_name, normalize, parent, resolve, tilde};
+    use crate::shell::testing::shell;
+    use crate::shell::vfs::MemoryFs;
+    use redpowder::SysError;
+
+    #[test]
+    fn normalize_is_lexical() {
+        assert_eq!(normalize("/a//b/./c/"), "/a/b/c");
+        assert_eq!(normalize("/a/b/../../.."), "/");
+        assert_eq!(normalize("/../x"), "/x");
+        assert_eq!(normalize(""), "/");
+        assert_eq!(resolve("/a/b", "../c/"), "/a/c");
+        assert_eq!(resolve("/a/b", "/x/./y"), "/x/y");
+    }
+
+    #[test]
+    fn parent_and_file_name() {
+        assert_eq!(parent("/a/b"), "/a");
+        assert_eq!(parent("/a"), "/");
+        assert_eq!(parent("/"), "/");
+        assert_eq!(file_name("/a/b/"), "b");
+        assert_eq!(file_name("/"), "");
+    }
+
+    #[test]
+    fn canonicalize_follows_links() {
+        let mut fs = MemoryFs::new();
+        fs.add_file("/real/dir/f", b"")
+            .add_symlink("/rel", "real/dir")
+            .add_symlink("/abs", "/real")
+            .add_symlink("/loop", "loop");
+
+        assert_eq!(
+            canonicalize(&fs, "/", "rel/f").as_deref(),
+            Ok("/real/dir/f")
+        );
+        // `..` sobe a partir do destino do link, não do link
+        assert_eq!(canonicalize(&fs, "/abs/dir", "..").as_deref(), Ok("/real"));
+        assert_eq!(canonicalize(&fs, "/", "/rel/..").as_deref(), Ok("/real"));
+        assert_eq!(canonicalize(&fs, "/", "/nada"), Err(SysError::NotFound));
+        assert_eq!(
+            canonicalize(&fs, "/", "/rel/f/g"),
+            Err(SysError::NotDirectory)
+        );
+        assert_eq!(canonicalize(&fs, "/", "/loop"), Err(SysError::IoError));
+    }
+
+    #[test]
+    fn tilde_and_home_abbreviation() {
+        let mut fs = MemoryFs::new();
+        fs.add_dir("/home/ana");
+        let mut ctx = shell(fs);
+        ctx.set_var("HOME", "/home/redstone");
+
+        assert_eq!(tilde(&ctx, "").as_deref(), Some("/home/redstone"));
+        assert_eq!(tilde(&ctx, "redstone").as_deref(), Some("/home/redstone"));
+        assert_eq!(tilde(&ctx, "ana").as_deref(), Some("/home/ana"));
+        assert_eq!(tilde(&ctx, "ninguem"), None);
+        assert_eq!(tilde(&ctx, "-"), None);
+
+        assert_eq!(abbreviate_home(&ctx, "/home/redstone/x"), "~/x");
+        assert_eq!(abbreviate_home(&ctx, "/home/redstone"), "~");
+        assert_eq!(abbreviate_home(&ctx, "/home/redstonex"), "/home/redstonex");
+    }
+}
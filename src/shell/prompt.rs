@@ -0,0 +1,114 @@
+//! # Prompt
+//!
+//! Expansão da variável `PS1`.
+//!
+//! ## Escapes
+//!
+//! | Escape      | Resultado                                        |
+//! |-------------|--------------------------------------------------|
+//! | `\u`        | Nome do usuário                                  |
+//! | `\h`, `\H`  | Hostname (até o primeiro `.` / completo)         |
+//! | `\w`, `\W`  | Diretório atual (completo / só o último nome)    |
+//! | `\?`        | Código de saída do último comando                |
+//! | `\t`, `\A`  | Hora `HH:MM:SS` / `HH:MM`                        |
+//! | `\$`        | `#` para root, `$` para os demais                |
+//! | `\e`, `\033`| Caractere ESC (para cores SGR)                   |
+//! | `\n`, `\\`  | Quebra de linha / barra invertida                |
+//! | `\[`, `\]`  | Delimitam trechos invisíveis (aceitos e ignorados) |
+//!
+//! Exemplo: `PS1='\e[32m\u@\h\e[0m:\e[34m\w\e[0m\$ '`
+
+use super::path;
+use super::ShellContext;
+use alloc::string::String;
+
+/// Prompt usado quando `PS1` não está definido
+pub const DEFAULT_PS1: &str = "\\[\\e[32m\\]\\u@\\h\\[\\e[0m\\]:\\[\\e[34m\\]\\w\\[\\e[0m\\]\\$ ";
+
+/// Expande os escapes de `ps1`
+pub fn render(ps1: &str, ctx: &ShellContext) -> String {
+    let mut out = String::new();
+    let mut chars = ps1.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+
+        let Some(esc) = chars.next() else {
+            out.push('\\');
+            break;
+        };
+
+        match esc {
+            'u' => out.push_str(&ctx.username),
+            'h' => out.push_str(ctx.hostname.split('.').next().unwrap_or("")),
+            'H' => out.push_str(&ctx.hostname),
+            'w' => out.push_str(&tilde_cwd(ctx)),
+            'W' => {
+                let cwd = tilde_cwd(ctx);
+                match cwd.rsplit('/').next() {
+                    Some(name) if !name.is_empty() => out.push_str(name),
+                    _ => out.push_str(&cwd),
+                }
+            }
+            '?' => push_number(&mut out, ctx.last_exit_code as i64, 1),
+            't' | 'A' => push_time(&mut out, esc == 't'),
+            '$' => out.push(if ctx.username == "root" { '#' } else { '$' }),
+            'e' => out.push('\x1b'),
+            'a' => out.push('\x07'),
+            'n' => out.push('\n'),
+            'r' => out.push('\r'),
+            '\\' => out.push('\\'),
+            '[' | ']' => {}
+            '0'..='7' => {
+                // Octal (\033)
+                let mut value = esc as u32 - '0' as u32;
+                for _ in 0..2 {
+                    match chars.peek() {
+                        Some(&d @ '0'..='7') => {
+                            value = value * 8 + (d as u32 - '0' as u32);
+                            chars.next();
+                        }
+                        _ => break,
+                    }
+                }
+                if let Some(c) = char::from_u32(value) {
+                    out.push(c);
+                }
+            }
+            other => {
+                out.push('\\');
+                out.push(other);
+            }
+        }
+    }
+
+    out
+}
+
+/// Diretório atual com `$HOME` abreviado para `~`
+fn tilde_cwd(ctx: &ShellContext) -> String {
+    path::abbreviate_home(ctx, &ctx.cwd)
+}
+
+/// Hora atual (tempo desde o boot, enquanto não há relógio de parede)
+fn push_time(out: &mut String, with_seconds: bool) {
+    let ms = redpowder::time::clock().unwrap_or(0);
+    let total_secs = ms / 1000;
+
+    push_number(out, ((total_secs / 3600) % 24) as i64, 2);
+    out.push(':');
+    push_number(out, ((total_secs % 3600) / 60) as i64, 2);
+    if with_seconds {
+        out.push(':');
+        push_number(out, (total_secs % 60) as i64, 2);
+    }
+}
+
+/// Escreve número com pelo menos `width` dígitos
+fn push_number(out: &mut String, n: i64, width: usize) {
+    use core::fmt::Write;
+    let _ = write!(out, "{:0width$}", n, width = width);
+}
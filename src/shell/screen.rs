@@ -0,0 +1,74 @@
+//! # Full-Screen Programs
+//!
+//! Programas internos que ocupam a janela inteira (`less`, `edit`).
+//!
+//! O comando devolve um [`Screen`] em `CommandResult::Screen`. A janela
+//! troca para a tela alternativa do [`TerminalState`], desenha o programa e
+//! passa a entregar todas as teclas a [`Screen::key`] até ele terminar;
+//! então volta à tela principal e mostra o prompt.
+
+use super::ShellContext;
+use crate::state::terminal::TerminalState;
+use alloc::format;
+use alloc::string::String;
+
+/// Tecla entregue ao programa pela janela
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Char(char),
+    /// Ctrl+letra (sempre minúscula)
+    Ctrl(char),
+    Enter,
+    Backspace,
+    Delete,
+    Escape,
+    Up,
+    Down,
+    Left,
+    Right,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+}
+
+/// Programa em tela cheia
+pub trait Screen {
+    /// Desenha a tela inteira
+    fn render(&self, output: &mut TerminalState);
+
+    /// Trata uma tecla e redesenha; `false` quando o programa terminou
+    fn key(&mut self, key: Key, ctx: &mut ShellContext, output: &mut TerminalState) -> bool;
+}
+
+/// Passa para a tela alternativa e desenha o programa
+pub fn enter(screen: &dyn Screen, output: &mut TerminalState) {
+    output.write_str("\x1b[?1049h\x1b[?25l");
+    screen.render(output);
+}
+
+/// Volta à tela principal, como estava antes do programa
+pub fn leave(output: &mut TerminalState) {
+    output.write_str("\x1b[0m\x1b[?25h\x1b[?1049l");
+}
+
+/// Posiciona o cursor no início da linha `row` da tela
+pub fn move_to(output: &mut TerminalState, row: usize) {
+    output.write_str(&format!("\x1b[{};1H", row + 1));
+}
+
+/// Escreve uma barra em vídeo reverso na linha `row`.
+///
+/// A barra não ocupa a última coluna: na última linha da tela, isso faria
+/// a grade rolar.
+pub fn write_bar(output: &mut TerminalState, row: usize, text: &str) {
+    move_to(output, row);
+    output.write_str("\x1b[7m");
+    output.write_str(&fit(text, (output.cols as usize).saturating_sub(1)));
+    output.write_str("\x1b[0m");
+}
+
+/// Corta o texto em `width` caracteres
+pub fn fit(text: &str, width: usize) -> String {
+    text.chars().take(width).collect()
+}
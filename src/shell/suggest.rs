@@ -0,0 +1,174 @@
+//! # Suggestions
+//!
+//! Sugestões "voce quis dizer" por distância de edição.
+//!
+//! Usa a distância de Damerau-Levenshtein restrita: inserção, remoção,
+//! troca e transposição de vizinhos custam 1 cada, então `sl` fica a 1 de
+//! `ls` e `tre` a 1 de `tree`.
+
+use super::vfs::Vfs;
+use super::ShellContext;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Distância de edição entre duas palavras
+pub fn distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    // Três linhas da matriz: i-2, i-1 e i
+    let mut prev2: Vec<usize> = Vec::new();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+
+    for i in 1..=a.len() {
+        let mut cur = Vec::with_capacity(b.len() + 1);
+        cur.push(i);
+
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut d = (prev[j] + 1).min(cur[j - 1] + 1).min(prev[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d = d.min(prev2[j - 2] + 1);
+            }
+            cur.push(d);
+        }
+
+        prev2 = core::mem::replace(&mut prev, cur);
+    }
+
+    prev[b.len()]
+}
+
+/// Distância máxima aceita para uma palavra deste tamanho
+fn max_distance(len: usize) -> usize {
+    match len {
+        0..=2 => 1,
+        3..=5 => 2,
+        _ => 3,
+    }
+}
+
+/// Candidato mais próximo de `word`, se estiver perto o bastante.
+///
+/// Em caso de empate vence o primeiro da lista.
+pub fn closest<'a>(word: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let limit = max_distance(word.chars().count());
+    let mut best: Option<(usize, &str)> = None;
+
+    for candidate in candidates {
+        if candidate == word {
+            continue;
+        }
+        let d = distance(word, candidate);
+        if d <= limit && best.is_none_or(|(bd, _)| d < bd) {
+            best = Some((d, candidate));
+        }
+    }
+
+    best.map(|(_, c)| c)
+}
+
+/// Comando interno, alias ou executável do PATH mais próximo de `name`
+pub fn command(ctx: &ShellContext, name: &str) -> Option<String> {
+    // Executáveis do PATH
+    let mut external: Vec<String> = Vec::new();
+    let path_var = ctx.var("PATH").unwrap_or_default();
+    for dir in path_var.split(':').filter(|d| !d.is_empty()) {
+        if let Ok(entries) = ctx.fs.read_dir(dir) {
+            external.extend(entries.into_iter().filter(|e| !e.is_dir).map(|e| e.name));
+        }
+    }
+
+    // Builtins primeiro: vencem os empates
+    let candidates = ctx
+        .commands
+        .names()
+        .chain(external.iter().map(|s| s.as_str()));
+    closest(name, candidates).map(String::from)
+}
+
+/// Caminho existente mais próximo de `full_path`, trocando só o último
+/// componente por um irmão no diretório pai
+pub fn path(fs: &dyn Vfs, full_path: &str) -> Option<String> {
+    let slash = full_path.rfind('/')?;
+    let (parent, name) = (&full_path[..slash.max(1)], &full_path[slash + 1..]);
+    if name.is_empty() {
+        return None;
+    }
+
+    let entries: Vec<String> = fs
+        .read_dir(parent)
+        .ok()?
+        .into_iter()
+        .map(|e| e.name)
+        .collect();
+
+    let best = closest(name, entries.iter().map(|s| s.as_str()))?;
+    let mut suggestion = String::from(&full_path[..slash + 1]);
+    suggestion.push_str(best);
+    Some(suggestion)
+}
+
+// =============================================================================
+// TESTES
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{closest, command, distance, path};
+    use crate::shell::testing::shell;
+    use crate::shell::vfs::MemoryFs;
+
+    #[test]
+    fn distance_counts_edits_and_transpositions() {
+        assert_eq!(distance("", ""), 0);
+        assert_eq!(distance("", "abc"), 3);
+        assert_eq!(distance("sl", "ls"), 1);
+        assert_eq!(distance("tre", "tree"), 1);
+        assert_eq!(distance("kitten", "sitting"), 3);
+        assert_eq!(distance("ação", "acao"), 2);
+        // Restrita: uma transposição não é editada de novo
+        assert_eq!(distance("ca", "abc"), 3);
+    }
+
+    #[test]
+    fn closest_respects_the_limit_and_ties() {
+        let names = ["ls", "cat", "cut", "tree"];
+        assert_eq!(closest("sl", names), Some("ls"));
+        // Empate: vence o primeiro
+        assert_eq!(closest("cxt", names), Some("cat"));
+        // Palavras curtas aceitam só uma edição
+        assert_eq!(closest("xy", names), None);
+        assert_eq!(closest("ls", names), None);
+        assert_eq!(closest("treeeee", names), Some("tree"));
+        assert_eq!(closest("treeeeee", names), None);
+    }
+
+    #[test]
+    fn command_looks_at_builtins_then_path() {
+        let mut fs = MemoryFs::new();
+        fs.add_file("/apps/hello", b"")
+            .add_dir("/system/bin/hallway");
+        let ctx = shell(fs);
+
+        assert_eq!(command(&ctx, "hallo").as_deref(), Some("hello"));
+        // `help` e `hello` estão a 1 de `helo`; o builtin vence
+        assert_eq!(command(&ctx, "helo").as_deref(), Some("help"));
+        // Diretórios do PATH não são comandos
+        assert_eq!(command(&ctx, "hallwayy"), None);
+        assert_eq!(command(&ctx, "dri").as_deref(), Some("dir"));
+    }
+
+    #[test]
+    fn path_suggests_a_sibling() {
+        let mut fs = MemoryFs::new();
+        fs.add_file("/docs/relatorio.txt", b"").add_dir("/apps");
+        assert_eq!(
+            path(&fs, "/docs/relatoro.txt").as_deref(),
+            Some("/docs/relatorio.txt")
+        );
+        assert_eq!(path(&fs, "/aps").as_deref(), Some("/apps"));
+        assert_eq!(path(&fs, "/nada/relatorio.txt"), None);
+        assert_eq!(path(&fs, "/docs/"), None);
+    }
+}
@@ -0,0 +1,298 @@
+//! # Structured Data
+//!
+//! Tabelas que circulam entre comandos num pipeline.
+//!
+//! Quando o próximo estágio de um pipeline consome tabelas (`where`,
+//! `sort-by`, `select`, ...), `ls`, `ps` e `stat` entregam registros em vez
+//! de texto:
+//!
+//! ```text
+//! ls /apps | where size > 1000 | sort-by size -r | select name size
+//! ```
+//!
+//! No fim do pipeline a tabela é desenhada com colunas alinhadas, ajustadas
+//! à largura do terminal. Um estágio de texto recebe a tabela já desenhada;
+//! um estágio de tabelas que recebe texto vê uma coluna `line` por linha.
+
+use super::json::JsonWriter;
+use super::ShellContext;
+use crate::state::terminal::TerminalState;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+/// Espaço entre colunas
+const COLUMN_GAP: usize = 2;
+
+/// Largura mínima de uma coluna encolhida
+const MIN_COLUMN_WIDTH: usize = 4;
+
+/// Valor de uma célula
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    /// Texto
+    Text(String),
+    /// Inteiro
+    Int(i64),
+    /// Ausente (`null` em JSON)
+    Nothing,
+}
+
+impl Value {
+    /// Interpreta um argumento: inteiro se possível, senão texto
+    pub fn parse(s: &str) -> Self {
+        match s.parse::<i64>() {
+            Ok(n) => Value::Int(n),
+            Err(_) => Value::Text(String::from(s)),
+        }
+    }
+
+    /// Texto exibido na célula
+    pub fn to_text(&self) -> String {
+        match self {
+            Value::Text(s) => s.clone(),
+            Value::Int(n) => n.to_string(),
+            Value::Nothing => String::new(),
+        }
+    }
+
+    /// Ordem usada por `sort-by` e `where`: números entre si, o resto como
+    /// texto; valores ausentes ficam no fim
+    pub fn compare(&self, other: &Value) -> Ordering {
+        match (self, other) {
+            (Value::Int(a), Value::Int(b)) => a.cmp(b),
+            (Value::Nothing, Value::Nothing) => Ordering::Equal,
+            (Value::Nothing, _) => Ordering::Greater,
+            (_, Value::Nothing) => Ordering::Less,
+            (a, b) => a.to_text().cmp(&b.to_text()),
+        }
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::Text(String::from(s))
+    }
+}
+
+impl From<u64> for Value {
+    fn from(n: u64) -> Self {
+        Value::Int(n as i64)
+    }
+}
+
+/// Tabela: colunas nomeadas e linhas de valores
+#[derive(Debug, Clone, Default)]
+pub struct Table {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+}
+
+impl Table {
+    /// Tabela vazia com as colunas dadas
+    pub fn new(columns: &[&str]) -> Self {
+        Self {
+            columns: columns.iter().map(|c| String::from(*c)).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Uma linha de texto por registro, na coluna `line`
+    pub fn from_text(text: &str) -> Self {
+        let mut table = Self::new(&["line"]);
+        for line in text.lines() {
+            table.push(alloc::vec![Value::from(line)]);
+        }
+        table
+    }
+
+    /// Adiciona uma linha (completada com `Nothing` se faltar coluna)
+    pub fn push(&mut self, mut row: Vec<Value>) {
+        row.resize(self.columns.len(), Value::Nothing);
+        self.rows.push(row);
+    }
+
+    /// Índice da coluna
+    pub fn column(&self, name: &str) -> Option<usize> {
+        self.columns.iter().position(|c| c == name)
+    }
+
+    /// Desenha a tabela na saída, na largura do terminal
+    pub fn render(&self, output: &mut TerminalState) {
+        for line in self.format(output.cols as usize) {
+            output.write_line(&line);
+        }
+    }
+
+    /// Linhas da tabela com colunas alinhadas em até `width` colunas.
+    ///
+    /// Se não couber, as colunas mais largas são encolhidas e o texto
+    /// cortado com `~`. Números ficam alinhados à direita.
+    pub fn format(&self, width: usize) -> Vec<String> {
+        if self.columns.is_empty() {
+            return Vec::new();
+        }
+
+        let texts: Vec<Vec<String>> = self
+            .rows
+            .iter()
+            .map(|row| row.iter().map(|v| v.to_text()).collect())
+            .collect();
+
+        let mut widths: Vec<usize> = self.columns.iter().map(|c| c.chars().count()).collect();
+        for row in &texts {
+            for (w, cell) in widths.iter_mut().zip(row) {
+                *w = (*w).max(cell.chars().count());
+            }
+        }
+
+        // Encolher a coluna mais larga até caber
+        let gaps = COLUMN_GAP * (widths.len() - 1);
+        while widths.iter().sum::<usize>() + gaps > width {
+            let (widest, w) = widths
+                .iter()
+                .copied()
+                .enumerate()
+                .max_by_key(|(_, w)| *w)
+                .unwrap_or((0, 0));
+            if w <= MIN_COLUMN_WIDTH {
+                break;
+            }
+            widths[widest] -= 1;
+        }
+
+        let mut lines = Vec::with_capacity(self.rows.len() + 2);
+        let header: Vec<&str> = self.columns.iter().map(|c| c.as_str()).collect();
+        lines.push(format_row(&header, &widths, &[]));
+        let rule: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+        let rule: Vec<&str> = rule.iter().map(|r| r.as_str()).collect();
+        lines.push(format_row(&rule, &widths, &[]));
+
+        for (row, text) in self.rows.iter().zip(&texts) {
+            let cells: Vec<&str> = text.iter().map(|t| t.as_str()).collect();
+            let numeric: Vec<bool> = row.iter().map(|v| matches!(v, Value::Int(_))).collect();
+            lines.push(format_row(&cells, &widths, &numeric));
+        }
+        lines
+    }
+
+    /// Array de objetos, um por linha
+    pub fn to_json(&self) -> String {
+        let mut json = JsonWriter::new();
+        json.begin_array();
+        for row in &self.rows {
+            json.begin_object();
+            for (column, value) in self.columns.iter().zip(row) {
+                json.key(column);
+                match value {
+                    Value::Text(s) => json.string(s),
+                    Value::Int(n) => json.number(*n),
+                    Value::Nothing => json.null(),
+                }
+            }
+            json.end_object();
+        }
+        json.end_array();
+        json.finish()
+    }
+
+    /// CSV com cabeçalho; campos com `,`, `"` ou quebra de linha vão entre aspas
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::new();
+        let header: Vec<String> = self.columns.iter().map(|c| csv_field(c)).collect();
+        csv.push_str(&header.join(","));
+        csv.push('\n');
+        for row in &self.rows {
+            let fields: Vec<String> = row.iter().map(|v| csv_field(&v.to_text())).collect();
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+/// Uma linha da tabela, cortando e alinhando cada célula
+fn format_row(cells: &[&str], widths: &[usize], numeric: &[bool]) -> String {
+    let mut line = String::new();
+    for (i, (cell, width)) in cells.iter().zip(widths).enumerate() {
+        if i > 0 {
+            line.push_str(&" ".repeat(COLUMN_GAP));
+        }
+
+        let len = cell.chars().count();
+        let text: String = if len > *width {
+            let mut cut: String = cell.chars().take(width - 1).collect();
+            cut.push('~');
+            cut
+        } else {
+            String::from(*cell)
+        };
+        let pad = " ".repeat(width - text.chars().count());
+
+        if numeric.get(i).copied().unwrap_or(false) {
+            line.push_str(&pad);
+            line.push_str(&text);
+        } else if i + 1 < cells.len() {
+            line.push_str(&text);
+            line.push_str(&pad);
+        } else {
+            // Última coluna sem espaços à direita
+            line.push_str(&text);
+        }
+    }
+    line
+}
+
+fn csv_field(text: &str) -> String {
+    if !text.contains([',', '"', '\n']) {
+        return String::from(text);
+    }
+    let mut field = String::from("\"");
+    field.push_str(&text.replace('"', "\"\""));
+    field.push('"');
+    field
+}
+
+/// Dados recebidos do estágio anterior do pipeline
+#[derive(Debug, Clone)]
+pub enum PipeData {
+    /// Saída de texto
+    Text(String),
+    /// Registros
+    Table(Table),
+}
+
+impl PipeData {
+    /// Como texto: tabelas são desenhadas sem limite de largura
+    pub fn into_text(self) -> String {
+        match self {
+            PipeData::Text(text) => text,
+            PipeData::Table(table) => {
+                let mut text = String::new();
+                for line in table.format(usize::MAX) {
+                    text.push_str(&line);
+                    text.push('\n');
+                }
+                text
+            }
+        }
+    }
+
+    /// Como tabela: texto vira uma coluna `line`
+    pub fn into_table(self) -> Table {
+        match self {
+            PipeData::Text(text) => Table::from_text(&text),
+            PipeData::Table(table) => table,
+        }
+    }
+}
+
+/// Entrega a tabela produzida por um comando: para o próximo estágio, se
+/// ele consome tabelas, ou desenhada na saída
+pub fn emit(ctx: &mut ShellContext, output: &mut TerminalState, table: Table) {
+    if ctx.pipe_table {
+        ctx.table_out = Some(table);
+    } else {
+        table.render(output);
+    }
+}
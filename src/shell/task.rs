@@ -0,0 +1,126 @@
+//! # Tasks
+//!
+//! Comandos longos (`tree /`, `cat` de arquivo grande) como tarefas
+//! retomáveis.
+//!
+//! Em vez de rodar até o fim dentro de `handle_enter`, o comando devolve uma
+//! [`Task`] que a janela avança a cada frame por uma fatia de tempo. Entre
+//! as fatias, o loop principal continua tratando eventos e redesenhando, e
+//! ^C descarta a tarefa.
+//!
+//! Uma tarefa pode pausar com uma pergunta (`rm -i`): ela escreve a
+//! pergunta, retorna [`TaskStatus::Ask`] e recebe a resposta em
+//! [`Task::answer`] antes do próximo passo. Da mesma forma, uma tarefa que
+//! lê do teclado (`write`) retorna [`TaskStatus::Input`] e recebe a linha
+//! digitada em [`Task::input`]. Antes de ser descartada por ^C, a tarefa
+//! recebe [`Task::cancel`] para remover arquivos temporários.
+//!
+//! Uma linha de comando que esperou por uma tarefa (um `$(prog)`, um
+//! estágio de pipeline) só sabe o resultado final ao terminar: a janela o
+//! pede em [`Task::finish`].
+
+use super::commands::CommandResult;
+use super::ShellContext;
+use crate::state::terminal::TerminalState;
+use alloc::boxed::Box;
+
+/// Tempo máximo de uma fatia de execução por frame (ms)
+pub const FRAME_BUDGET_MS: u64 = 8;
+
+/// Situação da tarefa após um passo
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskStatus {
+    /// Ainda há trabalho
+    Pending,
+    /// Escreveu uma pergunta `[s/N]` e espera a resposta
+    Ask,
+    /// Espera uma linha do teclado
+    Input,
+    /// Terminou
+    Done,
+}
+
+/// Comando executado aos poucos.
+///
+/// Cada `step` deve fazer pouco trabalho (uma linha, um bloco lido) e
+/// retornar; o estado para continuar fica na própria tarefa.
+pub trait Task {
+    /// Avança a tarefa, escrevendo sua saída em `output`
+    fn step(&mut self, ctx: &mut ShellContext, output: &mut TerminalState) -> TaskStatus;
+
+    /// Resposta à pergunta feita com [`TaskStatus::Ask`]
+    fn answer(&mut self, _yes: bool) {}
+
+    /// Texto digitado para [`TaskStatus::Input`] (a linha com `\n`, ou o
+    /// que havia antes de um ^D); `None` no fim da entrada
+    fn input(&mut self, _text: Option<&str>) {}
+
+    /// A tarefa foi interrompida com ^C; desfaz o que ficaria pela metade
+    fn cancel(&mut self, _ctx: &mut ShellContext) {}
+
+    /// Resultado depois de [`TaskStatus::Done`], tratado como o de um
+    /// comando (um programa para ligar ao PTY, um erro, sair)
+    fn finish(&mut self) -> CommandResult {
+        CommandResult::Ok
+    }
+}
+
+/// Executa passos até a tarefa terminar ou a fatia de tempo acabar
+pub fn run_for(
+    task: &mut dyn Task,
+    ctx: &mut ShellContext,
+    output: &mut TerminalState,
+    budget_ms: u64,
+) -> TaskStatus {
+    let start = redpowder::time::clock().unwrap_or(0);
+
+    loop {
+        match task.step(ctx, output) {
+            TaskStatus::Pending => {}
+            status => return status,
+        }
+
+        // Sem relógio, um passo por frame
+        match redpowder::time::clock() {
+            Ok(now) if now.saturating_sub(start) < budget_ms => {}
+            _ => return TaskStatus::Pending,
+        }
+    }
+}
+
+/// Executa a tarefa até o fim, sem devolver o controle à janela (usado
+/// por `find -exec`, que precisa do código de saída na hora).
+///
+/// Sem teclado, perguntas são respondidas com "não" e a leitura do teclado
+/// termina na hora.
+pub fn run_to_end(
+    mut task: Box<dyn Task>,
+    ctx: &mut ShellContext,
+    output: &mut TerminalState,
+) -> CommandResult {
+    while !step_unattended(task.as_mut(), ctx, output) {}
+    task.finish()
+}
+
+/// Um passo de uma tarefa sem teclado, como em [`run_to_end`].
+///
+/// Retorna `true` quando ela termina.
+pub fn step_unattended(
+    task: &mut dyn Task,
+    ctx: &mut ShellContext,
+    output: &mut TerminalState,
+) -> bool {
+    match task.step(ctx, output) {
+        TaskStatus::Pending => false,
+        TaskStatus::Ask => {
+            output.write_line("n");
+            task.answer(false);
+            false
+        }
+        TaskStatus::Input => {
+            task.input(None);
+            false
+        }
+        TaskStatus::Done => true,
+    }
+}
@@ -0,0 +1,217 @@
+//! # Testing
+//!
+//! Apoio aos testes dos comandos: um shell sobre um [`MemoryFs`], um
+//! lançador de processos falso e a execução de uma linha com a saída
+//! capturada.
+
+use super::commands::CommandResult;
+use super::launcher::{Pid, ProcessLauncher};
+use super::task;
+use super::vfs::MemoryFs;
+use super::{execute_command, ShellContext};
+use crate::pty::Signal;
+use crate::state::terminal::TerminalState;
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use redpowder::SysError;
+
+/// Shell com o sistema de arquivos dado, no diretório `/`
+pub(crate) fn shell(fs: MemoryFs) -> ShellContext {
+    let mut ctx = ShellContext::new();
+    ctx.fs = Box::new(fs);
+    ctx
+}
+
+/// Executa a linha até o fim e devolve o que foi escrito.
+///
+/// Tarefas rodam como numa captura: perguntas recebem "não".
+pub(crate) fn run(ctx: &mut ShellContext, line: &str) -> String {
+    let mut output = TerminalState::new(80, 24);
+    output.begin_capture();
+    if let CommandResult::Task(task) = execute_command(line, ctx, &mut output) {
+        task::run_to_end(task, ctx, &mut output);
+    }
+    output.end_capture()
+}
+
+/// Conteúdo de um arquivo do shell, como texto
+pub(crate) fn contents(ctx: &ShellContext, path: &str) -> Option<String> {
+    String::from_utf8(bytes(ctx, path)?).ok()
+}
+
+/// Conteúdo de um arquivo do shell
+pub(crate) fn bytes(ctx: &ShellContext, path: &str) -> Option<Vec<u8>> {
+    let mut file = ctx.fs.open(path).ok()?;
+    let mut data = Vec::new();
+    let mut buf = [0u8; 256];
+    loop {
+        match file.read(&mut buf).ok()? {
+            0 => break,
+            n => data.extend_from_slice(&buf[..n]),
+        }
+    }
+    Some(data)
+}
+
+// =============================================================================
+// LANÇADOR FALSO
+// =============================================================================
+
+/// Programa conhecido pelo [`FakeLauncher`]
+struct Program {
+    /// Saída escrita de uma vez
+    stdout: &'static str,
+    exit_code: i32,
+    /// Copia o stdin para a saída e só termina quando ele é fechado
+    filter: bool,
+    /// Consultas a `try_wait` que ainda o encontram rodando
+    polls: usize,
+}
+
+/// Processo iniciado
+struct Process {
+    stdout: Vec<u8>,
+    exit_code: i32,
+    filter: bool,
+    polls: usize,
+    stdin_closed: bool,
+}
+
+/// Lançador em memória: cada programa escreve um texto fixo e termina na
+/// hora (ou depois de algumas consultas, ver [`FakeLauncher::slow`]), ou
+/// funciona como `cat` (ver [`FakeLauncher::filter`]).
+///
+/// As chamadas a `spawn` ficam em `spawned` (caminho e argumentos), que o
+/// teste guarda antes de passar o lançador ao shell.
+#[derive(Default)]
+pub(crate) struct FakeLauncher {
+    programs: BTreeMap<String, Program>,
+    processes: BTreeMap<Pid, Process>,
+    next_pid: Pid,
+    /// Bytes aceitos por escrita no stdin (0: sem limite)
+    stdin_chunk: usize,
+    /// A escrita anterior foi aceita; a próxima encontra o pipe cheio
+    stdin_full: bool,
+    pub(crate) spawned: Rc<RefCell<Vec<Vec<String>>>>,
+}
+
+impl FakeLauncher {
+    /// Registra um executável em `path`
+    pub(crate) fn program(self, path: &str, stdout: &'static str, exit_code: i32) -> Self {
+        self.slow(path, stdout, exit_code, 0)
+    }
+
+    /// Registra um executável que continua rodando nas primeiras `polls`
+    /// consultas a `try_wait`
+    pub(crate) fn slow(
+        mut self,
+        path: &str,
+        stdout: &'static str,
+        exit_code: i32,
+        polls: usize,
+    ) -> Self {
+        let program = Program {
+            stdout,
+            exit_code,
+            filter: false,
+            polls,
+        };
+        self.programs.insert(String::from(path), program);
+        self
+    }
+
+    /// Registra um filtro em `path`: ecoa o stdin e termina no fim dele
+    pub(crate) fn filter(mut self, path: &str) -> Self {
+        let program = Program {
+            stdout: "",
+            exit_code: 0,
+            filter: true,
+            polls: 0,
+        };
+        self.programs.insert(String::from(path), program);
+        self
+    }
+
+    /// Pipe pequeno: cada escrita aceita até `n` bytes e a seguinte, nada
+    pub(crate) fn stdin_chunk(mut self, n: usize) -> Self {
+        self.stdin_chunk = n;
+        self
+    }
+}
+
+impl ProcessLauncher for FakeLauncher {
+    fn is_executable(&self, path: &str) -> bool {
+        self.programs.contains_key(path)
+    }
+
+    fn spawn(&mut self, path: &str, argv: &[&str], _envp: &[String]) -> Result<Pid, SysError> {
+        let program = self.programs.get(path).ok_or(SysError::NotFound)?;
+        let mut call = Vec::from([String::from(path)]);
+        call.extend(argv.iter().map(|a| String::from(*a)));
+        self.spawned.borrow_mut().push(call);
+
+        self.next_pid += 1;
+        let process = Process {
+            stdout: Vec::from(program.stdout.as_bytes()),
+            exit_code: program.exit_code,
+            filter: program.filter,
+            polls: program.polls,
+            stdin_closed: false,
+        };
+        self.processes.insert(self.next_pid, process);
+        Ok(self.next_pid)
+    }
+
+    fn try_wait(&mut self, pid: Pid) -> Result<Option<i32>, SysError> {
+        let process = self.processes.get_mut(&pid).ok_or(SysError::NotFound)?;
+        if process.polls > 0 {
+            process.polls -= 1;
+            return Ok(None);
+        }
+        let running = process.filter && !process.stdin_closed;
+        Ok((!running).then_some(process.exit_code))
+    }
+
+    fn write_stdin(&mut self, pid: Pid, data: &[u8]) -> Result<usize, SysError> {
+        let process = self.processes.get_mut(&pid).ok_or(SysError::NotFound)?;
+        if process.stdin_closed {
+            return Err(SysError::IoError);
+        }
+
+        let n = match self.stdin_chunk {
+            0 => data.len(),
+            _ if self.stdin_full => 0,
+            chunk => data.len().min(chunk),
+        };
+        self.stdin_full = self.stdin_chunk != 0 && n > 0;
+        if process.filter {
+            process.stdout.extend_from_slice(&data[..n]);
+        }
+        Ok(n)
+    }
+
+    fn close_stdin(&mut self, pid: Pid) -> Result<(), SysError> {
+        let process = self.processes.get_mut(&pid).ok_or(SysError::NotFound)?;
+        process.stdin_closed = true;
+        Ok(())
+    }
+
+    fn read_stdout(&mut self, pid: Pid, buf: &mut [u8]) -> Result<usize, SysError> {
+        let process = self.processes.get_mut(&pid).ok_or(SysError::NotFound)?;
+        let n = buf.len().min(process.stdout.len());
+        buf[..n].copy_from_slice(&process.stdout[..n]);
+        process.stdout.drain(..n);
+        Ok(n)
+    }
+
+    fn signal(&mut self, pid: Pid, _signal: Signal) -> Result<(), SysError> {
+        self.processes
+            .remove(&pid)
+            .map(|_| ())
+            .ok_or(SysError::NotFound)
+    }
+}
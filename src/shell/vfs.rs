@@ -0,0 +1,811 @@
+//! # Virtual Filesystem
+//!
+//! Acesso a arquivos usado pelos comandos do shell.
+//!
+//! Assim como os processos passam por [`ProcessLauncher`], os comandos de
+//! arquivo não chamam `redpowder::fs` diretamente: tudo passa pelo trait
+//! [`Vfs`] guardado no `ShellContext`. [`RedpowderFs`] é a implementação
+//! real; [`MemoryFs`] guarda uma árvore em memória, para exercitar `ls`,
+//! `cat`, `tree`, `stat` e os comandos de escrita fora do RedstoneOS.
+//!
+//! [`ProcessLauncher`]: super::launcher::ProcessLauncher
+
+use super::path::{self, components, parent};
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use redpowder::SysError;
+
+/// Tipo de entrada, como em `stat`
+pub mod file_type {
+    /// Arquivo regular
+    pub const FILE: u8 = 1;
+    /// Diretório
+    pub const DIR: u8 = 2;
+    /// Link simbólico
+    pub const SYMLINK: u8 = 3;
+}
+
+/// Informações de uma entrada (`stat`; links não são seguidos)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Metadata {
+    /// Tipo (ver [`file_type`])
+    pub file_type: u8,
+    /// Tamanho em bytes
+    pub size: u64,
+    /// Permissões (`0o755`); 0 se o sistema de arquivos não registra
+    pub mode: u32,
+    /// Data de modificação
+    pub mtime: u64,
+}
+
+impl Metadata {
+    pub fn is_dir(&self) -> bool {
+        self.file_type == file_type::DIR
+    }
+
+    pub fn is_symlink(&self) -> bool {
+        self.file_type == file_type::SYMLINK
+    }
+}
+
+/// Entrada de diretório
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirEntry {
+    pub name: String,
+    pub is_dir: bool,
+}
+
+/// Arquivo aberto
+pub trait VfsFile {
+    /// Lê até `buf.len()` bytes; 0 no fim do arquivo
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, SysError>;
+
+    /// Escreve bytes, retornando quantos foram aceitos
+    fn write(&mut self, buf: &[u8]) -> Result<usize, SysError>;
+}
+
+/// Sistema de arquivos visto pelo shell.
+///
+/// Caminhos são sempre absolutos e normalizados.
+pub trait Vfs {
+    /// Informações da entrada
+    fn stat(&self, path: &str) -> Result<Metadata, SysError>;
+
+    /// Entradas do diretório, sem `.` e `..`
+    fn read_dir(&self, path: &str) -> Result<Vec<DirEntry>, SysError>;
+
+    /// Destino de um link simbólico
+    fn read_link(&self, path: &str) -> Result<String, SysError>;
+
+    /// Abre um arquivo para leitura
+    fn open(&self, path: &str) -> Result<Box<dyn VfsFile>, SysError>;
+
+    /// Cria (ou trunca) um arquivo para escrita
+    fn create(&mut self, path: &str) -> Result<Box<dyn VfsFile>, SysError>;
+
+    /// Cria um diretório (o pai deve existir)
+    fn mkdir(&mut self, path: &str) -> Result<(), SysError>;
+
+    /// Remove um diretório vazio
+    fn rmdir(&mut self, path: &str) -> Result<(), SysError>;
+
+    /// Remove um arquivo ou link
+    fn remove(&mut self, path: &str) -> Result<(), SysError>;
+
+    /// Cria em `path` um link simbólico para `target`
+    fn symlink(&mut self, target: &str, path: &str) -> Result<(), SysError>;
+
+    /// Renomeia ou move uma entrada
+    fn rename(&mut self, from: &str, to: &str) -> Result<(), SysError>;
+
+    /// Altera as permissões
+    fn set_mode(&mut self, path: &str, mode: u32) -> Result<(), SysError>;
+
+    /// Altera a data de modificação
+    fn set_mtime(&mut self, path: &str, mtime: u64) -> Result<(), SysError>;
+
+    /// Marca a entrada como modificada agora (`touch`)
+    fn touch(&mut self, path: &str) -> Result<(), SysError>;
+
+    /// Diretório atual do processo
+    fn current_dir(&self) -> Result<String, SysError>;
+
+    /// Muda o diretório atual do processo
+    fn set_current_dir(&mut self, path: &str) -> Result<(), SysError>;
+
+    /// A entrada existe
+    fn exists(&self, path: &str) -> bool {
+        self.stat(path).is_ok()
+    }
+
+    /// Informações do destino, seguindo links
+    fn metadata(&self, path: &str) -> Result<Metadata, SysError> {
+        let mut current = String::from(path);
+        for _ in 0..=path::MAX_LINK_HOPS {
+            let meta = self.stat(&current)?;
+            if !meta.is_symlink() {
+                return Ok(meta);
+            }
+            let target = self.read_link(&current)?;
+            current = path::resolve(path::parent(&current), &target);
+        }
+        Err(SysError::IoError)
+    }
+
+    /// É um diretório (ou link para um)
+    fn is_dir(&self, path: &str) -> bool {
+        self.metadata(path).is_ok_and(|m| m.is_dir())
+    }
+}
+
+/// Escreve todo o buffer, repetindo escritas parciais
+pub fn write_all(file: &mut dyn VfsFile, mut data: &[u8]) -> Result<(), SysError> {
+    while !data.is_empty() {
+        match file.write(data)? {
+            0 => return Err(SysError::IoError),
+            n => data = &data[n..],
+        }
+    }
+    Ok(())
+}
+
+/// Leitura de bytes em memória, como a entrada de um pipeline
+#[derive(Debug, Clone, Default)]
+pub struct ByteReader {
+    data: Vec<u8>,
+    pos: usize,
+}
+
+impl ByteReader {
+    pub fn new(data: Vec<u8>) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    /// Total de bytes
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+}
+
+impl VfsFile for ByteReader {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, SysError> {
+        let n = buf.len().min(self.data.len() - self.pos);
+        buf[..n].copy_from_slice(&self.data[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+
+    fn write(&mut self, _buf: &[u8]) -> Result<usize, SysError> {
+        Err(SysError::PermissionDenied)
+    }
+}
+
+// =============================================================================
+// REDPOWDER
+// =============================================================================
+
+/// Sistema de arquivos real, via `redpowder::fs`
+#[derive(Debug, Default)]
+pub struct RedpowderFs;
+
+impl VfsFile for redpowder::fs::File {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, SysError> {
+        redpowder::fs::File::read(self, buf)
+    }
+
+    fn write(&mut self, buf: &[u8]) -> Result<usize, SysError> {
+        redpowder::fs::File::write(self, buf)
+    }
+}
+
+impl Vfs for RedpowderFs {
+    fn stat(&self, path: &str) -> Result<Metadata, SysError> {
+        let info = redpowder::fs::stat(path)?;
+        Ok(Metadata {
+            file_type: info.file_type,
+            size: info.size,
+            mode: info.mode as u32,
+            mtime: info.mtime,
+        })
+    }
+
+    fn read_dir(&self, path: &str) -> Result<Vec<DirEntry>, SysError> {
+        let dir = redpowder::fs::Dir::open(path)?;
+        Ok(dir
+            .entries()
+            .filter(|e| e.name() != "." && e.name() != "..")
+            .map(|e| DirEntry {
+                name: String::from(e.name()),
+                is_dir: e.is_dir(),
+            })
+            .collect())
+    }
+
+    fn read_link(&self, path: &str) -> Result<String, SysError> {
+        let mut buf = [0u8; 256];
+        redpowder::fs::readlink(path, &mut buf).map(String::from)
+    }
+
+    fn open(&self, path: &str) -> Result<Box<dyn VfsFile>, SysError> {
+        Ok(Box::new(redpowder::fs::File::open(path)?))
+    }
+
+    fn create(&mut self, path: &str) -> Result<Box<dyn VfsFile>, SysError> {
+        Ok(Box::new(redpowder::fs::File::create(path)?))
+    }
+
+    fn mkdir(&mut self, path: &str) -> Result<(), SysError> {
+        redpowder::fs::mkdir(path)
+    }
+
+    fn rmdir(&mut self, path: &str) -> Result<(), SysError> {
+        redpowder::fs::rmdir(path)
+    }
+
+    fn remove(&mut self, path: &str) -> Result<(), SysError> {
+        redpowder::fs::unlink(path)
+    }
+
+    fn symlink(&mut self, _target: &str, _path: &str) -> Result<(), SysError> {
+        // `redpowder::fs` ainda não cria links
+        Err(SysError::NotImplemented)
+    }
+
+    fn rename(&mut self, from: &str, to: &str) -> Result<(), SysError> {
+        redpowder::fs::rename(from, to)
+    }
+
+    fn set_mode(&mut self, path: &str, mode: u32) -> Result<(), SysError> {
+        redpowder::fs::chmod(path, mode)
+    }
+
+    fn set_mtime(&mut self, path: &str, mtime: u64) -> Result<(), SysError> {
+        redpowder::fs::utime(path, mtime)
+    }
+
+    fn touch(&mut self, path: &str) -> Result<(), SysError> {
+        // Ainda não há relógio de parede: o tempo vem do relógio do sistema
+        redpowder::fs::utime(path, redpowder::time::clock()?)
+    }
+
+    fn current_dir(&self) -> Result<String, SysError> {
+        let mut buf = [0u8; 256];
+        redpowder::fs::getcwd(&mut buf).map(String::from)
+    }
+
+    fn set_current_dir(&mut self, path: &str) -> Result<(), SysError> {
+        redpowder::fs::chdir(path)
+    }
+}
+
+// =============================================================================
+// MEMÓRIA
+// =============================================================================
+
+/// Conteúdo de um nó do [`MemoryFs`]
+#[derive(Debug)]
+enum NodeKind {
+    /// Compartilhado com os arquivos abertos
+    File(Rc<RefCell<Vec<u8>>>),
+    Dir,
+    Symlink(String),
+}
+
+#[derive(Debug)]
+struct Node {
+    kind: NodeKind,
+    mode: u32,
+    mtime: u64,
+}
+
+impl Node {
+    fn file_type(&self) -> u8 {
+        match self.kind {
+            NodeKind::File(_) => file_type::FILE,
+            NodeKind::Dir => file_type::DIR,
+            NodeKind::Symlink(_) => file_type::SYMLINK,
+        }
+    }
+}
+
+/// Sistema de arquivos em memória.
+///
+/// Os nós ficam num mapa indexado pelo caminho absoluto. Links simbólicos
+/// são seguidos em todos os componentes, menos no último em `stat`,
+/// `read_link`, `remove` e `rename`. `mtime` vem de um relógio lógico que
+/// avança a cada alteração. Com [`MemoryFs::read_only`], toda alteração
+/// falha com `PermissionDenied`, como numa montagem somente leitura.
+///
+/// ```ignore
+/// let mut fs = MemoryFs::new();
+/// fs.add_file("/apps/config.txt", b"cor=azul\n")
+///     .add_symlink("/cfg", "/apps/config.txt");
+/// ctx.fs = Box::new(fs);
+/// ```
+#[derive(Debug)]
+pub struct MemoryFs {
+    nodes: BTreeMap<String, Node>,
+    cwd: String,
+    clock: u64,
+    read_only: bool,
+}
+
+impl Default for MemoryFs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemoryFs {
+    /// Árvore vazia, só com a raiz
+    pub fn new() -> Self {
+        let mut nodes = BTreeMap::new();
+        nodes.insert(
+            String::from("/"),
+            Node {
+                kind: NodeKind::Dir,
+                mode: 0o755,
+                mtime: 0,
+            },
+        );
+        Self {
+            nodes,
+            cwd: String::from("/"),
+            clock: 0,
+            read_only: false,
+        }
+    }
+
+    /// Passa a recusar alterações; os `add_*` continuam valendo
+    pub fn read_only(&mut self) -> &mut Self {
+        self.read_only = true;
+        self
+    }
+
+    /// Cria um diretório e os pais que faltarem
+    pub fn add_dir(&mut self, path: &str) -> &mut Self {
+        let mut current = String::new();
+        for comp in path.split('/').filter(|c| !c.is_empty()) {
+            current.push('/');
+            current.push_str(comp);
+            if !self.nodes.contains_key(&current) {
+                let _ = self.insert(&current, NodeKind::Dir, 0o755);
+            }
+        }
+        self
+    }
+
+    /// Cria (ou substitui) um arquivo, criando os diretórios pais
+    pub fn add_file(&mut self, path: &str, contents: &[u8]) -> &mut Self {
+        self.add_dir(parent(path));
+        self.nodes.remove(path);
+        let data = Rc::new(RefCell::new(Vec::from(contents)));
+        let _ = self.insert(path, NodeKind::File(data), 0o644);
+        self
+    }
+
+    /// Cria um link simbólico, criando os diretórios pais
+    pub fn add_symlink(&mut self, path: &str, target: &str) -> &mut Self {
+        self.add_dir(parent(path));
+        self.nodes.remove(path);
+        let _ = self.insert(path, NodeKind::Symlink(String::from(target)), 0o777);
+        self
+    }
+
+    /// Falha se a árvore é somente leitura
+    fn writable(&self) -> Result<(), SysError> {
+        match self.read_only {
+            true => Err(SysError::PermissionDenied),
+            false => Ok(()),
+        }
+    }
+
+    /// Conteúdo de um arquivo, seguindo links
+    pub fn contents(&self, path: &str) -> Option<Vec<u8>> {
+        match &self.node(path, true).ok()?.1.kind {
+            NodeKind::File(data) => Some(data.borrow().clone()),
+            _ => None,
+        }
+    }
+
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    /// Caminho sem links (o último só é seguido com `follow_last`).
+    ///
+    /// O último componente pode não existir: quem chama decide se isso é
+    /// erro.
+    fn resolve(&self, path: &str, follow_last: bool) -> Result<String, SysError> {
+        let mut pending: Vec<String> = components(path).rev().map(String::from).collect();
+        let mut current = String::from("/");
+        let mut hops = 0;
+
+        while let Some(comp) = pending.pop() {
+            if comp == ".." {
+                current = String::from(parent(&current));
+                continue;
+            }
+
+            let next = path::join(&current, &comp);
+            let Some(node) = self.nodes.get(&next) else {
+                return if pending.is_empty() {
+                    Ok(next)
+                } else {
+                    Err(SysError::NotFound)
+                };
+            };
+
+            match &node.kind {
+                NodeKind::Symlink(target) if follow_last || !pending.is_empty() => {
+                    hops += 1;
+                    if hops > path::MAX_LINK_HOPS {
+                        return Err(SysError::IoError);
+                    }
+                    if target.starts_with('/') {
+                        current = String::from("/");
+                    }
+                    pending.extend(components(target).rev().map(String::from));
+                }
+                NodeKind::Dir => current = next,
+                _ if !pending.is_empty() => return Err(SysError::NotDirectory),
+                _ => current = next,
+            }
+        }
+        Ok(current)
+    }
+
+    /// Nó existente no caminho
+    fn node(&self, path: &str, follow_last: bool) -> Result<(String, &Node), SysError> {
+        let real = self.resolve(path, follow_last)?;
+        match self.nodes.get(&real) {
+            Some(node) => Ok((real, node)),
+            None => Err(SysError::NotFound),
+        }
+    }
+
+    fn node_mut(&mut self, path: &str) -> Result<&mut Node, SysError> {
+        let real = self.resolve(path, true)?;
+        self.nodes.get_mut(&real).ok_or(SysError::NotFound)
+    }
+
+    /// Cria um nó novo; o pai precisa ser um diretório
+    fn insert(&mut self, path: &str, kind: NodeKind, mode: u32) -> Result<(), SysError> {
+        let real = self.resolve(path, false)?;
+        if self.nodes.contains_key(&real) {
+            return Err(SysError::IoError);
+        }
+        match self.nodes.get(parent(&real)) {
+            Some(node) if matches!(node.kind, NodeKind::Dir) => {}
+            Some(_) => return Err(SysError::NotDirectory),
+            None => return Err(SysError::NotFound),
+        }
+
+        let mtime = self.tick();
+        self.nodes.insert(real, Node { kind, mode, mtime });
+        Ok(())
+    }
+
+    /// Caminhos dos filhos diretos de um diretório
+    fn children<'a>(&'a self, dir: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.nodes
+            .keys()
+            .map(|k| k.as_str())
+            .filter(move |k| *k != "/" && *k != dir && parent(k) == dir)
+    }
+}
+
+/// Arquivo aberto do [`MemoryFs`]
+struct MemoryFile {
+    data: Rc<RefCell<Vec<u8>>>,
+    pos: usize,
+}
+
+impl VfsFile for MemoryFile {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, SysError> {
+        let data = self.data.borrow();
+        let n = buf.len().min(data.len().saturating_sub(self.pos));
+        buf[..n].copy_from_slice(&data[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+
+    fn write(&mut self, buf: &[u8]) -> Result<usize, SysError> {
+        let mut data = self.data.borrow_mut();
+        let end = self.pos + buf.len();
+        if data.len() < end {
+            data.resize(end, 0);
+        }
+        data[self.pos..end].copy_from_slice(buf);
+        self.pos = end;
+        Ok(buf.len())
+    }
+}
+
+impl Vfs for MemoryFs {
+    fn stat(&self, path: &str) -> Result<Metadata, SysError> {
+        let (_, node) = self.node(path, false)?;
+        let size = match &node.kind {
+            NodeKind::File(data) => data.borrow().len() as u64,
+            NodeKind::Dir => 0,
+            NodeKind::Symlink(target) => target.len() as u64,
+        };
+        Ok(Metadata {
+            file_type: node.file_type(),
+            size,
+            mode: node.mode,
+            mtime: node.mtime,
+        })
+    }
+
+    fn read_dir(&self, path: &str) -> Result<Vec<DirEntry>, SysError> {
+        let (real, node) = self.node(path, true)?;
+        if !matches!(node.kind, NodeKind::Dir) {
+            return Err(SysError::NotDirectory);
+        }
+        Ok(self
+            .children(&real)
+            .map(|child| DirEntry {
+                name: String::from(&child[child.rfind('/').map_or(0, |i| i + 1)..]),
+                is_dir: matches!(self.nodes[child].kind, NodeKind::Dir),
+            })
+            .collect())
+    }
+
+    fn read_link(&self, path: &str) -> Result<String, SysError> {
+        match &self.node(path, false)?.1.kind {
+            NodeKind::Symlink(target) => Ok(target.clone()),
+            _ => Err(SysError::IoError),
+        }
+    }
+
+    fn open(&self, path: &str) -> Result<Box<dyn VfsFile>, SysError> {
+        match &self.node(path, true)?.1.kind {
+            NodeKind::File(data) => Ok(Box::new(MemoryFile {
+                data: data.clone(),
+                pos: 0,
+            })),
+            _ => Err(SysError::IsDirectory),
+        }
+    }
+
+    fn create(&mut self, path: &str) -> Result<Box<dyn VfsFile>, SysError> {
+        self.writable()?;
+        let real = self.resolve(path, true)?;
+        if !self.nodes.contains_key(&real) {
+            let data = Rc::new(RefCell::new(Vec::new()));
+            self.insert(&real, NodeKind::File(data), 0o644)?;
+        }
+
+        let mtime = self.tick();
+        let node = self.nodes.get_mut(&real).ok_or(SysError::NotFound)?;
+        match &node.kind {
+            NodeKind::File(data) => {
+                data.borrow_mut().clear();
+                node.mtime = mtime;
+                Ok(Box::new(MemoryFile {
+                    data: data.clone(),
+                    pos: 0,
+                }))
+            }
+            _ => Err(SysError::IsDirectory),
+        }
+    }
+
+    fn mkdir(&mut self, path: &str) -> Result<(), SysError> {
+        self.writable()?;
+        self.insert(path, NodeKind::Dir, 0o755)
+    }
+
+    fn rmdir(&mut self, path: &str) -> Result<(), SysError> {
+        self.writable()?;
+        let (real, node) = self.node(path, false)?;
+        if !matches!(node.kind, NodeKind::Dir) {
+            return Err(SysError::NotDirectory);
+        }
+        if real == "/" {
+            return Err(SysError::PermissionDenied);
+        }
+        if self.children(&real).next().is_some() {
+            return Err(SysError::NotEmpty);
+        }
+        self.nodes.remove(&real);
+        Ok(())
+    }
+
+    fn remove(&mut self, path: &str) -> Result<(), SysError> {
+        self.writable()?;
+        let (real, node) = self.node(path, false)?;
+        if matches!(node.kind, NodeKind::Dir) {
+            return Err(SysError::IsDirectory);
+        }
+        self.nodes.remove(&real);
+        Ok(())
+    }
+
+    fn symlink(&mut self, target: &str, path: &str) -> Result<(), SysError> {
+        self.writable()?;
+        self.insert(path, NodeKind::Symlink(String::from(target)), 0o777)
+    }
+
+    fn rename(&mut self, from: &str, to: &str) -> Result<(), SysError> {
+        self.writable()?;
+        let (from, node) = self.node(from, false)?;
+        let from_dir = matches!(node.kind, NodeKind::Dir);
+        let to = self.resolve(to, false)?;
+        if from == to {
+            return Ok(());
+        }
+        if from == "/" || to.starts_with(&path::join(&from, "")) {
+            return Err(SysError::PermissionDenied);
+        }
+
+        // Destino existente: só é substituído por algo do mesmo tipo
+        if let Some(target) = self.nodes.get(&to) {
+            match (from_dir, matches!(target.kind, NodeKind::Dir)) {
+                (false, true) => return Err(SysError::IsDirectory),
+                (true, false) => return Err(SysError::NotDirectory),
+                (true, true) if self.children(&to).next().is_some() => {
+                    return Err(SysError::NotEmpty)
+                }
+                _ => {}
+            }
+        }
+        match self.nodes.get(parent(&to)) {
+            Some(node) if matches!(node.kind, NodeKind::Dir) => {}
+            Some(_) => return Err(SysError::NotDirectory),
+            None => return Err(SysError::NotFound),
+        }
+
+        self.nodes.remove(&to);
+        let prefix = path::join(&from, "");
+        let moved: Vec<String> = self
+            .nodes
+            .keys()
+            .filter(|k| **k == from || k.starts_with(&prefix))
+            .cloned()
+            .collect();
+        for old in moved {
+            if let Some(node) = self.nodes.remove(&old) {
+                let mut new = to.clone();
+                new.push_str(&old[from.len()..]);
+                self.nodes.insert(new, node);
+            }
+        }
+        Ok(())
+    }
+
+    fn set_mode(&mut self, path: &str, mode: u32) -> Result<(), SysError> {
+        self.writable()?;
+        self.node_mut(path)?.mode = mode;
+        Ok(())
+    }
+
+    fn set_mtime(&mut self, path: &str, mtime: u64) -> Result<(), SysError> {
+        self.writable()?;
+        self.node_mut(path)?.mtime = mtime;
+        Ok(())
+    }
+
+    fn touch(&mut self, path: &str) -> Result<(), SysError> {
+        self.writable()?;
+        let now = self.tick();
+        self.node_mut(path)?.mtime = now;
+        Ok(())
+    }
+
+    fn current_dir(&self) -> Result<String, SysError> {
+        Ok(self.cwd.clone())
+    }
+
+    fn set_current_dir(&mut self, path: &str) -> Result<(), SysError> {
+        let (real, node) = self.node(path, true)?;
+        if !matches!(node.kind, NodeKind::Dir) {
+            return Err(SysError::NotDirectory);
+        }
+        self.cwd = real;
+        Ok(())
+    }
+}
+
+// =============================================================================
+// TESTES
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{MemoryFs, Vfs};
+    use alloc::string::String;
+    use alloc::vec::Vec;
+    use redpowder::SysError;
+
+    fn names(fs: &MemoryFs, path: &str) -> Vec<String> {
+        let mut names: Vec<String> = fs
+            .read_dir(path)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.name)
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn create_write_and_read_back() {
+        let mut fs = MemoryFs::new();
+        fs.add_dir("/tmp");
+        let mut file = fs.create("/tmp/a").unwrap();
+        assert_eq!(file.write(b"ola").unwrap(), 3);
+        drop(file);
+
+        assert_eq!(fs.contents("/tmp/a").as_deref(), Some(&b"ola"[..]));
+        assert_eq!(fs.stat("/tmp/a").unwrap().size, 3);
+        assert_eq!(fs.create("/nada/a").err(), Some(SysError::NotFound));
+        assert_eq!(fs.open("/tmp").err(), Some(SysError::IsDirectory));
+    }
+
+    #[test]
+    fn links_are_followed_except_by_stat() {
+        let mut fs = MemoryFs::new();
+        fs.add_file("/d/f", b"x")
+            .add_symlink("/d/rel", "f")
+            .add_symlink("/l", "/d");
+
+        assert!(fs.stat("/d/rel").unwrap().is_symlink());
+        assert_eq!(fs.read_link("/d/rel").unwrap(), "f");
+        assert_eq!(fs.contents("/l/rel").as_deref(), Some(&b"x"[..]));
+        assert_eq!(names(&fs, "/l"), ["f", "rel"]);
+    }
+
+    #[test]
+    fn read_only_tree_refuses_changes() {
+        let mut fs = MemoryFs::new();
+        fs.add_file("/a", b"a").read_only();
+
+        assert_eq!(fs.create("/a").err(), Some(SysError::PermissionDenied));
+        assert_eq!(fs.mkdir("/d"), Err(SysError::PermissionDenied));
+        assert_eq!(fs.remove("/a"), Err(SysError::PermissionDenied));
+        assert_eq!(fs.touch("/a"), Err(SysError::PermissionDenied));
+        assert!(fs.open("/a").is_ok());
+        assert_eq!(fs.contents("/a").as_deref(), Some(&b"a"[..]));
+    }
+
+    #[test]
+    fn rename_moves_whole_subtree() {
+        let mut fs = MemoryFs::new();
+        fs.add_file("/a/b/c", b"c").add_dir("/z");
+
+        fs.rename("/a", "/z/a").unwrap();
+        assert!(!fs.exists("/a"));
+        assert_eq!(fs.contents("/z/a/b/c").as_deref(), Some(&b"c"[..]));
+        assert_eq!(
+            fs.rename("/z", "/z/a/dentro").err(),
+            Some(SysError::PermissionDenied)
+        );
+        assert_eq!(
+            fs.rename("/z/a/b/c", "/z").err(),
+            Some(SysError::IsDirectory)
+        );
+    }
+
+    #[test]
+    fn remove_refuses_directories() {
+        let mut fs = MemoryFs::new();
+        fs.add_file("/d/f", b"");
+
+        assert_eq!(fs.remove("/d").err(), Some(SysError::IsDirectory));
+        assert_eq!(fs.rmdir("/d/f").err(), Some(SysError::NotDirectory));
+        fs.remove("/d/f").unwrap();
+        fs.rmdir("/d").unwrap();
+        assert_eq!(names(&fs, "/"), Vec::<String>::new());
+    }
+}
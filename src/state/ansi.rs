@@ -0,0 +1,184 @@
+//! # ANSI Escape Parser
+//!
+//! Máquina de estados para sequências de escape (subconjunto do ECMA-48).
+//!
+//! Reconhece:
+//! - Caracteres imprimíveis e controles C0 (`\n`, `\r`, `\x08`, `\t`)
+//! - CSI (`ESC [ ... final`), com até 16 parâmetros numéricos
+//! - OSC (`ESC ] ... BEL` ou `ESC ] ... ESC \`), que é descartado
+
+/// Número máximo de parâmetros numéricos em uma sequência CSI
+const MAX_PARAMS: usize = 16;
+
+/// Sequência CSI completa
+#[derive(Debug, Clone, Copy)]
+pub struct Csi {
+    /// Parâmetros numéricos (0 quando omitido)
+    params: [u16; MAX_PARAMS],
+    /// Quantidade de parâmetros presentes
+    len: usize,
+    /// Sequência privada (`ESC [ ?`)
+    pub private: bool,
+    /// Caractere final que identifica a sequência
+    pub final_char: char,
+}
+
+impl Csi {
+    /// Parâmetros presentes
+    pub fn params(&self) -> &[u16] {
+        &self.params[..self.len]
+    }
+
+    /// Retorna o parâmetro `i`, ou `default` se ausente ou zero
+    pub fn param(&self, i: usize, default: u16) -> u16 {
+        match self.params().get(i) {
+            Some(&p) if p != 0 => p,
+            _ => default,
+        }
+    }
+}
+
+/// Ação resultante de um caractere de entrada
+#[derive(Debug, Clone, Copy)]
+pub enum Action {
+    /// Nada a fazer (sequência em andamento ou ignorada)
+    None,
+    /// Caractere imprimível
+    Print(char),
+    /// Caractere de controle C0
+    Control(char),
+    /// Sequência CSI completa
+    Csi(Csi),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Ground,
+    Escape,
+    Csi,
+    Osc,
+}
+
+/// Parser de sequências de escape
+#[derive(Debug, Clone)]
+pub struct AnsiParser {
+    state: State,
+    params: [u16; MAX_PARAMS],
+    len: usize,
+    private: bool,
+}
+
+impl Default for AnsiParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AnsiParser {
+    /// Cria parser no estado inicial
+    pub const fn new() -> Self {
+        Self {
+            state: State::Ground,
+            params: [0; MAX_PARAMS],
+            len: 0,
+            private: false,
+        }
+    }
+
+    /// Processa um caractere
+    pub fn feed(&mut self, c: char) -> Action {
+        match self.state {
+            State::Ground => match c {
+                '\x1b' => {
+                    self.state = State::Escape;
+                    Action::None
+                }
+                c if (c as u32) < 0x20 || c == '\x7f' => Action::Control(c),
+                c => Action::Print(c),
+            },
+            State::Escape => {
+                match c {
+                    '[' => {
+                        self.params = [0; MAX_PARAMS];
+                        self.len = 0;
+                        self.private = false;
+                        self.state = State::Csi;
+                    }
+                    ']' => self.state = State::Osc,
+                    // Outras sequências de dois caracteres são ignoradas
+                    _ => self.state = State::Ground,
+                }
+                Action::None
+            }
+            State::Csi => match c {
+                '0'..='9' => {
+                    if self.len == 0 {
+                        self.len = 1;
+                    }
+                    let p = &mut self.params[self.len - 1];
+                    *p = p.saturating_mul(10).saturating_add(c as u16 - '0' as u16);
+                    Action::None
+                }
+                ';' | ':' => {
+                    if self.len == 0 {
+                        self.len = 1;
+                    }
+                    if self.len < MAX_PARAMS {
+                        self.len += 1;
+                    }
+                    Action::None
+                }
+                '?' | '>' | '=' | '<' => {
+                    self.private = true;
+                    Action::None
+                }
+                // Bytes intermediários são aceitos e ignorados
+                ' '..='/' => Action::None,
+                '@'..='~' => {
+                    self.state = State::Ground;
+                    Action::Csi(Csi {
+                        params: self.params,
+                        len: self.len,
+                        private: self.private,
+                        final_char: c,
+                    })
+                }
+                // Sequência inválida: abortar
+                _ => {
+                    self.state = State::Ground;
+                    Action::None
+                }
+            },
+            State::Osc => {
+                match c {
+                    '\x07' => self.state = State::Ground,
+                    // ESC \ (String Terminator)
+                    '\x1b' => self.state = State::Escape,
+                    _ => {}
+                }
+                Action::None
+            }
+        }
+    }
+}
+
+/// Largura visível de um texto, ignorando sequências de escape.
+///
+/// Conta apenas a última linha, pois é ela que determina a coluna
+/// onde o cursor fica após escrever o texto.
+pub fn display_width(text: &str) -> usize {
+    let mut parser = AnsiParser::new();
+    let mut width = 0usize;
+
+    for c in text.chars() {
+        match parser.feed(c) {
+            Action::Print(_) => width += 1,
+            Action::Control('\n') | Action::Control('\r') => width = 0,
+            Action::Control('\t') => width = (width / 8 + 1) * 8,
+            Action::Control('\x08') => width = width.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    width
+}
@@ -2,4 +2,7 @@
 //!
 //! Gerenciamento de estado do terminal.
 
+pub mod ansi;
+pub mod regex;
 pub mod terminal;
+pub mod utf8;
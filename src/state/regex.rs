@@ -0,0 +1,1076 @@
+//! # Regular Expressions
+//!
+//! Expressões regulares POSIX (básicas e estendidas) sem `std`, usadas pelo
+//! `grep` e por qualquer busca em texto do terminal.
+//!
+//! O padrão é compilado para um programa de uma máquina de NFA (Pike VM):
+//! a busca anda pelo texto uma vez, com todos os caminhos possíveis em
+//! paralelo, então o tempo é linear no texto mesmo para padrões como
+//! `(a*)*b`. O resultado segue o POSIX: o casamento que começa mais à
+//! esquerda e, entre esses, o mais longo.
+//!
+//! ## Sintaxe
+//!
+//! | Estendida (`-E`) | Básica       | Significado                          |
+//! |------------------|--------------|--------------------------------------|
+//! | `.`              | `.`          | Qualquer caractere, menos `\n`       |
+//! | `[a-z]`, `[^0-9]`| igual        | Classe (aceita `[:alpha:]` etc.)     |
+//! | `^`, `$`         | igual        | Início / fim da linha                |
+//! | `*`, `+`, `?`    | `*`, `\+`, `\?` | Repetição                         |
+//! | `{n}`, `{n,m}`   | `\{n,m\}`    | Repetição com limites                |
+//! | `(a)`, `a\|b`    | `\(a\)`, `a\|b` | Grupo / alternativa               |
+//! | `\w`, `\s`, `\d` | igual        | Palavra, espaço, dígito (`\W`... negam) |
+//! | `\b`, `\<`, `\>` | igual        | Borda de palavra / início / fim      |
+//!
+//! ```ignore
+//! let re = Regex::with_options("erro|aviso", RegexOptions { ignore_case: true, ..Default::default() })?;
+//! for (start, end) in re.find_iter(linha) { ... }
+//! ```
+
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Maior contagem aceita em `{n,m}` (o programa cresce com ela)
+const MAX_REPEAT: u32 = 255;
+
+/// Maior programa compilado
+const MAX_INSTS: usize = 32 * 1024;
+
+// =============================================================================
+// API
+// =============================================================================
+
+/// Dialeto do padrão
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Syntax {
+    /// POSIX básica (`grep`): `\(`, `\|`, `\{` são operadores
+    Basic,
+    /// POSIX estendida (`grep -E`)
+    #[default]
+    Extended,
+}
+
+/// Opções de compilação
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RegexOptions {
+    pub syntax: Syntax,
+    /// Ignora maiúsculas/minúsculas
+    pub ignore_case: bool,
+}
+
+/// Erro no padrão
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegexError {
+    /// `(` sem `)`, ou `)` sem `(`
+    UnmatchedParen,
+    /// `[` sem `]`
+    UnmatchedBracket,
+    /// `[:nome:]` desconhecida
+    UnknownClass(String),
+    /// Intervalo `z-a`
+    BadRange(char, char),
+    /// `{n,m}` com m < n ou acima do limite
+    BadRepeat,
+    /// `\` no fim do padrão
+    TrailingBackslash,
+    /// `\1` a `\9`: casar o texto de um grupo não cabe numa NFA
+    Backreference(char),
+    /// Padrão grande demais depois de expandir as repetições
+    TooBig,
+}
+
+impl RegexError {
+    /// Mensagem para o usuário
+    pub fn message(&self) -> String {
+        match self {
+            RegexError::UnmatchedParen => String::from("parenteses sem par"),
+            RegexError::UnmatchedBracket => String::from("'[' sem ']'"),
+            RegexError::UnknownClass(name) => format!("classe desconhecida: [:{}:]", name),
+            RegexError::BadRange(a, b) => format!("intervalo invalido: {}-{}", a, b),
+            RegexError::BadRepeat => format!("repeticao invalida (limite {})", MAX_REPEAT),
+            RegexError::TrailingBackslash => String::from("'\\' no fim do padrao"),
+            RegexError::Backreference(n) => {
+                format!("referencia anterior nao suportada: \\{}", n)
+            }
+            RegexError::TooBig => String::from("padrao grande demais"),
+        }
+    }
+}
+
+/// Expressão regular compilada
+#[derive(Debug, Clone)]
+pub struct Regex {
+    prog: Vec<Inst>,
+    ignore_case: bool,
+}
+
+impl Regex {
+    /// Compila um padrão estendido, diferenciando maiúsculas
+    pub fn new(pattern: &str) -> Result<Self, RegexError> {
+        Self::with_options(pattern, RegexOptions::default())
+    }
+
+    /// Compila com as opções dadas
+    pub fn with_options(pattern: &str, options: RegexOptions) -> Result<Self, RegexError> {
+        let mut parser = Parser {
+            chars: pattern.chars().collect(),
+            pos: 0,
+            syntax: options.syntax,
+        };
+        let node = parser.parse_alt()?;
+        if parser.pos < parser.chars.len() {
+            // Só sobra algo se um `)` fechou um grupo que não existe
+            return Err(RegexError::UnmatchedParen);
+        }
+
+        let mut compiler = Compiler {
+            prog: Vec::new(),
+            ignore_case: options.ignore_case,
+        };
+        compiler.node(&node)?;
+        compiler.push(Inst::Match)?;
+        Ok(Self {
+            prog: compiler.prog,
+            ignore_case: options.ignore_case,
+        })
+    }
+
+    /// Compila um glob (`*.rs`, `file?.[ch]`, `[!.]*`) que precisa casar
+    /// com o texto inteiro
+    pub fn glob(pattern: &str, ignore_case: bool) -> Result<Self, RegexError> {
+        let mut re = String::from("^");
+        let mut chars = pattern.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '*' => re.push_str(".*"),
+                '?' => re.push('.'),
+                '[' => {
+                    // Sem `]`, o `[` é literal
+                    let body: String = chars.clone().collect();
+                    let close = body.char_indices().skip(1).find(|(_, c)| *c == ']');
+                    let Some((end, _)) = close else {
+                        re.push_str("\\[");
+                        continue;
+                    };
+                    let body = &body[..end];
+                    for _ in 0..body.chars().count() + 1 {
+                        chars.next();
+                    }
+                    re.push('[');
+                    match body.strip_prefix('!') {
+                        Some(rest) => {
+                            re.push('^');
+                            re.push_str(rest);
+                        }
+                        None => re.push_str(body),
+                    }
+                    re.push(']');
+                }
+                '\\' => {
+                    re.push('\\');
+                    re.push(chars.next().unwrap_or('\\'));
+                }
+                c if "^$.+()|{}".contains(c) => {
+                    re.push('\\');
+                    re.push(c);
+                }
+                c => re.push(c),
+            }
+        }
+        re.push('$');
+
+        Self::with_options(
+            &re,
+            RegexOptions {
+                syntax: Syntax::Extended,
+                ignore_case,
+            },
+        )
+    }
+
+    /// O padrão aparece em `text`
+    pub fn is_match(&self, text: &str) -> bool {
+        self.find(text).is_some()
+    }
+
+    /// Primeiro casamento: `(início, fim)` em bytes
+    pub fn find(&self, text: &str) -> Option<(usize, usize)> {
+        self.find_at(text, 0)
+    }
+
+    /// Primeiro casamento que começa em `start` ou depois
+    pub fn find_at(&self, text: &str, start: usize) -> Option<(usize, usize)> {
+        Vm::new(self).run(text, start)
+    }
+
+    /// Todos os casamentos, sem sobreposição
+    pub fn find_iter<'r, 't>(&'r self, text: &'t str) -> Matches<'r, 't> {
+        Matches {
+            regex: self,
+            text,
+            pos: 0,
+            last_end: None,
+        }
+    }
+}
+
+/// Iterador de [`Regex::find_iter`]
+pub struct Matches<'r, 't> {
+    regex: &'r Regex,
+    text: &'t str,
+    pos: usize,
+    /// Fim do último casamento (um vazio logo nele é ignorado)
+    last_end: Option<usize>,
+}
+
+impl Iterator for Matches<'_, '_> {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.pos > self.text.len() {
+                return None;
+            }
+            let (start, end) = self.regex.find_at(self.text, self.pos)?;
+            if start == end {
+                // Vazio: avança um caractere para não repetir
+                self.pos = end + self.text[end..].chars().next().map_or(1, char::len_utf8);
+                if self.last_end == Some(end) {
+                    continue;
+                }
+            } else {
+                self.pos = end;
+            }
+            self.last_end = Some(end);
+            return Some((start, end));
+        }
+    }
+}
+
+// =============================================================================
+// PARSER
+// =============================================================================
+
+/// Árvore do padrão
+#[derive(Debug, Clone)]
+enum Node {
+    Empty,
+    Char(char),
+    /// `.`
+    Any,
+    Class(Class),
+    Assert(Assert),
+    Concat(Vec<Node>),
+    Alt(Vec<Node>),
+    Repeat {
+        node: Box<Node>,
+        min: u32,
+        /// `None` = sem limite
+        max: Option<u32>,
+    },
+}
+
+/// Condição sobre a posição, sem consumir texto
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Assert {
+    LineStart,
+    LineEnd,
+    /// `\b`
+    WordBoundary,
+    /// `\B`
+    NotWordBoundary,
+    /// `\<`
+    WordStart,
+    /// `\>`
+    WordEnd,
+}
+
+/// Conjunto de caracteres (`[...]`, `\w`)
+#[derive(Debug, Clone)]
+struct Class {
+    items: Vec<ClassItem>,
+    negated: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ClassItem {
+    Range(char, char),
+    Named(fn(char) -> bool),
+}
+
+impl Class {
+    fn named(test: fn(char) -> bool, negated: bool) -> Self {
+        Self {
+            items: vec![ClassItem::Named(test)],
+            negated,
+        }
+    }
+
+    fn contains(&self, c: char, ignore_case: bool) -> bool {
+        let test = |c: char| {
+            self.items.iter().any(|item| match *item {
+                ClassItem::Range(a, b) => a <= c && c <= b,
+                ClassItem::Named(f) => f(c),
+            })
+        };
+        let found = test(c) || (ignore_case && (test(fold(c)) || c.to_uppercase().any(test)));
+        found != self.negated
+    }
+}
+
+/// Operadores, cuja grafia muda com o dialeto
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Meta {
+    Open,
+    Close,
+    Alt,
+    Star,
+    Plus,
+    Quest,
+    Brace,
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    syntax: Syntax,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    /// Operador na posição atual e quantos caracteres ocupa
+    fn meta(&self) -> Option<(Meta, usize)> {
+        let c = self.peek()?;
+        let op = |c| match c {
+            '(' => Some(Meta::Open),
+            ')' => Some(Meta::Close),
+            '|' => Some(Meta::Alt),
+            '+' => Some(Meta::Plus),
+            '?' => Some(Meta::Quest),
+            '{' => Some(Meta::Brace),
+            _ => None,
+        };
+        if c == '*' {
+            return Some((Meta::Star, 1));
+        }
+        match self.syntax {
+            Syntax::Extended => op(c).map(|m| (m, 1)),
+            Syntax::Basic if c == '\\' => op(*self.chars.get(self.pos + 1)?).map(|m| (m, 2)),
+            Syntax::Basic => None,
+        }
+    }
+
+    /// `a|b|c`
+    fn parse_alt(&mut self) -> Result<Node, RegexError> {
+        let mut branches = vec![self.parse_concat()?];
+        while let Some((Meta::Alt, len)) = self.meta() {
+            self.pos += len;
+            branches.push(self.parse_concat()?);
+        }
+        Ok(if branches.len() == 1 {
+            branches.pop().unwrap_or(Node::Empty)
+        } else {
+            Node::Alt(branches)
+        })
+    }
+
+    /// Sequência até `|`, `)` ou o fim
+    fn parse_concat(&mut self) -> Result<Node, RegexError> {
+        let mut nodes = Vec::new();
+        while self.peek().is_some() {
+            if let Some((Meta::Alt | Meta::Close, _)) = self.meta() {
+                break;
+            }
+            let at_start = nodes.is_empty();
+            nodes.push(self.parse_repeat(at_start)?);
+        }
+        Ok(match nodes.len() {
+            0 => Node::Empty,
+            1 => nodes.pop().unwrap_or(Node::Empty),
+            _ => Node::Concat(nodes),
+        })
+    }
+
+    /// Átomo seguido de `*`, `+`, `?` ou `{n,m}`
+    fn parse_repeat(&mut self, at_start: bool) -> Result<Node, RegexError> {
+        let mut node = self.parse_atom(at_start)?;
+        loop {
+            let (min, max) = match self.meta() {
+                Some((Meta::Star, len)) => {
+                    self.pos += len;
+                    (0, None)
+                }
+                Some((Meta::Plus, len)) => {
+                    self.pos += len;
+                    (1, None)
+                }
+                Some((Meta::Quest, len)) => {
+                    self.pos += len;
+                    (0, Some(1))
+                }
+                Some((Meta::Brace, len)) => match self.parse_interval(len)? {
+                    Some(bounds) => bounds,
+                    // `{` que não abre um intervalo é literal
+                    None => break,
+                },
+                _ => break,
+            };
+            node = Node::Repeat {
+                node: Box::new(node),
+                min,
+                max,
+            };
+        }
+        Ok(node)
+    }
+
+    /// `{n}`, `{n,}`, `{,m}`, `{n,m}`; `None` se não for um intervalo
+    fn parse_interval(
+        &mut self,
+        open_len: usize,
+    ) -> Result<Option<(u32, Option<u32>)>, RegexError> {
+        let close: &[char] = match self.syntax {
+            Syntax::Extended => &['}'],
+            Syntax::Basic => &['\\', '}'],
+        };
+        let body_start = self.pos + open_len;
+        let Some(len) = self.chars[body_start..]
+            .windows(close.len())
+            .position(|w| w == close)
+        else {
+            return Ok(None);
+        };
+        let body: String = self.chars[body_start..body_start + len].iter().collect();
+
+        let number = |s: &str| -> Option<Option<u32>> {
+            if s.is_empty() {
+                Some(None)
+            } else {
+                s.parse().ok().map(Some)
+            }
+        };
+        let bounds = match body.split_once(',') {
+            Some((min, max)) => number(min).zip(number(max)),
+            None => number(&body).filter(Option::is_some).map(|n| (n, n)),
+        };
+        let Some((min, max)) = bounds else {
+            return Ok(None);
+        };
+
+        let min = min.unwrap_or(0);
+        if min > MAX_REPEAT || max.is_some_and(|max| max < min || max > MAX_REPEAT) {
+            return Err(RegexError::BadRepeat);
+        }
+        self.pos = body_start + len + close.len();
+        Ok(Some((min, max)))
+    }
+
+    fn parse_atom(&mut self, at_start: bool) -> Result<Node, RegexError> {
+        match self.meta() {
+            Some((Meta::Open, len)) => {
+                self.pos += len;
+                let node = self.parse_alt()?;
+                match self.meta() {
+                    Some((Meta::Close, len)) => self.pos += len,
+                    _ => return Err(RegexError::UnmatchedParen),
+                }
+                return Ok(node);
+            }
+            // Repetição sem nada antes vale como caractere
+            Some((Meta::Star | Meta::Plus | Meta::Quest | Meta::Brace, len)) => {
+                let c = self.chars[self.pos + len - 1];
+                self.pos += len;
+                return Ok(Node::Char(c));
+            }
+            _ => {}
+        }
+
+        let Some(c) = self.peek() else {
+            return Ok(Node::Empty);
+        };
+        self.pos += 1;
+        Ok(match c {
+            '.' => Node::Any,
+            '[' => Node::Class(self.parse_bracket()?),
+            // Na sintaxe básica, `^` e `$` só são âncoras nas pontas
+            '^' if self.syntax == Syntax::Extended || at_start => Node::Assert(Assert::LineStart),
+            '$' if self.syntax == Syntax::Extended || self.at_branch_end() => {
+                Node::Assert(Assert::LineEnd)
+            }
+            '\\' => self.parse_escape()?,
+            c => Node::Char(c),
+        })
+    }
+
+    /// A posição atual termina a alternativa (fim, `|` ou `)`)
+    fn at_branch_end(&self) -> bool {
+        self.peek().is_none() || matches!(self.meta(), Some((Meta::Alt | Meta::Close, _)))
+    }
+
+    /// Depois de `\`
+    fn parse_escape(&mut self) -> Result<Node, RegexError> {
+        let Some(c) = self.peek() else {
+            return Err(RegexError::TrailingBackslash);
+        };
+        self.pos += 1;
+        Ok(match c {
+            'w' => Node::Class(Class::named(is_word, false)),
+            'W' => Node::Class(Class::named(is_word, true)),
+            's' => Node::Class(Class::named(char::is_whitespace, false)),
+            'S' => Node::Class(Class::named(char::is_whitespace, true)),
+            'd' => Node::Class(Class::named(|c| c.is_ascii_digit(), false)),
+            'D' => Node::Class(Class::named(|c| c.is_ascii_digit(), true)),
+            'b' => Node::Assert(Assert::WordBoundary),
+            'B' => Node::Assert(Assert::NotWordBoundary),
+            '<' => Node::Assert(Assert::WordStart),
+            '>' => Node::Assert(Assert::WordEnd),
+            'n' => Node::Char('\n'),
+            't' => Node::Char('\t'),
+            // Tratar `\1` como `1` faria `\(a\)\1` procurar "a1"
+            '1'..='9' => return Err(RegexError::Backreference(c)),
+            c => Node::Char(c),
+        })
+    }
+
+    /// Depois de `[`, até o `]` que fecha
+    fn parse_bracket(&mut self) -> Result<Class, RegexError> {
+        let mut class = Class {
+            items: Vec::new(),
+            negated: false,
+        };
+        if self.peek() == Some('^') {
+            class.negated = true;
+            self.pos += 1;
+        }
+
+        let mut first = true;
+        loop {
+            let Some(c) = self.peek() else {
+                return Err(RegexError::UnmatchedBracket);
+            };
+            self.pos += 1;
+
+            // `]` logo no início é um caractere
+            if c == ']' && !first {
+                return Ok(class);
+            }
+            first = false;
+
+            if c == '[' && self.peek() == Some(':') {
+                let rest: String = self.chars[self.pos + 1..].iter().collect();
+                let Some((name, _)) = rest.split_once(":]") else {
+                    return Err(RegexError::UnmatchedBracket);
+                };
+                let test = named_class(name)
+                    .ok_or_else(|| RegexError::UnknownClass(String::from(name)))?;
+                self.pos += 1 + name.chars().count() + 2;
+                class.items.push(ClassItem::Named(test));
+                continue;
+            }
+
+            // `a-z`; `-` no fim ou no início é literal
+            let is_range =
+                self.peek() == Some('-') && self.chars.get(self.pos + 1).is_some_and(|&n| n != ']');
+            if is_range {
+                let end = self.chars[self.pos + 1];
+                self.pos += 2;
+                if end < c {
+                    return Err(RegexError::BadRange(c, end));
+                }
+                class.items.push(ClassItem::Range(c, end));
+            } else {
+                class.items.push(ClassItem::Range(c, c));
+            }
+        }
+    }
+}
+
+/// Classes POSIX `[:nome:]`
+fn named_class(name: &str) -> Option<fn(char) -> bool> {
+    let test: fn(char) -> bool = match name {
+        "alpha" => char::is_alphabetic,
+        "digit" => |c| c.is_ascii_digit(),
+        "alnum" => char::is_alphanumeric,
+        "upper" => char::is_uppercase,
+        "lower" => char::is_lowercase,
+        "space" => char::is_whitespace,
+        "blank" => |c| c == ' ' || c == '\t',
+        "punct" => |c| c.is_ascii_punctuation(),
+        "cntrl" => char::is_control,
+        "xdigit" => |c| c.is_ascii_hexdigit(),
+        "print" => |c| !c.is_control(),
+        "graph" => |c| !c.is_control() && !c.is_whitespace(),
+        _ => return None,
+    };
+    Some(test)
+}
+
+/// Caractere de palavra (`\w`)
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Forma usada para comparar sem diferenciar maiúsculas
+fn fold(c: char) -> char {
+    if c.is_ascii() {
+        c.to_ascii_lowercase()
+    } else {
+        c.to_lowercase().next().unwrap_or(c)
+    }
+}
+
+// =============================================================================
+// COMPILAÇÃO
+// =============================================================================
+
+/// Instrução da VM
+#[derive(Debug, Clone)]
+enum Inst {
+    Char(char),
+    Any,
+    Class(Class),
+    Assert(Assert),
+    /// Segue pelos dois caminhos
+    Split(usize, usize),
+    Jmp(usize),
+    Match,
+}
+
+struct Compiler {
+    prog: Vec<Inst>,
+    ignore_case: bool,
+}
+
+impl Compiler {
+    fn push(&mut self, inst: Inst) -> Result<usize, RegexError> {
+        if self.prog.len() >= MAX_INSTS {
+            return Err(RegexError::TooBig);
+        }
+        self.prog.push(inst);
+        Ok(self.prog.len() - 1)
+    }
+
+    fn node(&mut self, node: &Node) -> Result<(), RegexError> {
+        match node {
+            Node::Empty => {}
+            Node::Char(c) => {
+                let c = if self.ignore_case { fold(*c) } else { *c };
+                self.push(Inst::Char(c))?;
+            }
+            Node::Any => {
+                self.push(Inst::Any)?;
+            }
+            Node::Class(class) => {
+                self.push(Inst::Class(class.clone()))?;
+            }
+            Node::Assert(assert) => {
+                self.push(Inst::Assert(*assert))?;
+            }
+            Node::Concat(nodes) => {
+                for node in nodes {
+                    self.node(node)?;
+                }
+            }
+            Node::Alt(branches) => {
+                // split L1, next; L1: a; jmp end; next: split L2, ...
+                let mut jumps = Vec::new();
+                for (i, branch) in branches.iter().enumerate() {
+                    if i + 1 == branches.len() {
+                        self.node(branch)?;
+                        break;
+                    }
+                    let split = self.push(Inst::Split(0, 0))?;
+                    self.node(branch)?;
+                    jumps.push(self.push(Inst::Jmp(0))?);
+                    let next = self.prog.len();
+                    self.prog[split] = Inst::Split(split + 1, next);
+                }
+                let end = self.prog.len();
+                for jump in jumps {
+                    self.prog[jump] = Inst::Jmp(end);
+                }
+            }
+            Node::Repeat { node, min, max } => {
+                for _ in 0..*min {
+                    self.node(node)?;
+                }
+                match max {
+                    // L: split body, end; body; jmp L
+                    None => {
+                        let split = self.push(Inst::Split(0, 0))?;
+                        self.node(node)?;
+                        self.push(Inst::Jmp(split))?;
+                        let end = self.prog.len();
+                        self.prog[split] = Inst::Split(split + 1, end);
+                    }
+                    // Cada cópia opcional pode encerrar a repetição
+                    Some(max) => {
+                        let mut splits = Vec::new();
+                        for _ in *min..*max {
+                            splits.push(self.push(Inst::Split(0, 0))?);
+                            self.node(node)?;
+                        }
+                        let end = self.prog.len();
+                        for split in splits {
+                            self.prog[split] = Inst::Split(split + 1, end);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// =============================================================================
+// EXECUÇÃO (Pike VM)
+// =============================================================================
+
+/// Caminhos vivos numa posição: instrução e início do casamento
+struct Threads {
+    list: Vec<(usize, usize)>,
+    /// Instruções já na lista (cada uma entra uma vez por posição)
+    seen: Vec<bool>,
+}
+
+impl Threads {
+    fn new(len: usize) -> Self {
+        Self {
+            list: Vec::new(),
+            seen: vec![false; len],
+        }
+    }
+
+    fn clear(&mut self) {
+        for &(pc, _) in &self.list {
+            self.seen[pc] = false;
+        }
+        self.list.clear();
+    }
+}
+
+/// Caracteres em volta da posição, para as asserções
+#[derive(Clone, Copy)]
+struct Around {
+    prev: Option<char>,
+    next: Option<char>,
+}
+
+impl Around {
+    fn holds(&self, assert: Assert) -> bool {
+        let word_before = self.prev.is_some_and(is_word);
+        let word_after = self.next.is_some_and(is_word);
+        match assert {
+            Assert::LineStart => matches!(self.prev, None | Some('\n')),
+            Assert::LineEnd => matches!(self.next, None | Some('\n')),
+            Assert::WordBoundary => word_before != word_after,
+            Assert::NotWordBoundary => word_before == word_after,
+            Assert::WordStart => !word_before && word_after,
+            Assert::WordEnd => word_before && !word_after,
+        }
+    }
+}
+
+struct Vm<'r> {
+    regex: &'r Regex,
+    /// Pilha do fecho de transições vazias
+    stack: Vec<usize>,
+}
+
+impl<'r> Vm<'r> {
+    fn new(regex: &'r Regex) -> Self {
+        Self {
+            regex,
+            stack: Vec::new(),
+        }
+    }
+
+    /// Acrescenta `pc` e tudo o que se alcança dele sem consumir texto
+    fn add(&mut self, threads: &mut Threads, pc: usize, start: usize, around: Around) {
+        self.stack.push(pc);
+        while let Some(pc) = self.stack.pop() {
+            if threads.seen[pc] {
+                continue;
+            }
+            threads.seen[pc] = true;
+            threads.list.push((pc, start));
+
+            match self.regex.prog[pc] {
+                Inst::Jmp(target) => self.stack.push(target),
+                Inst::Split(a, b) => {
+                    self.stack.push(b);
+                    self.stack.push(a);
+                }
+                Inst::Assert(assert) if around.holds(assert) => self.stack.push(pc + 1),
+                _ => {}
+            }
+        }
+    }
+
+    fn run(&mut self, text: &str, start: usize) -> Option<(usize, usize)> {
+        let regex = self.regex;
+        let prog = &regex.prog;
+        let mut current = Threads::new(prog.len());
+        let mut next = Threads::new(prog.len());
+        let mut best: Option<(usize, usize)> = None;
+
+        let mut pos = start;
+        let mut prev = text[..start].chars().next_back();
+        loop {
+            let c = text[pos..].chars().next();
+            let around = Around { prev, next: c };
+
+            // Novos inícios só até achar o primeiro casamento
+            if best.is_none() {
+                self.add(&mut current, 0, pos, around);
+            }
+            if current.list.is_empty() {
+                break;
+            }
+
+            let after = Around {
+                prev: c,
+                next: c.and_then(|c| text[pos + c.len_utf8()..].chars().next()),
+            };
+            for i in 0..current.list.len() {
+                let (pc, thread_start) = current.list[i];
+                // Começou depois do casamento já achado: não pode vencer
+                if best.is_some_and(|(s, _)| thread_start > s) {
+                    continue;
+                }
+                let matches = match (&prog[pc], c) {
+                    (Inst::Match, _) => {
+                        let better = match best {
+                            None => true,
+                            Some((s, e)) => thread_start < s || (thread_start == s && pos > e),
+                        };
+                        if better {
+                            best = Some((thread_start, pos));
+                        }
+                        false
+                    }
+                    (Inst::Char(want), Some(c)) => {
+                        let c = if regex.ignore_case { fold(c) } else { c };
+                        *want == c
+                    }
+                    (Inst::Any, Some(c)) => c != '\n',
+                    (Inst::Class(class), Some(c)) => class.contains(c, regex.ignore_case),
+                    _ => false,
+                };
+                if matches {
+                    self.add(&mut next, pc + 1, thread_start, after);
+                }
+            }
+
+            let Some(c) = c else {
+                break;
+            };
+            pos += c.len_utf8();
+            prev = Some(c);
+            current.clear();
+            core::mem::swap(&mut current, &mut next);
+        }
+        best
+    }
+}
+
+// =============================================================================
+// TESTES
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{Regex, RegexError, RegexOptions, Syntax};
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    fn compile(pattern: &str, syntax: Syntax) -> Result<Regex, RegexError> {
+        let options = RegexOptions {
+            syntax,
+            ignore_case: false,
+        };
+        Regex::with_options(pattern, options)
+    }
+
+    /// Primeiro casamento de um padrão estendido
+    fn ere(pattern: &str, text: &str) -> Option<(usize, usize)> {
+        compile(pattern, Syntax::Extended).unwrap().find(text)
+    }
+
+    /// Primeiro casamento de um padrão básico
+    fn bre(pattern: &str, text: &str) -> Option<(usize, usize)> {
+        compile(pattern, Syntax::Basic).unwrap().find(text)
+    }
+
+    #[test]
+    fn basic_operators_need_a_backslash() {
+        assert_eq!(bre("a+", "aa+"), Some((1, 3)));
+        assert_eq!(bre("a\\+", "baaa"), Some((1, 4)));
+        assert_eq!(bre("(a)", "x(a)"), Some((1, 4)));
+        assert_eq!(bre("\\(ab\\)*c", "ababc"), Some((0, 5)));
+        assert_eq!(bre("x\\|y", "ay"), Some((1, 2)));
+        assert_eq!(bre("a|b", "a|b"), Some((0, 3)));
+
+        assert_eq!(ere("a+", "aa+"), Some((0, 2)));
+        assert_eq!(ere("(ab)*c", "ababc"), Some((0, 5)));
+        assert_eq!(ere("x|y", "ay"), Some((1, 2)));
+        assert_eq!(ere("a\\+", "aa+"), Some((1, 3)));
+    }
+
+    #[test]
+    fn intervals_and_their_limits() {
+        assert_eq!(ere("a{2,3}", "aaaa"), Some((0, 3)));
+        assert_eq!(ere("a{2}", "caaa"), Some((1, 3)));
+        assert_eq!(ere("ba{,2}", "baaa"), Some((0, 3)));
+        assert_eq!(ere("ba{2,}", "baaaa"), Some((0, 5)));
+        assert_eq!(ere("a{2}", "a"), None);
+        assert_eq!(bre("a\\{2\\}", "aaa"), Some((0, 2)));
+        assert_eq!(bre("a{2}", "a{2}"), Some((0, 4)));
+        // Sem número, o `{` é literal
+        assert_eq!(ere("a{x}", "a{x}"), Some((0, 4)));
+
+        assert!(compile("a{255}", Syntax::Extended).is_ok());
+        assert_eq!(
+            compile("a{3,2}", Syntax::Extended).err(),
+            Some(RegexError::BadRepeat)
+        );
+        assert_eq!(
+            compile("a{256}", Syntax::Extended).err(),
+            Some(RegexError::BadRepeat)
+        );
+    }
+
+    #[test]
+    fn bracket_classes() {
+        assert_eq!(ere("[a-c]+", "xxbcay"), Some((2, 5)));
+        assert_eq!(ere("[^0-9]+", "12ab3"), Some((2, 4)));
+        assert_eq!(ere("[]a]+", "x]a]"), Some((1, 4)));
+        assert_eq!(ere("[a-]+", "x-a"), Some((1, 3)));
+        assert_eq!(ere("[[:digit:][:upper:]]+", "abC1d"), Some((2, 4)));
+        assert_eq!(ere("[[:alpha:]]+", "1ação2"), Some((1, 7)));
+        assert_eq!(ere("\\d+\\s\\w+", "n 42 itens"), Some((2, 10)));
+
+        assert_eq!(
+            compile("[[:letra:]]", Syntax::Extended).err(),
+            Some(RegexError::UnknownClass("letra".into()))
+        );
+        assert_eq!(
+            compile("[z-a]", Syntax::Extended).err(),
+            Some(RegexError::BadRange('z', 'a'))
+        );
+        assert_eq!(
+            compile("[abc", Syntax::Extended).err(),
+            Some(RegexError::UnmatchedBracket)
+        );
+    }
+
+    #[test]
+    fn anchors_match_at_line_edges() {
+        assert_eq!(ere("^a", "ba"), None);
+        assert_eq!(ere("^a", "b\na"), Some((2, 3)));
+        assert_eq!(ere("a$", "ab"), None);
+        assert_eq!(ere("a$", "a\nb"), Some((0, 1)));
+        // Na básica, `^` e `$` no meio são caracteres
+        assert_eq!(bre("a^b$c", "a^b$c"), Some((0, 5)));
+        assert_eq!(bre("^a\\|b$", "xb"), Some((1, 2)));
+    }
+
+    #[test]
+    fn word_boundaries() {
+        assert_eq!(ere("\\bfoo\\b", "foobar foo"), Some((7, 10)));
+        assert_eq!(ere("\\Boo", "foo"), Some((1, 3)));
+        assert_eq!(ere("\\<b", "ab b"), Some((3, 4)));
+        assert_eq!(ere("a\\>", "ab a"), Some((3, 4)));
+        assert_eq!(ere("\\bé", "mé é"), Some((4, 6)));
+    }
+
+    #[test]
+    fn leftmost_longest_match_wins() {
+        assert_eq!(ere("a|ab", "ab"), Some((0, 2)));
+        assert_eq!(ere("(a|ab)(c|bcd)", "abcd"), Some((0, 4)));
+        assert_eq!(ere("b+|a", "abbb"), Some((0, 1)));
+        assert_eq!(ere("(a*)*b", "aaab"), Some((0, 4)));
+    }
+
+    #[test]
+    fn offsets_are_utf8_bytes() {
+        assert_eq!(ere("mundo", "olá mundo"), Some((5, 10)));
+        assert_eq!(ere("o.a", "oéa"), Some((0, 4)));
+
+        let re = Regex::new("é").unwrap();
+        assert_eq!(
+            re.find_iter("éxé").collect::<Vec<_>>(),
+            vec![(0, 2), (3, 5)]
+        );
+        let re = Regex::new("a*").unwrap();
+        assert_eq!(
+            re.find_iter("baa").collect::<Vec<_>>(),
+            vec![(0, 0), (1, 3)]
+        );
+    }
+
+    #[test]
+    fn ignore_case_folds_both_sides() {
+        let options = RegexOptions {
+            syntax: Syntax::Extended,
+            ignore_case: true,
+        };
+        let re = Regex::with_options("erro|ÇÃO", options).unwrap();
+        assert_eq!(re.find("ERRO"), Some((0, 4)));
+        assert_eq!(re.find("ação"), Some((1, 6)));
+        assert!(Regex::with_options("[a-z]+", options)
+            .unwrap()
+            .is_match("ABC"));
+    }
+
+    #[test]
+    fn backreferences_are_rejected() {
+        assert_eq!(
+            compile("\\(a\\)\\1", Syntax::Basic).err(),
+            Some(RegexError::Backreference('1'))
+        );
+        assert_eq!(
+            compile("(a)\\9", Syntax::Extended).err(),
+            Some(RegexError::Backreference('9'))
+        );
+        assert_eq!(ere("a\\0", "a0"), Some((0, 2)));
+    }
+
+    #[test]
+    fn parens_must_balance() {
+        assert_eq!(
+            compile("(a", Syntax::Extended).err(),
+            Some(RegexError::UnmatchedParen)
+        );
+        assert_eq!(
+            compile("a)", Syntax::Extended).err(),
+            Some(RegexError::UnmatchedParen)
+        );
+        assert_eq!(
+            compile("\\(a", Syntax::Basic).err(),
+            Some(RegexError::UnmatchedParen)
+        );
+        assert_eq!(
+            compile("a\\", Syntax::Extended).err(),
+            Some(RegexError::TrailingBackslash)
+        );
+    }
+
+    #[test]
+    fn globs_match_the_whole_name() {
+        let re = Regex::glob("*.rs", false).unwrap();
+        assert!(re.is_match("main.rs"));
+        assert!(!re.is_match("main.rs.bak"));
+        assert!(Regex::glob("file?.[ch]", false)
+            .unwrap()
+            .is_match("file1.h"));
+        assert!(!Regex::glob("[!.]*", false).unwrap().is_match(".oculto"));
+        assert!(Regex::glob("a+b(c)", false).unwrap().is_match("a+b(c)"));
+    }
+}
@@ -1,13 +1,100 @@
 //! # Terminal State
 //!
 //! Estado interno do emulador de terminal.
+//!
+//! O texto é guardado como uma grade de células (caractere + cores). Toda
+//! escrita passa pelo parser de escape, então sequências SGR
+//! (`ESC [ ... m`) mudam as cores das células seguintes.
+//!
+//! Programas de tela cheia (o pager, editores) usam a tela alternativa
+//! (`ESC [ ? 1049 h`): uma grade sem histórico que, ao sair
+//! (`ESC [ ? 1049 l`), devolve a tela principal como estava.
 
+use super::ansi::{Action, AnsiParser, Csi};
+use crate::render::colors;
 use alloc::string::String;
 use alloc::vec::Vec;
 
 /// Número máximo de linhas no buffer de scroll
 const MAX_SCROLL_LINES: usize = 1000;
 
+/// Largura da tabulação
+const TAB_WIDTH: u32 = 8;
+
+/// Célula da grade
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    /// Caractere
+    pub ch: char,
+    /// Cor do texto (ARGB)
+    pub fg: u32,
+    /// Cor de fundo (ARGB)
+    pub bg: u32,
+}
+
+impl Cell {
+    /// Célula vazia com as cores padrão
+    pub const BLANK: Cell = Cell {
+        ch: ' ',
+        fg: colors::TEXT,
+        bg: colors::BACKGROUND,
+    };
+}
+
+/// Linha da grade
+pub type Line = Vec<Cell>;
+
+/// Cor definida por SGR
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PenColor {
+    /// Cor padrão do tema
+    Default,
+    /// Índice da paleta de 256 cores
+    Indexed(u8),
+    /// Cor RGB direta
+    Rgb(u32),
+}
+
+/// Atributos de texto atuais
+#[derive(Debug, Clone, Copy)]
+struct Pen {
+    fg: PenColor,
+    bg: PenColor,
+    bold: bool,
+    reverse: bool,
+}
+
+impl Pen {
+    const DEFAULT: Pen = Pen {
+        fg: PenColor::Default,
+        bg: PenColor::Default,
+        bold: false,
+        reverse: false,
+    };
+
+    /// Resolve as cores finais (fg, bg) em ARGB
+    fn resolve(&self) -> (u32, u32) {
+        let fg = match self.fg {
+            PenColor::Default => colors::TEXT,
+            // Negrito usa a variante brilhante das 8 cores básicas
+            PenColor::Indexed(i) if self.bold && i < 8 => colors::ANSI_PALETTE[i as usize + 8],
+            PenColor::Indexed(i) => colors::xterm_256(i),
+            PenColor::Rgb(c) => c,
+        };
+        let bg = match self.bg {
+            PenColor::Default => colors::BACKGROUND,
+            PenColor::Indexed(i) => colors::xterm_256(i),
+            PenColor::Rgb(c) => c,
+        };
+
+        if self.reverse {
+            (bg, fg)
+        } else {
+            (fg, bg)
+        }
+    }
+}
+
 /// Estado do terminal
 pub struct TerminalState {
     /// Largura em caracteres
@@ -15,7 +102,7 @@ pub struct TerminalState {
     /// Altura em caracteres
     pub rows: u32,
     /// Buffer de linhas
-    pub lines: Vec<String>,
+    pub lines: Vec<Line>,
     /// Posição X do cursor (coluna)
     pub cursor_x: u32,
     /// Posição Y do cursor (linha visível)
@@ -26,6 +113,26 @@ pub struct TerminalState {
     pub scroll_offset: usize,
     /// Contador de frames para cursor piscante
     cursor_blink_counter: u32,
+    /// Pilha de capturas ativas (substituição de comando).
+    /// Enquanto houver captura, a saída vai para o topo da pilha em vez da tela.
+    capture: Vec<String>,
+    /// Parser de sequências de escape
+    parser: AnsiParser,
+    /// Atributos aplicados aos próximos caracteres
+    pen: Pen,
+    /// Cursor escondido pelo programa (`ESC [ ? 25 l`)
+    cursor_hidden: bool,
+    /// Tela principal guardada enquanto a alternativa está ativa
+    main_screen: Option<SavedScreen>,
+}
+
+/// Tela principal guardada durante a tela alternativa
+struct SavedScreen {
+    lines: Vec<Line>,
+    cursor_x: u32,
+    cursor_y: u32,
+    scroll_offset: usize,
+    pen: Pen,
 }
 
 impl TerminalState {
@@ -35,7 +142,7 @@ impl TerminalState {
 
         // Inicializar com linhas vazias
         for _ in 0..rows {
-            lines.push(String::new());
+            lines.push(Line::new());
         }
 
         Self {
@@ -47,46 +154,85 @@ impl TerminalState {
             cursor_visible: true,
             scroll_offset: 0,
             cursor_blink_counter: 0,
+            capture: Vec::new(),
+            parser: AnsiParser::new(),
+            pen: Pen::DEFAULT,
+            cursor_hidden: false,
+            main_screen: None,
         }
     }
 
+    /// Passa a capturar a saída em vez de escrevê-la na tela
+    pub fn begin_capture(&mut self) {
+        self.capture.push(String::new());
+    }
+
+    /// Encerra a captura mais recente e retorna o texto capturado
+    pub fn end_capture(&mut self) -> String {
+        self.capture.pop().unwrap_or_default()
+    }
+
+    /// A saída está sendo capturada
+    pub fn is_capturing(&self) -> bool {
+        !self.capture.is_empty()
+    }
+
     /// Escreve um caractere na posição atual do cursor
     pub fn write_char(&mut self, c: char) {
-        match c {
-            '\n' => self.new_line(),
-            '\r' => self.cursor_x = 0,
-            '\x08' => self.backspace(), // Backspace
-            _ => {
-                // Garantir que temos linhas suficientes
-                while self.lines.len() <= (self.scroll_offset + self.cursor_y as usize) {
-                    self.lines.push(String::new());
+        if let Some(buf) = self.capture.last_mut() {
+            buf.push(c);
+            return;
+        }
+
+        match self.parser.feed(c) {
+            Action::Print(c) => self.put_char(c),
+            Action::Control('\n') => self.new_line(),
+            Action::Control('\r') => self.cursor_x = 0,
+            Action::Control('\x08') => self.backspace(), // Backspace
+            Action::Control('\t') => {
+                let next = (self.cursor_x / TAB_WIDTH + 1) * TAB_WIDTH;
+                while self.cursor_x < next.min(self.cols) {
+                    self.put_char(' ');
+                    if self.cursor_x == 0 {
+                        break; // Quebrou a linha
+                    }
                 }
+            }
+            Action::Csi(csi) => self.handle_csi(&csi),
+            Action::Control(_) | Action::None => {}
+        }
+    }
 
-                let line_idx = self.scroll_offset + self.cursor_y as usize;
+    /// Escreve um caractere imprimível com os atributos atuais
+    fn put_char(&mut self, c: char) {
+        let (fg, bg) = self.pen.resolve();
+        let cell = Cell { ch: c, fg, bg };
 
-                // Expandir a linha se necessário
-                let line = &mut self.lines[line_idx];
-                while line.len() < self.cursor_x as usize {
-                    line.push(' ');
-                }
+        // Garantir que temos linhas suficientes
+        let line_idx = self.current_line_index();
+        while self.lines.len() <= line_idx {
+            self.lines.push(Line::new());
+        }
 
-                // Inserir ou substituir caractere
-                if self.cursor_x as usize >= line.len() {
-                    line.push(c);
-                } else {
-                    line.replace_range(
-                        self.cursor_x as usize..self.cursor_x as usize + 1,
-                        &char_to_string(c),
-                    );
-                }
+        // Expandir a linha se necessário
+        let x = self.cursor_x as usize;
+        let line = &mut self.lines[line_idx];
+        while line.len() < x {
+            line.push(Cell::BLANK);
+        }
+
+        // Inserir ou substituir caractere
+        if x >= line.len() {
+            line.push(cell);
+        } else {
+            line[x] = cell;
+        }
 
-                self.cursor_x += 1;
+        self.cursor_x += 1;
 
-                // Wrap se necessário
-                if self.cursor_x >= self.cols {
-                    self.new_line();
-                }
-            }
+        // Wrap se necessário
+        if self.cursor_x >= self.cols {
+            self.new_line();
         }
     }
 
@@ -100,7 +246,12 @@ impl TerminalState {
     /// Escreve uma linha completa com quebra
     pub fn write_line(&mut self, s: &str) {
         self.write_str(s);
-        self.new_line();
+        self.write_char('\n');
+    }
+
+    /// Índice em `lines` da linha do cursor
+    fn current_line_index(&self) -> usize {
+        self.scroll_offset + self.cursor_y as usize
     }
 
     /// Nova linha
@@ -108,13 +259,22 @@ impl TerminalState {
         self.cursor_x = 0;
         self.cursor_y += 1;
 
+        // Na tela alternativa não há histórico: a linha do topo é descartada
+        if self.cursor_y >= self.rows && self.main_screen.is_some() {
+            self.cursor_y = self.rows - 1;
+            self.lines.remove(self.scroll_offset);
+            self.lines
+                .insert(self.scroll_offset + self.cursor_y as usize, Line::new());
+            return;
+        }
+
         // Scroll se ultrapassar área visível
         if self.cursor_y >= self.rows {
             self.scroll_offset += 1;
             self.cursor_y = self.rows - 1;
 
             // Adicionar linha vazia
-            self.lines.push(String::new());
+            self.lines.push(Line::new());
 
             // Limitar buffer
             if self.lines.len() > MAX_SCROLL_LINES {
@@ -130,7 +290,7 @@ impl TerminalState {
     pub fn backspace(&mut self) {
         if self.cursor_x > 0 {
             self.cursor_x -= 1;
-            let line_idx = self.scroll_offset + self.cursor_y as usize;
+            let line_idx = self.current_line_index();
             if line_idx < self.lines.len() {
                 let line = &mut self.lines[line_idx];
                 if (self.cursor_x as usize) < line.len() {
@@ -144,13 +304,220 @@ impl TerminalState {
     pub fn clear(&mut self) {
         self.lines.clear();
         for _ in 0..self.rows {
-            self.lines.push(String::new());
+            self.lines.push(Line::new());
+        }
+        self.cursor_x = 0;
+        self.cursor_y = 0;
+        self.scroll_offset = 0;
+    }
+
+    // =========================================================================
+    // SEQUÊNCIAS DE ESCAPE
+    // =========================================================================
+
+    /// Executa uma sequência CSI
+    fn handle_csi(&mut self, csi: &Csi) {
+        if csi.private {
+            self.set_private_mode(csi);
+            return;
+        }
+
+        match csi.final_char {
+            'm' => self.handle_sgr(csi.params()),
+            'A' => self.cursor_y = self.cursor_y.saturating_sub(csi.param(0, 1) as u32),
+            'B' => self.cursor_y = (self.cursor_y + csi.param(0, 1) as u32).min(self.rows - 1),
+            'C' => self.cursor_x = (self.cursor_x + csi.param(0, 1) as u32).min(self.cols - 1),
+            'D' => self.cursor_x = self.cursor_x.saturating_sub(csi.param(0, 1) as u32),
+            'G' => self.cursor_x = (csi.param(0, 1) as u32 - 1).min(self.cols - 1),
+            'H' | 'f' => {
+                self.cursor_y = (csi.param(0, 1) as u32 - 1).min(self.rows - 1);
+                self.cursor_x = (csi.param(1, 1) as u32 - 1).min(self.cols - 1);
+            }
+            'K' => self.erase_in_line(csi.param(0, 0)),
+            'J' => self.erase_in_display(csi.param(0, 0)),
+            _ => {}
+        }
+    }
+
+    /// Modos privados (`ESC [ ? n h` liga, `ESC [ ? n l` desliga)
+    fn set_private_mode(&mut self, csi: &Csi) {
+        let on = match csi.final_char {
+            'h' => true,
+            'l' => false,
+            _ => return,
+        };
+
+        for &mode in csi.params() {
+            match mode {
+                25 => self.cursor_hidden = !on,
+                47 | 1047 | 1049 if on => self.enter_alt_screen(),
+                47 | 1047 | 1049 => self.leave_alt_screen(),
+                _ => {}
+            }
+        }
+    }
+
+    /// Troca para uma tela alternativa vazia, guardando a principal
+    fn enter_alt_screen(&mut self) {
+        if self.main_screen.is_some() {
+            return;
+        }
+
+        let mut lines = Vec::with_capacity(self.rows as usize);
+        for _ in 0..self.rows {
+            lines.push(Line::new());
         }
+        self.main_screen = Some(SavedScreen {
+            lines: core::mem::replace(&mut self.lines, lines),
+            cursor_x: self.cursor_x,
+            cursor_y: self.cursor_y,
+            scroll_offset: self.scroll_offset,
+            pen: self.pen,
+        });
         self.cursor_x = 0;
         self.cursor_y = 0;
         self.scroll_offset = 0;
     }
 
+    /// Volta à tela principal como estava antes da alternativa
+    fn leave_alt_screen(&mut self) {
+        let Some(saved) = self.main_screen.take() else {
+            return;
+        };
+
+        self.lines = saved.lines;
+        self.cursor_x = saved.cursor_x;
+        self.cursor_y = saved.cursor_y;
+        self.scroll_offset = saved.scroll_offset;
+        self.pen = saved.pen;
+
+        // Cursor dentro da grade e linhas suficientes para a tela
+        self.resize(self.cols, self.rows);
+    }
+
+    /// O cursor deve ser desenhado (piscando e não escondido pelo programa)
+    pub fn cursor_shown(&self) -> bool {
+        self.cursor_visible && !self.cursor_hidden
+    }
+
+    /// Select Graphic Rendition (cores e atributos)
+    fn handle_sgr(&mut self, params: &[u16]) {
+        if params.is_empty() {
+            self.pen = Pen::DEFAULT;
+            return;
+        }
+
+        let mut i = 0;
+        while i < params.len() {
+            match params[i] {
+                0 => self.pen = Pen::DEFAULT,
+                1 => self.pen.bold = true,
+                7 => self.pen.reverse = true,
+                22 => self.pen.bold = false,
+                27 => self.pen.reverse = false,
+                p @ 30..=37 => self.pen.fg = PenColor::Indexed((p - 30) as u8),
+                39 => self.pen.fg = PenColor::Default,
+                p @ 40..=47 => self.pen.bg = PenColor::Indexed((p - 40) as u8),
+                49 => self.pen.bg = PenColor::Default,
+                p @ 90..=97 => self.pen.fg = PenColor::Indexed((p - 90 + 8) as u8),
+                p @ 100..=107 => self.pen.bg = PenColor::Indexed((p - 100 + 8) as u8),
+                p @ (38 | 48) => {
+                    // 38;5;n (256 cores) ou 38;2;r;g;b (RGB)
+                    let color = match params.get(i + 1) {
+                        Some(5) => {
+                            let color = params.get(i + 2).map(|&n| PenColor::Indexed(n as u8));
+                            i += 2;
+                            color
+                        }
+                        Some(2) => {
+                            let channel =
+                                |k: usize| params.get(i + k).copied().unwrap_or(0) as u32 & 0xFF;
+                            let rgb =
+                                0xFF000000 | (channel(2) << 16) | (channel(3) << 8) | channel(4);
+                            i += 4;
+                            Some(PenColor::Rgb(rgb))
+                        }
+                        _ => None,
+                    };
+
+                    if let Some(color) = color {
+                        if p == 38 {
+                            self.pen.fg = color;
+                        } else {
+                            self.pen.bg = color;
+                        }
+                    }
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+    }
+
+    /// Apaga parte da linha do cursor (CSI K)
+    fn erase_in_line(&mut self, mode: u16) {
+        let x = self.cursor_x as usize;
+        let line_idx = self.current_line_index();
+        let Some(line) = self.lines.get_mut(line_idx) else {
+            return;
+        };
+
+        match mode {
+            0 => line.truncate(x),
+            1 => {
+                for cell in line.iter_mut().take(x + 1) {
+                    *cell = Cell::BLANK;
+                }
+            }
+            _ => line.clear(),
+        }
+    }
+
+    /// Apaga parte da tela (CSI J)
+    fn erase_in_display(&mut self, mode: u16) {
+        match mode {
+            0 => {
+                self.erase_in_line(0);
+                let start = self.current_line_index() + 1;
+                let end = (self.scroll_offset + self.rows as usize).min(self.lines.len());
+                for line in self.lines.iter_mut().take(end).skip(start) {
+                    line.clear();
+                }
+            }
+            1 => {
+                self.erase_in_line(1);
+                let start = self.scroll_offset;
+                let end = self.current_line_index();
+                for line in self.lines.iter_mut().take(end).skip(start) {
+                    line.clear();
+                }
+            }
+            _ => {
+                let (x, y) = (self.cursor_x, self.cursor_y);
+                self.clear();
+                self.cursor_x = x;
+                self.cursor_y = y;
+            }
+        }
+    }
+
+    /// Altera o tamanho da grade, mantendo a linha do cursor visível
+    pub fn resize(&mut self, cols: u32, rows: u32) {
+        let cursor_line = self.current_line_index();
+        self.cols = cols.max(1);
+        self.rows = rows.max(1);
+
+        if cursor_line >= self.scroll_offset + self.rows as usize {
+            self.scroll_offset = cursor_line + 1 - self.rows as usize;
+        }
+        self.cursor_y = (cursor_line - self.scroll_offset) as u32;
+        self.cursor_x = self.cursor_x.min(self.cols - 1);
+
+        while self.lines.len() < self.scroll_offset + self.rows as usize {
+            self.lines.push(Line::new());
+        }
+    }
+
     /// Atualiza animação do cursor (chamado a cada frame)
     pub fn tick(&mut self) {
         self.cursor_blink_counter += 1;
@@ -162,9 +529,9 @@ impl TerminalState {
     }
 
     /// Retorna linha visível por índice (0 = topo)
-    pub fn get_visible_line(&self, row: u32) -> Option<&str> {
+    pub fn get_visible_line(&self, row: u32) -> Option<&[Cell]> {
         let idx = self.scroll_offset + row as usize;
-        self.lines.get(idx).map(|s| s.as_str())
+        self.lines.get(idx).map(|l| l.as_slice())
     }
 
     // TODO: Revisar no futuro
@@ -186,10 +553,3 @@ impl TerminalState {
         self.scroll_offset = (self.scroll_offset + lines).min(max_scroll);
     }
 }
-
-/// Converte um char para String (helper interno)
-fn char_to_string(c: char) -> String {
-    let mut s = String::new();
-    s.push(c);
-    s
-}
@@ -0,0 +1,95 @@
+//! # UTF-8 Streaming Decoder
+//!
+//! Decodifica UTF-8 que chega em pedaços (leituras de arquivo, saída de
+//! processos). Sequências multibyte partidas entre dois pedaços são
+//! guardadas até o próximo; bytes inválidos viram U+FFFD.
+
+use alloc::string::String;
+
+/// Caractere de substituição para bytes inválidos
+pub const REPLACEMENT: char = '\u{FFFD}';
+
+/// Decodificador UTF-8 incremental
+#[derive(Debug, Clone, Default)]
+pub struct Utf8Decoder {
+    /// Bytes de uma sequência incompleta
+    pending: [u8; 4],
+    /// Bytes já recebidos da sequência
+    len: usize,
+    /// Tamanho total esperado da sequência
+    need: usize,
+}
+
+impl Utf8Decoder {
+    /// Cria decodificador vazio
+    pub const fn new() -> Self {
+        Self {
+            pending: [0; 4],
+            len: 0,
+            need: 0,
+        }
+    }
+
+    /// Decodifica `data`, acrescentando os caracteres completos a `out`
+    pub fn decode(&mut self, data: &[u8], out: &mut String) {
+        self.decode_with(data, |c| out.push(c));
+    }
+
+    /// Decodifica `data` chamando `f` para cada caractere completo
+    pub fn decode_with(&mut self, data: &[u8], mut f: impl FnMut(char)) {
+        for &byte in data {
+            self.push_byte(byte, &mut f);
+        }
+    }
+
+    /// Finaliza o fluxo: uma sequência incompleta vira U+FFFD
+    pub fn finish(&mut self, out: &mut String) {
+        if self.len > 0 {
+            out.push(REPLACEMENT);
+            self.len = 0;
+        }
+    }
+
+    fn push_byte(&mut self, byte: u8, mut f: impl FnMut(char)) {
+        if self.len > 0 {
+            if byte & 0xC0 == 0x80 {
+                self.pending[self.len] = byte;
+                self.len += 1;
+                if self.len == self.need {
+                    let c = core::str::from_utf8(&self.pending[..self.len])
+                        .ok()
+                        .and_then(|s| s.chars().next())
+                        .unwrap_or(REPLACEMENT);
+                    f(c);
+                    self.len = 0;
+                }
+                return;
+            }
+
+            // Sequência interrompida: descartar e reprocessar o byte atual
+            f(REPLACEMENT);
+            self.len = 0;
+        }
+
+        match sequence_len(byte) {
+            1 => f(byte as char),
+            0 => f(REPLACEMENT),
+            need => {
+                self.pending[0] = byte;
+                self.len = 1;
+                self.need = need;
+            }
+        }
+    }
+}
+
+/// Tamanho da sequência iniciada por `byte` (0 se não for byte inicial)
+fn sequence_len(byte: u8) -> usize {
+    match byte {
+        0x00..=0x7F => 1,
+        0xC2..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        0xF0..=0xF4 => 4,
+        _ => 0,
+    }
+}
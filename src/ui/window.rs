@@ -2,14 +2,23 @@
 //!
 //! Janela principal do terminal que integra todos os componentes.
 
+use crate::pty::{self, PtyMaster, PtySlave, Signal, WinSize};
 use crate::render::colors;
 use crate::render::font::{CHAR_HEIGHT, CHAR_WIDTH};
 use crate::render::text::TextRenderer;
 use crate::shell::commands::CommandResult;
+use crate::shell::jobs::{self, JobState};
+use crate::shell::launcher::{Pid, StdinFeed};
+use crate::shell::screen::{self, Key, Screen};
+use crate::shell::task::{self, Task, TaskStatus};
 use crate::shell::{execute_command, ShellContext};
+use crate::state::ansi::display_width;
 use crate::state::terminal::TerminalState;
+use crate::state::utf8::Utf8Decoder;
 use crate::ui::decorations::{WindowDecorations, BUTTON_WIDTH, CONTENT_PADDING, TITLE_BAR_HEIGHT};
+use alloc::boxed::Box;
 use alloc::string::String;
+use alloc::vec::Vec;
 use gfx_types::color::Color;
 use gfx_types::geometry::Rect;
 use redpowder::window::Window;
@@ -17,6 +26,23 @@ use redpowder::window::Window;
 use redpowder::event::{event_type, Event};
 use redpowder::input::KeyCode;
 
+/// Processo externo em primeiro plano, ligado ao terminal por um PTY
+struct Foreground {
+    /// PID do processo
+    pid: Pid,
+    /// Linha de comando (se for suspenso, vira um job)
+    command: String,
+    /// Lado do terminal
+    master: PtyMaster,
+    /// Lado do processo (stdin/stdout)
+    slave: PtySlave,
+    /// Entrada (pipeline ou teclado) ainda não aceita pelo processo;
+    /// reenviada a cada frame
+    stdin: StdinFeed,
+    /// Decodificador da saída do PTY
+    decoder: Utf8Decoder,
+}
+
 // TODO: Revisar no futuro
 #[allow(unused)]
 /// Janela do terminal
@@ -35,6 +61,8 @@ pub struct TerminalWindow {
     height: u32,
     /// Shift pressionado
     shift: bool,
+    /// Ctrl pressionado
+    ctrl: bool,
     /// Janela deve fechar
     pub should_close: bool,
     /// Flag para evitar redesenho desnecessário (flicker)
@@ -43,19 +71,24 @@ pub struct TerminalWindow {
     input_buffer: String,
     /// Posição do prompt na linha atual
     prompt_pos: usize,
+    /// Processo externo em execução, que recebe o teclado
+    foreground: Option<Foreground>,
+    /// Comando interno em execução, avançado a cada frame
+    task: Option<Box<dyn Task>>,
+    /// A tarefa fez uma pergunta `[s/N]` e espera a resposta
+    task_waiting: bool,
+    /// A tarefa espera uma linha do teclado (`write`)
+    task_reading: bool,
+    /// Programa em tela cheia (`less`, `edit`), que recebe o teclado
+    screen: Option<Box<dyn Screen>>,
+    /// Linha corrigida aguardando confirmação (`[s/N]`)
+    correction: Option<String>,
 }
 
 impl TerminalWindow {
     /// Cria nova janela do terminal
     pub fn new(width: u32, height: u32) -> Self {
-        // Calcular área de conteúdo disponível
-        let content_width = width - 2 - (CONTENT_PADDING * 2);
-        let content_height = height - TITLE_BAR_HEIGHT - 1 - (CONTENT_PADDING * 2);
-
-        // Calcular colunas e linhas disponíveis
-        let cols = content_width / CHAR_WIDTH;
-        let rows = content_height / CHAR_HEIGHT;
-
+        let (cols, rows) = grid_size(width, height);
         let shell_ctx = ShellContext::new();
 
         Self {
@@ -66,10 +99,44 @@ impl TerminalWindow {
             width,
             height,
             shift: false,
+            ctrl: false,
             should_close: false,
             dirty: true,
             input_buffer: String::new(),
             prompt_pos: 0,
+            foreground: None,
+            task: None,
+            task_waiting: false,
+            task_reading: false,
+            screen: None,
+            correction: None,
+        }
+    }
+
+    /// Redimensiona o terminal (em pixels), atualizando a grade e o PTY
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.width = width;
+        self.height = height;
+        self.decorations.width = width;
+        self.decorations.height = height;
+
+        let (cols, rows) = grid_size(width, height);
+        self.state.resize(cols, rows);
+
+        if let Some(fg) = &self.foreground {
+            fg.master.set_winsize(self.winsize());
+        }
+        if let Some(screen) = &self.screen {
+            screen.render(&mut self.state);
+        }
+        self.dirty = true;
+    }
+
+    /// Tamanho da grade para o PTY
+    fn winsize(&self) -> WinSize {
+        WinSize {
+            cols: self.state.cols as u16,
+            rows: self.state.rows as u16,
         }
     }
 
@@ -88,9 +155,13 @@ impl TerminalWindow {
 
     /// Mostra o prompt
     fn show_prompt(&mut self) {
+        // Avisar jobs que terminaram desde o último prompt
+        self.shell_ctx.jobs.report_finished(&mut self.state);
+
         let prompt = self.shell_ctx.prompt();
         self.state.write_str(&prompt);
-        self.prompt_pos = prompt.len();
+        // Largura visível: sequências de cor não ocupam colunas
+        self.prompt_pos = display_width(&prompt);
         self.input_buffer.clear();
     }
 
@@ -106,6 +177,81 @@ impl TerminalWindow {
                         return;
                     }
 
+                    if code == KeyCode::Ctrl {
+                        self.ctrl = true;
+                        return;
+                    }
+
+                    // Com um processo em primeiro plano, o teclado vai para o PTY
+                    if self.foreground.is_some() {
+                        self.send_key(code);
+                        return;
+                    }
+
+                    // O programa em tela cheia recebe todas as teclas
+                    if self.screen.is_some() {
+                        self.screen_key(code);
+                        self.dirty = true;
+                        return;
+                    }
+
+                    // Resposta à correção sugerida
+                    if let Some(line) = self.correction.take() {
+                        self.confirm_correction(line, code);
+                        self.dirty = true;
+                        return;
+                    }
+
+                    if self.ctrl {
+                        // ^C cancela o comando em execução ou descarta a linha atual
+                        if code.to_char(false) == Some('c') {
+                            self.task_waiting = false;
+                            self.task_reading = false;
+                            if let Some(mut task) = self.task.take() {
+                                task.cancel(&mut self.shell_ctx);
+                                if self.state.cursor_x != 0 {
+                                    self.state.write_char('\n');
+                                }
+                                self.shell_ctx.last_exit_code = 130;
+                            }
+                            self.state.write_line("^C");
+                            self.show_prompt();
+                            self.dirty = true;
+                        } else if code.to_char(false) == Some('d') && self.task_reading {
+                            // ^D encerra a entrada da tarefa
+                            self.send_task_input(false);
+                            self.dirty = true;
+                        }
+                        return;
+                    }
+
+                    // Resposta à pergunta da tarefa
+                    if self.task_waiting {
+                        self.answer_task(code);
+                        self.dirty = true;
+                        return;
+                    }
+
+                    // Linha digitada para a tarefa, com eco na tela
+                    if self.task_reading {
+                        match code {
+                            KeyCode::Backspace => self.handle_backspace(),
+                            KeyCode::Enter => self.send_task_input(true),
+                            _ => {
+                                if let Some(c) = code.to_char(self.shift) {
+                                    self.handle_char(c);
+                                }
+                            }
+                        }
+                        self.dirty = true;
+                        return;
+                    }
+
+                    // Enquanto um comando roda, só ^C é aceito
+                    if self.task.is_some() {
+                        return;
+                    }
+
                     if code == KeyCode::Backspace {
                         self.handle_backspace();
                         self.dirty = true;
@@ -119,13 +265,19 @@ impl TerminalWindow {
                     }
 
                     if let Some(c) = code.to_char(self.shift) {
-                        self.handle_char(c);
+                        if c == '\t' {
+                            self.complete_command();
+                        } else {
+                            self.handle_char(c);
+                        }
                         self.dirty = true;
                     }
                 } else if input.event_type == event_type::KEY_UP {
                     let code = unsafe { core::mem::transmute::<u8, KeyCode>(input.param1 as u8) };
                     if code == KeyCode::Shift {
                         self.shift = false;
+                    } else if code == KeyCode::Ctrl {
+                        self.ctrl = false;
                     }
                 } else if input.event_type == event_type::MOUSE_DOWN {
                     // Tratar clique em botões
@@ -145,12 +297,286 @@ impl TerminalWindow {
         }
     }
 
+    /// Envia uma tecla ao processo em primeiro plano
+    fn send_key(&mut self, code: KeyCode) {
+        let Some(fg) = &self.foreground else {
+            return;
+        };
+
+        let mut bytes = [0u8; 4];
+        let data: &[u8] = match code {
+            KeyCode::Enter => b"\r",
+            KeyCode::Backspace => b"\x7f",
+            _ => match code.to_char(self.shift) {
+                // Ctrl+letra gera o caractere de controle correspondente
+                Some(c) if self.ctrl && c.is_ascii_alphabetic() => {
+                    bytes[0] = c.to_ascii_uppercase() as u8 & 0x1F;
+                    &bytes[..1]
+                }
+                Some(c) => c.encode_utf8(&mut bytes).as_bytes(),
+                None => return,
+            },
+        };
+
+        fg.master.write(data);
+    }
+
+    /// Envia uma tecla ao programa em tela cheia, voltando ao prompt
+    /// quando ele termina
+    fn screen_key(&mut self, code: KeyCode) {
+        let Some(screen) = self.screen.as_mut() else {
+            return;
+        };
+
+        let key = match code {
+            KeyCode::Enter => Key::Enter,
+            KeyCode::Backspace => Key::Backspace,
+            KeyCode::Delete => Key::Delete,
+            KeyCode::Escape => Key::Escape,
+            KeyCode::Up => Key::Up,
+            KeyCode::Down => Key::Down,
+            KeyCode::Left => Key::Left,
+            KeyCode::Right => Key::Right,
+            KeyCode::PageUp => Key::PageUp,
+            KeyCode::PageDown => Key::PageDown,
+            KeyCode::Home => Key::Home,
+            KeyCode::End => Key::End,
+            _ => match code.to_char(self.shift) {
+                Some(c) if self.ctrl => Key::Ctrl(c.to_ascii_lowercase()),
+                Some(c) => Key::Char(c),
+                None => return,
+            },
+        };
+
+        if !screen.key(key, &mut self.shell_ctx, &mut self.state) {
+            screen::leave(&mut self.state);
+            self.screen = None;
+            self.show_prompt();
+        }
+    }
+
+    /// Liga um processo recém-iniciado ao terminal
+    fn start_foreground(&mut self, pid: Pid, command: String, stdin: StdinFeed) {
+        let (master, slave) = pty::pair(self.winsize());
+        self.foreground = Some(Foreground {
+            pid,
+            command,
+            master,
+            slave,
+            stdin,
+            decoder: Utf8Decoder::new(),
+        });
+    }
+
+    /// Troca dados entre o processo em primeiro plano, o PTY e a tela
+    fn pump_foreground(&mut self) {
+        let Some(fg) = self.foreground.as_mut() else {
+            return;
+        };
+        let launcher = &mut self.shell_ctx.launcher;
+        let mut buf = [0u8; 512];
+
+        // Teclado (já processado pela disciplina de linha) → processo. Com
+        // o pipe cheio, o resto espera em `stdin` pelo próximo frame
+        while let Some(n) = fg.slave.read(&mut buf) {
+            if n == 0 {
+                fg.stdin.finish();
+                break;
+            }
+            fg.stdin.push(&buf[..n]);
+        }
+        fg.stdin.flush(launcher.as_mut(), fg.pid);
+
+        // Sinais (^C, ^Z, mudança de tamanho)
+        let mut suspended = false;
+        while let Some(signal) = fg.master.take_signal() {
+            let _ = launcher.signal(fg.pid, signal);
+            suspended |= signal == Signal::Suspend;
+        }
+
+        // Verificar término antes de ler, para não perder a saída final
+        let exited = launcher.try_wait(fg.pid);
+
+        // Processo → PTY
+        while let Ok(n @ 1..) = launcher.read_stdout(fg.pid, &mut buf) {
+            fg.slave.write(&buf[..n]);
+        }
+
+        // PTY → tela (passando pelo parser de escape)
+        loop {
+            let n = fg.master.read(&mut buf);
+            if n == 0 {
+                break;
+            }
+            fg.decoder
+                .decode_with(&buf[..n], |c| self.state.write_char(c));
+            self.dirty = true;
+        }
+
+        let code = match exited {
+            Ok(Some(code)) => code,
+            Ok(None) if suspended => {
+                self.suspend_foreground();
+                return;
+            }
+            Ok(None) => return,
+            Err(_) => 1,
+        };
+
+        self.foreground = None;
+        self.shell_ctx.last_exit_code = code;
+        if self.state.cursor_x != 0 {
+            self.state.write_char('\n');
+        }
+        self.show_prompt();
+        self.dirty = true;
+    }
+
+    /// Move o processo suspenso (^Z) para a tabela de jobs e volta ao prompt
+    fn suspend_foreground(&mut self) {
+        let Some(fg) = self.foreground.take() else {
+            return;
+        };
+
+        let jobs = &mut self.shell_ctx.jobs;
+        let id = jobs.add(fg.pid, &fg.command, JobState::Stopped, fg.stdin);
+        if let Some(job) = jobs.get(id) {
+            jobs::write_job(&mut self.state, job, jobs.marker(id));
+        }
+
+        // 128 + SIGTSTP, como em outros shells
+        self.shell_ctx.last_exit_code = 148;
+        self.show_prompt();
+        self.dirty = true;
+    }
+
+    /// Avança o comando em execução por uma fatia de tempo
+    fn run_task(&mut self) {
+        if self.task_waiting || self.task_reading {
+            return;
+        }
+        let Some(task) = self.task.as_mut() else {
+            return;
+        };
+
+        let status = task::run_for(
+            task.as_mut(),
+            &mut self.shell_ctx,
+            &mut self.state,
+            task::FRAME_BUDGET_MS,
+        );
+        self.dirty = true;
+
+        match status {
+            TaskStatus::Done => {
+                let result = task.finish();
+                self.task = None;
+                self.handle_result(result);
+            }
+            TaskStatus::Ask => self.task_waiting = true,
+            TaskStatus::Input => {
+                self.task_reading = true;
+                self.input_buffer.clear();
+            }
+            TaskStatus::Pending => {}
+        }
+    }
+
+    /// `s`/`y` confirma a pergunta da tarefa; qualquer outra tecla nega
+    fn answer_task(&mut self, code: KeyCode) {
+        let yes = matches!(code.to_char(false), Some('s' | 'y'));
+        self.state.write_line(if yes { "s" } else { "n" });
+        self.task_waiting = false;
+        if let Some(task) = self.task.as_mut() {
+            task.answer(yes);
+        }
+    }
+
+    /// Entrega a linha digitada à tarefa: com Enter, a linha e o `\n`; com
+    /// ^D, o que houver na linha e o fim da entrada
+    fn send_task_input(&mut self, enter: bool) {
+        let mut text = core::mem::take(&mut self.input_buffer);
+        self.task_reading = false;
+        if enter {
+            text.push('\n');
+        }
+        if !text.is_empty() {
+            self.state.write_char('\n');
+        }
+
+        let Some(task) = self.task.as_mut() else {
+            return;
+        };
+        if !text.is_empty() {
+            task.input(Some(&text));
+        }
+        if !enter {
+            task.input(None);
+        }
+    }
+
     /// Trata caractere digitado
     fn handle_char(&mut self, c: char) {
         self.input_buffer.push(c);
         self.state.write_char(c);
     }
 
+    /// Completa o nome do comando (Tab) a partir do registro
+    fn complete_command(&mut self) {
+        // Só a primeira palavra é completada
+        if self.input_buffer.contains(' ') {
+            return;
+        }
+
+        let matches: Vec<String> = self
+            .shell_ctx
+            .commands
+            .complete(&self.input_buffer)
+            .into_iter()
+            .map(String::from)
+            .collect();
+
+        let Some(first) = matches.first() else {
+            return;
+        };
+
+        if matches.len() == 1 {
+            let rest = String::from(&first[self.input_buffer.len()..]);
+            for c in rest.chars().chain(core::iter::once(' ')) {
+                self.handle_char(c);
+            }
+            return;
+        }
+
+        // Prefixo comum a todas as opções
+        let mut common = first.len();
+        for name in &matches[1..] {
+            common = first
+                .bytes()
+                .zip(name.bytes())
+                .take(common)
+                .take_while(|(a, b)| a == b)
+                .count();
+        }
+
+        if common > self.input_buffer.len() {
+            let rest = String::from(&first[self.input_buffer.len()..common]);
+            for c in rest.chars() {
+                self.handle_char(c);
+            }
+            return;
+        }
+
+        // Ambíguo: listar e redesenhar a linha
+        let input = core::mem::take(&mut self.input_buffer);
+        self.state.write_char('\n');
+        self.state.write_line(&matches.join("  "));
+        self.show_prompt();
+        for c in input.chars() {
+            self.handle_char(c);
+        }
+    }
+
     /// Trata backspace
     fn handle_backspace(&mut self) {
         if !self.input_buffer.is_empty() {
@@ -165,7 +591,13 @@ impl TerminalWindow {
 
         // Executar comando
         let cmd = self.input_buffer.clone();
-        match execute_command(&cmd, &mut self.shell_ctx, &mut self.state) {
+        let result = execute_command(&cmd, &mut self.shell_ctx, &mut self.state);
+        self.handle_result(result);
+    }
+
+    /// Trata o resultado de um comando (ou de uma tarefa que terminou)
+    fn handle_result(&mut self, result: CommandResult) {
+        match result {
             CommandResult::Ok => {
                 self.show_prompt();
             }
@@ -181,6 +613,36 @@ impl TerminalWindow {
                 self.state.write_line(&msg);
                 self.show_prompt();
             }
+            CommandResult::Spawned {
+                pid,
+                command,
+                stdin,
+            } => {
+                self.start_foreground(pid, command, stdin);
+            }
+            CommandResult::Task(task) => {
+                self.task = Some(task);
+            }
+            CommandResult::Screen(screen) => {
+                screen::enter(screen.as_ref(), &mut self.state);
+                self.screen = Some(screen);
+            }
+            CommandResult::Correct { line } => {
+                self.correction = Some(line);
+            }
+        }
+    }
+
+    /// `s`/`y` executa a linha corrigida; qualquer outra tecla desiste
+    fn confirm_correction(&mut self, line: String, code: KeyCode) {
+        let accepted = !self.ctrl && matches!(code.to_char(false), Some('s' | 'y'));
+        if accepted {
+            self.state.write_char('s');
+            self.input_buffer = line;
+            self.handle_enter();
+        } else {
+            self.state.write_char('\n');
+            self.show_prompt();
         }
     }
 
@@ -205,9 +667,17 @@ impl TerminalWindow {
         self.state.clear();
     }
 
-    /// Atualiza animações (cursor piscante)
+    /// Atualiza animações (cursor piscante), o processo em primeiro plano e
+    /// os jobs em segundo plano
     pub fn tick(&mut self) {
         self.state.tick();
+        self.pump_foreground();
+        self.run_task();
+
+        let ctx = &mut self.shell_ctx;
+        if ctx.jobs.poll(ctx.launcher.as_mut(), &mut self.state) {
+            self.dirty = true;
+        }
     }
 
     /// Desenha todo o terminal na janela
@@ -224,7 +694,7 @@ impl TerminalWindow {
         self.draw_content(window, content_x, content_y, content_w, content_h);
 
         // 4. Desenhar cursor
-        if self.state.cursor_visible {
+        if self.state.cursor_shown() {
             self.draw_cursor(window, content_x, content_y);
         }
     }
@@ -242,7 +712,11 @@ impl TerminalWindow {
                     break;
                 }
 
-                self.renderer.draw_string(window, x, line_y, line);
+                for (col, cell) in line.iter().take(self.state.cols as usize).enumerate() {
+                    let cell_x = x + (col as u32 * CHAR_WIDTH);
+                    self.renderer
+                        .draw_char_colored(window, cell_x, line_y, cell.ch, cell.fg, cell.bg);
+                }
             }
         }
     }
@@ -267,3 +741,13 @@ impl TerminalWindow {
         self.state.rows
     }
 }
+
+/// Calcula colunas e linhas que cabem na área de conteúdo
+fn grid_size(width: u32, height: u32) -> (u32, u32) {
+    // Calcular área de conteúdo disponível
+    let content_width = width - 2 - (CONTENT_PADDING * 2);
+    let content_height = height - TITLE_BAR_HEIGHT - 1 - (CONTENT_PADDING * 2);
+
+    // Calcular colunas e linhas disponíveis
+    (content_width / CHAR_WIDTH, content_height / CHAR_HEIGHT)
+}